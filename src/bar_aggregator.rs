@@ -0,0 +1,242 @@
+//! 체결 기반 OHLCV 봉 집계기
+//! `klines=local` 세션의 Trade/TradeBatch를 config.ini `bar_intervals` 길이의 봉으로 모아 확정 시 전송
+
+use crate::data_parser::{ParsedData, StandardizedKline, StandardizedTrade};
+use std::collections::HashMap;
+
+/// 봉 마감 후 지연 체결을 기다리는 기본 유예 시간 (2초)
+pub const DEFAULT_GRACE_NS: u64 = 2_000_000_000;
+
+/// 봉 길이 문자열을 초 단위로 변환 (`30s`, `1m`, `4h`, `1d`, `1w`, 숫자만 있으면 초)
+pub fn parse_interval(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u32 = amount.parse().ok().filter(|n| *n > 0)?;
+    let unit_secs = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" | "H" => 3_600,
+        "d" | "D" => 86_400,
+        "w" | "W" => 7 * 86_400,
+        _ => return None,
+    };
+    amount.checked_mul(unit_secs)
+}
+
+/// 쉼표로 구분된 봉 길이 목록 파싱 (중복 제거, 오름차순)
+pub fn parse_interval_list(value: &str) -> std::result::Result<Vec<u32>, String> {
+    let mut intervals = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let secs = parse_interval(item)
+            .ok_or_else(|| format!("bar_intervals 값 오류 '{}' (예: 1m, 5m, 1h)", item))?;
+        intervals.push(secs);
+    }
+    if intervals.is_empty() {
+        return Err("bar_intervals가 비어 있음".to_string());
+    }
+    intervals.sort_unstable();
+    intervals.dedup();
+    Ok(intervals)
+}
+
+/// 봉 식별 키 (포트, 거래소, 심볼, 봉 길이)
+type BarKey = (u16, String, String, u32);
+
+/// 체결을 봉 길이별 OHLCV로 집계
+///
+/// 봉은 체결 시각 기준 `[start, start + interval)` 구간이며, 다음 구간 체결이 오거나
+/// 마감 시각 + 유예 시간이 지나면(`close_due`) 확정됩니다. 체결이 없는 구간의 빈 봉은 만들지 않습니다.
+pub struct BarAggregator {
+    intervals: Vec<u32>,
+    grace_ns: u64,
+    bars: HashMap<BarKey, StandardizedKline>,
+    // 키별로 확정된 마지막 봉의 마감 시각 (이보다 이른 체결은 지연 체결)
+    closed_until: HashMap<BarKey, u64>,
+    /// 이미 확정된 봉에 속해 버려진 체결 수
+    pub late_trades: u64,
+}
+
+impl BarAggregator {
+    pub fn new(intervals: Vec<u32>) -> Self {
+        Self {
+            intervals,
+            grace_ns: DEFAULT_GRACE_NS,
+            bars: HashMap::new(),
+            closed_until: HashMap::new(),
+            late_trades: 0,
+        }
+    }
+
+    /// 지연 체결 유예 시간 설정
+    pub fn with_grace_ns(mut self, grace_ns: u64) -> Self {
+        self.grace_ns = grace_ns;
+        self
+    }
+
+    /// 파싱된 데이터의 체결을 집계하고, 새 구간 체결로 확정된 봉 반환
+    pub fn ingest(&mut self, port: u16, data: &ParsedData) -> Vec<StandardizedKline> {
+        let mut closed = Vec::new();
+        match data {
+            ParsedData::Trade(trade) => self.on_trade(port, trade, &mut closed),
+            ParsedData::TradeBatch(batch) => {
+                for trade in &batch.trades {
+                    self.on_trade(port, trade, &mut closed);
+                }
+            }
+            ParsedData::Multi(items) => {
+                for item in items {
+                    closed.extend(self.ingest(port, item));
+                }
+            }
+            _ => {}
+        }
+        closed
+    }
+
+    fn on_trade(&mut self, port: u16, trade: &StandardizedTrade, closed: &mut Vec<StandardizedKline>) {
+        // 가격 0 등 비정상 체결은 집계하지 않음
+        if !trade.price.is_finite() || trade.price <= 0.0 || !trade.quantity.is_finite() {
+            return;
+        }
+        for &interval in &self.intervals {
+            let interval_ns = interval as u64 * 1_000_000_000;
+            let start = trade.timestamp - trade.timestamp % interval_ns;
+            let key = (port, trade.exchange.clone(), trade.symbol.clone(), interval);
+
+            if self.closed_until.get(&key).is_some_and(|until| start < *until) {
+                self.late_trades += 1;
+                continue;
+            }
+            if let Some(bar) = self.bars.get(&key) {
+                if start < bar.start_time {
+                    self.late_trades += 1;
+                    continue;
+                }
+                if start > bar.start_time {
+                    let bar = self.bars.remove(&key).unwrap();
+                    closed.push(finish(&mut self.closed_until, key.clone(), bar));
+                }
+            }
+
+            let bar = self.bars.entry(key).or_insert_with(|| StandardizedKline {
+                symbol: trade.symbol.clone(),
+                exchange: trade.exchange.clone(),
+                interval_secs: interval,
+                start_time: start,
+                open: trade.price,
+                high: trade.price,
+                low: trade.price,
+                close: trade.price,
+                volume: 0.0,
+                quote_volume: 0.0,
+                trade_count: 0,
+                is_closed: false,
+                timestamp: start,
+            });
+            bar.high = bar.high.max(trade.price);
+            bar.low = bar.low.min(trade.price);
+            bar.close = trade.price;
+            bar.volume += trade.quantity;
+            bar.quote_volume += trade.price * trade.quantity;
+            bar.trade_count += 1;
+        }
+    }
+
+    /// 마감 시각 + 유예 시간이 지난 봉을 확정하여 (포트, 봉) 목록으로 반환
+    pub fn close_due(&mut self, now_ns: u64) -> Vec<(u16, StandardizedKline)> {
+        let due: Vec<BarKey> = self.bars.iter()
+            .filter(|(_, bar)| bar_end(bar).saturating_add(self.grace_ns) <= now_ns)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut closed: Vec<(u16, StandardizedKline)> = due.into_iter()
+            .map(|key| {
+                let bar = self.bars.remove(&key).unwrap();
+                (key.0, finish(&mut self.closed_until, key, bar))
+            })
+            .collect();
+        closed.sort_by(|a, b| (a.1.start_time, &a.1.symbol, a.1.interval_secs).cmp(&(b.1.start_time, &b.1.symbol, b.1.interval_secs)));
+        closed
+    }
+}
+
+/// 봉 확정 처리 (timestamp는 봉 마감 시각)
+fn finish(closed_until: &mut HashMap<BarKey, u64>, key: BarKey, mut bar: StandardizedKline) -> StandardizedKline {
+    let end = bar_end(&bar);
+    bar.is_closed = true;
+    bar.timestamp = end;
+    closed_until.insert(key, end);
+    bar
+}
+
+fn bar_end(bar: &StandardizedKline) -> u64 {
+    bar.start_time + bar.interval_secs as u64 * 1_000_000_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: u64 = 1_000_000_000;
+
+    fn trade(ts_secs: u64, price: f64, quantity: f64) -> ParsedData {
+        ParsedData::Trade(StandardizedTrade {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            price,
+            quantity,
+            is_buyer_taker: true,
+            timestamp: ts_secs * SEC,
+            trade_id: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_intervals() {
+        assert_eq!(parse_interval("1m"), Some(60));
+        assert_eq!(parse_interval("4H"), Some(14_400));
+        assert_eq!(parse_interval("1D"), Some(86_400));
+        assert_eq!(parse_interval("15"), Some(15));
+        assert_eq!(parse_interval("0m"), None);
+        assert_eq!(parse_interval("1M"), None);
+        assert_eq!(parse_interval_list("5m, 1m, 1m").unwrap(), vec![60, 300]);
+        assert!(parse_interval_list("1m, x").is_err());
+    }
+
+    #[test]
+    fn test_trades_roll_into_bars() {
+        let mut agg = BarAggregator::new(vec![60]);
+        assert!(agg.ingest(55555, &trade(120, 100.0, 1.0)).is_empty());
+        assert!(agg.ingest(55555, &trade(130, 105.0, 2.0)).is_empty());
+        assert!(agg.ingest(55555, &trade(170, 95.0, 1.0)).is_empty());
+
+        // 다음 구간 체결이 들어오면 이전 봉 확정
+        let closed = agg.ingest(55555, &trade(185, 101.0, 1.0));
+        assert_eq!(closed.len(), 1);
+        let bar = &closed[0];
+        assert_eq!(bar.start_time, 120 * SEC);
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (100.0, 105.0, 95.0, 95.0));
+        assert_eq!((bar.volume, bar.quote_volume, bar.trade_count), (4.0, 405.0, 3));
+        assert!(bar.is_closed);
+        assert_eq!(bar.timestamp, 180 * SEC);
+
+        // 확정된 구간의 지연 체결은 버림
+        assert!(agg.ingest(55555, &trade(179, 90.0, 1.0)).is_empty());
+        assert_eq!(agg.late_trades, 1);
+    }
+
+    #[test]
+    fn test_close_due_after_grace() {
+        let mut agg = BarAggregator::new(vec![60, 300]).with_grace_ns(2 * SEC);
+        agg.ingest(55556, &trade(10, 100.0, 1.0));
+        assert!(agg.close_due(61 * SEC).is_empty());
+        let closed = agg.close_due(62 * SEC);
+        assert_eq!(closed.len(), 1);
+        assert_eq!((closed[0].0, closed[0].1.interval_secs), (55556, 60));
+        assert_eq!(agg.close_due(302 * SEC)[0].1.interval_secs, 300);
+
+        // 더미 체결(가격 0)은 집계하지 않음
+        agg.ingest(55556, &trade(400, 0.0, 0.0));
+        assert!(agg.close_due(u64::MAX).is_empty());
+    }
+}
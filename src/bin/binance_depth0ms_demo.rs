@@ -12,7 +12,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 	let timeout_secs: Option<u64> = if args.len() >= 2 { args[1].parse::<u64>().ok().filter(|v| *v > 0) } else { None };
 
 	// 테스트 심볼 (Futures USDT 계약) - 여러 개 가능
-	let symbols = vec!["btcusdt", "ethusdt"];
+	let symbols = ["btcusdt", "ethusdt"];
     let streams: Vec<String> = symbols
         .iter()
         .flat_map(|s| vec![format!("{}@depth@0ms", s)])
@@ -31,7 +31,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 	if let Some(t) = timeout_secs {
 		let deadline = start + Duration::from_secs(t);
-		let mut timer = sleep_until(deadline);
+		let timer = sleep_until(deadline);
 		tokio::pin!(timer);
 		loop {
 			let next_msg = read.next().fuse();
@@ -21,7 +21,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 	let timeout_secs: Option<u64> = if args.len() >= 2 { args[1].parse::<u64>().ok().filter(|v| *v > 0) } else { None };
 
 	// target symbols (UM futures). Keep lowercase for stream path
-	let symbols = vec!["btcusdt", "ethusdt"];
+	let symbols = ["btcusdt", "ethusdt"];
 	let streams: Vec<String> = symbols
 		.iter()
 		.flat_map(|s| vec![
@@ -47,10 +47,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // UDP 송신기 준비
     let cfg = Config::load().unwrap_or_else(|_| Config {
         exchanges: vec![], symbols: vec![],
-        udp: crypto_feeder::config::UdpConfig { multicast_addr: "239.255.1.1".into(), port: 55555, interface_addr: "0.0.0.0".into() },
+        udp: crypto_feeder::config::UdpConfig { multicast_addr: "239.255.1.1".into(), port: 55555, interface_addr: "0.0.0.0".into(), mtu: 1500 },
         logging: crypto_feeder::config::LoggingConfig { level: "info".into(), file_path: None },
         runtime_threads: None, metrics: crypto_feeder::config::MetricsConfig { enabled: false, interval_secs: 5 },
-        symbol_config: None, endpoint_config: None,
+        symbol_config: None, endpoint_config: None, config_watch_interval_secs: 0, control_port: 0,
+        journal: crypto_feeder::config::JournalConfig { enabled: false, dir: "journal".into(), max_file_bytes: 512 * 1024 * 1024, queue_capacity: 65536 },
+        protocol: crypto_feeder::config::ProtocolConfig::default(),
+        reference: crypto_feeder::config::ReferenceDataConfig::default(),
+        instrument_scales: std::collections::HashMap::new(),
+        bar_intervals: vec![60],
+        ticker_interval_ms: 1000,
+        open_interest_poll_secs: 30,
+        consolidated_bbo: crypto_feeder::config::ConsolidatedBboConfig::default(),
+        kimchi_premium: crypto_feeder::config::KimchiPremiumConfig::default(),
+        trade_flow: crypto_feeder::config::TradeFlowConfig::default(),
+        index_config: None,
     });
     let udp = UdpMulticaster::new(&cfg.udp).expect("UDP 초기화 실패");
     let builder = PacketBuilder::new();
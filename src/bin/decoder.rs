@@ -1,92 +1,16 @@
-/// 패킷 디코더 유틸리티
-/// UDP 멀티캐스트로 전송된 패킷을 수신하고 사람이 읽을 수 있는 형태로 출력
-
-use std::net::UdpSocket;
-use std::mem;
-use std::fs;
+//! 패킷 디코더 유틸리티
+//! UDP 멀티캐스트로 전송된 패킷을 수신하고 사람이 읽을 수 있는 형태로 출력
+//!
+//! 사용법: packet-decoder [포트[,포트...]]  (기본: config.ini의 port, 없으면 55555)
+//! v1/v2 헤더를 모두 해석합니다 (v2는 거래소/심볼 대신 venue_id/instrument_id 표시).
+
+use crypto_feeder::consumer::{
+    GapDetector, Message, MessageAssembler, MulticastSubscriber, PacketView, ScaleBook, SequenceCheck, SubscriberConfig,
+};
+use crypto_feeder::reference_data::market_type_name;
 use std::collections::HashMap;
-use socket2::{Domain, Type, Protocol, Socket};
-use std::time::{Instant, Duration};
-
-// 프로토콜 구조체 재정의 (크로스 바이너리 호환성을 위해)
-#[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
-struct PacketHeader {
-    protocol_version: u8,
-    sequence_number: u64,
-    exchange_timestamp: u64,
-    local_timestamp: u64,
-    message_type: u8,
-    flags_and_count: u8,
-    symbol: [u8; 20],
-    exchange: [u8; 20],
-}
-
-#[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
-struct OrderBookItem {
-    price: i64,
-    quantity_with_flags: i64,
-}
-
-#[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
-struct TradeTickItem {
-    price: i64,
-    quantity_with_flags: i64,
-}
-
-impl PacketHeader {
-    fn is_last(&self) -> bool {
-        (self.flags_and_count & 0b1000_0000) != 0
-    }
-
-    fn item_count(&self) -> u8 {
-        self.flags_and_count & 0b0111_1111
-    }
-
-    fn symbol_as_string(&self) -> String {
-        let end = self.symbol.iter().position(|&b| b == 0).unwrap_or(self.symbol.len());
-        String::from_utf8_lossy(&self.symbol[..end]).to_string()
-    }
-
-    fn exchange_as_string(&self) -> String {
-        let end = self.exchange.iter().position(|&b| b == 0).unwrap_or(self.exchange.len());
-        String::from_utf8_lossy(&self.exchange[..end]).to_string()
-    }
-}
-
-impl TradeTickItem {
-    fn is_buyer_taker(&self) -> bool {
-        (self.quantity_with_flags & (1i64 << 63)) != 0
-    }
-
-    fn quantity(&self) -> i64 {
-        self.quantity_with_flags & 0x7FFF_FFFF_FFFF_FFFF
-    }
-
-    fn get_real_price(&self) -> f64 {
-        self.price as f64 / 100_000_000.0
-    }
-
-    fn get_real_quantity(&self) -> f64 {
-        self.quantity() as f64 / 100_000_000.0
-    }
-}
-
-impl OrderBookItem {
-    fn get_real_price(&self) -> f64 {
-        self.price as f64 / 100_000_000.0
-    }
-
-    fn get_real_quantity(&self) -> f64 {
-        (self.quantity_with_flags & 0x7FFF_FFFF_FFFF_FFFF) as f64 / 100_000_000.0
-    }
-
-    fn is_ask(&self) -> bool {
-        (self.quantity_with_flags & (1i64 << 63)) != 0
-    }
-}
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 struct Stats {
     total_packets: u64,
@@ -95,110 +19,77 @@ struct Stats {
     tradetick_packets: u64,
     orderbook_items: u64,
     tradetick_items: u64,
+    event_packets: u64,
 }
 
 impl Stats {
-    fn new() -> Self { Self { total_packets:0, total_bytes:0, orderbook_packets:0, tradetick_packets:0, orderbook_items:0, tradetick_items:0 } }
+    fn new() -> Self { Self { total_packets:0, total_bytes:0, orderbook_packets:0, tradetick_packets:0, orderbook_items:0, tradetick_items:0, event_packets:0 } }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔍 CryptoFeeder 패킷 디코더 시작");
 
-    let args: Vec<String> = std::env::args().collect();
-    // 우선순위: CLI 포트 > config.ini 기본 포트 > 55555
-    let mut udp_cfg = read_udp_config_from_ini().unwrap_or(UdpCfg {
-        multicast_addr: "239.255.1.1".to_string(),
-        port: 55555,
-        interface_addr: "0.0.0.0".to_string(),
-    });
-    if args.len() >= 2 {
-        if let Ok(p) = args[1].parse::<u16>() { udp_cfg.port = p; }
+    // 우선순위: CLI 포트 > config.ini > 기본값
+    let mut config = SubscriberConfig::from_config_ini(Path::new("config/config.ini")).unwrap_or_default();
+    if let Some(arg) = std::env::args().nth(1) {
+        let ports: Vec<u16> = arg.split(',').filter_map(|p| p.trim().parse().ok()).collect();
+        if !ports.is_empty() { config.ports = ports; }
     }
 
-    println!(
-        "📡 멀티캐스트 그룹 {}:{} 수신 대기 중...",
-        udp_cfg.multicast_addr, udp_cfg.port
-    );
-
-    // UDP 소켓 생성 및 멀티캐스트 그룹 가입
-    let bind_addr = format!("0.0.0.0:{}", udp_cfg.port);
-    // reuseaddr를 설정하여 멀티캐스트 포트 공유 가능하도록 함 (Windows 호환)
-    let sock = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
-    sock.set_reuse_address(true)?;
-    sock.bind(&bind_addr.parse::<std::net::SocketAddr>()?.into())?;
-    let socket: UdpSocket = sock.into();
-    socket.join_multicast_v4(&udp_cfg.multicast_addr.parse()?, &udp_cfg.interface_addr.parse()?)?;
+    println!("📡 멀티캐스트 그룹 {} 포트 {:?} 수신 대기 중...", config.group, config.ports);
+    let subscriber = MulticastSubscriber::join(&config)?;
 
-    let mut buffer = [0u8; 1500]; // MTU 크기 버퍼
     let mut stats = Stats::new();
     let mut interval_map: HashMap<String, IntervalStat> = HashMap::new();
+    let mut gaps = GapDetector::new();
+    let mut assembler = MessageAssembler::new();
+    // 종목별 스케일: config.ini의 instrument_scales, 이후 종목 정의 수신 시 갱신
+    let mut scales = ScaleBook::from_config_ini(Path::new("config/config.ini")).unwrap_or_default();
     let start = Instant::now();
 
     println!("✅ 수신 준비 완료\n");
 
-    loop {
-        match socket.recv_from(&mut buffer) {
-            Ok((size, addr)) => {
-                println!("📦 패킷 수신: {} bytes from {}", size, addr);
-                stats.total_packets += 1;
-                stats.total_bytes += size as u64;
-
-                if let Err(e) = decode_packet(&buffer[..size], &mut stats, &mut interval_map) {
-                    eprintln!("❌ 디코딩 오류: {}", e);
+    while let Some(datagram) = subscriber.recv() {
+        println!("📦 패킷 수신: {} bytes from {} (port {})", datagram.data.len(), datagram.source, datagram.port);
+        stats.total_packets += 1;
+        stats.total_bytes += datagram.data.len() as u64;
+
+        match datagram.packet() {
+            Ok(packet) => {
+                match gaps.observe(datagram.port, packet.header.sequence_number()) {
+                    SequenceCheck::Gap { expected, received, missing } => {
+                        println!("⚠️ 시퀀스 누락: 예상 {} 수신 {} ({}개 누락)", expected, received, missing);
+                    }
+                    SequenceCheck::Stale { last, received } => println!("⚠️ 지난 시퀀스: 마지막 {} 수신 {}", last, received),
+                    SequenceCheck::Reset => println!("🔄 시퀀스 재시작 (피더 재시작 추정)"),
+                    SequenceCheck::First | SequenceCheck::InOrder => {}
                 }
-                if start.elapsed() >= Duration::from_secs(5) && stats.total_packets % 10 == 0 {
-                    println!(
-                        "📊 요약: pkts={} bytes={} avg={:.1}B ob_pkts={} ob_items={} tr_pkts={} tr_items={}",
-                        stats.total_packets, stats.total_bytes,
-                        (stats.total_bytes as f64 / stats.total_packets.max(1) as f64),
-                        stats.orderbook_packets, stats.orderbook_items,
-                        stats.tradetick_packets, stats.tradetick_items
-                    );
+                scales.learn(&packet.message());
+                print_packet(&packet, &scales, &mut stats, &mut interval_map);
+                if let Some(message) = assembler.push_from(datagram.port, &datagram.data) {
+                    if message.packets > 1 {
+                        println!("🧩 메시지 재조립 완료: {}개 패킷, {} bytes", message.packets, message.items.len());
+                    }
                 }
-                println!("{}", "─".repeat(80));
-            },
-            Err(e) => {
-                eprintln!("❌ 수신 오류: {}", e);
-                break;
             }
+            Err(e) => eprintln!("❌ 디코딩 오류: {}", e),
         }
+        if start.elapsed() >= Duration::from_secs(5) && stats.total_packets.is_multiple_of(10) {
+            println!(
+                "📊 요약: pkts={} bytes={} avg={:.1}B ob_pkts={} ob_items={} tr_pkts={} tr_items={} events={} gaps={} missing={}",
+                stats.total_packets, stats.total_bytes,
+                (stats.total_bytes as f64 / stats.total_packets.max(1) as f64),
+                stats.orderbook_packets, stats.orderbook_items,
+                stats.tradetick_packets, stats.tradetick_items, stats.event_packets,
+                gaps.gaps, gaps.missing
+            );
+        }
+        println!("{}", "─".repeat(80));
     }
 
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct UdpCfg {
-    multicast_addr: String,
-    port: u16,
-    interface_addr: String,
-}
-
-fn read_udp_config_from_ini() -> Result<UdpCfg, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string("config/config.ini")?;
-    let map = parse_simple_ini(&content);
-    let multicast_addr = map
-        .get("multicast_addr").cloned().unwrap_or_else(|| "239.255.1.1".to_string());
-    // 포트는 symbol_config에서 세션별로 관리되므로 여기서는 기본값만 사용
-    let port = 55555u16;
-    let interface_addr = map
-        .get("interface_addr").cloned().unwrap_or_else(|| "0.0.0.0".to_string());
-    Ok(UdpCfg { multicast_addr, port, interface_addr })
-}
-
-fn parse_simple_ini(content: &str) -> HashMap<String, String> {
-    let mut cfg = HashMap::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') || line.starts_with(';') { continue; }
-        if (line.starts_with('[') && line.ends_with(']')) { continue; }
-        if let Some((k, v)) = line.split_once('=') {
-            cfg.insert(k.trim().to_string(), v.trim().to_string());
-        }
-    }
-    cfg
-}
-
 #[derive(Debug, Clone)]
 struct IntervalStat {
     last: Option<Instant>,
@@ -221,61 +112,186 @@ impl IntervalStat {
     fn avg(&self) -> f64 { if self.count == 0 { 0.0 } else { self.sum_ms / self.count as f64 } }
 }
 
-fn decode_packet(data: &[u8], stats: &mut Stats, intervals: &mut HashMap<String, IntervalStat>) -> Result<(), Box<dyn std::error::Error>> {
-    if data.len() < mem::size_of::<PacketHeader>() {
-        return Err("패킷이 너무 작음".into());
+fn message_type_name(message_type: u8) -> &'static str {
+    match message_type {
+        0 => "OrderBook",
+        1 => "TradeTick",
+        2 => "IndexPrice",
+        3 => "MarkPrice",
+        4 => "FundingRate",
+        5 => "Liquidation",
+        6 => "TradeTickWithId",
+        7 => "Kline",
+        8 => "Ticker24h",
+        9 => "OpenInterest",
+        10 => "FundingInfo",
+        11 => "LiquidationDetail",
+        12 => "ConsolidatedBbo",
+        13 => "KimchiPremium",
+        14 => "TradeFlow",
+        50 => "InstrumentDefinition",
+        100 => "SystemHeartbeat",
+        101 => "ConnectionStatus",
+        102 => "SubscriptionStatus",
+        103 => "SystemStats",
+        104 => "ErrorEvent",
+        105 => "SystemShutdown",
+        _ => "Unknown",
     }
+}
 
-    // 헤더 디코딩
-    let header = unsafe {
-        std::ptr::read_unaligned(data.as_ptr() as *const PacketHeader)
+fn print_packet(packet: &PacketView, scales: &ScaleBook, stats: &mut Stats, intervals: &mut HashMap<String, IntervalStat>) {
+    let header = &packet.header;
+    // v2 헤더에는 문자열이 없으므로 ID로 표시
+    let (ex, sym) = if header.is_v2() {
+        (format!("venue#{}", header.venue_id()), format!("instrument#{}", header.instrument_id()))
+    } else {
+        (header.exchange().to_string(), header.symbol().to_string())
     };
 
-    // packed struct 필드 직접 접근 방지를 위해 로컬 변수에 복사
-    let protocol_version = header.protocol_version;
-    let sequence_number = header.sequence_number;
-    let message_type = header.message_type;
-    let exchange_timestamp = header.exchange_timestamp;
-    let local_timestamp = header.local_timestamp;
-    
     println!("📋 헤더 정보:");
-    println!("  - 프로토콜 버전: {}", protocol_version);
-    println!("  - 시퀀스 번호: {}", sequence_number);
-    let ex = header.exchange_as_string();
-    let sym = header.symbol_as_string();
+    println!("  - 프로토콜 버전: {}", header.protocol_version());
+    println!("  - 시퀀스 번호: {}{}", header.sequence_number(), if header.is_v2() { " (채널별)" } else { "" });
     println!("  - 거래소: {}", ex);
     println!("  - 심볼: {}", sym);
-    println!("  - 메시지 타입: {} ({})", message_type, 
-             match message_type {
-                 0 => "OrderBook",
-                 1 => "TradeTick",
-                 _ => "Unknown"
-             });
+    if header.is_v2() {
+        println!("  - 페이로드 길이: {} bytes", header.payload_length().unwrap_or(0));
+        println!("  - 플래그: snapshot={} replay={} crc={}", header.is_snapshot(), header.is_replay(),
+                 header.crc32().map(|c| format!("{:#010x} (검증됨)", c)).unwrap_or_else(|| "없음".to_string()));
+    }
+    println!("  - 메시지 타입: {} ({})", header.message_type(), message_type_name(header.message_type()));
     println!("  - 아이템 수: {}", header.item_count());
     println!("  - 마지막 패킷: {}", header.is_last());
-    println!("  - 거래소 타임스탬프: {} ns", exchange_timestamp);
-    println!("  - 로컬 타임스탬프: {} ns", local_timestamp);
+    if let Some(fragment) = packet.fragment {
+        println!("  - 분할: 메시지 #{} 조각 {}/{}", { fragment.message_id }, { fragment.fragment_index } + 1, { fragment.fragment_total });
+    }
+    if let Some(update_ids) = packet.update_ids {
+        println!("  - 업데이트 ID: {} ~ {}", { update_ids.first_update_id }, { update_ids.last_update_id });
+    }
+    println!("  - 거래소 타임스탬프: {} ns", header.exchange_timestamp());
+    println!("  - 로컬 타임스탬프: {} ns", header.local_timestamp());
 
-    // 페이로드 디코딩
-    let payload_start = mem::size_of::<PacketHeader>();
-    let payload = &data[payload_start..];
-    
-    match header.message_type {
-        0 => {
+    match packet.message_with(scales.for_header(header)) {
+        Message::OrderBook(items) => {
             stats.orderbook_packets += 1;
-            stats.orderbook_items += header.item_count() as u64;
-            decode_order_book_items(payload, header.item_count())?
-        },
-        1 => {
+            stats.orderbook_items += items.len() as u64;
+            println!("📈 오더북 데이터:");
+            for (i, item) in items.enumerate() {
+                println!("  #{}: ${:.8} x {:.8} [{}]", i + 1, item.price(), item.quantity(), if item.flag() { "ASK" } else { "BID" });
+            }
+        }
+        Message::Trade(items) => {
             stats.tradetick_packets += 1;
-            stats.tradetick_items += header.item_count() as u64;
-            decode_trade_tick_items(payload, header.item_count())?
-        },
-        _ => println!("⚠️ 알 수 없는 메시지 타입"),
+            stats.tradetick_items += items.len() as u64;
+            println!("💹 체결 데이터:");
+            for (i, item) in items.enumerate() {
+                let trade_id = item.trade_id().map(|id| format!(" id={} time={}", id, item.trade_time().unwrap_or(0))).unwrap_or_default();
+                println!("  #{}: ${:.8} x {:.8} [{}]{}", i + 1, item.price(), item.quantity(), if item.flag() { "BUY" } else { "SELL" }, trade_id);
+            }
+        }
+        Message::IndexPrice(v) | Message::MarkPrice(v) => println!("🏷️ 가격: {:.8}", v),
+        Message::FundingRate(v) => println!("💸 펀딩비: {:.8}", v),
+        Message::Liquidation(items) => {
+            for item in items {
+                println!("💥 청산: ${:.8} x {:.8} [{}]", item.price(), item.quantity(), if item.flag() { "SELL" } else { "BUY" });
+            }
+        }
+        Message::LiquidationDetail(l) => {
+            let status = match l.order_status {
+                1 => "NEW",
+                2 => "PARTIALLY_FILLED",
+                3 => "FILLED",
+                4 => "EXPIRED",
+                _ => "-",
+            };
+            println!("💥 청산: ${:.8} x {:.8} [{}] filled={:.8} status={} order_time={}",
+                     l.price, l.quantity, if l.is_sell { "SELL" } else { "BUY" }, l.filled_quantity, status,
+                     l.order_time.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string()));
+        }
+        Message::ConsolidatedBbo(b) => {
+            let side = |s: Option<crypto_feeder::consumer::BboSide>| s.map(|s| format!("${:.8} x {:.8} @{}{}",
+                s.price, s.quantity, s.venue_name().map(str::to_string).unwrap_or_else(|| format!("venue#{}", s.venue_id)),
+                if s.fx_converted { " (FX)" } else { "" })).unwrap_or_else(|| "-".to_string());
+            let state = if b.crossed { " [CROSSED]" } else if b.locked { " [LOCKED]" } else { "" };
+            println!("🧮 통합 호가: bid={} ask={} venues={}{}", side(b.bid), side(b.ask), b.venue_count, state);
+        }
+        Message::TradeFlow(windows) => {
+            for w in windows {
+                println!("🌊 체결 흐름 {}s: trades={} vwap={:.8} buy={:.8} sell={:.8} imbalance={:+.4}",
+                         w.window_secs, w.trade_count, w.vwap, w.buy_volume, w.sell_volume, w.imbalance);
+            }
+        }
+        Message::KimchiPremium(k) => {
+            println!("🇰🇷 김치 프리미엄: {:+.4}% krw={:.8} foreign={:.8}@{} fx={:.4} ({})",
+                     k.premium * 100.0, k.krw_price, k.foreign_price,
+                     k.foreign_venue_name().map(str::to_string).unwrap_or_else(|| format!("venue#{}", k.foreign_venue_id)),
+                     k.fx_rate, if k.market_fx { "market" } else { "fixed" });
+        }
+        Message::Kline(k) => {
+            println!("🕯️ 봉 {}s start={} O={:.8} H={:.8} L={:.8} C={:.8} vol={:.8} quote_vol={:.8} trades={} [{}]",
+                     k.interval_secs, k.start_time, k.open, k.high, k.low, k.close, k.volume, k.quote_volume, k.trade_count,
+                     if k.is_closed { "CLOSED" } else { "OPEN" });
+        }
+        Message::Ticker24h(t) => {
+            println!("📊 24h 티커: last={:.8} open={:.8} H={:.8} L={:.8} vol={:.8} quote_vol={:.8} change={:+.4}% trades={}",
+                     t.last_price, t.open_price, t.high_price, t.low_price, t.volume, t.quote_volume, t.price_change_percent, t.trade_count);
+        }
+        Message::FundingInfo(f) => {
+            println!("💸 펀딩 정보: rate={:.8} predicted={} next={} interval={}",
+                     f.funding_rate,
+                     f.predicted_rate.map(|v| format!("{:.8}", v)).unwrap_or_else(|| "-".to_string()),
+                     f.next_funding_time.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string()),
+                     f.interval_secs.map(|s| format!("{}s", s)).unwrap_or_else(|| "-".to_string()));
+        }
+        Message::OpenInterest(oi) => {
+            let optional = |v: Option<f64>| v.map(|v| format!("{:.8}", v)).unwrap_or_else(|| "-".to_string());
+            println!("📈 미결제약정: {:.8} value={} long/short={}", oi.open_interest, optional(oi.open_interest_value), optional(oi.long_short_ratio));
+        }
+        Message::InstrumentDefinitions(definitions) => {
+            println!("📚 종목 정의:");
+            for d in definitions {
+                let (instrument_id, venue_id) = (d.instrument_id, d.venue_id);
+                println!("  #{} {} {} (venue#{}) base={} quote={} type={} tick={} lot={} multiplier={}",
+                         instrument_id, d.exchange_str(), d.symbol_str(), venue_id, d.base_str(), d.quote_str(),
+                         market_type_name(d.market_type), d.tick_size_f64(), d.lot_size_f64(), d.contract_multiplier_f64());
+            }
+        }
+        Message::Heartbeat(h) => {
+            stats.event_packets += 1;
+            let (uptime, total) = (h.uptime_seconds, h.total_packets_sent);
+            let active = h.active_connections;
+            println!("💓 하트비트: uptime={}s active_connections={} total_packets={}", uptime, active, total);
+        }
+        Message::ConnectionStatus(c) => {
+            stats.event_packets += 1;
+            let (id, prev, cur, retry, code) = (c.exchange_id, c.previous_status, c.current_status, c.retry_count, c.error_code);
+            println!("🔌 연결 상태: exchange_id={} {} → {} retry={} error_code={}", id, prev, cur, retry, code);
+        }
+        Message::SubscriptionStatus(s) => {
+            stats.event_packets += 1;
+            let (id, ty, status) = (s.exchange_id, s.subscription_type, s.status);
+            println!("📬 구독 상태: exchange_id={} type={} status={} symbol={}", id, ty, status, sym);
+        }
+        Message::SystemStats(s) => {
+            stats.event_packets += 1;
+            let (cpu, mem, pps, bps) = (s.cpu_usage_percent, s.memory_usage_mb, s.packets_per_second, s.bytes_per_second);
+            println!("🖥️ 시스템 통계: cpu={}% mem={}MB pps={} bps={}", cpu, mem, pps, bps);
+        }
+        Message::Error(e) => {
+            stats.event_packets += 1;
+            let (ty, id, severity, details) = (e.error_type, e.exchange_id, e.severity, e.error_details);
+            println!("🚨 오류 이벤트: type={} exchange_id={} severity={} details={}", ty, id, severity, details);
+        }
+        Message::Shutdown(s) => {
+            stats.event_packets += 1;
+            let (uptime, total, reason) = (s.uptime_seconds, s.total_packets_sent, s.reason);
+            println!("🛑 피더 종료: uptime={}s total_packets={} reason={}", uptime, total, reason);
+        }
+        Message::Unknown(_) => println!("⚠️ 알 수 없는 메시지 타입"),
     }
 
     // 오더북 패킷 간격 측정 (BinanceFutures의 depth0ms 확인용 휴리스틱)
-    if message_type == 0 {
+    if header.message_type() == 0 {
         let key = format!("{}|{}", ex, sym);
         let entry = intervals.entry(key.clone()).or_insert(IntervalStat { last: None, count: 0, sum_ms: 0.0 });
         if let Some(dt) = entry.update(Instant::now()) {
@@ -288,56 +304,4 @@ fn decode_packet(data: &[u8], stats: &mut Stats, intervals: &mut HashMap<String,
             }
         }
     }
-
-    Ok(())
-}
-
-fn decode_order_book_items(payload: &[u8], count: u8) -> Result<(), Box<dyn std::error::Error>> {
-    println!("📈 오더북 데이터:");
-    
-    let item_size = mem::size_of::<OrderBookItem>();
-    
-    for i in 0..count {
-        let start = (i as usize) * item_size;
-        let end = start + item_size;
-        
-        if end > payload.len() {
-            return Err("페이로드가 너무 작음".into());
-        }
-        
-        let item = unsafe {
-            std::ptr::read_unaligned(payload[start..].as_ptr() as *const OrderBookItem)
-        };
-        
-        println!("  #{}: ${:.8} x {:.8}", 
-                 i + 1, item.get_real_price(), item.get_real_quantity());
-    }
-    
-    Ok(())
 }
-
-fn decode_trade_tick_items(payload: &[u8], count: u8) -> Result<(), Box<dyn std::error::Error>> {
-    println!("💹 체결 데이터:");
-    
-    let item_size = mem::size_of::<TradeTickItem>();
-    
-    for i in 0..count {
-        let start = (i as usize) * item_size;
-        let end = start + item_size;
-        
-        if end > payload.len() {
-            return Err("페이로드가 너무 작음".into());
-        }
-        
-        let item = unsafe {
-            std::ptr::read_unaligned(payload[start..].as_ptr() as *const TradeTickItem)
-        };
-        
-        let side = if item.is_buyer_taker() { "BUY" } else { "SELL" };
-        
-        println!("  #{}: ${:.8} x {:.8} [{}]", 
-                 i + 1, item.get_real_price(), item.get_real_quantity(), side);
-    }
-    
-    Ok(())
-}
\ No newline at end of file
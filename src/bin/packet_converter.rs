@@ -0,0 +1,116 @@
+//! 연구용 변환기
+//! UDP 캡처(.cap/.pcap) 또는 원시 WebSocket 저널(.wsj, 디렉터리)을 메시지 타입별 CSV/Parquet 테이블로 변환
+//!
+//! 사용법: packet-converter <입력>... --out <디렉터리> [--format parquet|csv] [--partition]
+//! - 저널 입력은 리플레이 결정적 모드(실시간과 같은 처리 단계, config.ini의 파생 데이터 설정 사용)로 패킷을 생성한 뒤 변환 (local_timestamp = 기록된 수신 시각)
+//! - --partition: `{out}/{table}/date=YYYYMMDD/exchange={거래소}/` 구조로 분할
+
+use crypto_feeder::capture::CaptureReader;
+use crypto_feeder::config::Config;
+use crypto_feeder::data_parser::DataParser;
+use crypto_feeder::consumer::{MessageAssembler, ScaleBook};
+use crypto_feeder::feed_export::{ExportFormat, TableExporter};
+use crypto_feeder::journal::{JournalReader, JOURNAL_EXTENSION};
+use crypto_feeder::packet_builder::PacketBuilder;
+use crypto_feeder::replay::{resolve_inputs, MergedJournal, ReplayOptions, ReplaySpeed, Replayer};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+struct Args {
+    inputs: Vec<PathBuf>,
+    out: PathBuf,
+    format: ExportFormat,
+    partition: bool,
+}
+
+fn parse_args() -> Result<Args, Box<dyn std::error::Error>> {
+    let mut args = Args { inputs: Vec::new(), out: PathBuf::new(), format: ExportFormat::Parquet, partition: false };
+    let mut out = None;
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("{} 값이 필요합니다", arg));
+        match arg.as_str() {
+            "--out" => out = Some(PathBuf::from(value()?)),
+            "--format" => {
+                let v = value()?;
+                args.format = ExportFormat::parse(&v).ok_or_else(|| format!("지원하지 않는 형식: '{}' (parquet, csv)", v))?;
+            }
+            "--partition" => args.partition = true,
+            other if other.starts_with("--") => return Err(format!("알 수 없는 옵션: {}", other).into()),
+            path => args.inputs.push(PathBuf::from(path)),
+        }
+    }
+    args.out = out.ok_or("--out 디렉터리를 지정하세요")?;
+    if args.inputs.is_empty() {
+        return Err("입력 파일을 지정하세요".into());
+    }
+    Ok(args)
+}
+
+fn is_journal(path: &Path) -> bool {
+    path.is_dir() || path.extension().map(|e| e == JOURNAL_EXTENSION).unwrap_or(false)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args()?;
+    println!("🧮 CryptoFeeder 변환기: {}개 입력 → {} ({:?}{})",
+             args.inputs.len(), args.out.display(), args.format, if args.partition { ", 날짜/거래소 파티션" } else { "" });
+
+    // 종목별 스케일: config.ini의 instrument_scales (입력에 종목 정의가 있으면 그 값 우선)
+    let scales = ScaleBook::from_config_ini(Path::new("config/config.ini")).unwrap_or_default();
+    let mut assembler = MessageAssembler::new();
+    let mut exporter = TableExporter::new(&args.out, args.format, args.partition).with_scales(scales.clone());
+
+    // 저널 입력: 수신 시각 순으로 병합하여 실시간 경로와 동일하게 패킷 생성
+    let journals: Vec<PathBuf> = args.inputs.iter().filter(|p| is_journal(p)).cloned().collect();
+    if !journals.is_empty() {
+        let files = resolve_inputs(&journals)?;
+        let readers = files.iter().map(|f| JournalReader::open(f)).collect::<Result<Vec<_>, _>>()?;
+        let options = ReplayOptions { inputs: files, speed: ReplaySpeed::AsFastAsPossible, deterministic: true };
+        let packet_builder = PacketBuilder::new().with_scales(scales.symbol_scales().clone());
+        let replayer = Replayer::new(Arc::new(Config::load()?), Arc::new(DataParser::new()), Arc::new(packet_builder), options);
+        let mut parse_skipped = 0u64;
+        for record in MergedJournal::new(readers) {
+            let Ok(packets) = replayer.packets_for(&record) else {
+                parse_skipped += 1;
+                continue;
+            };
+            for packet in packets {
+                if let Some(message) = assembler.push_from(record.port, &packet.data) {
+                    exporter.write_message(&message)?;
+                }
+            }
+        }
+        println!("📼 저널 {}개 처리 (파싱 제외 {}건)", journals.len(), parse_skipped);
+    }
+
+    // 캡처 입력: 기록된 데이터그램 그대로
+    for path in args.inputs.iter().filter(|p| !is_journal(p)) {
+        // 캡처 레코드의 목적지 포트를 채널로 사용
+        let mut count = 0u64;
+        for record in CaptureReader::open(path)? {
+            let record = match record {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("⚠️ {} 읽기 중단: {}", path.display(), e);
+                    break;
+                }
+            };
+            count += 1;
+            if let Some(message) = assembler.push_from(record.destination.port(), &record.data) {
+                exporter.write_message(&message)?;
+            }
+        }
+        println!("📦 {}: {}개 데이터그램", path.display(), count);
+    }
+
+    assembler.finish();
+    let stats = assembler.stats;
+    let tables = exporter.finish()?;
+    println!("📊 변환 완료: 패킷 {}, 메시지 {}, 미완성 {} (유실 조각 {}), 손상 {}, 알 수 없는 타입 {}",
+             stats.packets, stats.messages, stats.incomplete, stats.missing_fragments, stats.malformed, stats.skipped);
+    for (table, rows) in tables {
+        println!("   └─ {}: {}행", table, rows);
+    }
+    Ok(())
+}
@@ -0,0 +1,155 @@
+//! UDP 패킷 레코더
+//! 세션 포트의 멀티캐스트 그룹에 가입하여 소비자가 실제로 받은 데이터그램을 수신 시각과 함께 캡처 파일(.cap) 또는 pcap으로 기록
+//!
+//! 사용법: packet-recorder [--out <파일.cap|파일.pcap>] [--ports 55555,55556] [--duration <초>]
+//! - 포트 미지정 시 config.ini 기본 포트와 symbol_config.ini의 모든 세션 포트
+//! - Ctrl+C 또는 --duration 만료 시 버퍼를 비우고 종료
+
+use crypto_feeder::capture::{CaptureFormat, CaptureRecord, CaptureWriter};
+use crypto_feeder::config::Config;
+use crypto_feeder::journal::now_nanos;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+struct Args {
+    out: PathBuf,
+    ports: Vec<u16>,
+    duration: Option<Duration>,
+}
+
+fn parse_args(default_ports: Vec<u16>) -> Result<Args, Box<dyn std::error::Error>> {
+    let mut args = Args {
+        out: PathBuf::from(format!("captures/udp_{}.cap", chrono::Utc::now().format("%Y%m%d_%H%M%S"))),
+        ports: default_ports,
+        duration: None,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("{} 값이 필요합니다", arg));
+        match arg.as_str() {
+            "--out" => args.out = PathBuf::from(value()?),
+            "--ports" => {
+                args.ports = value()?.split(',').map(|p| p.trim().parse::<u16>()).collect::<Result<_, _>>()?;
+            }
+            "--duration" => args.duration = Some(Duration::from_secs(value()?.parse()?)),
+            other => return Err(format!("알 수 없는 옵션: {}", other).into()),
+        }
+    }
+    if args.ports.is_empty() {
+        return Err("수신할 포트가 없습니다".into());
+    }
+    Ok(args)
+}
+
+/// 멀티캐스트 그룹에 가입한 포트별 수신 소켓 (decoder와 동일하게 reuseaddr 설정)
+fn join_port(group: Ipv4Addr, interface: Ipv4Addr, port: u16) -> Result<UdpSocket, Box<dyn std::error::Error>> {
+    let sock = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    sock.set_reuse_address(true)?;
+    sock.set_recv_buffer_size(4 << 20).ok();
+    sock.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)).into())?;
+    sock.join_multicast_v4(&group, &interface)?;
+    sock.set_nonblocking(true)?;
+    Ok(UdpSocket::from_std(sock.into())?)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🎙️ CryptoFeeder 패킷 레코더 시작");
+
+    // 세션 포트는 symbol_config.ini 기준, 설정이 없으면 기본 포트만
+    let (group, interface, default_ports) = match Config::load() {
+        Ok(config) => {
+            let mut ports: BTreeSet<u16> = BTreeSet::from([config.udp.port]);
+            if let Some(symbol_config) = &config.symbol_config {
+                for name in symbol_config.get_exchange_names() {
+                    for session in symbol_config.get_exchange_sessions(&name).into_iter().flatten() {
+                        ports.insert(session.port);
+                    }
+                }
+            }
+            (config.udp.multicast_addr.parse()?, config.udp.interface_addr.parse()?, ports.into_iter().collect())
+        }
+        Err(e) => {
+            eprintln!("⚠️ 설정 로드 실패({}), 기본값 사용", e);
+            (Ipv4Addr::new(239, 255, 1, 1), Ipv4Addr::UNSPECIFIED, vec![55555])
+        }
+    };
+    let args = parse_args(default_ports)?;
+
+    if let Some(parent) = args.out.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let format = CaptureFormat::from_path(&args.out);
+    let mut writer = CaptureWriter::new(BufWriter::with_capacity(1 << 20, File::create(&args.out)?), format)?;
+    println!("📡 멀티캐스트 그룹 {} 포트 {:?} 수신 → {} ({:?})", group, args.ports, args.out.display(), format);
+
+    let (tx, mut rx) = mpsc::channel::<CaptureRecord>(65_536);
+    for port in &args.ports {
+        let socket = join_port(group, interface, *port)?;
+        let destination = SocketAddrV4::new(group, *port);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 65_536];
+            loop {
+                match socket.recv_from(&mut buf).await {
+                    Ok((size, from)) => {
+                        // 수신 직후 시각 기록
+                        let recv_timestamp_ns = now_nanos();
+                        let source = match from {
+                            SocketAddr::V4(v4) => v4,
+                            SocketAddr::V6(_) => SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, from.port()),
+                        };
+                        let record = CaptureRecord { recv_timestamp_ns, source, destination, data: buf[..size].to_vec() };
+                        if tx.send(record).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => eprintln!("❌ 포트 {} 수신 오류: {}", destination.port(), e),
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let deadline = async {
+        match args.duration {
+            Some(d) => tokio::time::sleep(d).await,
+            None => std::future::pending::<()>().await,
+        }
+    };
+    tokio::pin!(deadline);
+
+    println!("✅ 기록 중 (Ctrl+C로 종료)\n");
+    let started = Instant::now();
+    let (mut packets, mut bytes) = (0u64, 0u64);
+    loop {
+        tokio::select! {
+            record = rx.recv() => {
+                let Some(record) = record else { break };
+                bytes += record.data.len() as u64;
+                packets += 1;
+                writer.write(&record)?;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+            _ = &mut deadline => break,
+        }
+    }
+    // 이미 수신된 데이터그램까지 기록
+    while let Ok(record) = rx.try_recv() {
+        bytes += record.data.len() as u64;
+        packets += 1;
+        writer.write(&record)?;
+    }
+    writer.flush()?;
+
+    println!("\n📊 기록 완료: {}개 패킷, {} bytes, {:.1}초 → {}",
+             packets, bytes, started.elapsed().as_secs_f64(), args.out.display());
+    Ok(())
+}
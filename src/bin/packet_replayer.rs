@@ -0,0 +1,129 @@
+//! UDP 패킷 리플레이어
+//! packet-recorder 캡처(.cap) 또는 pcap 파일의 데이터그램을 원래 수신 간격(또는 배속)으로 멀티캐스트에 재전송
+//!
+//! 사용법: packet-replayer <캡처 파일> [--speed max|realtime|<N>x] [--exchange A,B] [--symbol BTC^USDT,..]
+//!                         [--type 1,2] [--group 239.255.1.1] [--interface 0.0.0.0] [--ttl 1]
+//! - 목적지 포트는 캡처된 포트를 그대로 사용, 그룹은 --group 지정 시 변경
+//! - 같은 호스트의 소비자도 받을 수 있도록 멀티캐스트 루프백 활성화
+//! - v2 패킷의 거래소/심볼 필터는 피더 설정(symbol_config.ini, reference_id_file)으로 venue_id/instrument_id를 구해 비교
+
+use crypto_feeder::capture::{CaptureReader, PacketFilter};
+use crypto_feeder::config::Config;
+use crypto_feeder::instruments::InstrumentRegistry;
+use crypto_feeder::replay::ReplaySpeed;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::time::Instant;
+
+struct Args {
+    input: PathBuf,
+    speed: ReplaySpeed,
+    filter: PacketFilter,
+    group: Option<Ipv4Addr>,
+    interface: Ipv4Addr,
+    ttl: u32,
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+}
+
+fn parse_args() -> Result<Args, Box<dyn std::error::Error>> {
+    let mut input = None;
+    let mut args = Args {
+        input: PathBuf::new(),
+        speed: ReplaySpeed::Realtime,
+        filter: PacketFilter::default(),
+        group: None,
+        interface: Ipv4Addr::UNSPECIFIED,
+        ttl: 1,
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("{} 값이 필요합니다", arg));
+        match arg.as_str() {
+            "--speed" => {
+                let v = value()?;
+                args.speed = ReplaySpeed::parse(&v).ok_or_else(|| format!("잘못된 재생 속도: '{}' (max, realtime, <N>x)", v))?;
+            }
+            "--exchange" => args.filter.exchanges = split_list(&value()?),
+            "--symbol" => args.filter.symbols = split_list(&value()?),
+            "--type" => {
+                args.filter.message_types = split_list(&value()?).iter().map(|t| t.parse::<u8>()).collect::<Result<_, _>>()?;
+            }
+            "--group" => args.group = Some(value()?.parse()?),
+            "--interface" => args.interface = value()?.parse()?,
+            "--ttl" => args.ttl = value()?.parse()?,
+            other if other.starts_with("--") => return Err(format!("알 수 없는 옵션: {}", other).into()),
+            path => input = Some(PathBuf::from(path)),
+        }
+    }
+    args.input = input.ok_or("캡처 파일을 지정하세요")?;
+    Ok(args)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = parse_args()?;
+    println!("⏪ CryptoFeeder 패킷 리플레이어: {} (속도 {:?})", args.input.display(), args.speed);
+    if !args.filter.is_empty() {
+        println!("🔎 필터: exchange={:?} symbol={:?} type={:?}", args.filter.exchanges, args.filter.symbols, args.filter.message_types);
+        // v2 헤더 비교용 ID (피더와 같은 설정/ID 파일 기준, 설정이 없으면 고정 venue_id만 사용)
+        let registry = match Config::load() {
+            Ok(config) => match &config.reference.id_file {
+                Some(path) => InstrumentRegistry::from_config_with_id_file_read_only(&config, std::path::Path::new(path))?,
+                None => InstrumentRegistry::from_config(&config),
+            },
+            Err(_) => InstrumentRegistry::new(),
+        };
+        let unresolved = args.filter.resolve_ids(&registry);
+        if !unresolved.is_empty() {
+            eprintln!("⚠️ ID를 찾을 수 없어 v2 패킷에는 일치하지 않는 필터 값: {:?}", unresolved);
+        }
+    }
+
+    let sock = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    sock.bind(&SocketAddr::from((args.interface, 0)).into())?;
+    sock.set_multicast_ttl_v4(args.ttl)?;
+    sock.set_multicast_loop_v4(true)?;
+    if !args.interface.is_unspecified() {
+        sock.set_multicast_if_v4(&args.interface)?;
+    }
+    let socket: UdpSocket = sock.into();
+
+    let started = Instant::now();
+    let mut first_ts = None;
+    let (mut sent, mut skipped, mut bytes) = (0u64, 0u64, 0u64);
+    for record in CaptureReader::open(&args.input)? {
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("⚠️ 캡처 읽기 중단: {}", e);
+                break;
+            }
+        };
+        if !args.filter.matches(&record.data) {
+            skipped += 1;
+            continue;
+        }
+        // 원래 수신 간격 유지 (필터 통과 첫 패킷 기준)
+        let first = *first_ts.get_or_insert(record.recv_timestamp_ns);
+        if let Some(offset) = args.speed.scaled(record.recv_timestamp_ns.saturating_sub(first)) {
+            if let Some(wait) = offset.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+        let target = SocketAddr::from((args.group.unwrap_or(*record.destination.ip()), record.destination.port()));
+        match socket.send_to(&record.data, target) {
+            Ok(n) => {
+                sent += 1;
+                bytes += n as u64;
+            }
+            Err(e) => eprintln!("❌ 전송 실패 ({}): {}", target, e),
+        }
+    }
+
+    println!("📊 리플레이 완료: 전송 {}개 ({} bytes), 필터 제외 {}개, {:.1}초",
+             sent, bytes, skipped, started.elapsed().as_secs_f64());
+    Ok(())
+}
@@ -1,85 +1,27 @@
-use socket2::{Domain, Type, Protocol, Socket};
+use crypto_feeder::consumer::{HeaderView, MulticastSubscriber, SubscriberConfig};
 use std::collections::HashMap;
-use std::fs;
-use std::net::UdpSocket;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
-struct UdpCfg {
-	multicast_addr: String,
-	port: u16,
-	interface_addr: String,
-}
-
-fn read_udp_config_from_ini() -> Result<UdpCfg, Box<dyn std::error::Error>> {
-	let content = fs::read_to_string("config/config.ini")?;
-	let map = parse_simple_ini(&content);
-	let multicast_addr = map
-		.get("multicast_addr").cloned().unwrap_or_else(|| "239.255.1.1".to_string());
-	// 기본 포트는 55555 (실제 세션 포트는 symbol_config.ini에서 지정)
-	let port = 55555u16;
-	let interface_addr = map
-		.get("interface_addr").cloned().unwrap_or_else(|| "0.0.0.0".to_string());
-	Ok(UdpCfg { multicast_addr, port, interface_addr })
-}
-
-fn parse_simple_ini(content: &str) -> HashMap<String, String> {
-	let mut cfg = HashMap::new();
-	for line in content.lines() {
-		let line = line.trim();
-		if line.is_empty() || line.starts_with('#') || line.starts_with(';') { continue; }
-		if line.starts_with('[') && line.ends_with(']') { continue; }
-		if let Some((k, v)) = line.split_once('=') {
-			cfg.insert(k.trim().to_string(), v.trim().to_string());
-		}
-	}
-	cfg
-}
-
-#[repr(C, packed)]
-#[derive(Copy, Clone)]
-struct PacketHeader {
-	protocol_version: u8,
-	sequence_number: u64,
-	exchange_timestamp: u64,
-	local_timestamp: u64,
-	message_type: u8,
-	flags_and_count: u8,
-	symbol: [u8; 20],
-	exchange: [u8; 20],
-}
-
 fn main() -> Result<(), Box<dyn std::error::Error>> {
 	println!("🔎 UDP 패킷 카운터 시작 (30초)");
-	let mut cfg = read_udp_config_from_ini()?;
+	let mut cfg = SubscriberConfig::from_config_ini(Path::new("config/config.ini"))?;
 	let args: Vec<String> = std::env::args().collect();
-	if args.len() >= 2 { if let Ok(p) = args[1].parse::<u16>() { cfg.port = p; } }
-	println!("📡 수신: {}:{} (iface {})", cfg.multicast_addr, cfg.port, cfg.interface_addr);
+	if args.len() >= 2 { if let Ok(p) = args[1].parse::<u16>() { cfg.ports = vec![p]; } }
+	println!("📡 수신: {}:{:?} (iface {})", cfg.group, cfg.ports, cfg.interface);
 
-	let bind_addr = format!("0.0.0.0:{}", cfg.port);
-	let sock = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
-	sock.set_reuse_address(true)?;
-	sock.bind(&bind_addr.parse::<std::net::SocketAddr>()?.into())?;
-	let socket: UdpSocket = sock.into();
-	socket.join_multicast_v4(&cfg.multicast_addr.parse()?, &cfg.interface_addr.parse()?)?;
-	socket.set_read_timeout(Some(Duration::from_millis(250)))?;
+	let subscriber = MulticastSubscriber::join(&cfg)?;
 
 	let mut counts: HashMap<u8, u64> = HashMap::new();
 	let mut total: u64 = 0;
 	let deadline = Instant::now() + Duration::from_secs(30);
-	let mut buf = [0u8; 1500];
 
-	while Instant::now() < deadline {
-		match socket.recv(&mut buf) {
-			Ok(n) if n >= std::mem::size_of::<PacketHeader>() => {
-				let header = unsafe { std::ptr::read_unaligned(buf.as_ptr() as *const PacketHeader) };
-				let mt = header.message_type;
-				*counts.entry(mt).or_insert(0) += 1;
-				total += 1;
-			}
-			Ok(_) => { /* too small, ignore */ }
-			Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => { /* spin */ }
-			Err(e) => { eprintln!("recv 오류: {}", e); }
+	while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+		let Some(datagram) = subscriber.recv_timeout(remaining.min(Duration::from_millis(250))) else { continue };
+		// 헤더보다 짧은 패킷은 무시
+		if let Ok(header) = HeaderView::new(&datagram.data) {
+			*counts.entry(header.message_type()).or_insert(0) += 1;
+			total += 1;
 		}
 	}
 
@@ -104,5 +46,3 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 	Ok(())
 }
-
-
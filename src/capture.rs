@@ -0,0 +1,431 @@
+//! UDP 출력 캡처 파일
+//! 소비자가 실제로 받은 멀티캐스트 데이터그램을 수신 시각과 함께 기록/재생하기 위한 형식 (packet-recorder, packet-replayer)
+//!
+//! 지원 형식 (Little Endian):
+//! - 기본 캡처(`.cap`): 헤더 `CFCAP001` | 레코드 `body_len: u32` | `recv_timestamp_ns: u64`
+//!   | `src_ip: [u8; 4]` | `src_port: u16` | `dst_ip: [u8; 4]` | `dst_port: u16` | datagram
+//! - pcap(`.pcap`): 나노초 해상도 libpcap, Ethernet/IPv4/UDP 헤더를 합성하여 Wireshark/tcpdump로 열람 가능
+//!   (읽기 시 마이크로초 해상도 pcap도 지원)
+
+use crate::errors::{CryptoFeederError, Result};
+use crate::consumer::HeaderView;
+use crate::instruments::InstrumentRegistry;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::path::Path;
+
+/// 기본 캡처 파일 매직 헤더
+pub const CAPTURE_MAGIC: &[u8; 8] = b"CFCAP001";
+/// pcap 매직 (나노초 / 마이크로초 해상도)
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_LINKTYPE_ETHERNET: u32 = 1;
+const PCAP_SNAPLEN: u32 = 65_535;
+const ETHERNET_HEADER_LEN: usize = 14;
+const IPV4_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+
+/// 캡처된 데이터그램 한 건
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    pub recv_timestamp_ns: u64,
+    pub source: SocketAddrV4,
+    /// 멀티캐스트 그룹 + 세션 포트
+    pub destination: SocketAddrV4,
+    pub data: Vec<u8>,
+}
+
+/// 캡처 파일 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    Native,
+    Pcap,
+}
+
+impl CaptureFormat {
+    /// 확장자로 형식 결정 (`.pcap`이면 pcap, 그 외 기본 형식)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("pcap") => Self::Pcap,
+            _ => Self::Native,
+        }
+    }
+}
+
+fn io_err(context: &str, e: std::io::Error) -> CryptoFeederError {
+    CryptoFeederError::Other(format!("{}: {}", context, e))
+}
+
+/// 캡처 파일 기록기
+pub struct CaptureWriter<W: Write> {
+    out: W,
+    format: CaptureFormat,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// 파일 헤더를 기록하고 기록기 생성
+    pub fn new(mut out: W, format: CaptureFormat) -> Result<Self> {
+        match format {
+            CaptureFormat::Native => out.write_all(CAPTURE_MAGIC),
+            CaptureFormat::Pcap => {
+                let mut header = Vec::with_capacity(24);
+                header.extend_from_slice(&PCAP_MAGIC_NANOS.to_le_bytes());
+                header.extend_from_slice(&2u16.to_le_bytes());
+                header.extend_from_slice(&4u16.to_le_bytes());
+                header.extend_from_slice(&0i32.to_le_bytes());
+                header.extend_from_slice(&0u32.to_le_bytes());
+                header.extend_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+                header.extend_from_slice(&PCAP_LINKTYPE_ETHERNET.to_le_bytes());
+                out.write_all(&header)
+            }
+        }.map_err(|e| io_err("캡처 헤더 쓰기 실패", e))?;
+        Ok(Self { out, format, buf: Vec::with_capacity(2048) })
+    }
+
+    /// 데이터그램 한 건 기록
+    pub fn write(&mut self, record: &CaptureRecord) -> Result<()> {
+        self.buf.clear();
+        match self.format {
+            CaptureFormat::Native => {
+                let body_len = 8 + 6 + 6 + record.data.len();
+                self.buf.extend_from_slice(&(body_len as u32).to_le_bytes());
+                self.buf.extend_from_slice(&record.recv_timestamp_ns.to_le_bytes());
+                for addr in [record.source, record.destination] {
+                    self.buf.extend_from_slice(&addr.ip().octets());
+                    self.buf.extend_from_slice(&addr.port().to_le_bytes());
+                }
+                self.buf.extend_from_slice(&record.data);
+            }
+            CaptureFormat::Pcap => {
+                let frame_len = ETHERNET_HEADER_LEN + IPV4_HEADER_LEN + UDP_HEADER_LEN + record.data.len();
+                self.buf.extend_from_slice(&((record.recv_timestamp_ns / 1_000_000_000) as u32).to_le_bytes());
+                self.buf.extend_from_slice(&((record.recv_timestamp_ns % 1_000_000_000) as u32).to_le_bytes());
+                self.buf.extend_from_slice(&(frame_len as u32).to_le_bytes());
+                self.buf.extend_from_slice(&(frame_len as u32).to_le_bytes());
+                append_ethernet_frame(&mut self.buf, record);
+            }
+        }
+        self.out.write_all(&self.buf).map_err(|e| io_err("캡처 쓰기 실패", e))
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush().map_err(|e| io_err("캡처 flush 실패", e))
+    }
+}
+
+/// 멀티캐스트 목적지에 대한 Ethernet/IPv4/UDP 프레임 합성 (네트워크 바이트 순서)
+fn append_ethernet_frame(out: &mut Vec<u8>, record: &CaptureRecord) {
+    let dst = record.destination.ip().octets();
+    // 멀티캐스트 MAC: 01:00:5e + 그룹 주소 하위 23비트
+    out.extend_from_slice(&[0x01, 0x00, 0x5e, dst[1] & 0x7f, dst[2], dst[3]]);
+    out.extend_from_slice(&[0u8; 6]);
+    out.extend_from_slice(&0x0800u16.to_be_bytes());
+
+    let udp_len = (UDP_HEADER_LEN + record.data.len()) as u16;
+    let mut ip = [0u8; IPV4_HEADER_LEN];
+    ip[0] = 0x45;
+    ip[2..4].copy_from_slice(&(IPV4_HEADER_LEN as u16 + udp_len).to_be_bytes());
+    ip[6] = 0x40; // DF
+    ip[8] = 1; // TTL
+    ip[9] = 17; // UDP
+    ip[12..16].copy_from_slice(&record.source.ip().octets());
+    ip[16..20].copy_from_slice(&dst);
+    let checksum = ipv4_checksum(&ip);
+    ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+    out.extend_from_slice(&ip);
+
+    out.extend_from_slice(&record.source.port().to_be_bytes());
+    out.extend_from_slice(&record.destination.port().to_be_bytes());
+    out.extend_from_slice(&udp_len.to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes()); // UDP 체크섬 생략 (IPv4에서 허용)
+    out.extend_from_slice(&record.data);
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]]) as u32).sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// 캡처 파일 순차 읽기 (형식 자동 감지)
+pub struct CaptureReader<R: Read> {
+    input: R,
+    kind: ReaderKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ReaderKind {
+    Native,
+    Pcap { nanos: bool },
+}
+
+impl CaptureReader<std::io::BufReader<std::fs::File>> {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(|e| io_err(&format!("캡처 파일 열기 실패 ({})", path.display()), e))?;
+        Self::new(std::io::BufReader::new(file))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic).map_err(|e| io_err("캡처 헤더 읽기 실패", e))?;
+        if &magic == CAPTURE_MAGIC {
+            return Ok(Self { input, kind: ReaderKind::Native });
+        }
+        let pcap_magic = u32::from_le_bytes(magic[0..4].try_into().unwrap());
+        let nanos = match pcap_magic {
+            PCAP_MAGIC_NANOS => true,
+            PCAP_MAGIC_MICROS => false,
+            _ => return Err(CryptoFeederError::SerializationError("지원하지 않는 캡처 형식 (매직 불일치)".to_string())),
+        };
+        let mut rest = [0u8; 16];
+        input.read_exact(&mut rest).map_err(|e| io_err("pcap 헤더 읽기 실패", e))?;
+        let linktype = u32::from_le_bytes(rest[12..16].try_into().unwrap());
+        if linktype != PCAP_LINKTYPE_ETHERNET {
+            return Err(CryptoFeederError::SerializationError(format!("지원하지 않는 pcap 링크 타입: {}", linktype)));
+        }
+        Ok(Self { input, kind: ReaderKind::Pcap { nanos } })
+    }
+
+    /// 다음 블록 읽기 (파일 끝이면 None)
+    fn read_block(&mut self, header_len: usize, len_at: usize) -> Option<Result<(Vec<u8>, Vec<u8>)>> {
+        let mut header = vec![0u8; header_len];
+        match self.input.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(io_err("캡처 읽기 실패", e))),
+        }
+        let len = u32::from_le_bytes(header[len_at..len_at + 4].try_into().unwrap()) as usize;
+        let mut body = vec![0u8; len];
+        if let Err(e) = self.input.read_exact(&mut body) {
+            return Some(Err(CryptoFeederError::SerializationError(format!("캡처 레코드가 잘렸습니다: {}", e))));
+        }
+        Some(Ok((header, body)))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.kind {
+                ReaderKind::Native => {
+                    let (_, body) = match self.read_block(4, 0)? {
+                        Ok(block) => block,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    if body.len() < 20 {
+                        return Some(Err(CryptoFeederError::SerializationError(format!("캡처 레코드 손상 ({} bytes)", body.len()))));
+                    }
+                    let addr = |at: usize| SocketAddrV4::new(
+                        Ipv4Addr::new(body[at], body[at + 1], body[at + 2], body[at + 3]),
+                        u16::from_le_bytes([body[at + 4], body[at + 5]]),
+                    );
+                    return Some(Ok(CaptureRecord {
+                        recv_timestamp_ns: u64::from_le_bytes(body[0..8].try_into().unwrap()),
+                        source: addr(8),
+                        destination: addr(14),
+                        data: body[20..].to_vec(),
+                    }));
+                }
+                ReaderKind::Pcap { nanos } => {
+                    let (header, frame) = match self.read_block(16, 8)? {
+                        Ok(block) => block,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    let secs = u32::from_le_bytes(header[0..4].try_into().unwrap()) as u64;
+                    let frac = u32::from_le_bytes(header[4..8].try_into().unwrap()) as u64;
+                    let recv_timestamp_ns = secs * 1_000_000_000 + if nanos { frac } else { frac * 1_000 };
+                    // UDP/IPv4가 아닌 프레임은 건너뜀
+                    if let Some(record) = parse_udp_frame(&frame, recv_timestamp_ns) {
+                        return Some(Ok(record));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Ethernet/IPv4/UDP 프레임에서 데이터그램 추출
+fn parse_udp_frame(frame: &[u8], recv_timestamp_ns: u64) -> Option<CaptureRecord> {
+    if frame.len() < ETHERNET_HEADER_LEN + IPV4_HEADER_LEN + UDP_HEADER_LEN
+        || u16::from_be_bytes([frame[12], frame[13]]) != 0x0800 {
+        return None;
+    }
+    let ip = &frame[ETHERNET_HEADER_LEN..];
+    let ihl = (ip[0] & 0x0f) as usize * 4;
+    if ip[0] >> 4 != 4 || ip[9] != 17 || ihl < IPV4_HEADER_LEN || ip.len() < ihl + UDP_HEADER_LEN {
+        return None;
+    }
+    let udp = &ip[ihl..];
+    let udp_len = (u16::from_be_bytes([udp[4], udp[5]]) as usize).clamp(UDP_HEADER_LEN, udp.len());
+    Some(CaptureRecord {
+        recv_timestamp_ns,
+        source: SocketAddrV4::new(Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]), u16::from_be_bytes([udp[0], udp[1]])),
+        destination: SocketAddrV4::new(Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]), u16::from_be_bytes([udp[2], udp[3]])),
+        data: udp[UDP_HEADER_LEN..udp_len].to_vec(),
+    })
+}
+
+/// 패킷 헤더 기준 필터 (목록이 비어 있으면 해당 조건은 모두 통과)
+///
+/// v2 헤더는 거래소/심볼 대신 venue_id/instrument_id를 담으므로, `resolve_ids`로 이름을 ID로 바꾼 뒤 비교합니다.
+#[derive(Debug, Clone, Default)]
+pub struct PacketFilter {
+    pub exchanges: Vec<String>,
+    pub symbols: Vec<String>,
+    pub message_types: Vec<u8>,
+    /// v2 헤더 비교용 (`exchanges`에 대응하는 venue_id)
+    pub venue_ids: Vec<u16>,
+    /// v2 헤더 비교용 (`symbols`에 대응하는 instrument_id)
+    pub instrument_ids: Vec<u32>,
+}
+
+impl PacketFilter {
+    pub fn is_empty(&self) -> bool {
+        self.exchanges.is_empty() && self.symbols.is_empty() && self.message_types.is_empty()
+    }
+
+    /// 거래소/심볼 이름을 레지스트리의 venue_id/instrument_id로 변환 (레지스트리에 없는 이름 목록 반환)
+    pub fn resolve_ids(&mut self, registry: &InstrumentRegistry) -> Vec<String> {
+        let mut unresolved = Vec::new();
+        let (venues, instruments) = (registry.venues(), registry.instruments());
+        self.venue_ids = self.exchanges.iter()
+            .filter_map(|name| {
+                let id = venues.iter().find(|(_, v)| v.eq_ignore_ascii_case(name)).map(|(id, _)| *id);
+                if id.is_none() {
+                    unresolved.push(name.clone());
+                }
+                id
+            })
+            .collect();
+        self.instrument_ids = self.symbols.iter()
+            .filter_map(|name| {
+                let id = instruments.iter().find(|(_, s)| s.eq_ignore_ascii_case(name)).map(|(id, _)| *id);
+                if id.is_none() {
+                    unresolved.push(name.clone());
+                }
+                id
+            })
+            .collect();
+        unresolved
+    }
+
+    /// 데이터그램이 필터 조건을 만족하는지 확인 (헤더보다 짧은 패킷은 필터가 없을 때만 통과)
+    pub fn matches(&self, datagram: &[u8]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let Ok(header) = HeaderView::new(datagram) else {
+            return false;
+        };
+        let (exchange_ok, symbol_ok) = if header.is_v2() {
+            (self.venue_ids.contains(&header.venue_id()), self.instrument_ids.contains(&header.instrument_id()))
+        } else {
+            (self.exchanges.iter().any(|e| e.eq_ignore_ascii_case(header.exchange())), self.symbols.iter().any(|s| s.eq_ignore_ascii_case(header.symbol())))
+        };
+        (self.message_types.is_empty() || self.message_types.contains(&header.message_type()))
+            && (self.exchanges.is_empty() || exchange_ok)
+            && (self.symbols.is_empty() || symbol_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{PacketHeader, MESSAGE_TYPE_TRADE_TICK};
+
+    fn packet(symbol: &str, exchange: &str, message_type: u8) -> Vec<u8> {
+        let mut header = PacketHeader::new();
+        header.message_type = message_type;
+        header.set_symbol(symbol);
+        header.set_exchange(exchange);
+        let mut bytes = header.to_bytes();
+        bytes.extend_from_slice(&[0xAB; 16]);
+        bytes
+    }
+
+    fn record(ts: u64, port: u16, data: Vec<u8>) -> CaptureRecord {
+        CaptureRecord {
+            recv_timestamp_ns: ts,
+            source: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 7), 40000),
+            destination: SocketAddrV4::new(Ipv4Addr::new(239, 255, 1, 1), port),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_native_and_pcap_roundtrip() {
+        let records = vec![
+            record(1_700_000_000_123_456_789, 55555, packet("BTC^USDT", "BinanceSpot", MESSAGE_TYPE_TRADE_TICK)),
+            record(1_700_000_000_223_456_789, 55556, vec![1, 2, 3]),
+        ];
+        for format in [CaptureFormat::Native, CaptureFormat::Pcap] {
+            let mut writer = CaptureWriter::new(Vec::new(), format).unwrap();
+            for r in &records {
+                writer.write(r).unwrap();
+            }
+            let bytes = writer.out;
+            let read: Vec<CaptureRecord> = CaptureReader::new(&bytes[..]).unwrap().map(|r| r.unwrap()).collect();
+            assert_eq!(read, records, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_pcap_ip_header_checksum_verifies() {
+        let mut writer = CaptureWriter::new(Vec::new(), CaptureFormat::Pcap).unwrap();
+        writer.write(&record(1, 55555, vec![0; 10])).unwrap();
+        let ip = &writer.out[24 + 16 + ETHERNET_HEADER_LEN..][..IPV4_HEADER_LEN];
+        assert_eq!(ipv4_checksum(ip), 0);
+        assert_eq!(&writer.out[24 + 16..24 + 16 + 3], &[0x01, 0x00, 0x5e]);
+    }
+
+    #[test]
+    fn test_filter_by_exchange_symbol_and_type() {
+        let pkt = packet("BTC^USDT", "BinanceSpot", MESSAGE_TYPE_TRADE_TICK);
+        assert!(PacketFilter::default().matches(&pkt));
+        assert!(PacketFilter { exchanges: vec!["binancespot".into()], ..Default::default() }.matches(&pkt));
+        assert!(!PacketFilter { exchanges: vec!["OkxSpot".into()], ..Default::default() }.matches(&pkt));
+        assert!(PacketFilter { symbols: vec!["BTC^USDT".into()], message_types: vec![MESSAGE_TYPE_TRADE_TICK], ..Default::default() }.matches(&pkt));
+        assert!(!PacketFilter { message_types: vec![MESSAGE_TYPE_TRADE_TICK + 1], ..Default::default() }.matches(&pkt));
+        assert!(!PacketFilter { symbols: vec!["BTC^USDT".into()], ..Default::default() }.matches(&[0u8; 4]));
+    }
+
+    #[test]
+    fn test_filter_v2_by_registry_ids() {
+        use crate::protocol::{PacketHeaderV2, PROTOCOL_VERSION_V2};
+        let registry = InstrumentRegistry::new();
+        let v2 = |exchange: &str, symbol: &str| PacketHeaderV2 {
+            protocol_version: PROTOCOL_VERSION_V2,
+            message_type: MESSAGE_TYPE_TRADE_TICK,
+            flags: 0,
+            item_count: 1,
+            venue_id: registry.venue_id(exchange),
+            payload_length: 0,
+            instrument_id: registry.instrument_id(symbol),
+            crc32: 0,
+            sequence_number: 1,
+            exchange_timestamp: 0,
+            local_timestamp: 0,
+        }.encode_packet(&[0xAB; 16]).unwrap();
+        let (btc, eth) = (v2("BinanceSpot", "BTC^USDT"), v2("OkxSpot", "ETH^USDT"));
+
+        let mut filter = PacketFilter { exchanges: vec!["binancespot".into()], symbols: vec!["BTC^USDT".into(), "SOL^USDT".into()], ..Default::default() };
+        // 해석 전에는 v2 패킷이 거래소/심볼 조건을 통과하지 못함
+        assert!(!filter.matches(&btc));
+        assert_eq!(filter.resolve_ids(&registry), vec!["SOL^USDT".to_string()]);
+        assert!(filter.matches(&btc));
+        assert!(!filter.matches(&eth));
+        // v1 패킷은 이름으로 비교
+        assert!(filter.matches(&packet("BTC^USDT", "BinanceSpot", MESSAGE_TYPE_TRADE_TICK)));
+
+        let mut filter = PacketFilter { exchanges: vec!["OkxSpot".into()], message_types: vec![MESSAGE_TYPE_TRADE_TICK], ..Default::default() };
+        assert!(filter.resolve_ids(&registry).is_empty());
+        assert!(filter.matches(&eth) && !filter.matches(&btc));
+    }
+}
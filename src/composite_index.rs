@@ -0,0 +1,245 @@
+//! 자체 인덱스 가격(composite index) 계산기
+//! index_config.ini에 정의한 심볼마다 여러 거래소의 중간 가격/마지막 체결가를 가중 중앙값 또는 가중 평균으로 합성
+//!
+//! - `stale_ms`보다 오래 갱신되지 않은 거래소 가격은 제외
+//! - `outlier_pct`를 주면 1차 가중 중앙값에서 그 비율보다 벗어난 거래소를 제외한 뒤 다시 계산
+//! - 남은 거래소가 `min_venues`보다 적으면 발행하지 않음 (거래소 하나가 끊겨도 나머지로 계속 발행)
+
+use crate::config::{IndexConfig, IndexDefinition, IndexMethod, IndexPriceSource};
+use crate::data_parser::{ParsedData, StandardizedTrade};
+use crate::venue_book::VenueBook;
+use std::collections::HashMap;
+
+/// 인덱스 하나의 계산 결과
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexValue {
+    pub symbol: String,
+    /// 패킷 헤더 거래소 (`CfIndex`)
+    pub exchange: String,
+    pub price: f64,
+    /// 계산에 포함된 거래소 수 (이상치 제외 후)
+    pub venue_count: usize,
+    /// 계산 시각 (나노초)
+    pub timestamp: u64,
+}
+
+impl IndexValue {
+    /// 기존 인덱스 가격(message_type 2) 패킷 경로로 보내기 위한 변환
+    pub fn to_parsed(&self) -> ParsedData {
+        ParsedData::IndexPrice {
+            symbol: self.symbol.clone(),
+            exchange: self.exchange.clone(),
+            value: self.price,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// 자체 인덱스 계산기
+pub struct CompositeIndex {
+    config: IndexConfig,
+    stale_ns: u64,
+    /// (거래소, 심볼)별 호가창
+    books: HashMap<(String, String), VenueBook>,
+    /// (거래소, 심볼)별 마지막 체결가와 수신 시각
+    last_trades: HashMap<(String, String), (f64, u64)>,
+}
+
+impl CompositeIndex {
+    pub fn new(config: &IndexConfig) -> Self {
+        Self {
+            config: config.clone(),
+            stale_ns: config.stale_ms * 1_000_000,
+            books: HashMap::new(),
+            last_trades: HashMap::new(),
+        }
+    }
+
+    /// 파싱된 데이터의 오더북/체결 반영 (`now_ns`는 수신 시각)
+    pub fn ingest(&mut self, data: &ParsedData, now_ns: u64) {
+        match data {
+            ParsedData::OrderBook(update) if self.is_tracked(&update.exchange, &update.symbol, IndexPriceSource::Mid) => {
+                self.books.entry((update.exchange.clone(), update.symbol.clone())).or_default().apply(update, now_ns);
+            }
+            ParsedData::Trade(trade) => self.on_trade(trade, now_ns),
+            ParsedData::TradeBatch(batch) => {
+                if let Some(trade) = batch.trades.last() {
+                    self.on_trade(trade, now_ns);
+                }
+            }
+            ParsedData::Multi(items) => {
+                for item in items {
+                    self.ingest(item, now_ns);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 스냅샷을 기다리는 호가창인지 (추적하지 않는 (거래소, 심볼)이면 false)
+    pub fn awaits_snapshot(&self, exchange: &str, symbol: &str) -> bool {
+        self.books.get(&(exchange.to_string(), symbol.to_string())).is_some_and(|book| !book.is_synced())
+    }
+
+    fn on_trade(&mut self, trade: &StandardizedTrade, now_ns: u64) {
+        if self.is_tracked(&trade.exchange, &trade.symbol, IndexPriceSource::Last) && trade.price.is_finite() && trade.price > 0.0 {
+            self.last_trades.insert((trade.exchange.clone(), trade.symbol.clone()), (trade.price, now_ns));
+        }
+    }
+
+    /// 해당 가격 출처를 쓰는 인덱스의 구성 거래소인지
+    fn is_tracked(&self, exchange: &str, symbol: &str, source: IndexPriceSource) -> bool {
+        self.config.indices.iter().any(|index| index.symbol == symbol && index.source == source
+            && index.venues.iter().any(|venue| venue.exchange == exchange))
+    }
+
+    /// 거래소의 유효한 가격 (오래되었거나 없으면 None)
+    fn venue_price(&self, index: &IndexDefinition, exchange: &str, now_ns: u64) -> Option<f64> {
+        let key = (exchange.to_string(), index.symbol.clone());
+        match index.source {
+            IndexPriceSource::Mid => {
+                let book = self.books.get(&key)?;
+                if book.is_stale(now_ns, self.stale_ns) { None } else { book.mid() }
+            }
+            IndexPriceSource::Last => {
+                let &(price, received_at) = self.last_trades.get(&key)?;
+                (now_ns.saturating_sub(received_at) <= self.stale_ns).then_some(price)
+            }
+        }
+    }
+
+    /// 인덱스 하나 계산 (남은 거래소가 min_venues보다 적으면 None)
+    pub fn compute(&self, index: &IndexDefinition, now_ns: u64) -> Option<IndexValue> {
+        let mut prices: Vec<(f64, f64)> = index.venues.iter()
+            .filter_map(|venue| self.venue_price(index, &venue.exchange, now_ns).map(|price| (price, venue.weight)))
+            .collect();
+        if index.outlier_pct > 0.0 && prices.len() > 2 {
+            let median = weighted_median(&mut prices)?;
+            prices.retain(|(price, _)| ((price / median) - 1.0).abs() * 100.0 <= index.outlier_pct);
+        }
+        if prices.len() < index.min_venues {
+            return None;
+        }
+        let price = match index.method {
+            IndexMethod::Median => weighted_median(&mut prices)?,
+            IndexMethod::Mean => weighted_mean(&prices)?,
+        };
+        Some(IndexValue {
+            symbol: index.symbol.clone(),
+            exchange: self.config.exchange.clone(),
+            price,
+            venue_count: prices.len(),
+            timestamp: now_ns,
+        })
+    }
+
+    /// 모든 인덱스의 현재 값 (발행 조건을 만족하는 것만)
+    pub fn flush(&self, now_ns: u64) -> Vec<IndexValue> {
+        self.config.indices.iter().filter_map(|index| self.compute(index, now_ns)).collect()
+    }
+}
+
+/// (가격, 가중치) 목록의 가중 중앙값 (누적 가중치가 정확히 절반이면 양쪽 가격의 평균)
+fn weighted_median(prices: &mut [(f64, f64)]) -> Option<f64> {
+    prices.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let total: f64 = prices.iter().map(|(_, w)| w).sum();
+    let mut cumulative = 0.0;
+    for (i, &(price, weight)) in prices.iter().enumerate() {
+        cumulative += weight;
+        if cumulative * 2.0 > total {
+            return Some(price);
+        }
+        if cumulative * 2.0 == total {
+            return Some(prices.get(i + 1).map_or(price, |next| (price + next.0) / 2.0));
+        }
+    }
+    None
+}
+
+/// (가격, 가중치) 목록의 가중 평균
+fn weighted_mean(prices: &[(f64, f64)]) -> Option<f64> {
+    let total: f64 = prices.iter().map(|(_, w)| w).sum();
+    (total > 0.0).then(|| prices.iter().map(|(p, w)| p * w).sum::<f64>() / total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venue_book::test_books::snapshot;
+
+    const MS: u64 = 1_000_000;
+
+    fn trade(exchange: &str, price: f64) -> ParsedData {
+        ParsedData::Trade(StandardizedTrade {
+            symbol: "ETH^USDT".into(),
+            exchange: exchange.into(),
+            price,
+            quantity: 1.0,
+            is_buyer_taker: true,
+            timestamp: 0,
+            trade_id: None,
+        })
+    }
+
+    const CONFIG: &str = "
+[settings]
+port=56000
+stale_ms=1000
+
+[BTC^USDT]
+method=median
+outlier_pct=1
+min_venues=2
+venues=BinanceSpot:2, OkxSpot:1, BybitSpot:1
+
+[ETH^USDT]
+method=mean
+source=last
+venues=BinanceSpot:3, OkxSpot
+";
+
+    #[test]
+    fn test_parses_index_config() {
+        let config = IndexConfig::parse(CONFIG).unwrap();
+        assert_eq!((config.port, config.interval_ms, config.stale_ms, config.exchange.as_str()), (56000, 1000, 1000, "CfIndex"));
+        assert_eq!(config.indices.len(), 2);
+        let eth = &config.indices[1];
+        assert_eq!((eth.method, eth.source, eth.min_venues), (IndexMethod::Mean, IndexPriceSource::Last, 1));
+        assert_eq!(eth.venues[1].weight, 1.0);
+
+        assert!(IndexConfig::parse("[BTC^USDT]\nmethod=vwap\nvenues=BinanceSpot").is_err());
+        assert!(IndexConfig::parse("[BTC^USDT]\nvenues=BinanceSpot:0").is_err());
+        assert!(IndexConfig::parse("[BTC^USDT]\nmethod=mean").is_err());
+    }
+
+    #[test]
+    fn test_weighted_median_with_outlier_and_stale() {
+        let mut index = CompositeIndex::new(&IndexConfig::parse(CONFIG).unwrap());
+        index.ingest(&snapshot("BinanceSpot", "BTC^USDT", &[(99.0, 1.0)], &[(101.0, 1.0)]), 0);
+        index.ingest(&snapshot("OkxSpot", "BTC^USDT", &[(100.0, 1.0)], &[(102.0, 1.0)]), 0);
+        // 이상치 (중앙값 대비 1% 초과)
+        index.ingest(&snapshot("BybitSpot", "BTC^USDT", &[(109.0, 1.0)], &[(111.0, 1.0)]), 0);
+
+        let value = &index.flush(0)[0];
+        assert_eq!((value.symbol.as_str(), value.exchange.as_str()), ("BTC^USDT", "CfIndex"));
+        // Binance(2) + Okx(1) 중 누적 가중치 절반은 Binance
+        assert_eq!((value.price, value.venue_count), (100.0, 2));
+
+        // Okx만 갱신되면 나머지는 오래되어 min_venues 미달
+        index.ingest(&snapshot("OkxSpot", "BTC^USDT", &[(100.0, 1.0)], &[(102.0, 1.0)]), 2000 * MS);
+        assert!(index.compute(&index.config.indices[0].clone(), 2000 * MS).is_none());
+    }
+
+    #[test]
+    fn test_weighted_mean_of_last_trades() {
+        let mut index = CompositeIndex::new(&IndexConfig::parse(CONFIG).unwrap());
+        index.ingest(&trade("BinanceSpot", 10.0), 0);
+        index.ingest(&ParsedData::Multi(vec![trade("OkxSpot", 14.0)]), 0);
+        // 구성 거래소가 아니면 무시
+        index.ingest(&trade("BybitSpot", 100.0), 0);
+
+        let value = index.flush(0).pop().unwrap();
+        assert_eq!((value.symbol.as_str(), value.price, value.venue_count), ("ETH^USDT", 11.0, 2));
+        assert!(matches!(value.to_parsed(), ParsedData::IndexPrice { value, .. } if value == 11.0));
+    }
+}
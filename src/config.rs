@@ -1,9 +1,11 @@
 use crate::errors::Result;
+use crate::protocol::InstrumentScale;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::env;
+use std::net::IpAddr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -15,6 +17,26 @@ pub struct Config {
     pub metrics: MetricsConfig,
     pub symbol_config: Option<SymbolConfig>,
     pub endpoint_config: Option<EndpointConfig>,
+    /// 자체 인덱스 가격 설정 (index_config.ini, 파일이 없으면 None)
+    pub index_config: Option<IndexConfig>,
+    /// symbol_config.ini/endpoint.ini 변경 감지 주기 (0이면 SIGHUP으로만 재로드)
+    pub config_watch_interval_secs: u64,
+    /// 세션 명령(심볼 추가/제거/이동)을 받는 127.0.0.1 UDP 포트 (0이면 끔)
+    pub control_port: u16,
+    pub journal: JournalConfig,
+    pub protocol: ProtocolConfig,
+    pub reference: ReferenceDataConfig,
+    /// 종목(표준 심볼)별 가격/수량 스케일 지수 (없으면 10^8)
+    pub instrument_scales: HashMap<String, InstrumentScale>,
+    /// 체결 기반 로컬 봉 길이 목록 (초, `klines=local` 세션용)
+    pub bar_intervals: Vec<u32>,
+    /// 24시간 티커 병합 전송 주기 (ms, 0이면 업데이트마다 전송)
+    pub ticker_interval_ms: u64,
+    /// REST 전용 거래소(Binance 선물) 미결제약정 조회 주기 (초, 0이면 조회 안 함)
+    pub open_interest_poll_secs: u64,
+    pub consolidated_bbo: ConsolidatedBboConfig,
+    pub kimchi_premium: KimchiPremiumConfig,
+    pub trade_flow: TradeFlowConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +54,24 @@ pub struct UdpConfig {
     // port는 하위호환 목적의 필드입니다. 실제 전송 포트는 symbol_config.ini의 세션별 포트를 사용합니다.
     pub port: u16,
     pub interface_addr: String,
+    /// 송신 인터페이스 MTU (패킷 크기 상한 = MTU - IPv4/UDP 헤더 28바이트)
+    pub mtu: usize,
+}
+
+impl UdpConfig {
+    /// 일반 이더넷 MTU
+    pub const DEFAULT_MTU: usize = 1500;
+    /// IPv4 최소 재조립 보장 크기
+    pub const MIN_MTU: usize = 576;
+    /// 점보 프레임 상한
+    pub const MAX_MTU: usize = 9216;
+    /// IPv4(20) + UDP(8) 헤더
+    const IP_UDP_OVERHEAD: usize = 28;
+
+    /// IP 단편화 없이 보낼 수 있는 UDP 페이로드 크기 (MTU 1500이면 1472)
+    pub fn max_datagram_size(&self) -> usize {
+        self.mtu.saturating_sub(Self::IP_UDP_OVERHEAD)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +86,214 @@ pub struct MetricsConfig {
     pub interval_secs: u64,
 }
 
+/// 원시 WebSocket 메시지 저널 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalConfig {
+    pub enabled: bool,
+    /// 저널 루트 디렉터리 (하위에 YYYYMMDD 디렉터리 생성)
+    pub dir: String,
+    /// 파일당 최대 크기 (초과 시 다음 순번 파일로 교체)
+    pub max_file_bytes: u64,
+    /// 수신 루프와 기록 쓰레드 사이 큐 크기 (가득 차면 드롭)
+    pub queue_capacity: usize,
+}
+
+/// 종목 참조 데이터(InstrumentDefinition) 발행 설정
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceDataConfig {
+    pub enabled: bool,
+    /// 참조 데이터 전용 멀티캐스트 포트
+    pub port: u16,
+    /// 전체 종목 정의 재전송 주기
+    pub interval_secs: u64,
+    /// exchangeInfo 형식의 거래소별 메타데이터 JSON 파일
+    pub metadata_file: Option<String>,
+    /// 시작 시 거래소 REST API에서 메타데이터 조회 (지원 거래소만)
+    pub fetch: bool,
+    /// instrument_id 영속화 파일 (재시작/심볼 추가 후에도 같은 ID 유지)
+    pub id_file: Option<String>,
+}
+
+impl Default for ReferenceDataConfig {
+    fn default() -> Self {
+        Self { enabled: false, port: 55600, interval_secs: 30, metadata_file: None, fetch: false, id_file: None }
+    }
+}
+
+/// 거래소 통합 최우선 호가(consolidated BBO) 발행 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedBboConfig {
+    /// 통합 호가 전용 멀티캐스트 포트 (0이면 비활성)
+    pub port: u16,
+    /// 바뀐 심볼의 통합 호가 전송 주기
+    pub interval_ms: u64,
+    /// 이보다 오래 갱신되지 않은 거래소 호가는 통합에서 제외
+    pub stale_ms: u64,
+    /// KRW 환산 환율 (1 fx_quote = N KRW, None이면 KRW는 KRW끼리만 통합)
+    pub krw_rate: Option<f64>,
+    /// KRW 호가를 환산해 합칠 quote 통화
+    pub fx_quote: String,
+    /// 통합 대상 거래소 (비어 있으면 오더북을 받는 전체 거래소)
+    pub exchanges: Vec<String>,
+}
+
+impl Default for ConsolidatedBboConfig {
+    fn default() -> Self {
+        Self { port: 0, interval_ms: 100, stale_ms: 5000, krw_rate: None, fx_quote: "USDT".to_string(), exchanges: Vec::new() }
+    }
+}
+
+/// KRW 환율 출처
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FxRateSource {
+    /// 고정 환율 (1 foreign_quote = N KRW)
+    Fixed(f64),
+    /// 거래소 시장의 중간 가격 (예: UpbitSpot `USDT^KRW`)
+    Market { exchange: String, symbol: String },
+}
+
+impl FxRateSource {
+    /// `1390.5` 또는 `거래소:심볼`(예: `UpbitSpot:USDT^KRW`) 파싱
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let value = value.trim();
+        if let Some((exchange, symbol)) = value.split_once(':') {
+            if exchange.is_empty() || !symbol.contains('^') {
+                return Err(format!("kimchi_fx_rate 값 오류 '{}' (예: UpbitSpot:USDT^KRW)", value));
+            }
+            return Ok(Self::Market { exchange: exchange.to_string(), symbol: symbol.to_string() });
+        }
+        value.parse::<f64>().ok()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .map(Self::Fixed)
+            .ok_or_else(|| format!("kimchi_fx_rate 값 오류 '{}' (예: 1390.5)", value))
+    }
+}
+
+/// 김치 프리미엄(KRW 거래소와 해외 거래소 가격 차이) 발행 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KimchiPremiumConfig {
+    /// 김치 프리미엄 전용 멀티캐스트 포트 (0이면 비활성)
+    pub port: u16,
+    /// 전송 주기
+    pub interval_ms: u64,
+    /// 이보다 오래 갱신되지 않은 호가(환율 시장 포함)는 계산에서 제외
+    pub stale_ms: u64,
+    pub fx_rate: FxRateSource,
+    /// `X^KRW` 호가를 받을 거래소
+    pub krw_exchanges: Vec<String>,
+    /// 비교할 해외 거래소 (앞에 있는 거래소부터 사용)
+    pub foreign_exchanges: Vec<String>,
+    /// 해외 거래소의 quote 통화
+    pub foreign_quote: String,
+}
+
+impl Default for KimchiPremiumConfig {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            interval_ms: 1000,
+            stale_ms: 5000,
+            fx_rate: FxRateSource::Market { exchange: "UpbitSpot".to_string(), symbol: "USDT^KRW".to_string() },
+            krw_exchanges: vec!["UpbitSpot".to_string(), "BithumbSpot".to_string()],
+            foreign_exchanges: vec!["BinanceSpot".to_string()],
+            foreign_quote: "USDT".to_string(),
+        }
+    }
+}
+
+/// 체결 흐름 분석(VWAP, 매수/매도 체결량, 체결 수, 불균형) 발행 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeFlowConfig {
+    /// 체결 흐름 전용 멀티캐스트 포트 (0이면 비활성)
+    pub port: u16,
+    /// 전송 주기
+    pub interval_ms: u64,
+    /// 집계 구간 (초, 오름차순)
+    pub windows_secs: Vec<u32>,
+}
+
+impl Default for TradeFlowConfig {
+    fn default() -> Self {
+        Self { port: 0, interval_ms: 1000, windows_secs: vec![1, 10, 60] }
+    }
+}
+
+/// 포트별 UDP 프로토콜 버전 설정
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolConfig {
+    /// 포트별 지정이 없을 때의 버전 (1 또는 2)
+    pub default_version: u8,
+    /// 포트별 버전 재정의
+    pub port_versions: HashMap<u16, u8>,
+    /// v2 헤더에 CRC32 포함 여부
+    pub v2_crc: bool,
+    /// 체결을 체결 ID/체결 시각 포함 형식(message_type 6)으로 전송 (v1/v2 공통)
+    pub trade_ids: bool,
+    /// 펀딩비를 예상 펀딩비/다음 정산 시각/정산 주기 포함 형식(message_type 10)으로 전송 (v1/v2 공통)
+    pub funding_info: bool,
+    /// 청산을 주문 상태/누적 체결 수량/주문 시각 포함 형식(message_type 11)으로 전송 (v1/v2 공통)
+    pub liquidation_detail: bool,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self { default_version: 1, port_versions: HashMap::new(), v2_crc: false, trade_ids: false, funding_info: false, liquidation_detail: false }
+    }
+}
+
+impl ProtocolConfig {
+    /// config.ini의 protocol_version / protocol_port_versions(`포트:버전, ...`) / protocol_v2_crc / protocol_trade_ids / protocol_funding_info / protocol_liquidation_detail 적용
+    pub fn from_ini(ini_map: &HashMap<String, String>) -> Result<Self> {
+        let parse_version = |value: &str| -> Result<u8> {
+            match value.trim() {
+                "1" => Ok(1),
+                "2" => Ok(2),
+                other => Err(crate::errors::CryptoFeederError::Other(format!("지원하지 않는 프로토콜 버전: '{}' (1, 2)", other))),
+            }
+        };
+        let mut config = Self::default();
+        if let Some(v) = ini_map.get("protocol_version") {
+            config.default_version = parse_version(v)?;
+        }
+        if let Some(list) = ini_map.get("protocol_port_versions") {
+            for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (port, version) = entry.split_once(':')
+                    .ok_or_else(|| crate::errors::CryptoFeederError::Other(format!("protocol_port_versions 항목 형식 오류: '{}' (포트:버전)", entry)))?;
+                let port = port.trim().parse::<u16>()
+                    .map_err(|_| crate::errors::CryptoFeederError::Other(format!("protocol_port_versions 포트 오류: '{}'", port)))?;
+                config.port_versions.insert(port, parse_version(version)?);
+            }
+        }
+        if let Some(v) = ini_map.get("protocol_v2_crc") {
+            config.v2_crc = v.eq_ignore_ascii_case("true") || v == "1";
+        }
+        if let Some(v) = ini_map.get("protocol_trade_ids") {
+            config.trade_ids = v.eq_ignore_ascii_case("true") || v == "1";
+        }
+        if let Some(v) = ini_map.get("protocol_funding_info") {
+            config.funding_info = v.eq_ignore_ascii_case("true") || v == "1";
+        }
+        if let Some(v) = ini_map.get("protocol_liquidation_detail") {
+            config.liquidation_detail = v.eq_ignore_ascii_case("true") || v == "1";
+        }
+        Ok(config)
+    }
+
+    pub fn version_for(&self, port: u16) -> u8 {
+        self.port_versions.get(&port).copied().unwrap_or(self.default_version)
+    }
+
+    /// v2를 사용하는 포트가 하나라도 있는지
+    pub fn uses_v2(&self) -> bool {
+        self.default_version == 2 || self.port_versions.values().any(|v| *v == 2)
+    }
+
+    /// 모든 포트가 v2인지 (v1 item_count 한도 없이 MTU 기준으로 패킷 분할 가능)
+    pub fn all_v2(&self) -> bool {
+        self.default_version == 2 && self.port_versions.values().all(|v| *v == 2)
+    }
+}
+
 /// 심볼 설정 전체 구조체
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolConfig {
@@ -60,11 +308,105 @@ pub struct ExchangeSymbolGroup {
 }
 
 /// 세션별 심볼 목록 (WebSocket 연결 단위)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SymbolSession {
     pub symbols: Vec<String>,
     pub is_btc_session: bool,
     pub port: u16,
+    /// 세션 부가 스트림 (세션 라인의 `| klines=..., ticker=on`, 기본 off)
+    #[serde(default)]
+    pub streams: SessionStreams,
+}
+
+/// 테스트용 세션 (부가 스트림 없음, BTC 단독 세션 여부는 설정 파싱과 같은 규칙)
+#[cfg(test)]
+pub(crate) fn test_session(port: u16, symbols: &[&str]) -> SymbolSession {
+    let symbols: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+    let is_btc_session = symbols.len() == 1 && symbols[0].starts_with("BTC^");
+    SymbolSession { symbols, is_btc_session, port, streams: SessionStreams::default() }
+}
+
+/// 세션별로 켜는 부가 스트림 (체결/오더북 외)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SessionStreams {
+    /// OHLCV 봉 소스
+    pub klines: KlineSource,
+    /// 24시간 통계 티커 구독 (Binance `@ticker`, OKX `tickers`, Bybit `tickers`)
+    pub ticker: bool,
+    /// 미결제약정 (OKX `open-interest`, Bybit 파생상품 `tickers`, Binance는 REST 조회)
+    pub open_interest: bool,
+    /// 펀딩 정보 (OKX `funding-rate`, Bybit 파생상품 `tickers`, Binance 선물은 markPrice 스트림에 항상 포함)
+    pub funding: bool,
+    /// 청산 구독 범위 (Binance 선물은 꺼져 있어도 심볼별 `@forceOrder`를 항상 구독)
+    pub liquidations: LiquidationScope,
+}
+
+impl SessionStreams {
+    /// 심볼 하나당 추가로 구독하는 거래소 토픽 수
+    pub fn extra_topics(&self) -> usize {
+        usize::from(self.klines.subscribes_exchange()) + usize::from(self.ticker)
+    }
+
+    /// 심볼과 무관하게 연결마다 한 번 구독하는 전체 시장 토픽 수
+    /// (Binance 선물 `!forceOrder@arr`, OKX 무기한 `liquidation-orders`)
+    pub fn market_wide_topics(&self, exchange_name: &str) -> usize {
+        match (exchange_name, self.liquidations) {
+            ("BinanceFutures", LiquidationScope::All) => 1,
+            ("OkxSwap", LiquidationScope::Symbols | LiquidationScope::All) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// 세션의 청산 구독 범위
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LiquidationScope {
+    /// 청산 추가 구독 안 함
+    #[default]
+    Off,
+    /// 세션 심볼의 청산만 전송 (Bybit `allLiquidation`, OKX는 `liquidation-orders`를 세션 심볼로 거름)
+    Symbols,
+    /// 전체 시장 청산 전송 (Binance 선물 `!forceOrder@arr`, OKX `liquidation-orders`). 심볼 없는 전용 세션 가능
+    All,
+}
+
+impl LiquidationScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" => Some(Self::Off),
+            "on" | "true" => Some(Self::Symbols),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+/// 세션의 OHLCV 봉 소스
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum KlineSource {
+    /// 봉 전송 안 함
+    #[default]
+    Off,
+    /// 거래소 1분봉 스트림 구독 (Binance `@kline_1m`, Bybit `kline.1`, OKX는 미지원)
+    Exchange,
+    /// 체결 스트림을 `bar_intervals` 길이로 로컬 집계
+    Local,
+}
+
+impl KlineSource {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(Self::Off),
+            "exchange" => Some(Self::Exchange),
+            "local" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    /// 거래소 kline 스트림을 구독하는지
+    pub fn subscribes_exchange(&self) -> bool {
+        *self == Self::Exchange
+    }
 }
 
 /// 엔드포인트 설정 전체 구조체
@@ -74,13 +416,27 @@ pub struct EndpointConfig {
 }
 
 /// 거래소별 엔드포인트 설정
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExchangeEndpoint {
     pub exchange_name: String,
     pub ws_url_base: String,
     pub timeout_ms: u64,
     pub ping_interval_ms: Option<u64>,
     pub enabled: bool,
+    /// 연결당 최대 스트림 수 (미지정 시 어댑터 기본값)
+    pub max_streams_per_connection: Option<usize>,
+    /// 연결당 최대 구독 수 (미지정 시 어댑터 기본값)
+    pub max_subscriptions_per_connection: Option<usize>,
+    /// 최대 WebSocket URL 길이 (미지정 시 어댑터 기본값)
+    pub max_url_length: Option<usize>,
+    /// 아웃바운드 프록시 (`http://host:port` 또는 `socks5://host:port`)
+    pub proxy: Option<String>,
+    /// 송신 IP 목록 (여러 개면 세션별로 순환 배정)
+    pub bind_addrs: Vec<IpAddr>,
+    /// DNS 재정의 (`host:ip`)
+    pub resolve: Vec<(String, IpAddr)>,
+    /// REST API 기본 URL 재정의 (예: 로컬 스텁 `http://127.0.0.1:8080`, 미지정 시 거래소 기본값)
+    pub rest_url_base: Option<String>,
 }
 
 impl Config {
@@ -99,6 +455,14 @@ impl Config {
             multicast_addr: ini_map.get("multicast_addr").cloned().unwrap_or_else(|| "239.255.1.1".to_string()),
             port: ini_map.get("port").and_then(|p| p.parse::<u16>().ok()).unwrap_or(55555),
             interface_addr: ini_map.get("interface_addr").cloned().unwrap_or_else(|| "0.0.0.0".to_string()),
+            mtu: match ini_map.get("mtu").filter(|v| !v.is_empty()) {
+                // 잘못된 값은 시작 실패 (패킷 크기가 네트워크와 맞지 않으면 IP 단편화/드롭 발생)
+                Some(v) => v.parse::<usize>().ok()
+                    .filter(|mtu| (UdpConfig::MIN_MTU..=UdpConfig::MAX_MTU).contains(mtu))
+                    .ok_or_else(|| crate::errors::CryptoFeederError::Other(format!(
+                        "잘못된 mtu 값: '{}' ({}~{})", v, UdpConfig::MIN_MTU, UdpConfig::MAX_MTU)))?,
+                None => UdpConfig::DEFAULT_MTU,
+            },
         };
 
         // 런타임 쓰레드 수 (0 또는 미지정이면 런타임 기본값 사용)
@@ -116,12 +480,124 @@ impl Config {
             file_path: ini_map.get("log_file_path").cloned(),
         };
 
+        // 설정 파일 변경 감지 주기 (기본 0 = 폴링 비활성, SIGHUP만 사용)
+        let config_watch_interval_secs = ini_map.get("config_watch_interval_secs").and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+        // 세션 명령 수신 포트 (기본 0 = 끔)
+        let control_port = ini_map.get("control_port").and_then(|v| v.parse::<u16>().ok()).unwrap_or(0);
+
+        // 원시 메시지 저널 설정 (기본 비활성)
+        let journal = JournalConfig {
+            enabled: ini_map.get("journal_enabled").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false),
+            dir: ini_map.get("journal_dir").cloned().unwrap_or_else(|| "journal".to_string()),
+            max_file_bytes: ini_map.get("journal_max_file_mb").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(512) * 1024 * 1024,
+            queue_capacity: ini_map.get("journal_queue_capacity").and_then(|v| v.parse::<usize>().ok()).filter(|v| *v > 0).unwrap_or(65536),
+        };
+
+        // 포트별 프로토콜 버전 (기본 v1, 잘못된 값은 시작 실패)
+        let protocol = ProtocolConfig::from_ini(&ini_map)?;
+
+        // 종목 참조 데이터 발행 (기본 비활성)
+        let defaults = ReferenceDataConfig::default();
+        let reference = ReferenceDataConfig {
+            enabled: ini_map.get("reference_enabled").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false),
+            port: ini_map.get("reference_port").and_then(|v| v.parse::<u16>().ok()).unwrap_or(defaults.port),
+            interval_secs: ini_map.get("reference_interval_secs").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.interval_secs),
+            metadata_file: ini_map.get("reference_metadata_file").filter(|v| !v.is_empty()).cloned(),
+            fetch: ini_map.get("reference_fetch").map(|v| v.eq_ignore_ascii_case("true") || v == "1").unwrap_or(false),
+            id_file: ini_map.get("reference_id_file").filter(|v| !v.is_empty()).cloned(),
+        };
+
+        // 종목별 스케일 지수 (instrument_scales=심볼:가격지수:수량지수, ..., 잘못된 값은 시작 실패)
+        let instrument_scales = ini_map.get("instrument_scales")
+            .map(|v| InstrumentScale::parse_list(v))
+            .transpose()
+            .map_err(crate::errors::CryptoFeederError::Other)?
+            .unwrap_or_default();
+
+        // 로컬 봉 길이 (bar_intervals=1m, 5m, ..., 잘못된 값은 시작 실패)
+        let bar_intervals = match ini_map.get("bar_intervals").filter(|v| !v.is_empty()) {
+            Some(v) => crate::bar_aggregator::parse_interval_list(v).map_err(crate::errors::CryptoFeederError::Other)?,
+            None => vec![60],
+        };
+
+        // 티커 병합 주기 (기본 1초)
+        let ticker_interval_ms = ini_map.get("ticker_interval_ms")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(1000);
+
+        // 미결제약정 REST 조회 주기 (기본 30초)
+        let open_interest_poll_secs = ini_map.get("open_interest_poll_secs")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30);
+
+        // 거래소 통합 최우선 호가 (기본 비활성, 잘못된 환율은 시작 실패)
+        let defaults = ConsolidatedBboConfig::default();
+        let consolidated_bbo = ConsolidatedBboConfig {
+            port: ini_map.get("consolidated_bbo_port").and_then(|v| v.parse::<u16>().ok()).unwrap_or(defaults.port),
+            interval_ms: ini_map.get("consolidated_bbo_interval_ms").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.interval_ms),
+            stale_ms: ini_map.get("consolidated_bbo_stale_ms").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.stale_ms),
+            krw_rate: match ini_map.get("consolidated_bbo_krw_rate").filter(|v| !v.is_empty()) {
+                Some(v) => Some(v.parse::<f64>().ok().filter(|r| r.is_finite() && *r > 0.0)
+                    .ok_or_else(|| crate::errors::CryptoFeederError::Other(format!("consolidated_bbo_krw_rate 값 오류 '{}'", v)))?),
+                None => None,
+            },
+            fx_quote: ini_map.get("consolidated_bbo_fx_quote").filter(|v| !v.is_empty()).cloned().unwrap_or(defaults.fx_quote),
+            exchanges: ini_map.get("consolidated_bbo_exchanges")
+                .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+
+        // 김치 프리미엄 (기본 비활성, 잘못된 환율 출처는 시작 실패)
+        let defaults = KimchiPremiumConfig::default();
+        let exchange_list = |key: &str, default: Vec<String>| ini_map.get(key)
+            .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect::<Vec<_>>())
+            .filter(|list| !list.is_empty())
+            .unwrap_or(default);
+        let kimchi_premium = KimchiPremiumConfig {
+            port: ini_map.get("kimchi_port").and_then(|v| v.parse::<u16>().ok()).unwrap_or(defaults.port),
+            interval_ms: ini_map.get("kimchi_interval_ms").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.interval_ms),
+            stale_ms: ini_map.get("kimchi_stale_ms").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.stale_ms),
+            fx_rate: match ini_map.get("kimchi_fx_rate").filter(|v| !v.is_empty()) {
+                Some(v) => FxRateSource::parse(v).map_err(crate::errors::CryptoFeederError::Other)?,
+                None => defaults.fx_rate,
+            },
+            krw_exchanges: exchange_list("kimchi_krw_exchanges", defaults.krw_exchanges),
+            foreign_exchanges: exchange_list("kimchi_foreign_exchanges", defaults.foreign_exchanges),
+            foreign_quote: ini_map.get("kimchi_quote").filter(|v| !v.is_empty()).cloned().unwrap_or(defaults.foreign_quote),
+        };
+
+        // 체결 흐름 분석 (기본 비활성, 구간은 1~3600초)
+        let defaults = TradeFlowConfig::default();
+        let trade_flow = TradeFlowConfig {
+            port: ini_map.get("trade_flow_port").and_then(|v| v.parse::<u16>().ok()).unwrap_or(defaults.port),
+            interval_ms: ini_map.get("trade_flow_interval_ms").and_then(|v| v.parse::<u64>().ok()).filter(|v| *v > 0).unwrap_or(defaults.interval_ms),
+            windows_secs: match ini_map.get("trade_flow_windows").filter(|v| !v.is_empty()) {
+                Some(v) => {
+                    let mut windows = v.split(',').map(str::trim)
+                        .map(|w| w.parse::<u32>().ok().filter(|w| (1..=3600).contains(w))
+                            .ok_or_else(|| crate::errors::CryptoFeederError::Other(format!("trade_flow_windows 값 오류 '{}' (1~3600초)", w))))
+                        .collect::<Result<Vec<_>>>()?;
+                    windows.sort_unstable();
+                    windows.dedup();
+                    windows
+                }
+                None => defaults.windows_secs,
+            },
+        };
+
         // symbol_config.ini 파일 읽기 시도
         let symbol_config = SymbolConfig::load().ok();
 
         // endpoint.ini 파일 읽기 시도
         let endpoint_config = EndpointConfig::load().ok();
 
+        // index_config.ini는 선택 사항 (있는데 유효하지 않으면 시작 실패)
+        let index_config = match config_file_path("index_config.ini") {
+            Some(_) => Some(IndexConfig::load()?),
+            None => None,
+        };
+
         // 기본 설정 (Checkpoint 1용)
         Ok(Config {
             exchanges: vec![
@@ -140,11 +616,45 @@ impl Config {
             metrics,
             symbol_config,
             endpoint_config,
+            index_config,
+            config_watch_interval_secs,
+            control_port,
+            journal,
+            protocol,
+            reference,
+            instrument_scales,
+            bar_intervals,
+            ticker_interval_ms,
+            open_interest_poll_secs,
+            consolidated_bbo,
+            kimchi_premium,
+            trade_flow,
         })
     }
 
+    /// symbol_config.ini/endpoint.ini를 다시 읽어 새 Config 생성 (핫 리로드용)
+    ///
+    /// 파일이 없으면 현재 값을 유지하고, 파일이 있지만 유효하지 않으면 에러를 반환합니다.
+    /// config.ini(UDP/로깅/런타임) 설정은 재로드 대상이 아닙니다.
+    pub fn reload_exchange_configs(&self) -> Result<Config> {
+        let mut reloaded = self.clone();
+
+        if config_file_path("symbol_config.ini").is_some() {
+            let symbol_config = SymbolConfig::load()?;
+            symbol_config.validate()?;
+            reloaded.symbol_config = Some(symbol_config);
+        }
+
+        if config_file_path("endpoint.ini").is_some() {
+            let content = read_config_file("endpoint.ini")?;
+            reloaded.endpoint_config = Some(EndpointConfig::parse_validated(&content)?);
+        }
+
+        Ok(reloaded)
+    }
+
     /// 간단한 INI 파일 파서
-    fn parse_ini(content: &str) -> Result<HashMap<String, String>> {
+    pub fn parse_ini(content: &str) -> Result<HashMap<String, String>> {
         let mut config_map = HashMap::new();
 
         for line in content.lines() {
@@ -245,10 +755,47 @@ impl SymbolConfig {
     }
 
     /// 심볼 라인을 파싱하여 SymbolSession 생성
+    /// 세션 옵션의 on/off 값 파싱
+    fn parse_switch(key: &str, value: &str) -> Result<bool> {
+        match value.to_ascii_lowercase().as_str() {
+            "on" | "true" => Ok(true),
+            "off" | "false" => Ok(false),
+            _ => Err(crate::errors::CryptoFeederError::Other(
+                format!("세션 라인의 {} 값 오류 '{}' (on, off)", key, value)
+            )),
+        }
+    }
+
     fn parse_symbol_line(line: &str) -> Result<SymbolSession> {
         // 지원 형식:
-        //   <port>=SYM1, SYM2, ...
+        //   <port>=SYM1, SYM2, ... [| klines=exchange|local, ticker=on, open_interest=on, funding=on, liquidations=on|all]
+        //   <port>= | liquidations=all  → 심볼 없는 전체 시장 청산 전용 세션
         //   (하위호환) SYM1, SYM2, ...  → 이 경우 에러로 처리하거나 기본 포트(55555) 사용
+        let (line, options) = match line.split_once('|') {
+            Some((symbols, options)) => (symbols.trim(), options),
+            None => (line, ""),
+        };
+        let mut streams = SessionStreams::default();
+        for option in options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            match option.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("klines", value)) => {
+                    streams.klines = KlineSource::parse(value).ok_or_else(|| crate::errors::CryptoFeederError::Other(
+                        format!("세션 라인의 klines 값 오류 '{}' (off, exchange, local)", value)
+                    ))?;
+                }
+                Some(("ticker", value)) => streams.ticker = Self::parse_switch("ticker", value)?,
+                Some(("open_interest", value)) => streams.open_interest = Self::parse_switch("open_interest", value)?,
+                Some(("funding", value)) => streams.funding = Self::parse_switch("funding", value)?,
+                Some(("liquidations", value)) => {
+                    streams.liquidations = LiquidationScope::parse(value).ok_or_else(|| crate::errors::CryptoFeederError::Other(
+                        format!("세션 라인의 liquidations 값 오류 '{}' (off, on, all)", value)
+                    ))?;
+                }
+                _ => return Err(crate::errors::CryptoFeederError::Other(
+                    format!("알 수 없는 세션 옵션 '{}': {}", option, line)
+                )),
+            }
+        }
         let (port, symbols_str) = if let Some((p, rest)) = line.split_once('=') {
             let port_num = p.trim().parse::<u16>().map_err(|e| crate::errors::CryptoFeederError::Other(
                 format!("세션 라인의 포트 파싱 실패 '{}': {}", p.trim(), e)
@@ -266,7 +813,7 @@ impl SymbolConfig {
             .filter(|s| !s.is_empty())
             .collect();
 
-        if symbols.is_empty() {
+        if symbols.is_empty() && streams.liquidations != LiquidationScope::All {
             return Err(crate::errors::CryptoFeederError::Other(
                 format!("심볼 라인이 비어있습니다: {}", line)
             ));
@@ -278,9 +825,51 @@ impl SymbolConfig {
             symbols,
             is_btc_session,
             port,
+            streams,
         })
     }
 
+    /// 심볼 설정 유효성 검사 (포트 0, 잘못된 심볼 표기, 세션 내 중복 심볼 거부)
+    pub fn validate(&self) -> Result<()> {
+        for (exchange_name, group) in &self.exchanges {
+            for session in &group.sessions {
+                if session.port == 0 {
+                    return Err(crate::errors::CryptoFeederError::Other(
+                        format!("[{}] 세션 포트는 0일 수 없습니다: {}", exchange_name, session.symbols.join(", "))
+                    ));
+                }
+                if session.symbols.is_empty() && session.streams.market_wide_topics(exchange_name) == 0 {
+                    return Err(crate::errors::CryptoFeederError::Other(
+                        format!("[{}] 포트 {} 세션에 심볼이 없습니다 (심볼 없는 liquidations=all 세션은 BinanceFutures, OkxSwap만 가능)", exchange_name, session.port)
+                    ));
+                }
+                // OKX candle 채널은 /business 엔드포인트에서만 제공되어 체결/호가와 같은 /public 연결로 구독할 수 없음
+                if exchange_name.starts_with("Okx") && session.streams.klines.subscribes_exchange() {
+                    return Err(crate::errors::CryptoFeederError::Other(
+                        format!("[{}] 포트 {} 세션: OKX는 klines=exchange를 지원하지 않습니다 (klines=local 사용)", exchange_name, session.port)
+                    ));
+                }
+                let mut seen = std::collections::HashSet::new();
+                for symbol in &session.symbols {
+                    let valid = symbol.split_once('^')
+                        .map(|(base, quote)| !base.is_empty() && !quote.is_empty() && !quote.contains('^'))
+                        .unwrap_or(false);
+                    if !valid {
+                        return Err(crate::errors::CryptoFeederError::Other(
+                            format!("[{}] 잘못된 심볼 표기 '{}' (A^B 형식이어야 함)", exchange_name, symbol)
+                        ));
+                    }
+                    if !seen.insert(symbol) {
+                        return Err(crate::errors::CryptoFeederError::Other(
+                            format!("[{}] 포트 {} 세션에 중복 심볼: {}", exchange_name, session.port, symbol)
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// 특정 거래소의 세션 목록 반환
     pub fn get_exchange_sessions(&self, exchange_name: &str) -> Option<&Vec<SymbolSession>> {
         self.exchanges.get(exchange_name).map(|group| &group.sessions)
@@ -382,6 +971,46 @@ impl EndpointConfig {
         Ok(EndpointConfig { exchanges })
     }
 
+    /// endpoint.ini 내용을 엄격하게 파싱 (핫 리로드용)
+    ///
+    /// `parse`는 필수 항목이 빠진 섹션을 조용히 건너뛰지만, 여기서는 모든 섹션이
+    /// 유효한 엔드포인트여야 하며 `ws_url_base`는 ws/wss URL이어야 합니다.
+    pub fn parse_validated(content: &str) -> Result<Self> {
+        let config = Self::parse(content)?;
+
+        let sections: Vec<&str> = content.lines()
+            .map(|l| l.trim())
+            .filter(|l| l.starts_with('[') && l.ends_with(']'))
+            .map(|l| &l[1..l.len()-1])
+            .collect();
+        for section in &sections {
+            if !config.exchanges.contains_key(*section) {
+                return Err(crate::errors::CryptoFeederError::Other(
+                    format!("endpoint.ini [{}] 섹션에 ws_url_base/timeout_ms가 없거나 잘못되었습니다", section)
+                ));
+            }
+        }
+        if sections.len() != config.exchanges.len() {
+            return Err(crate::errors::CryptoFeederError::Other("endpoint.ini에 중복된 거래소 섹션이 있습니다".to_string()));
+        }
+
+        for endpoint in config.exchanges.values() {
+            let url = url::Url::parse(&endpoint.ws_url_base)?;
+            if url.scheme() != "ws" && url.scheme() != "wss" {
+                return Err(crate::errors::CryptoFeederError::Other(
+                    format!("endpoint.ini [{}] ws_url_base는 ws/wss URL이어야 합니다: {}", endpoint.exchange_name, endpoint.ws_url_base)
+                ));
+            }
+            if endpoint.timeout_ms == 0 {
+                return Err(crate::errors::CryptoFeederError::Other(
+                    format!("endpoint.ini [{}] timeout_ms는 0일 수 없습니다", endpoint.exchange_name)
+                ));
+            }
+        }
+
+        Ok(config)
+    }
+
     /// 거래소 설정을 파싱하여 ExchangeEndpoint 생성
     fn parse_exchange_settings(exchange_name: &str, settings: &HashMap<String, String>) -> Option<ExchangeEndpoint> {
         let ws_url_base = settings.get("ws_url_base")?.clone();
@@ -391,6 +1020,46 @@ impl EndpointConfig {
         let enabled = settings.get("enabled")
             .map(|s| s.parse().unwrap_or(false))
             .unwrap_or(false);
+        let max_streams_per_connection = settings.get("max_streams_per_connection")
+            .and_then(|s| s.parse().ok())
+            .filter(|v: &usize| *v > 0);
+        let max_subscriptions_per_connection = settings.get("max_subscriptions_per_connection")
+            .and_then(|s| s.parse().ok())
+            .filter(|v: &usize| *v > 0);
+        let max_url_length = settings.get("max_url_length")
+            .and_then(|s| s.parse().ok())
+            .filter(|v: &usize| *v > 0);
+
+        // 네트워크 경로 설정: 잘못된 값이면 의도와 다른 경로로 나가지 않도록 섹션 전체를 무효 처리
+        let proxy = settings.get("proxy").map(|s| s.to_string()).filter(|s| !s.is_empty());
+        if let Some(value) = &proxy {
+            if let Err(e) = crate::ws_connect::ProxyConfig::parse(value) {
+                log::error!("endpoint.ini [{}] proxy 설정 오류: {}", exchange_name, e);
+                return None;
+            }
+        }
+        let mut bind_addrs = Vec::new();
+        for value in settings.get("bind_addr").map(|s| s.split(',')).into_iter().flatten().map(str::trim).filter(|s| !s.is_empty()) {
+            match value.parse::<IpAddr>() {
+                Ok(ip) => bind_addrs.push(ip),
+                Err(_) => {
+                    log::error!("endpoint.ini [{}] bind_addr 설정 오류: '{}'", exchange_name, value);
+                    return None;
+                }
+            }
+        }
+        let mut resolve = Vec::new();
+        for value in settings.get("resolve").map(|s| s.split(',')).into_iter().flatten().map(str::trim).filter(|s| !s.is_empty()) {
+            match value.split_once(':').and_then(|(host, ip)| Some((host.trim(), ip.trim().parse::<IpAddr>().ok()?))) {
+                Some((host, ip)) if !host.is_empty() => resolve.push((host.to_string(), ip)),
+                _ => {
+                    log::error!("endpoint.ini [{}] resolve 설정 오류: '{}' (host:ip 형식)", exchange_name, value);
+                    return None;
+                }
+            }
+        }
+
+        let rest_url_base = settings.get("rest_url_base").map(|s| s.trim_end_matches('/').to_string()).filter(|s| !s.is_empty());
 
         Some(ExchangeEndpoint {
             exchange_name: exchange_name.to_string(),
@@ -398,6 +1067,13 @@ impl EndpointConfig {
             timeout_ms,
             ping_interval_ms,
             enabled,
+            max_streams_per_connection,
+            max_subscriptions_per_connection,
+            max_url_length,
+            proxy,
+            bind_addrs,
+            resolve,
+            rest_url_base,
         })
     }
 
@@ -421,20 +1097,175 @@ impl EndpointConfig {
     }
 }
 
-/// 구성 파일을 여러 후보 경로에서 탐색하여 읽는다.
+/// 인덱스 계산 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexMethod {
+    /// 가중 중앙값
+    Median,
+    /// 가중 평균
+    Mean,
+}
+
+/// 인덱스에 쓰는 거래소 가격
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexPriceSource {
+    /// 최우선 매수/매도 중간 가격
+    Mid,
+    /// 마지막 체결가
+    Last,
+}
+
+/// 인덱스 구성 거래소
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexVenue {
+    pub exchange: String,
+    pub weight: f64,
+}
+
+/// 심볼 하나의 인덱스 정의 (`[BTC^USDT]` 섹션)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    pub symbol: String,
+    pub method: IndexMethod,
+    pub source: IndexPriceSource,
+    /// 1차 가중 중앙값에서 이 비율(%)보다 벗어난 거래소 제외 (0이면 끔)
+    pub outlier_pct: f64,
+    /// 제외 후 남은 거래소가 이보다 적으면 발행하지 않음
+    pub min_venues: usize,
+    pub venues: Vec<IndexVenue>,
+}
+
+/// 자체 인덱스 가격 설정 (index_config.ini)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexConfig {
+    /// 인덱스 전송 포트 (0이면 비활성)
+    pub port: u16,
+    pub interval_ms: u64,
+    /// 이보다 오래 갱신되지 않은 거래소 가격은 제외
+    pub stale_ms: u64,
+    /// 패킷 헤더의 거래소 표시명
+    pub exchange: String,
+    pub indices: Vec<IndexDefinition>,
+}
+
+impl IndexConfig {
+    /// index_config.ini 파일을 로드하여 IndexConfig 생성
+    pub fn load() -> Result<Self> {
+        let content = read_config_file("index_config.ini")?;
+
+        Self::parse(&content)
+    }
+
+    /// index_config.ini 내용 파싱 (`[settings]` + 심볼 섹션, 잘못된 값은 에러)
+    pub fn parse(content: &str) -> Result<Self> {
+        let error = |msg: String| crate::errors::CryptoFeederError::Other(format!("index_config.ini: {}", msg));
+        let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+
+            // 빈 줄이나 주석 라인 무시
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                sections.push((line[1..line.len()-1].trim().to_string(), HashMap::new()));
+                continue;
+            }
+
+            match (sections.last_mut(), line.split_once('=')) {
+                (Some((_, settings)), Some((key, value))) => {
+                    settings.insert(key.trim().to_string(), value.trim().to_string());
+                }
+                _ => return Err(error(format!("섹션 밖이거나 key=value가 아닌 줄 '{}'", line))),
+            }
+        }
+
+        let mut config = IndexConfig {
+            port: 0,
+            interval_ms: 1000,
+            stale_ms: 5000,
+            exchange: "CfIndex".to_string(),
+            indices: Vec::new(),
+        };
+        let number = |section: &str, key: &str, value: &str| value.parse::<f64>().ok()
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .ok_or_else(|| error(format!("[{}] {} 값 오류 '{}'", section, key, value)));
+
+        for (name, settings) in &sections {
+            if name == "settings" {
+                let get = |key: &str| settings.get(key).filter(|v| !v.is_empty());
+                if let Some(v) = get("port") {
+                    config.port = v.parse().map_err(|_| error(format!("[settings] port 값 오류 '{}'", v)))?;
+                }
+                if let Some(v) = get("interval_ms") {
+                    config.interval_ms = number(name, "interval_ms", v)?.max(1.0) as u64;
+                }
+                if let Some(v) = get("stale_ms") {
+                    config.stale_ms = number(name, "stale_ms", v)?.max(1.0) as u64;
+                }
+                if let Some(v) = get("exchange") {
+                    config.exchange = v.clone();
+                }
+                continue;
+            }
+
+            if !name.contains('^') {
+                return Err(error(format!("섹션 [{}]는 표준 심볼(A^B)이어야 합니다", name)));
+            }
+            let method = match settings.get("method").map(String::as_str).unwrap_or("median") {
+                "median" => IndexMethod::Median,
+                "mean" => IndexMethod::Mean,
+                other => return Err(error(format!("[{}] method는 median|mean ('{}')", name, other))),
+            };
+            let source = match settings.get("source").map(String::as_str).unwrap_or("mid") {
+                "mid" => IndexPriceSource::Mid,
+                "last" => IndexPriceSource::Last,
+                other => return Err(error(format!("[{}] source는 mid|last ('{}')", name, other))),
+            };
+            let outlier_pct = match settings.get("outlier_pct") {
+                Some(v) => number(name, "outlier_pct", v)?,
+                None => 0.0,
+            };
+            let min_venues = match settings.get("min_venues") {
+                Some(v) => v.parse::<usize>().ok().filter(|n| *n > 0)
+                    .ok_or_else(|| error(format!("[{}] min_venues 값 오류 '{}'", name, v)))?,
+                None => 1,
+            };
+            // venues=거래소:가중치,... (가중치 생략 시 1)
+            let mut venues = Vec::new();
+            for entry in settings.get("venues").map(String::as_str).unwrap_or("").split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (exchange, weight) = match entry.split_once(':') {
+                    Some((exchange, weight)) => (exchange.trim(), number(name, "venues", weight.trim())?),
+                    None => (entry, 1.0),
+                };
+                if weight <= 0.0 {
+                    return Err(error(format!("[{}] 가중치는 0보다 커야 합니다 ('{}')", name, entry)));
+                }
+                venues.push(IndexVenue { exchange: exchange.to_string(), weight });
+            }
+            if venues.is_empty() {
+                return Err(error(format!("[{}] venues가 비어 있습니다", name)));
+            }
+            config.indices.push(IndexDefinition { symbol: name.clone(), method, source, outlier_pct, min_venues, venues });
+        }
+
+        Ok(config)
+    }
+}
+
+/// 구성 파일을 여러 후보 경로에서 탐색하여 경로를 반환한다.
 /// 탐색 순서:
 /// 1) 현재 작업 디렉터리 기준: ./config/<name>
 /// 2) 실행 파일 디렉터리 기준: <exe_dir>/config/<name>
 /// 3) 개발 빌드 위치 보정: <exe_dir>/../../config/<name> (target/{debug,release}에서 프로젝트 루트로 상향)
 /// 4) 환경 변수 CRYPTOFEEDER_CONFIG_DIR 지정 시: <env>/name (최우선)
-fn read_config_file(name: &str) -> Result<String> {
+pub fn config_file_path(name: &str) -> Option<PathBuf> {
     // 0) 환경 변수 우선
     if let Ok(custom_dir) = env::var("CRYPTOFEEDER_CONFIG_DIR") {
         let p = Path::new(&custom_dir).join(name);
         if p.exists() {
-            return fs::read_to_string(&p).map_err(|e| crate::errors::CryptoFeederError::Other(
-                format!("{} 읽기 실패 ({}): {}", name, p.display(), e)
-            ));
+            return Some(p);
         }
     }
 
@@ -456,16 +1287,18 @@ fn read_config_file(name: &str) -> Result<String> {
         }
     }
 
-    for p in candidates {
-        if p.exists() {
-            return fs::read_to_string(&p).map_err(|e| crate::errors::CryptoFeederError::Other(
-                format!("{} 읽기 실패 ({}): {}", name, p.display(), e)
-            ));
-        }
-    }
+    candidates.into_iter().find(|p| p.exists())
+}
 
-    Err(crate::errors::CryptoFeederError::Other(format!(
-        "{} 파일을 찾을 수 없습니다. CWD/config, exe_dir/config, exe_dir/../../config, CRYPTOFEEDER_CONFIG_DIR 를 확인하세요.",
-        name
-    )))
-}
\ No newline at end of file
+/// 구성 파일을 탐색하여 읽는다. (탐색 순서는 `config_file_path` 참고)
+fn read_config_file(name: &str) -> Result<String> {
+    match config_file_path(name) {
+        Some(p) => fs::read_to_string(&p).map_err(|e| crate::errors::CryptoFeederError::Other(
+            format!("{} 읽기 실패 ({}): {}", name, p.display(), e)
+        )),
+        None => Err(crate::errors::CryptoFeederError::Other(format!(
+            "{} 파일을 찾을 수 없습니다. CWD/config, exe_dir/config, exe_dir/../../config, CRYPTOFEEDER_CONFIG_DIR 를 확인하세요.",
+            name
+        ))),
+    }
+}
@@ -0,0 +1,249 @@
+//! 설정 핫 리로드
+//! 실행 중 설정과 새로 읽은 symbol_config.ini/endpoint.ini를 비교하여 변경된 세션만 골라냄
+
+use crate::config::{Config, ExchangeEndpoint, SessionStreams, SymbolSession};
+use std::collections::BTreeSet;
+
+/// 거래소 하나에 대한 재로드 계획
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeReloadPlan {
+    pub exchange_name: String,
+    /// 엔드포인트 변경으로 거래소의 모든 세션을 재시작
+    pub restart_all: bool,
+    /// 중지할 세션 (기존 설정 기준)
+    pub stopped: Vec<SymbolSession>,
+    /// 시작할 세션의 새 설정 내 인덱스
+    pub started: Vec<usize>,
+}
+
+/// 세션 동일성 비교 키 (포트 + 정렬된 심볼 목록 + 부가 스트림)
+pub fn session_key(session: &SymbolSession) -> (u16, Vec<String>, SessionStreams) {
+    let mut symbols = session.symbols.clone();
+    symbols.sort();
+    (session.port, symbols, session.streams)
+}
+
+/// 거래소 엔드포인트 조회 (endpoint.ini가 없으면 None)
+fn endpoint_of<'a>(config: &'a Config, exchange_name: &str) -> Option<&'a ExchangeEndpoint> {
+    config.endpoint_config.as_ref().and_then(|c| c.get_exchange_endpoint(exchange_name))
+}
+
+/// 거래소 연결 여부 (엔드포인트 설정이 없으면 기본 URL로 연결)
+fn is_enabled(config: &Config, exchange_name: &str) -> bool {
+    endpoint_of(config, exchange_name).map(|e| e.enabled).unwrap_or(true)
+}
+
+/// 기존/새 설정을 비교하여 거래소별 재로드 계획 생성 (변경 없는 거래소는 제외)
+pub fn diff_configs(old: &Config, new: &Config) -> Vec<ExchangeReloadPlan> {
+    let empty: Vec<SymbolSession> = Vec::new();
+    let mut names: BTreeSet<String> = BTreeSet::new();
+    for config in [old, new] {
+        if let Some(symbol_config) = &config.symbol_config {
+            names.extend(symbol_config.get_exchange_names());
+        }
+    }
+
+    let mut plans = Vec::new();
+    for exchange_name in names {
+        let old_sessions = old.symbol_config.as_ref()
+            .and_then(|c| c.get_exchange_sessions(&exchange_name))
+            .unwrap_or(&empty);
+        let new_sessions = new.symbol_config.as_ref()
+            .and_then(|c| c.get_exchange_sessions(&exchange_name))
+            .unwrap_or(&empty);
+        let was_enabled = is_enabled(old, &exchange_name);
+        let now_enabled = is_enabled(new, &exchange_name);
+
+        let plan = if endpoint_of(old, &exchange_name) != endpoint_of(new, &exchange_name) {
+            // 엔드포인트(URL, 제한, 활성화 여부 등)가 바뀌면 거래소 전체 재시작
+            if !was_enabled && !now_enabled {
+                continue;
+            }
+            ExchangeReloadPlan {
+                exchange_name,
+                restart_all: true,
+                stopped: if was_enabled { old_sessions.clone() } else { Vec::new() },
+                started: if now_enabled { (0..new_sessions.len()).collect() } else { Vec::new() },
+            }
+        } else {
+            if !now_enabled {
+                continue;
+            }
+            // 세션 단위 비교: 기존 세션과 일치하는 새 세션은 그대로 유지
+            let mut unmatched_old: Vec<&SymbolSession> = old_sessions.iter().collect();
+            let mut started = Vec::new();
+            for (idx, session) in new_sessions.iter().enumerate() {
+                let key = session_key(session);
+                if let Some(pos) = unmatched_old.iter().position(|s| session_key(s) == key) {
+                    unmatched_old.remove(pos);
+                } else {
+                    started.push(idx);
+                }
+            }
+            ExchangeReloadPlan {
+                exchange_name,
+                restart_all: false,
+                stopped: unmatched_old.into_iter().cloned().collect(),
+                started,
+            }
+        };
+
+        if plan.restart_all || !plan.stopped.is_empty() || !plan.started.is_empty() {
+            plans.push(plan);
+        }
+    }
+
+    plans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConsolidatedBboConfig, EndpointConfig, ExchangeSymbolGroup, JournalConfig, KimchiPremiumConfig, KlineSource, LoggingConfig, MetricsConfig, ProtocolConfig, ReferenceDataConfig, SymbolConfig, test_session, TradeFlowConfig, UdpConfig};
+    use std::collections::HashMap;
+
+    fn endpoint(name: &str, url: &str, enabled: bool) -> ExchangeEndpoint {
+        ExchangeEndpoint {
+            exchange_name: name.to_string(),
+            ws_url_base: url.to_string(),
+            timeout_ms: 5000,
+            ping_interval_ms: None,
+            enabled,
+            max_streams_per_connection: None,
+            max_subscriptions_per_connection: None,
+            max_url_length: None,
+            proxy: None,
+            bind_addrs: vec![],
+            resolve: vec![],
+            rest_url_base: None,
+        }
+    }
+
+    fn config(sessions: &[(&str, Vec<SymbolSession>)], endpoints: &[ExchangeEndpoint]) -> Config {
+        let exchanges = sessions.iter()
+            .map(|(name, s)| (name.to_string(), ExchangeSymbolGroup { exchange_name: name.to_string(), sessions: s.clone() }))
+            .collect::<HashMap<_, _>>();
+        let endpoints = endpoints.iter()
+            .map(|e| (e.exchange_name.clone(), e.clone()))
+            .collect::<HashMap<_, _>>();
+        Config {
+            exchanges: vec![],
+            symbols: vec![],
+            udp: UdpConfig { multicast_addr: "239.255.1.1".into(), port: 55555, interface_addr: "0.0.0.0".into(), mtu: 1500 },
+            logging: LoggingConfig { level: "info".into(), file_path: None },
+            runtime_threads: None,
+            metrics: MetricsConfig { enabled: false, interval_secs: 5 },
+            symbol_config: Some(SymbolConfig { exchanges }),
+            endpoint_config: Some(EndpointConfig { exchanges: endpoints }),
+            config_watch_interval_secs: 0,
+            control_port: 0,
+            journal: JournalConfig { enabled: false, dir: "journal".into(), max_file_bytes: 1024, queue_capacity: 16 },
+            protocol: ProtocolConfig::default(),
+            reference: ReferenceDataConfig::default(),
+            instrument_scales: HashMap::new(),
+            bar_intervals: vec![60],
+            ticker_interval_ms: 1000,
+            open_interest_poll_secs: 30,
+            consolidated_bbo: ConsolidatedBboConfig::default(),
+            kimchi_premium: KimchiPremiumConfig::default(),
+            trade_flow: TradeFlowConfig::default(),
+            index_config: None,
+        }
+    }
+
+    #[test]
+    fn test_unchanged_config_has_no_plan() {
+        let ep = endpoint("BinanceSpot", "wss://stream.binance.com:9443/ws/", true);
+        let old = config(&[("BinanceSpot", vec![test_session(55555, &["BTC^USDT"]), test_session(55556, &["ETH^USDT", "ADA^USDT"])])], std::slice::from_ref(&ep));
+        // 심볼 순서만 바뀐 세션은 동일 세션으로 취급
+        let new = config(&[("BinanceSpot", vec![test_session(55555, &["BTC^USDT"]), test_session(55556, &["ADA^USDT", "ETH^USDT"])])], &[ep]);
+        assert!(diff_configs(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_only_changed_sessions_are_started_and_stopped() {
+        let ep = endpoint("BinanceSpot", "wss://stream.binance.com:9443/ws/", true);
+        let old = config(&[("BinanceSpot", vec![test_session(55555, &["BTC^USDT"]), test_session(55556, &["ETH^USDT", "ADA^USDT"])])], std::slice::from_ref(&ep));
+        let new = config(&[("BinanceSpot", vec![test_session(55555, &["BTC^USDT"]), test_session(55556, &["ETH^USDT", "SOL^USDT"])])], &[ep]);
+        let plans = diff_configs(&old, &new);
+        assert_eq!(plans.len(), 1);
+        assert!(!plans[0].restart_all);
+        assert_eq!(plans[0].stopped, vec![test_session(55556, &["ETH^USDT", "ADA^USDT"])]);
+        assert_eq!(plans[0].started, vec![1]);
+    }
+
+    #[test]
+    fn test_kline_source_change_restarts_session() {
+        let ep = endpoint("BybitSpot", "wss://stream.bybit.com/v5/public/spot", true);
+        let old = config(&[("BybitSpot", vec![test_session(55555, &["BTC^USDT"])])], std::slice::from_ref(&ep));
+        let mut with_klines = test_session(55555, &["BTC^USDT"]);
+        with_klines.streams.klines = crate::config::KlineSource::Local;
+        let new = config(&[("BybitSpot", vec![with_klines])], &[ep]);
+        let plans = diff_configs(&old, &new);
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].stopped, vec![test_session(55555, &["BTC^USDT"])]);
+        assert_eq!(plans[0].started, vec![0]);
+    }
+
+    #[test]
+    fn test_endpoint_change_restarts_exchange() {
+        let sessions = vec![test_session(55555, &["BTC^USDT"])];
+        let old = config(&[("BinanceSpot", sessions.clone()), ("BybitSpot", sessions.clone())],
+                         &[endpoint("BinanceSpot", "wss://a/ws/", true), endpoint("BybitSpot", "wss://b/", true)]);
+        let new = config(&[("BinanceSpot", sessions.clone()), ("BybitSpot", sessions)],
+                         &[endpoint("BinanceSpot", "wss://c/ws/", true), endpoint("BybitSpot", "wss://b/", true)]);
+        let plans = diff_configs(&old, &new);
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].exchange_name, "BinanceSpot");
+        assert!(plans[0].restart_all);
+        assert_eq!(plans[0].stopped.len(), 1);
+        assert_eq!(plans[0].started, vec![0]);
+    }
+
+    #[test]
+    fn test_disabling_and_removing_exchanges_stops_sessions() {
+        let sessions = vec![test_session(55555, &["BTC^USDT"])];
+        let old = config(&[("BinanceSpot", sessions.clone()), ("OkxSpot", sessions.clone())],
+                         &[endpoint("BinanceSpot", "wss://a/ws/", true), endpoint("OkxSpot", "wss://o/", true)]);
+        let new = config(&[("BinanceSpot", sessions)],
+                         &[endpoint("BinanceSpot", "wss://a/ws/", false), endpoint("OkxSpot", "wss://o/", true)]);
+        let plans = diff_configs(&old, &new);
+        assert_eq!(plans.len(), 2);
+        let binance = plans.iter().find(|p| p.exchange_name == "BinanceSpot").unwrap();
+        assert_eq!(binance.stopped.len(), 1);
+        assert!(binance.started.is_empty());
+        let okx = plans.iter().find(|p| p.exchange_name == "OkxSpot").unwrap();
+        assert!(!okx.restart_all);
+        assert_eq!(okx.stopped.len(), 1);
+        assert!(okx.started.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_reload_files_are_rejected() {
+        let bad_symbol = config(&[("BinanceSpot", vec![test_session(55555, &["BTCUSDT"])])], &[]);
+        assert!(bad_symbol.symbol_config.as_ref().unwrap().validate().is_err());
+        let bad_port = config(&[("BinanceSpot", vec![test_session(0, &["BTC^USDT"])])], &[]);
+        assert!(bad_port.symbol_config.as_ref().unwrap().validate().is_err());
+        let mut okx_klines = test_session(55555, &["BTC^USDT"]);
+        okx_klines.streams.klines = KlineSource::Exchange;
+        let okx_klines = config(&[("OkxSpot", vec![okx_klines])], &[]);
+        assert!(okx_klines.symbol_config.as_ref().unwrap().validate().is_err());
+
+        let valid = "[BinanceSpot]\nws_url_base=wss://stream.binance.com:9443/ws/\ntimeout_ms=5000\nenabled=true\n";
+        assert!(EndpointConfig::parse_validated(valid).is_ok());
+        // ws_url_base 누락 섹션은 기동 시에는 무시되지만 재로드 시에는 거부
+        let missing_url = "[BinanceSpot]\ntimeout_ms=5000\nenabled=true\n";
+        assert!(EndpointConfig::parse_validated(missing_url).is_err());
+        let http_url = "[BinanceSpot]\nws_url_base=https://example.com\ntimeout_ms=5000\n";
+        assert!(EndpointConfig::parse_validated(http_url).is_err());
+
+        let routed = "[BinanceSpot]\nws_url_base=wss://stream.binance.com:9443/ws/\ntimeout_ms=5000\nproxy=socks5://10.0.0.9:1080\nbind_addr=10.0.0.1, 10.0.0.2\nresolve=stream.binance.com:13.1.2.3\n";
+        let parsed = EndpointConfig::parse_validated(routed).unwrap();
+        let ep = parsed.get_exchange_endpoint("BinanceSpot").unwrap();
+        assert_eq!(ep.proxy.as_deref(), Some("socks5://10.0.0.9:1080"));
+        assert_eq!(ep.bind_addrs.len(), 2);
+        assert_eq!(ep.resolve, vec![("stream.binance.com".to_string(), "13.1.2.3".parse().unwrap())]);
+        let bad_bind = "[BinanceSpot]\nws_url_base=wss://a/ws/\ntimeout_ms=5000\nbind_addr=10.0.0.300\n";
+        assert!(EndpointConfig::parse_validated(bad_bind).is_err());
+    }
+}
@@ -1,35 +1,182 @@
-/// WebSocket 연결 관리자
-/// 거래소별 WebSocket 연결 생성, 유지, 모니터링 및 재연결 담당
+//! WebSocket 연결 관리자
+//! 거래소별 WebSocket 연결 생성, 유지, 모니터링 및 재연결 담당
 
-use crate::config::{Config, ExchangeConfig, SymbolSession, ExchangeEndpoint};
-use crate::data_parser::DataParser;
-use crate::packet_builder::PacketBuilder;
+use crate::config::{Config, ExchangeConfig, LiquidationScope, SessionStreams, SymbolSession, ExchangeEndpoint};
+use crate::config_reload::{diff_configs, session_key};
+use crate::session_planner::{AdapterLimits, PlannedSession, plan_exchange_sessions, log_plan, streams_per_symbol};
+use crate::data_parser::{DataParser, ParsedData};
+use crate::feed_stage::{FeedStage, SessionFilter};
+use crate::depth_snapshot::{self, SnapshotRequests};
+use crate::packet_builder::{PacketBuilder, UdpPacket};
 use crate::udp_broadcaster::UdpMulticaster;
+use crate::ws_connect::{connect_websocket, ConnectOptions};
+use crate::journal::{now_nanos, FrameJournal, SessionMeta};
 use crate::errors::{CryptoFeederError, Result};
 use crate::events::{
     SystemEvent,
+    SystemShutdown,
     ConnectionStatus,
+    SubscriptionStatus,
+    ErrorEvent,
     exchange_name_to_id,
     CONNECTION_STATUS_CONNECTING,
     CONNECTION_STATUS_CONNECTED,
     CONNECTION_STATUS_DISCONNECTED,
     CONNECTION_STATUS_RECONNECTING,
     CONNECTION_STATUS_FAILED,
+    SUBSCRIPTION_TYPE_ORDER_BOOK,
+    SUBSCRIPTION_TYPE_TRADE,
+    SUBSCRIPTION_STATUS_FAILED,
+    SUBSCRIPTION_STATUS_SUBSCRIBED,
+    SUBSCRIPTION_STATUS_UNSUBSCRIBED,
+    ERROR_TYPE_CONFIG_RELOAD_REJECTED,
+    ERROR_SEVERITY_ERROR,
 };
 
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use log::{info, warn, error, debug};
-use std::sync::Arc;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
 use tokio::time;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
 pub struct ConnectionManager {
-    config: Arc<Config>,
+    // 핫 리로드 시 통째로 교체되는 현재 설정
+    config: Arc<RwLock<Arc<Config>>>,
     data_parser: Arc<DataParser>,
     packet_builder: Arc<PacketBuilder>,
     udp_broadcaster: Arc<UdpMulticaster>,
+    // 실행 중 세션 레지스트리 (런타임 구독 변경 명령 라우팅용)
+    sessions: Arc<Mutex<Vec<SessionHandle>>>,
+    runtime_session_counter: Arc<AtomicUsize>,
+    session_id_counter: Arc<AtomicUsize>,
+    // 거래소별 세션 순번 (bind_addr 순환 배정용)
+    exchange_slot_counters: Arc<Mutex<HashMap<String, usize>>>,
+    // 종료 신호 (취소 시 모든 세션이 Close 프레임 전송 후 종료)
+    shutdown: CancellationToken,
+    // 원시 메시지 저널 (비활성 시 None)
+    journal: Option<Arc<FrameJournal>>,
+    // 프레임 처리 단계 (세션 필터, 티커 병합, 로컬 봉, 파생 데이터, 리플레이와 공용)
+    stage: Arc<FeedStage>,
+    // 파생 데이터 호가창용 REST 스냅샷 요청 기록 (Binance)
+    depth_snapshots: Arc<Mutex<SnapshotRequests>>,
+}
+
+/// 실행 중 세션에 대한 구독 변경 명령
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// 지정 포트의 세션에 심볼 추가
+    Add { exchange: String, port: u16, symbol: String },
+    /// 지정 포트의 세션에서 심볼 제거
+    Remove { exchange: String, port: u16, symbol: String },
+    /// 심볼을 다른 포트의 세션으로 이동
+    Move { exchange: String, symbol: String, from_port: u16, to_port: u16 },
+}
+
+impl SessionCommand {
+    /// 명령 한 줄 파싱
+    ///
+    /// - `add <거래소> <포트> <심볼>`
+    /// - `remove <거래소> <포트> <심볼>`
+    /// - `move <거래소> <심볼> <기존 포트> <새 포트>`
+    pub fn parse(line: &str) -> Result<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let port = |value: &str| value.parse::<u16>().ok().filter(|p| *p != 0)
+            .ok_or_else(|| CryptoFeederError::Other(format!("잘못된 포트: {}", value)));
+        match words.as_slice() {
+            [cmd, exchange, p, symbol] if cmd.eq_ignore_ascii_case("add") => {
+                Ok(Self::Add { exchange: exchange.to_string(), port: port(p)?, symbol: symbol.to_string() })
+            }
+            [cmd, exchange, p, symbol] if cmd.eq_ignore_ascii_case("remove") => {
+                Ok(Self::Remove { exchange: exchange.to_string(), port: port(p)?, symbol: symbol.to_string() })
+            }
+            [cmd, exchange, symbol, from, to] if cmd.eq_ignore_ascii_case("move") => {
+                Ok(Self::Move { exchange: exchange.to_string(), symbol: symbol.to_string(), from_port: port(from)?, to_port: port(to)? })
+            }
+            _ => Err(CryptoFeederError::Other(format!("알 수 없는 명령: {}", line.trim()))),
+        }
+    }
+}
+
+/// 세션 태스크로 전달되는 제어 메시지
+#[derive(Debug, Clone)]
+enum SessionControl {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+    Stop,
+}
+
+/// 세션 연결 루프 종료 사유
+enum SessionExit {
+    /// 구독 변경 반영을 위한 재연결
+    Resubscribe,
+    /// 제어 명령에 의한 세션 종료
+    Stopped,
+    /// 프로그램 종료 신호에 의한 세션 종료
+    Shutdown,
+}
+
+/// 실행 중 세션 레지스트리 항목
+struct SessionHandle {
+    id: usize,
+    exchange_name: String,
+    label: String,
+    /// 세션을 만든 symbol_config 세션 (런타임 명령으로 생성된 세션은 None)
+    origin: Option<SymbolSession>,
+    session: Arc<Mutex<SymbolSession>>,
+    control_tx: mpsc::UnboundedSender<SessionControl>,
+}
+
+/// 거래소 응답 대기 중인 구독 변경
+struct PendingSubscription {
+    symbols: Vec<String>,
+    subscribe: bool,
+    /// 요청 ID별 남은 구독 응답 수
+    remaining_acks: HashMap<u64, usize>,
+    failed: bool,
+}
+
+impl PendingSubscription {
+    fn new(symbols: Vec<String>, subscribe: bool, frames: &[SubscriptionFrame]) -> Self {
+        let remaining_acks = frames.iter()
+            .filter(|f| f.expected_acks > 0)
+            .map(|f| (f.request_id, f.expected_acks))
+            .collect();
+        Self { symbols, subscribe, remaining_acks, failed: false }
+    }
+}
+
+/// 구독 응답 한 건을 대기 목록에 반영하고, 변경의 모든 요청이 응답을 마치면 (심볼, 구독 상태) 반환
+///
+/// 요청 ID가 없는 응답은 가장 먼저 보낸 요청의 응답으로 봅니다. 실패 응답은 해당 요청 전체를 끝낸 것으로 처리합니다
+/// (OKX는 요청의 채널 중 하나라도 잘못되면 요청 전체를 거부).
+fn record_subscription_ack(pending: &mut Vec<PendingSubscription>, ack_id: Option<u64>, success: bool) -> Option<(Vec<String>, u8)> {
+    let request_id = ack_id.or_else(|| pending.first().and_then(|p| p.remaining_acks.keys().min().copied()))?;
+    let idx = pending.iter().position(|p| p.remaining_acks.contains_key(&request_id))?;
+    let entry = &mut pending[idx];
+    let remaining = entry.remaining_acks.get_mut(&request_id)?;
+    *remaining -= 1;
+    if *remaining == 0 || !success {
+        entry.remaining_acks.remove(&request_id);
+    }
+    entry.failed |= !success;
+    if !entry.remaining_acks.is_empty() {
+        return None;
+    }
+    let entry = pending.remove(idx);
+    let status = match (entry.failed, entry.subscribe) {
+        (true, _) => SUBSCRIPTION_STATUS_FAILED,
+        (false, true) => SUBSCRIPTION_STATUS_SUBSCRIBED,
+        (false, false) => SUBSCRIPTION_STATUS_UNSUBSCRIBED,
+    };
+    Some((entry.symbols, status))
 }
 
 impl ConnectionManager {
@@ -39,36 +186,82 @@ impl ConnectionManager {
         packet_builder: Arc<PacketBuilder>,
         udp_broadcaster: Arc<UdpMulticaster>,
     ) -> Self {
+        let stage = Arc::new(FeedStage::new(Arc::clone(&config), Arc::clone(&data_parser), Arc::clone(&packet_builder)));
         Self {
-            config,
             data_parser,
             packet_builder,
             udp_broadcaster,
+            sessions: Arc::new(Mutex::new(Vec::new())),
+            runtime_session_counter: Arc::new(AtomicUsize::new(0)),
+            session_id_counter: Arc::new(AtomicUsize::new(0)),
+            exchange_slot_counters: Arc::new(Mutex::new(HashMap::new())),
+            stage,
+            depth_snapshots: Arc::new(Mutex::new(SnapshotRequests::default())),
+            config: Arc::new(RwLock::new(config)),
+            shutdown: CancellationToken::new(),
+            journal: None,
+        }
+    }
+
+    /// 원시 메시지 저널 연결 (세션 수신 프레임을 파싱 전에 기록)
+    pub fn with_journal(mut self, journal: Arc<FrameJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// 수신 프레임을 저널 큐에 기록 (저널 비활성 시 무시)
+    fn journal_frame(&self, recv_timestamp_ns: u64, exchange_name: &str, session_label: &str, port: u16, binary: bool, data: &[u8]) {
+        if let Some(journal) = &self.journal {
+            journal.record(recv_timestamp_ns, exchange_name, session_label, port, binary, data);
         }
     }
 
-    /// 모든 연결 시작
+    /// 저널이 켜져 있으면 세션의 현재 구독 심볼/부가 스트림 기록
+    fn journal_session_meta(&self, exchange_name: &str, session_label: &str, session: &Mutex<SymbolSession>) {
+        if let Some(journal) = &self.journal {
+            let (port, meta) = {
+                let session = session.lock().unwrap();
+                (session.port, SessionMeta { symbols: session.symbols.clone(), streams: session.streams })
+            };
+            journal.record_session_meta(now_nanos(), exchange_name, session_label, port, &meta);
+        }
+    }
+
+    /// 현재 설정 스냅샷
+    pub fn config(&self) -> Arc<Config> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// 종료 토큰. 취소하면 `run`이 모든 세션을 정리한 뒤 반환됩니다.
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// 모든 연결 시작 (종료 토큰이 취소되거나 모든 세션이 끝나면 반환)
     pub async fn run(&self) -> Result<()> {
         info!("🌐 연결 관리자 시작");
 
         let mut handles = Vec::new();
+        let config = self.config();
+        self.spawn_publishers();
 
         // symbol_config가 있으면 그것을 우선 사용, 없으면 기본 거래소 설정 사용
-        if let Some(symbol_config) = &self.config.symbol_config {
+        if let Some(symbol_config) = &config.symbol_config {
             info!("📋 심볼 설정 파일 기반으로 연결 생성");
             
-            // 각 거래소의 세션별 연결 작업 생성
+            // 각 거래소의 세션을 어댑터 제한에 맞춰 분할한 뒤 연결 작업 생성
             for exchange_name in symbol_config.get_exchange_names() {
                 if let Some(sessions) = symbol_config.get_exchange_sessions(&exchange_name) {
-                    for (session_idx, session) in sessions.iter().enumerate() {
-                        let manager = self.clone();
-                        let exchange_name_clone = exchange_name.clone();
-                        let session_clone = session.clone();
-                        
-                        let handle = tokio::spawn(async move {
-                            manager.manage_symbol_session(&exchange_name_clone, session_idx, &session_clone).await
-                        });
-                        
+                    let plan = match self.plan_sessions(&exchange_name, sessions) {
+                        Ok(plan) => plan,
+                        Err(e) => {
+                            error!("❌ {} 세션 계획 실패. 연결을 건너뜁니다: {}", exchange_name, e);
+                            continue;
+                        }
+                    };
+                    for planned in plan {
+                        let origin = sessions[planned.session_idx].clone();
+                        let handle = self.spawn_session(&exchange_name, planned.label(), planned.session, Some(origin));
                         handles.push(handle);
                     }
                 }
@@ -77,7 +270,7 @@ impl ConnectionManager {
             info!("⚠️ 심볼 설정 파일이 없음. 기본 거래소 설정 사용");
             
             // 기존 방식으로 각 거래소에 대한 연결 작업 생성
-            for exchange_config in &self.config.exchanges {
+            for exchange_config in &config.exchanges {
                 if !exchange_config.enabled {
                     info!("⏭️ {} 거래소는 비활성화됨", exchange_config.name);
                     continue;
@@ -101,9 +294,48 @@ impl ConnectionManager {
             }
         }
 
+        // 런타임 명령/설정 재로드로 생성된 세션이 남아 있으면 계속 대기
+        while !self.sessions.lock().unwrap().is_empty() {
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
         Ok(())
     }
 
+    /// 주기 발행 타이머 (봉 마감, 병합 티커, 파생 데이터를 발행 종류별 간격으로 전송하고 종료 신호 시 중단)
+    fn spawn_publishers(&self) {
+        for &(publisher, interval) in self.stage.publishers() {
+            let manager = self.clone();
+            tokio::spawn(async move {
+                let mut ticker = time::interval(interval);
+                loop {
+                    tokio::select! {
+                        _ = ticker.tick() => {}
+                        _ = manager.shutdown.cancelled() => break,
+                    }
+                    for (port, packet) in manager.stage.publish(publisher, now_nanos()) {
+                        if let Err(e) = manager.udp_broadcaster.send_packet_to_port(packet, port).await {
+                            warn!("⚠️ {} 전송 실패 (포트 {}): {}", publisher.label(), port, e);
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    /// 거래소 세션 목록을 어댑터 제한(스트림/구독/URL 길이)에 맞춰 분할하고 계획을 로그로 남김
+    fn plan_sessions(&self, exchange_name: &str, sessions: &[SymbolSession]) -> Result<Vec<PlannedSession>> {
+        let config = self.config();
+        let endpoint = config.endpoint_config.as_ref()
+            .and_then(|c| c.get_exchange_endpoint(exchange_name));
+        let limits = AdapterLimits::for_exchange(exchange_name).with_endpoint_overrides(endpoint);
+        let plan = plan_exchange_sessions(exchange_name, sessions, &limits, |symbols, streams| {
+            self.session_url(exchange_name, symbols, streams).map(|u| u.len())
+        })?;
+        log_plan(exchange_name, &limits, &plan);
+        Ok(plan)
+    }
+
     /// 단일 거래소 연결 관리 (재연결 로직 포함)
     async fn manage_exchange_connection(&self, exchange_config: ExchangeConfig) -> Result<()> {
         let mut retry_count = 0;
@@ -113,6 +345,10 @@ impl ConnectionManager {
             info!("🔌 {} 거래소 연결 시도 중... (시도 #{}/{})", exchange_config.name, retry_count + 1, MAX_RETRY_COUNT);
 
             match self.connect_to_exchange(&exchange_config).await {
+                Ok(_) if self.shutdown.is_cancelled() => {
+                    info!("🛑 {} 거래소 연결 종료 (종료 신호)", exchange_config.name);
+                    return Ok(());
+                },
                 Ok(_) => {
                     info!("✅ {} 거래소 연결 성공", exchange_config.name);
                     retry_count = 0; // 성공 시 재시도 카운트 리셋
@@ -134,7 +370,10 @@ impl ConnectionManager {
                     let delay = self.calculate_backoff_delay(retry_count - 1);
                     warn!("🔄 {}초 후 {} 거래소 재연결 시도", delay.as_secs(), exchange_config.name);
                     
-                    time::sleep(delay).await;
+                    tokio::select! {
+                        _ = time::sleep(delay) => {}
+                        _ = self.shutdown.cancelled() => return Ok(()),
+                    }
                 }
             }
         }
@@ -143,25 +382,56 @@ impl ConnectionManager {
     /// 단일 거래소에 연결
     async fn connect_to_exchange(&self, exchange_config: &ExchangeConfig) -> Result<()> {
         let url = Url::parse(&exchange_config.ws_url)?;
+        // 세션 경로와 동일하게 endpoint.ini의 proxy/bind_addr/resolve 적용
+        let options = ConnectOptions::for_session(
+            self.config().endpoint_config.as_ref().and_then(|c| c.get_exchange_endpoint(&exchange_config.name)),
+            0,
+        )?;
         info!("🚀 {} 연결 중: {}", exchange_config.name, url);
 
-        // WebSocket 연결
-        let (ws_stream, response) = connect_async(url).await
-            .map_err(|e| CryptoFeederError::WebSocketError(e))?;
+        // WebSocket 연결 (연결 중 종료 신호 수신 시 바로 반환)
+        let (ws_stream, response) = tokio::select! {
+            result = connect_websocket(&url, &options) => result?,
+            _ = self.shutdown.cancelled() => return Ok(()),
+        };
 
         info!("🤝 {} WebSocket 연결 성공 (상태: {})", 
               exchange_config.name, response.status());
 
         let (mut write, mut read) = ws_stream.split();
 
-        // Ping은 별도 구현하지 않고 WebSocket 내장 핑 사용
-        let ping_handle: Option<tokio::task::JoinHandle<()>> = None;
+        // 거래소가 요구하는 keepalive를 ping_interval_ms 주기로 전송
+        let mut keepalive = keepalive_timer(exchange_config.ping_interval_ms);
 
-        // 메시지 수신 루프
-        while let Some(message) = read.next().await {
+        // 메시지 수신 루프 (종료 신호 시 Close 프레임 전송 후 남은 메시지 처리)
+        loop {
+            let message = tokio::select! {
+                message = read.next() => match message {
+                    Some(message) => message,
+                    None => break,
+                },
+                _ = self.shutdown.cancelled() => {
+                    self.close_for_shutdown(&exchange_config.name, "legacy", &mut write, &mut read, None).await;
+                    return Ok(());
+                }
+                _ = keepalive_tick(&mut keepalive) => {
+                    debug!("🏓 {} keepalive 전송", exchange_config.name);
+                    if let Err(e) = write.send(keepalive_frame(&exchange_config.name)).await {
+                        error!("❌ {} keepalive 전송 실패: {}", exchange_config.name, e);
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let recv_timestamp_ns = now_nanos();
             match message {
                 Ok(Message::Text(text)) => {
+                    self.journal_frame(recv_timestamp_ns, &exchange_config.name, "legacy", 0, false, text.as_bytes());
                     debug!("📨 {} 텍스트 메시지 수신: {} bytes", exchange_config.name, text.len());
+                    if is_keepalive_reply(&text) {
+                        debug!("🏓 {} keepalive 응답 수신", exchange_config.name);
+                        continue;
+                    }
                     
                     if let Err(e) = self.process_message(&exchange_config.name, text.into_bytes()).await {
                         error!("❌ {} 메시지 처리 실패: {}", exchange_config.name, e);
@@ -169,6 +439,7 @@ impl ConnectionManager {
                     }
                 },
                 Ok(Message::Binary(data)) => {
+                    self.journal_frame(recv_timestamp_ns, &exchange_config.name, "legacy", 0, true, &data);
                     debug!("📨 {} 바이너리 메시지 수신: {} bytes", exchange_config.name, data.len());
                     
                     if let Err(e) = self.process_message(&exchange_config.name, data).await {
@@ -200,26 +471,15 @@ impl ConnectionManager {
             }
         }
 
-        // Ping 작업 정리
-        if let Some(handle) = ping_handle {
-            handle.abort();
-        }
-
         warn!("🔌 {} WebSocket 연결 종료됨", exchange_config.name);
         Err(CryptoFeederError::Other(format!("{} 연결 종료", exchange_config.name)))
     }
 
     /// 수신된 메시지 처리
     async fn process_message(&self, exchange: &str, data: Vec<u8>) -> Result<()> {
-        // 데이터 파싱
-        let parsed_data = self.data_parser.parse_message(exchange, data)?;
-
-        // UDP 패킷 생성 및 전송
-        // TradeTick의 배치 전송을 극대화하기 위해, 같은 폴링 사이클의 여러 Trade를 묶을 수 있는 경로를 우선 사용
-        let packets = self.packet_builder.build_packets(parsed_data)?;
-
-        // 기본 경로: 레거시 포트로 전송 (세션 컨텍스트가 없는 경우)
-        for packet in packets { self.udp_broadcaster.send_packet(packet).await?; }
+        // 기본 경로: 세션 필터 없이 파싱/패킷 생성 후 레거시 포트로 전송 (세션 컨텍스트가 없는 경우)
+        let output = self.stage.process(exchange, data, None, now_nanos())?;
+        for packet in output.packets { self.udp_broadcaster.send_packet(packet).await?; }
 
         Ok(())
     }
@@ -240,202 +500,662 @@ impl ConnectionManager {
         Duration::from_millis(delay_ms)
     }
 
+    /// 세션 태스크 생성 및 레지스트리 등록
+    fn spawn_session(&self, exchange_name: &str, label: String, session: SymbolSession, origin: Option<SymbolSession>) -> tokio::task::JoinHandle<Result<()>> {
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        let shared = Arc::new(Mutex::new(session));
+        let id = self.session_id_counter.fetch_add(1, AtomicOrdering::Relaxed);
+        let slot = self.next_exchange_slot(exchange_name);
+        self.sessions.lock().unwrap().push(SessionHandle {
+            id,
+            exchange_name: exchange_name.to_string(),
+            label: label.clone(),
+            origin,
+            session: shared.clone(),
+            control_tx,
+        });
+
+        let manager = self.clone();
+        let exchange_name = exchange_name.to_string();
+        tokio::spawn(async move {
+            let result = manager.manage_symbol_session(&exchange_name, &label, slot, shared, control_rx).await;
+            manager.sessions.lock().unwrap().retain(|h| h.id != id);
+            result
+        })
+    }
+
+    /// 거래소 내 세션 순번 발급 (전역 세션 ID와 달리 거래소마다 0부터 증가)
+    fn next_exchange_slot(&self, exchange_name: &str) -> usize {
+        let mut counters = self.exchange_slot_counters.lock().unwrap();
+        let counter = counters.entry(exchange_name.to_string()).or_insert(0);
+        let slot = *counter;
+        *counter += 1;
+        slot
+    }
+
+    /// 설정 파일을 다시 읽어 적용. 유효하지 않으면 현재 설정을 유지하고 ErrorEvent 전송
+    pub async fn reload_config(&self) -> Result<()> {
+        info!("🔄 설정 파일 재로드 시작 (symbol_config.ini, endpoint.ini)");
+        match self.config().reload_exchange_configs() {
+            Ok(new_config) => {
+                self.apply_config(new_config);
+                Ok(())
+            }
+            Err(e) => {
+                error!("❌ 설정 재로드 거부, 기존 설정 유지: {}", e);
+                let _ = self.send_error_event_to_session_ports(ERROR_TYPE_CONFIG_RELOAD_REJECTED, 0, ERROR_SEVERITY_ERROR, 0).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// 새 설정과 현재 설정을 비교하여 변경된 세션만 중지/시작 (변경 없는 세션은 유지)
+    pub fn apply_config(&self, new_config: Config) {
+        let old_config = self.config();
+        let plans = diff_configs(&old_config, &new_config);
+        *self.config.write().unwrap() = Arc::new(new_config);
+
+        if plans.is_empty() {
+            info!("✅ 설정 재로드 완료: 변경된 세션 없음");
+            return;
+        }
+
+        let config = self.config();
+        for plan in &plans {
+            let exchange_name = plan.exchange_name.as_str();
+            self.data_parser.register_exchange(exchange_name);
+
+            // 중지 대상 세션 정리 (전체 재시작이면 런타임 생성 세션 포함 전부)
+            let stopped_keys: BTreeSet<_> = plan.stopped.iter().map(session_key).collect();
+            let mut stopped = 0;
+            for handle in self.sessions.lock().unwrap().iter().filter(|h| h.exchange_name == exchange_name) {
+                let matches = plan.restart_all
+                    || handle.origin.as_ref().map(|o| stopped_keys.contains(&session_key(o))).unwrap_or(false);
+                if matches && handle.control_tx.send(SessionControl::Stop).is_ok() {
+                    stopped += 1;
+                }
+            }
+
+            // 새 설정 기준으로 세션을 분할한 뒤 변경된 세션만 시작
+            let mut started = 0;
+            if let Some(sessions) = config.symbol_config.as_ref().and_then(|c| c.get_exchange_sessions(exchange_name)) {
+                if !plan.started.is_empty() {
+                    match self.plan_sessions(exchange_name, sessions) {
+                        Ok(planned_sessions) => {
+                            for planned in planned_sessions {
+                                if plan.started.contains(&planned.session_idx) {
+                                    let origin = sessions[planned.session_idx].clone();
+                                    let _detached = self.spawn_session(exchange_name, planned.label(), planned.session, Some(origin));
+                                    started += 1;
+                                }
+                            }
+                        }
+                        Err(e) => error!("❌ {} 세션 계획 실패. 새 세션을 시작하지 않습니다: {}", exchange_name, e),
+                    }
+                }
+            }
+
+            info!("🔁 {} 설정 변경 반영: {}개 연결 중지, {}개 연결 시작{}",
+                  exchange_name, stopped, started, if plan.restart_all { " (엔드포인트 변경으로 전체 재시작)" } else { "" });
+        }
+        info!("✅ 설정 재로드 완료: {}개 거래소 변경", plans.len());
+    }
+
+    /// 실행 중 세션에 대한 구독 변경 명령 처리 (심볼 추가/제거/포트 이동)
+    pub fn execute_command(&self, command: SessionCommand) -> Result<()> {
+        info!("🎛️ 세션 명령 수신: {:?}", command);
+        match command {
+            SessionCommand::Add { exchange, port, symbol } => self.add_symbol(&exchange, port, symbol),
+            SessionCommand::Remove { exchange, port, symbol } => self.remove_symbol(&exchange, port, &symbol),
+            SessionCommand::Move { exchange, symbol, from_port, to_port } => self.move_symbol(&exchange, symbol, from_port, to_port),
+        }
+    }
+
+    /// 127.0.0.1 UDP 제어 포트에서 세션 명령을 받아 실행 (데이터그램 하나에 명령 한 줄, 응답은 `OK` 또는 `ERR <사유>`)
+    pub async fn serve_commands(&self, socket: UdpSocket) {
+        let mut buf = [0u8; 1024];
+        loop {
+            let (len, peer) = tokio::select! {
+                received = socket.recv_from(&mut buf) => match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        warn!("⚠️ 세션 명령 수신 실패: {}", e);
+                        continue;
+                    }
+                },
+                _ = self.shutdown.cancelled() => break,
+            };
+            let line = String::from_utf8_lossy(&buf[..len]);
+            let reply = match SessionCommand::parse(&line).and_then(|command| self.execute_command(command)) {
+                Ok(()) => "OK".to_string(),
+                Err(e) => {
+                    warn!("⚠️ 세션 명령 실패 ({}): {}", line.trim(), e);
+                    format!("ERR {}", e)
+                }
+            };
+            let _ = socket.send_to(reply.as_bytes(), peer).await;
+        }
+    }
+
+    /// 심볼을 다른 포트로 이동 (제거 후 추가)
+    ///
+    /// 원자적이지 않습니다. 양쪽 포트의 상태(심볼 위치, 대상 세션 종료 여부)를 먼저 확인하지만, 제거와 추가는 각 세션에
+    /// 비동기로 전달되므로 그 사이 추가가 실패하면 심볼은 어느 포트에도 구독되지 않은 상태로 남습니다.
+    /// 이 경우 오류를 반환하며, 결과는 포트별 `SubscriptionStatus` 이벤트로 확인할 수 있습니다.
+    fn move_symbol(&self, exchange_name: &str, symbol: String, from_port: u16, to_port: u16) -> Result<()> {
+        if from_port == to_port {
+            return Err(CryptoFeederError::Other(format!("{} 같은 포트로 이동할 수 없음: {}", exchange_name, from_port)));
+        }
+        {
+            let sessions = self.sessions.lock().unwrap();
+            let on_port = |port: u16| sessions.iter()
+                .filter(move |h| h.exchange_name == exchange_name && h.session.lock().unwrap().port == port);
+            if !on_port(from_port).any(|h| h.session.lock().unwrap().symbols.contains(&symbol)) {
+                return Err(CryptoFeederError::Other(format!("{} 포트 {}에서 심볼을 찾을 수 없음: {}", exchange_name, from_port, symbol)));
+            }
+            if on_port(to_port).any(|h| h.session.lock().unwrap().symbols.contains(&symbol)) {
+                return Err(CryptoFeederError::Other(format!("{} 포트 {}에 이미 구독 중인 심볼: {}", exchange_name, to_port, symbol)));
+            }
+            if on_port(to_port).any(|h| h.control_tx.is_closed()) {
+                return Err(CryptoFeederError::Other(format!("{} 포트 {}의 세션이 종료 중", exchange_name, to_port)));
+            }
+        }
+        self.remove_symbol(exchange_name, from_port, &symbol)?;
+        self.add_symbol(exchange_name, to_port, symbol.clone()).map_err(|e| {
+            error!("❌ {} {} 포트 {}에서 제거 후 포트 {} 추가 실패: {}", exchange_name, symbol, from_port, to_port, e);
+            CryptoFeederError::Other(format!("{} 포트 {}에서 제거됐으나 포트 {} 추가 실패: {}", exchange_name, from_port, to_port, e))
+        })
+    }
+
+    /// 포트의 세션 중 여유가 있는 곳에 심볼 추가. 여유가 없으면 같은 포트로 새 연결 생성
+    fn add_symbol(&self, exchange_name: &str, port: u16, symbol: String) -> Result<()> {
+        let config = self.config();
+        let endpoint = config.endpoint_config.as_ref()
+            .and_then(|c| c.get_exchange_endpoint(exchange_name));
+        let limits = AdapterLimits::for_exchange(exchange_name).with_endpoint_overrides(endpoint);
+        // 새 연결은 같은 포트 세션의 부가 스트림 설정을 그대로 따름
+        let mut streams = SessionStreams::default();
+
+        {
+            let sessions = self.sessions.lock().unwrap();
+            let on_port: Vec<&SessionHandle> = sessions.iter()
+                .filter(|h| h.exchange_name == exchange_name && h.session.lock().unwrap().port == port)
+                .collect();
+            if on_port.iter().any(|h| h.session.lock().unwrap().symbols.contains(&symbol)) {
+                return Err(CryptoFeederError::Other(format!("{} 포트 {}에 이미 구독 중인 심볼: {}", exchange_name, port, symbol)));
+            }
+            if let Some(handle) = on_port.first() {
+                streams = handle.session.lock().unwrap().streams;
+            }
+            let target = on_port.iter()
+                .filter(|h| {
+                    let session = h.session.lock().unwrap();
+                    let count = session.symbols.len() + 1;
+                    count * streams_per_symbol(exchange_name, session.streams) + session.streams.market_wide_topics(exchange_name) <= limits.max_streams_per_connection.min(limits.max_subscriptions_per_connection)
+                })
+                .min_by_key(|h| h.session.lock().unwrap().symbols.len());
+            if let Some(handle) = target {
+                info!("➕ {} [세션 #{}] 심볼 추가 요청: {}", exchange_name, handle.label, symbol);
+                return handle.control_tx.send(SessionControl::Subscribe(vec![symbol]))
+                    .map_err(|_| CryptoFeederError::Other(format!("{} [세션 #{}] 제어 채널 종료됨", exchange_name, handle.label)));
+            }
+        }
+
+        let label = format!("r{}", self.runtime_session_counter.fetch_add(1, AtomicOrdering::Relaxed));
+        info!("🆕 {} 포트 {}에 여유 세션이 없어 새 연결 #{} 생성: {}", exchange_name, port, label, symbol);
+        let is_btc_session = symbol.starts_with("BTC^");
+        let _detached = self.spawn_session(exchange_name, label, SymbolSession { symbols: vec![symbol], is_btc_session, port, streams }, None);
+        Ok(())
+    }
+
+    /// 포트의 세션에서 심볼 제거. 마지막 심볼이면 세션을 종료 (종료 시 남은 심볼의 UNSUBSCRIBED 상태 전송)
+    fn remove_symbol(&self, exchange_name: &str, port: u16, symbol: &str) -> Result<()> {
+        let sessions = self.sessions.lock().unwrap();
+        let handle = sessions.iter()
+            .find(|h| {
+                let s = h.session.lock().unwrap();
+                h.exchange_name == exchange_name && s.port == port && s.symbols.iter().any(|x| x == symbol)
+            })
+            .ok_or_else(|| CryptoFeederError::Other(format!("{} 포트 {}에서 심볼을 찾을 수 없음: {}", exchange_name, port, symbol)))?;
+
+        let control = if handle.session.lock().unwrap().symbols.len() == 1 {
+            info!("🛑 {} [세션 #{}] 마지막 심볼 제거, 세션 종료: {}", exchange_name, handle.label, symbol);
+            SessionControl::Stop
+        } else {
+            info!("➖ {} [세션 #{}] 심볼 제거 요청: {}", exchange_name, handle.label, symbol);
+            SessionControl::Unsubscribe(vec![symbol.to_string()])
+        };
+        handle.control_tx.send(control)
+            .map_err(|_| CryptoFeederError::Other(format!("{} [세션 #{}] 제어 채널 종료됨", exchange_name, handle.label)))
+    }
+
     /// 심볼 세션별 연결 관리 (재연결 로직 포함)
-    async fn manage_symbol_session(&self, exchange_name: &str, session_idx: usize, session: &SymbolSession) -> Result<()> {
+    async fn manage_symbol_session(
+        &self,
+        exchange_name: &str,
+        session_label: &str,
+        slot: usize,
+        session: Arc<Mutex<SymbolSession>>,
+        mut control_rx: mpsc::UnboundedReceiver<SessionControl>,
+    ) -> Result<()> {
         let mut retry_count = 0;
         const MAX_RETRY_COUNT: u32 = 10;
-
-        let session_type = if session.is_btc_session { "BTC" } else { "일반" };
-        let symbols_str = session.symbols.join(", ");
+        // 재연결로 반영된 구독 변경 (연결 후 SubscriptionStatus로 확인)
+        let mut pending_changes: Vec<(String, u8)> = Vec::new();
 
         loop {
+            if self.shutdown.is_cancelled() {
+                let port = session.lock().unwrap().port;
+                let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_RECONNECTING, CONNECTION_STATUS_DISCONNECTED, retry_count, 0, port).await;
+                return Ok(());
+            }
+
+            // 연결 대기 중 도착한 제어 명령은 심볼 목록에 먼저 반영
+            while let Ok(control) = control_rx.try_recv() {
+                if matches!(control, SessionControl::Stop) {
+                    info!("🛑 {} [세션 #{}] 연결 전 종료 요청 수신", exchange_name, session_label);
+                    self.send_session_unsubscribed(exchange_name, &session).await;
+                    return Ok(());
+                }
+                pending_changes.extend(apply_control_to_session(&session, &control));
+            }
+
+            let snapshot = session.lock().unwrap().clone();
+            let session_type = if snapshot.is_btc_session { "BTC" } else { "일반" };
             info!("🔌 {} [{}세션 #{}] 연결 시도 중... (심볼: {}) (시도 #{}/{})", 
-                  exchange_name, session_type, session_idx, symbols_str, retry_count + 1, MAX_RETRY_COUNT);
+                  exchange_name, session_type, session_label, snapshot.symbols.join(", "), retry_count + 1, MAX_RETRY_COUNT);
 
             // 상태 이벤트: CONNECTING (표시용 거래소명 그대로 기록)
-            let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_DISCONNECTED, CONNECTION_STATUS_CONNECTING, retry_count, 0, session.port).await;
+            let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_DISCONNECTED, CONNECTION_STATUS_CONNECTING, retry_count, 0, snapshot.port).await;
 
-            match self.connect_to_symbol_session(exchange_name, session_idx, session).await {
-                Ok(_) => {
-                    info!("✅ {} [{}세션 #{}] 연결 성공", exchange_name, session_type, session_idx);
-                    // 상태 이벤트: CONNECTED (표시용 거래소명 그대로 기록)
-                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTING, CONNECTION_STATUS_CONNECTED, retry_count, 0, session.port).await;
-                    retry_count = 0; // 성공 시 재시도 카운트 리셋
+            match self.connect_to_symbol_session(exchange_name, session_label, slot, &session, &mut control_rx, &mut pending_changes).await {
+                Ok(SessionExit::Resubscribe) => {
+                    info!("🔁 {} [{}세션 #{}] 구독 변경 반영을 위해 재연결", exchange_name, session_type, session_label);
+                    retry_count = 0; // 의도된 재연결은 재시도로 계산하지 않음
+                },
+                Ok(SessionExit::Shutdown) => {
+                    info!("🛑 {} [{}세션 #{}] 종료 신호로 세션 정리 완료", exchange_name, session_type, session_label);
+                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, snapshot.port).await;
+                    return Ok(());
+                },
+                Ok(SessionExit::Stopped) => {
+                    info!("🛑 {} [{}세션 #{}] 세션 종료", exchange_name, session_type, session_label);
+                    self.send_session_unsubscribed(exchange_name, &session).await;
+                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, snapshot.port).await;
+                    return Ok(());
                 },
                 Err(e) => {
-                    error!("❌ {} [{}세션 #{}] 연결 실패: {}", exchange_name, session_type, session_idx, e);
+                    error!("❌ {} [{}세션 #{}] 연결 실패: {}", exchange_name, session_type, session_label, e);
                     
                     retry_count += 1;
                     
                     if retry_count >= MAX_RETRY_COUNT {
                         error!("💀 {} [{}세션 #{}] 최대 재시도 횟수({}) 초과. 연결 포기", 
-                               exchange_name, session_type, session_idx, MAX_RETRY_COUNT);
+                               exchange_name, session_type, session_label, MAX_RETRY_COUNT);
                         // 상태 이벤트: FAILED (표시용 거래소명 그대로 기록)
-                        let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_RECONNECTING, CONNECTION_STATUS_FAILED, retry_count, 0, session.port).await;
+                        let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_RECONNECTING, CONNECTION_STATUS_FAILED, retry_count, 0, snapshot.port).await;
                         return Err(CryptoFeederError::Other(
                             format!("{} [{}세션 #{}] 연결 실패 - 최대 재시도 횟수 초과", 
-                                    exchange_name, session_type, session_idx)
+                                    exchange_name, session_type, session_label)
                         ));
                     }
                     
                     let delay = self.calculate_backoff_delay(retry_count - 1);
                     warn!("🔄 {}초 후 {} [{}세션 #{}] 재연결 시도", 
-                          delay.as_secs(), exchange_name, session_type, session_idx);
+                          delay.as_secs(), exchange_name, session_type, session_label);
                     // 상태 이벤트: RECONNECTING (표시용 거래소명 그대로 기록)
-                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_RECONNECTING, retry_count, 0, session.port).await;
+                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_RECONNECTING, retry_count, 0, snapshot.port).await;
                     
-                    time::sleep(delay).await;
+                    // 대기 중 종료 신호는 루프 시작에서 처리
+                    tokio::select! {
+                        _ = time::sleep(delay) => {}
+                        _ = self.shutdown.cancelled() => {}
+                    }
                 }
             }
         }
     }
 
     /// 심볼 세션에 대한 WebSocket 연결
-    async fn connect_to_symbol_session(&self, exchange_name: &str, session_idx: usize, session: &SymbolSession) -> Result<()> {
-        // endpoint.ini에서 거래소 엔드포인트 정보 가져오기
-        let ws_url = if let Some(endpoint_config) = &self.config.endpoint_config {
-            if let Some(endpoint) = endpoint_config.get_exchange_endpoint(exchange_name) {
-                if !endpoint.enabled {
-                    warn!("⚠️ {} 거래소가 비활성화됨. 연결을 건너뜁니다.", exchange_name);
-                    return Err(CryptoFeederError::Other(format!("{} 거래소가 비활성화됨", exchange_name)));
-                }
-                self.build_websocket_url_from_endpoint(endpoint, &session.symbols)?
-            } else {
-                warn!("⚠️ {} 거래소 엔드포인트 설정을 찾을 수 없음. 기본 URL 사용", exchange_name);
-                self.build_default_websocket_url(exchange_name, &session.symbols)?
-            }
-        } else {
-            warn!("⚠️ endpoint.ini 파일이 없음. 기본 URL 사용");
-            self.build_default_websocket_url(exchange_name, &session.symbols)?
-        };
+    async fn connect_to_symbol_session(
+        &self,
+        exchange_name: &str,
+        session_label: &str,
+        slot: usize,
+        session: &Mutex<SymbolSession>,
+        control_rx: &mut mpsc::UnboundedReceiver<SessionControl>,
+        pending_changes: &mut Vec<(String, u8)>,
+    ) -> Result<SessionExit> {
+        let snapshot = session.lock().unwrap().clone();
+        let port = snapshot.port;
+        let ws_url = self.resolve_session_url(exchange_name, &snapshot.symbols, snapshot.streams)?;
 
         let url = Url::parse(&ws_url)?;
-        info!("🚀 {} [세션 #{}] 연결 중: {}", exchange_name, session_idx, url);
+        let config = self.config();
+        let endpoint = config.endpoint_config.as_ref().and_then(|c| c.get_exchange_endpoint(exchange_name));
+        // endpoint.ini의 proxy/bind_addr/resolve 적용 (bind_addr는 세션별 순환 배정)
+        let options = ConnectOptions::for_session(endpoint, slot)?;
+        let mut keepalive = keepalive_timer(endpoint.and_then(|e| e.ping_interval_ms));
+        info!("🚀 {} [세션 #{}] 연결 중: {}{}{}", exchange_name, session_label, url,
+              options.proxy.as_ref().map(|p| format!(" (proxy {}:{})", p.host, p.port)).unwrap_or_default(),
+              options.bind_addr.map(|ip| format!(" (bind {})", ip)).unwrap_or_default());
 
-        // WebSocket 연결
-        let (ws_stream, response) = connect_async(url).await
-            .map_err(|e| CryptoFeederError::WebSocketError(e))?;
+        // WebSocket 연결 (연결 중 종료 신호 수신 시 바로 반환)
+        let (ws_stream, response) = tokio::select! {
+            result = connect_websocket(&url, &options) => result?,
+            _ = self.shutdown.cancelled() => return Ok(SessionExit::Shutdown),
+        };
 
         info!("🤝 {} [세션 #{}] WebSocket 연결 성공 (상태: {})", 
-              exchange_name, session_idx, response.status());
+              exchange_name, session_label, response.status());
+        // 상태 이벤트: CONNECTED (표시용 거래소명 그대로 기록)
+        let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTING, CONNECTION_STATUS_CONNECTED, 0, 0, port).await;
 
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+        let mut next_request_id: u64 = 1;
+        let mut pending_acks: Vec<PendingSubscription> = Vec::new();
 
         // 구독 메시지 전송 (거래소별로 다름)
-        if let Some(subscription_msg) = self.build_subscription_message(exchange_name, &session.symbols)? {
-            ws_sender.send(subscription_msg).await
-                .map_err(|e| CryptoFeederError::WebSocketError(e))?;
-            info!("📨 {} [세션 #{}] 구독 메시지 전송 완료", exchange_name, session_idx);
+        if let Some(frames) = self.build_subscription_message(exchange_name, &snapshot.symbols, snapshot.streams, next_request_id) {
+            for frame in &frames {
+                ws_sender.send(Message::Text(frame.text.clone())).await
+                    .map_err(CryptoFeederError::from)?;
+            }
+            next_request_id += frames.len() as u64;
+            let pending = PendingSubscription::new(snapshot.symbols.clone(), true, &frames);
+            if !pending.remaining_acks.is_empty() {
+                pending_acks.push(pending);
+            }
+            info!("📨 {} [세션 #{}] 구독 메시지 전송 완료 ({}건)", exchange_name, session_label, frames.len());
         }
+        self.journal_session_meta(exchange_name, session_label, session);
 
-        // 메시지 수신 루프
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    debug!("📥 {} [세션 #{}] 텍스트 메시지 수신: {} bytes", 
-                           exchange_name, session_idx, text.len());
-                    // 세션 포트로 전송
-                    if let Err(e) = self.process_and_send_to_port(exchange_name, text.into_bytes(), session.port).await {
-                        error!("❌ {} [세션 #{}] 메시지 처리 실패: {}", exchange_name, session_idx, e);
+        // 재연결로 반영된 구독 변경 확인
+        for (symbol, status) in pending_changes.drain(..) {
+            let _ = self.send_subscription_events_to_port(exchange_name, &symbol, status, port).await;
+        }
+
+        // 메시지 수신 루프 (WebSocket 메시지와 세션 제어 명령을 함께 대기)
+        loop {
+            tokio::select! {
+                msg = ws_receiver.next() => {
+                    let Some(msg) = msg else { break };
+                    let recv_timestamp_ns = now_nanos();
+                    match msg {
+                        Ok(Message::Text(text)) => {
+                            self.journal_frame(recv_timestamp_ns, exchange_name, session_label, port, false, text.as_bytes());
+                            debug!("📥 {} [세션 #{}] 텍스트 메시지 수신: {} bytes", 
+                                   exchange_name, session_label, text.len());
+                            if is_keepalive_reply(&text) {
+                                debug!("🏓 {} [세션 #{}] keepalive 응답 수신", exchange_name, session_label);
+                                continue;
+                            }
+                            // 구독 응답은 파서로 보내지 않고 SubscriptionStatus로 확인
+                            if !pending_acks.is_empty() {
+                                if let Some((ack_id, success)) = parse_subscription_ack(&text) {
+                                    if !success {
+                                        warn!("⚠️ {} [세션 #{}] 구독 실패 응답: {}", exchange_name, session_label, text);
+                                    }
+                                    if let Some((symbols, status)) = record_subscription_ack(&mut pending_acks, ack_id, success) {
+                                        info!("📬 {} [세션 #{}] 구독 응답 수신 완료: {:?} status={}", exchange_name, session_label, symbols, status);
+                                        for symbol in &symbols {
+                                            let _ = self.send_subscription_events_to_port(exchange_name, symbol, status, port).await;
+                                        }
+                                    }
+                                    continue;
+                                }
+                            }
+                            // 세션 포트로 전송
+                            if let Err(e) = self.process_and_send_to_port(exchange_name, text.into_bytes(), session, recv_timestamp_ns).await {
+                                error!("❌ {} [세션 #{}] 메시지 처리 실패: {}", exchange_name, session_label, e);
+                            }
+                        },
+                        Ok(Message::Binary(data)) => {
+                            self.journal_frame(recv_timestamp_ns, exchange_name, session_label, port, true, &data);
+                            debug!("📥 {} [세션 #{}] 바이너리 메시지 수신: {} bytes", 
+                                   exchange_name, session_label, data.len());
+                            if let Err(e) = self.process_and_send_to_port(exchange_name, data, session, recv_timestamp_ns).await {
+                                error!("❌ {} [세션 #{}] 메시지 처리 실패: {}", exchange_name, session_label, e);
+                            }
+                        },
+                        Ok(Message::Close(_)) => {
+                            info!("🔌 {} [세션 #{}] WebSocket 연결 종료됨", exchange_name, session_label);
+                            // 상태 이벤트: DISCONNECTED (표시용 거래소명 그대로 기록)
+                            let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, port).await;
+                            break;
+                        },
+                        Ok(Message::Ping(payload)) => {
+                            debug!("🏓 {} [세션 #{}] Ping 수신, Pong 응답", exchange_name, session_label);
+                            ws_sender.send(Message::Pong(payload)).await
+                                .map_err(CryptoFeederError::from)?;
+                        },
+                        Ok(Message::Pong(_)) => {
+                            debug!("🏓 {} [세션 #{}] Pong 수신", exchange_name, session_label);
+                        },
+                        Ok(Message::Frame(_)) => {
+                            // Frame 메시지는 일반적으로 내부적으로 처리되므로 무시
+                            debug!("🔧 {} [세션 #{}] Frame 메시지 수신 (무시)", exchange_name, session_label);
+                        },
+                        Err(e) => {
+                            error!("❌ {} [세션 #{}] WebSocket 오류: {}", exchange_name, session_label, e);
+                            // 상태 이벤트: DISCONNECTED (표시용 거래소명 그대로 기록)
+                            let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, port).await;
+                            return Err(e.into());
+                        }
                     }
-                },
-                Ok(Message::Binary(data)) => {
-                    debug!("📥 {} [세션 #{}] 바이너리 메시지 수신: {} bytes", 
-                           exchange_name, session_idx, data.len());
-                    if let Err(e) = self.process_and_send_to_port(exchange_name, data, session.port).await {
-                        error!("❌ {} [세션 #{}] 메시지 처리 실패: {}", exchange_name, session_idx, e);
+                }
+                _ = self.shutdown.cancelled() => {
+                    self.close_for_shutdown(exchange_name, session_label, &mut ws_sender, &mut ws_receiver, Some(session)).await;
+                    return Ok(SessionExit::Shutdown);
+                }
+                _ = keepalive_tick(&mut keepalive) => {
+                    debug!("🏓 {} [세션 #{}] keepalive 전송", exchange_name, session_label);
+                    ws_sender.send(keepalive_frame(exchange_name)).await
+                        .map_err(CryptoFeederError::from)?;
+                }
+                control = control_rx.recv() => {
+                    let control = match control {
+                        Some(SessionControl::Stop) | None => {
+                            let _ = ws_sender.send(Message::Close(None)).await;
+                            return Ok(SessionExit::Stopped);
+                        }
+                        Some(control) => control,
+                    };
+                    let (symbols, subscribe) = match &control {
+                        SessionControl::Subscribe(symbols) => (symbols.clone(), true),
+                        SessionControl::Unsubscribe(symbols) => (symbols.clone(), false),
+                        SessionControl::Stop => unreachable!(),
+                    };
+                    let streams = session.lock().unwrap().streams;
+                    let frames = build_subscription_frames(exchange_name, &symbols, streams, subscribe, next_request_id);
+                    match frames {
+                        Some(frames) => {
+                            // 거래소 SUBSCRIBE/UNSUBSCRIBE 프레임으로 즉시 반영
+                            for frame in &frames {
+                                ws_sender.send(Message::Text(frame.text.clone())).await
+                                    .map_err(CryptoFeederError::from)?;
+                            }
+                            apply_control_to_session(session, &control);
+                            self.journal_session_meta(exchange_name, session_label, session);
+                            next_request_id += frames.len() as u64;
+                            pending_acks.push(PendingSubscription::new(symbols, subscribe, &frames));
+                            info!("📨 {} [세션 #{}] 실시간 구독 변경 전송: {:?}", exchange_name, session_label, control);
+                        }
+                        None => {
+                            // 실시간 구독 변경을 지원하지 않는 거래소는 해당 세션만 재연결
+                            pending_changes.extend(apply_control_to_session(session, &control));
+                            let _ = ws_sender.send(Message::Close(None)).await;
+                            return Ok(SessionExit::Resubscribe);
+                        }
                     }
-                },
-                Ok(Message::Close(_)) => {
-                    info!("🔌 {} [세션 #{}] WebSocket 연결 종료됨", exchange_name, session_idx);
-                    // 상태 이벤트: DISCONNECTED (표시용 거래소명 그대로 기록)
-                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, session.port).await;
-                    break;
-                },
-                Ok(Message::Ping(payload)) => {
-                    debug!("🏓 {} [세션 #{}] Ping 수신, Pong 응답", exchange_name, session_idx);
-                    ws_sender.send(Message::Pong(payload)).await
-                        .map_err(|e| CryptoFeederError::WebSocketError(e))?;
-                },
-                Ok(Message::Pong(_)) => {
-                    debug!("🏓 {} [세션 #{}] Pong 수신", exchange_name, session_idx);
-                },
-                Ok(Message::Frame(_)) => {
-                    // Frame 메시지는 일반적으로 내부적으로 처리되므로 무시
-                    debug!("🔧 {} [세션 #{}] Frame 메시지 수신 (무시)", exchange_name, session_idx);
-                },
-                Err(e) => {
-                    error!("❌ {} [세션 #{}] WebSocket 오류: {}", exchange_name, session_idx, e);
-                    // 상태 이벤트: DISCONNECTED (표시용 거래소명 그대로 기록)
-                    let _ = self.send_connection_event_to_port(exchange_name, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_DISCONNECTED, 0, 0, session.port).await;
-                    return Err(CryptoFeederError::WebSocketError(e));
                 }
             }
         }
 
-        info!("🔚 {} [세션 #{}] 메시지 수신 루프 종료", exchange_name, session_idx);
+        info!("🔚 {} [세션 #{}] 메시지 수신 루프 종료", exchange_name, session_label);
         Err(CryptoFeederError::Other("WebSocket 연결이 예기치 않게 종료됨".to_string()))
     }
 
+    /// 종료 시 WebSocket Close 프레임을 보내고, 서버 Close 응답 전까지 이미 수신된 메시지를 UDP로 처리
+    async fn close_for_shutdown<S, R>(&self, exchange_name: &str, session_label: &str, sender: &mut S, receiver: &mut R, session: Option<&Mutex<SymbolSession>>)
+    where
+        S: Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+        R: Stream<Item = std::result::Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        const CLOSE_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+        info!("🛑 {} [세션 #{}] 종료 신호 수신, Close 프레임 전송", exchange_name, session_label);
+        let frame = CloseFrame { code: CloseCode::Normal, reason: "feeder shutting down".into() };
+        if let Err(e) = sender.send(Message::Close(Some(frame))).await {
+            warn!("⚠️ {} [세션 #{}] Close 프레임 전송 실패: {}", exchange_name, session_label, e);
+            return;
+        }
+
+        let port = session.map(|s| s.lock().unwrap().port);
+        let mut flushed = 0usize;
+        let drain = async {
+            while let Some(Ok(msg)) = receiver.next().await {
+                let recv_timestamp_ns = now_nanos();
+                let data = match msg {
+                    Message::Text(text) => {
+                        self.journal_frame(recv_timestamp_ns, exchange_name, session_label, port.unwrap_or(0), false, text.as_bytes());
+                        text.into_bytes()
+                    }
+                    Message::Binary(data) => {
+                        self.journal_frame(recv_timestamp_ns, exchange_name, session_label, port.unwrap_or(0), true, &data);
+                        data
+                    }
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let result = match session {
+                    Some(session) => self.process_and_send_to_port(exchange_name, data, session, recv_timestamp_ns).await,
+                    None => self.process_message(exchange_name, data).await,
+                };
+                if result.is_ok() {
+                    flushed += 1;
+                }
+            }
+        };
+        if time::timeout(CLOSE_DRAIN_TIMEOUT, drain).await.is_err() {
+            warn!("⚠️ {} [세션 #{}] Close 응답 대기 시간 초과", exchange_name, session_label);
+        }
+        info!("🔒 {} [세션 #{}] WebSocket 종료 완료 (종료 중 처리한 메시지 {}개)", exchange_name, session_label, flushed);
+    }
+
+    /// 종료 직전 각 세션 포트로 SystemShutdown 이벤트 전송 (`reason`은 `SHUTDOWN_REASON_*`)
+    pub async fn send_shutdown_events(&self, uptime_seconds: u64, reason: u32) -> Result<()> {
+        let total_packets_sent = self.udp_broadcaster.get_stats().packets_sent as u32;
+        let ports: BTreeSet<u16> = self.config().symbol_config.as_ref()
+            .map(|c| c.exchanges.values().flat_map(|g| g.sessions.iter().map(|s| s.port)).collect())
+            .unwrap_or_default();
+        let shutdown = SystemShutdown::new(uptime_seconds, total_packets_sent, reason);
+        if ports.is_empty() {
+            let packet = self.packet_builder.build_event_packet(SystemEvent::Shutdown(shutdown))?;
+            return self.udp_broadcaster.send_packet(packet).await;
+        }
+        for port in ports {
+            let packet = self.packet_builder.build_event_packet(SystemEvent::Shutdown(shutdown))?;
+            self.udp_broadcaster.send_packet_to_port(packet, port).await?;
+        }
+        Ok(())
+    }
+
+    /// endpoint.ini에서 거래소 엔드포인트 정보를 찾아 세션 WebSocket URL 생성 (연결 시 사용, 선택된 경로를 로그로 남김)
+    fn resolve_session_url(&self, exchange_name: &str, symbols: &[String], streams: SessionStreams) -> Result<String> {
+        match self.config().endpoint_config.as_ref().map(|c| c.get_exchange_endpoint(exchange_name)) {
+            Some(Some(endpoint)) if !endpoint.enabled => warn!("⚠️ {} 거래소가 비활성화됨. 연결을 건너뜁니다.", exchange_name),
+            Some(Some(_)) => info!("🔗 {} 세션 연결 준비: {} 심볼", exchange_name, symbols.len()),
+            Some(None) => warn!("⚠️ {} 거래소 엔드포인트 설정을 찾을 수 없음. 기본 URL 사용", exchange_name),
+            None => warn!("⚠️ endpoint.ini 파일이 없음. 기본 URL 사용"),
+        }
+        self.session_url(exchange_name, symbols, streams)
+    }
+
+    /// 세션 WebSocket URL 생성 (로그 없음, 세션 분할 계획의 URL 길이 계산에도 사용)
+    fn session_url(&self, exchange_name: &str, symbols: &[String], streams: SessionStreams) -> Result<String> {
+        match self.config().endpoint_config.as_ref().and_then(|c| c.get_exchange_endpoint(exchange_name)) {
+            Some(endpoint) if !endpoint.enabled => Err(CryptoFeederError::Other(format!("{} 거래소가 비활성화됨", exchange_name))),
+            Some(endpoint) => self.build_websocket_url_from_endpoint(endpoint, symbols, streams),
+            None => self.build_default_websocket_url(exchange_name, symbols, streams),
+        }
+    }
+
     /// 메시지를 파싱하여 세션 포트로 전송
-    async fn process_and_send_to_port(&self, exchange: &str, data: Vec<u8>, port: u16) -> Result<()> {
-        let parsed = self.data_parser.parse_message(exchange, data)?;
-        let packets = self.packet_builder.build_packets(parsed)?;
+    async fn process_and_send_to_port(&self, exchange: &str, data: Vec<u8>, session: &Mutex<SymbolSession>, recv_timestamp_ns: u64) -> Result<()> {
+        let (port, packets) = self.process_session_frame(exchange, data, session, recv_timestamp_ns)?;
         for packet in packets { self.udp_broadcaster.send_packet_to_port(packet, port).await?; }
         Ok(())
     }
 
-    /// endpoint.ini 설정을 기반으로 WebSocket URL 생성
-    fn build_websocket_url_from_endpoint(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        match endpoint.exchange_name.as_str() {
-            name if name.starts_with("Binance") => {
-                self.build_binance_websocket_url_from_endpoint(endpoint, symbols)
-            },
-            name if name.starts_with("Okx") => {
-                self.build_okx_websocket_url(endpoint, symbols)
-            },
-            name if name.starts_with("Bybit") => {
-                self.build_bybit_websocket_url(endpoint, symbols)
-            },
-            name if name.starts_with("Upbit") => {
-                self.build_upbit_websocket_url(endpoint, symbols)
-            },
-            name if name.starts_with("Bithumb") => {
-                self.build_bithumb_websocket_url(endpoint, symbols)
-            },
-            name if name.starts_with("Coinbase") => {
-                self.build_coinbase_websocket_url(endpoint, symbols)
-            },
-            _ => {
-                warn!("⚠️ {} 거래소의 상세 URL 생성 로직이 구현되지 않음. 기본 URL 사용", endpoint.exchange_name);
-                Ok(endpoint.ws_url_base.clone())
+    /// 세션 프레임 한 건을 처리 단계(`FeedStage`)에 넣고 (세션 포트, 전송할 패킷) 반환
+    ///
+    /// 세션 필터, 티커 병합, 로컬 봉, 파생 데이터 반영은 리플레이와 같은 경로를 거치며,
+    /// 파생 데이터 호가창이 스냅샷을 기다리면 REST depth 스냅샷 조회를 시작합니다.
+    fn process_session_frame(&self, exchange: &str, data: Vec<u8>, session: &Mutex<SymbolSession>, recv_timestamp_ns: u64) -> Result<(u16, Vec<UdpPacket>)> {
+        let (port, streams) = {
+            let session = session.lock().unwrap();
+            (session.port, session.streams)
+        };
+        let in_session = |symbol: &str| session.lock().unwrap().symbols.iter().any(|s| s == symbol);
+        let filter = SessionFilter { port, streams, in_session: &in_session };
+        let output = self.stage.process(exchange, data, Some(&filter), recv_timestamp_ns)?;
+        self.request_depth_snapshots(output.awaiting_snapshots);
+        Ok((port, output.packets))
+    }
+
+    /// 스냅샷을 기다리는 파생 데이터 호가창의 REST depth 스냅샷을 조회해 반영 (저널이 켜져 있으면 스냅샷도 기록)
+    fn request_depth_snapshots(&self, keys: Vec<(String, String)>) {
+        for (exchange, symbol) in keys {
+            if !self.depth_snapshots.lock().unwrap().try_request(&exchange, &symbol, now_nanos()) {
+                continue;
             }
+            let manager = self.clone();
+            tokio::spawn(async move {
+                match depth_snapshot::fetch(&manager.config(), &exchange, &symbol).await {
+                    Ok(snapshot) => {
+                        debug!("📚 {} {} 오더북 스냅샷 수신: lastUpdateId={:?}", exchange, symbol, snapshot.last_update_id);
+                        let recv_timestamp_ns = now_nanos();
+                        if let Some(journal) = &manager.journal {
+                            journal.record_depth_snapshot(recv_timestamp_ns, &exchange, &snapshot);
+                        }
+                        manager.stage.ingest_derived(&ParsedData::OrderBook(snapshot), recv_timestamp_ns);
+                    }
+                    Err(e) => warn!("⚠️ {} {} 오더북 스냅샷 조회 실패: {}", exchange, symbol, e),
+                }
+            });
         }
     }
 
+    /// endpoint.ini 설정을 기반으로 WebSocket URL 생성
+    fn build_websocket_url_from_endpoint(&self, endpoint: &ExchangeEndpoint, symbols: &[String], streams: SessionStreams) -> Result<String> {
+        if endpoint.exchange_name.starts_with("Binance") {
+            return self.build_binance_websocket_url_from_endpoint(endpoint, symbols, streams);
+        }
+        // OKX/Bybit/Upbit/Bithumb/Coinbase 등은 연결 후 구독 메시지로 심볼을 지정하므로 기본 URL만 사용
+        Ok(endpoint.ws_url_base.clone())
+    }
+
     /// 기본 WebSocket URL 생성 (endpoint.ini가 없을 때)
-    fn build_default_websocket_url(&self, exchange_name: &str, symbols: &[String]) -> Result<String> {
+    fn build_default_websocket_url(&self, exchange_name: &str, symbols: &[String], streams: SessionStreams) -> Result<String> {
         match exchange_name {
             "BinanceSpot" => {
-                self.build_binance_websocket_url_legacy("wss://stream.binance.com:9443/ws/", symbols)
+                self.build_binance_websocket_url_legacy("wss://stream.binance.com:9443/ws/", symbols, streams)
             },
             "BinanceFutures" => {
-                self.build_binance_websocket_url_legacy("wss://fstream.binance.com/ws/", symbols)
+                self.build_binance_websocket_url_legacy("wss://fstream.binance.com/ws/", symbols, streams)
             },
-            _ => {
-                warn!("⚠️ {} 거래소의 기본 URL을 찾을 수 없음. 임시 URL 사용", exchange_name);
-                Ok("wss://stream.binance.com:9443/ws/btcusdt@trade".to_string())
-            }
+            _ => Err(CryptoFeederError::Other(format!("{} 거래소의 기본 URL이 없음 (endpoint.ini 설정 필요)", exchange_name))),
         }
     }
 
     /// endpoint 설정을 사용한 Binance WebSocket URL 생성
-    fn build_binance_websocket_url_from_endpoint(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        self.build_binance_websocket_url_legacy(&endpoint.ws_url_base, symbols)
+    fn build_binance_websocket_url_from_endpoint(&self, endpoint: &ExchangeEndpoint, symbols: &[String], streams: SessionStreams) -> Result<String> {
+        self.build_binance_websocket_url_legacy(&endpoint.ws_url_base, symbols, streams)
     }
 
     /// 레거시 Binance WebSocket URL 생성 (기존 로직)
-    fn build_binance_websocket_url_legacy(&self, base_url: &str, symbols: &[String]) -> Result<String> {
+    fn build_binance_websocket_url_legacy(&self, base_url: &str, symbols: &[String], session_streams: SessionStreams) -> Result<String> {
         // Combined Stream: Use @trade for both, and use @depth@0ms for Futures
         let is_futures = base_url.contains("fstream.binance.com");
         let trade_topic = "trade";
@@ -445,9 +1165,29 @@ impl ConnectionManager {
             let binance_symbol = symbol.replace("^", "").to_lowercase();
             streams.push(format!("{}@{}", &binance_symbol, trade_topic));
             streams.push(format!("{}@{}", &binance_symbol, depth_topic));
-            if is_futures {
+        }
+        // Futures 부가 스트림은 체결/오더북 스트림 뒤에 배치 (전체 시장 청산 구독 시 심볼별 청산은 생략)
+        let market_wide_liquidations = is_futures && session_streams.liquidations == LiquidationScope::All;
+        if is_futures {
+            for symbol in symbols {
+                let binance_symbol = symbol.replace("^", "").to_lowercase();
                 streams.push(format!("{}@markPrice@1s", &binance_symbol));
-                streams.push(format!("{}@forceOrder", &binance_symbol));
+                if !market_wide_liquidations {
+                    streams.push(format!("{}@forceOrder", &binance_symbol));
+                }
+            }
+        }
+        if market_wide_liquidations {
+            streams.push("!forceOrder@arr".to_string());
+        }
+        // 세션 부가 스트림 (kline, ticker)
+        for symbol in symbols {
+            let binance_symbol = symbol.replace("^", "").to_lowercase();
+            if session_streams.klines.subscribes_exchange() {
+                streams.push(format!("{}@kline_1m", &binance_symbol));
+            }
+            if session_streams.ticker {
+                streams.push(format!("{}@ticker", &binance_symbol));
             }
         }
         let url = if base_url.ends_with("/ws/") {
@@ -466,79 +1206,295 @@ impl ConnectionManager {
         Ok(url)
     }
 
-    /// OKX WebSocket URL 생성
-    fn build_okx_websocket_url(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        // OKX는 구독 후 메시지로 심볼을 지정하므로 기본 URL만 사용
-        info!("🔗 OKX {} 세션 연결 준비: {} 심볼", endpoint.exchange_name, symbols.len());
-        for symbol in symbols {
-            debug!("   └─ 심볼: {}", symbol);
+    /// 거래소별 구독 메시지 생성
+    fn build_subscription_message(&self, exchange_name: &str, symbols: &[String], streams: SessionStreams, request_id: u64) -> Option<Vec<SubscriptionFrame>> {
+        match exchange_name {
+            "BinanceSpot" | "BinanceFutures" => {
+                // Binance는 URL에서 구독을 처리하므로 별도 메시지 불필요
+                None
+            },
+            name if name.starts_with("Okx") || name.starts_with("Bybit") => {
+                build_subscription_frames(exchange_name, symbols, streams, true, request_id)
+            },
+            name if name.starts_with("Upbit") => {
+                // Upbit은 구독 응답을 보내지 않음
+                Some(vec![SubscriptionFrame { request_id, text: build_upbit_subscription(symbols, request_id), expected_acks: 0 }])
+            },
+            _ => {
+                // 다른 거래소들은 나중에 구현
+                None
+            }
         }
-        Ok(endpoint.ws_url_base.clone())
     }
+}
 
-    /// Bybit WebSocket URL 생성  
-    fn build_bybit_websocket_url(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        // Bybit은 구독 후 메시지로 심볼을 지정하므로 기본 URL만 사용
-        info!("🔗 Bybit {} 세션 연결 준비: {} 심볼", endpoint.exchange_name, symbols.len());
-        for symbol in symbols {
-            debug!("   └─ 심볼: {}", symbol);
-        }
-        Ok(endpoint.ws_url_base.clone())
+/// Upbit 구독 요청 생성 (연결당 한 번, 체결/호가 타입의 마켓 코드 목록)
+///
+/// Upbit은 구독 해지 요청이 없고 새 요청이 이전 구독을 대체하므로 실시간 구독 변경은 세션 재연결로 처리합니다.
+/// 예: `[{"ticket":"crypto-feeder-1"},{"type":"trade","codes":["KRW-BTC"]},{"type":"orderbook","codes":["KRW-BTC"]},{"format":"DEFAULT"}]`
+pub fn build_upbit_subscription(symbols: &[String], request_id: u64) -> String {
+    // BTC^KRW -> KRW-BTC (quote가 앞)
+    let codes: Vec<String> = symbols.iter()
+        .map(|s| match s.split_once('^') {
+            Some((base, quote)) => format!("{}-{}", quote, base),
+            None => s.clone(),
+        })
+        .collect();
+    serde_json::json!([
+        { "ticket": format!("crypto-feeder-{}", request_id) },
+        { "type": "trade", "codes": codes },
+        { "type": "orderbook", "codes": codes },
+        { "format": "DEFAULT" },
+    ]).to_string()
+}
+
+/// 거래소로 보내는 구독 요청 프레임 한 건
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionFrame {
+    pub request_id: u64,
+    pub text: String,
+    /// 이 요청에 대해 거래소가 보내는 구독 응답 수 (OKX는 채널(arg)마다 응답, 0이면 응답 없음)
+    pub expected_acks: usize,
+}
+
+/// 실시간 SUBSCRIBE/UNSUBSCRIBE 프레임 생성. 지원하지 않는 거래소는 None (대상 세션 재연결로 대체)
+///
+/// `session_streams`에 따라 심볼마다 1분봉(`klines=exchange`)/24시간 티커 토픽을 함께 구독합니다.
+/// OKX `liquidation-orders`처럼 심볼과 무관한 전체 시장 채널은 구독 요청에만 포함합니다.
+/// 요청당 토픽 수 제한(`AdapterLimits::max_topics_per_request`, Bybit 10개)을 넘으면 여러 프레임으로 나누며,
+/// 요청 ID는 `first_request_id`부터 프레임마다 1씩 증가합니다.
+pub fn build_subscription_frames(exchange_name: &str, symbols: &[String], session_streams: SessionStreams, subscribe: bool, first_request_id: u64) -> Option<Vec<SubscriptionFrame>> {
+    let request_id = first_request_id;
+    match exchange_name {
+        name if name.starts_with("Binance") => {
+            let is_futures = name == "BinanceFutures";
+            let params: Vec<String> = symbols.iter()
+                .flat_map(|s| {
+                    let sym = s.replace('^', "").to_lowercase();
+                    let mut streams = vec![
+                        format!("{}@trade", sym),
+                        format!("{}@{}", sym, if is_futures { "depth@0ms" } else { "depth" }),
+                    ];
+                    if is_futures {
+                        streams.push(format!("{}@markPrice@1s", sym));
+                        // 전체 시장 청산(!forceOrder@arr) 세션은 연결 URL에서 이미 구독
+                        if session_streams.liquidations != LiquidationScope::All {
+                            streams.push(format!("{}@forceOrder", sym));
+                        }
+                    }
+                    if session_streams.klines.subscribes_exchange() {
+                        streams.push(format!("{}@kline_1m", sym));
+                    }
+                    if session_streams.ticker {
+                        streams.push(format!("{}@ticker", sym));
+                    }
+                    streams
+                })
+                .collect();
+            let method = if subscribe { "SUBSCRIBE" } else { "UNSUBSCRIBE" };
+            let text = serde_json::json!({ "method": method, "params": params, "id": request_id }).to_string();
+            Some(vec![SubscriptionFrame { request_id, text, expected_acks: 1 }])
+        },
+        name if name.starts_with("Okx") => {
+            let suffix = if name == "OkxSwap" { "-SWAP" } else { "" };
+            let mut args: Vec<serde_json::Value> = symbols.iter()
+                .flat_map(|s| {
+                    let inst_id = format!("{}{}", s.replace('^', "-"), suffix);
+                    let mut args = vec![
+                        serde_json::json!({ "channel": "trades", "instId": inst_id }),
+                        serde_json::json!({ "channel": "books", "instId": inst_id }),
+                    ];
+                    if session_streams.ticker {
+                        args.push(serde_json::json!({ "channel": "tickers", "instId": inst_id }));
+                    }
+                    if session_streams.open_interest && name == "OkxSwap" {
+                        args.push(serde_json::json!({ "channel": "open-interest", "instId": inst_id }));
+                    }
+                    if session_streams.funding && name == "OkxSwap" {
+                        args.push(serde_json::json!({ "channel": "funding-rate", "instId": inst_id }));
+                    }
+                    args
+                })
+                .collect();
+            // liquidation-orders는 instType 단위 채널이므로 구독 요청에만 한 번 포함 (심볼 해지로 끊지 않음)
+            if subscribe && session_streams.market_wide_topics(name) > 0 {
+                args.push(serde_json::json!({ "channel": "liquidation-orders", "instType": "SWAP" }));
+            }
+            let op = if subscribe { "subscribe" } else { "unsubscribe" };
+            let expected_acks = args.len();
+            let text = serde_json::json!({ "id": request_id.to_string(), "op": op, "args": args }).to_string();
+            Some(vec![SubscriptionFrame { request_id, text, expected_acks }])
+        },
+        name if name.starts_with("Bybit") => {
+            // 파생상품 tickers 토픽은 미결제약정/펀딩비를 함께 제공
+            let tickers = session_streams.ticker
+                || ((session_streams.open_interest || session_streams.funding) && name != "BybitSpot");
+            let args: Vec<String> = symbols.iter()
+                .flat_map(|s| {
+                    let sym = s.replace('^', "");
+                    let mut args = vec![format!("publicTrade.{}", sym), format!("orderbook.50.{}", sym)];
+                    if session_streams.klines.subscribes_exchange() {
+                        args.push(format!("kline.1.{}", sym));
+                    }
+                    if tickers {
+                        args.push(format!("tickers.{}", sym));
+                    }
+                    // Bybit는 전체 시장 청산 토픽이 없으므로 all도 심볼별 구독
+                    if session_streams.liquidations != LiquidationScope::Off && name != "BybitSpot" {
+                        args.push(format!("allLiquidation.{}", sym));
+                    }
+                    args
+                })
+                .collect();
+            let op = if subscribe { "subscribe" } else { "unsubscribe" };
+            let per_request = AdapterLimits::for_exchange(name).max_topics_per_request.unwrap_or(args.len()).max(1);
+            let frames = args.chunks(per_request)
+                .zip(request_id..)
+                .map(|(chunk, request_id)| {
+                    let text = serde_json::json!({ "req_id": request_id.to_string(), "op": op, "args": chunk }).to_string();
+                    SubscriptionFrame { request_id, text, expected_acks: 1 }
+                })
+                .collect();
+            Some(frames)
+        },
+        _ => None,
     }
+}
 
-    /// Upbit WebSocket URL 생성
-    fn build_upbit_websocket_url(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        // Upbit은 연결 후 별도 구독 메시지로 심볼 지정
-        info!("🔗 Upbit {} 세션 연결 준비: {} 심볼", endpoint.exchange_name, symbols.len());
-        for symbol in symbols {
-            debug!("   └─ 심볼: {}", symbol);
-        }
-        Ok(endpoint.ws_url_base.clone())
+/// 구독 응답 메시지 판별. (요청 ID, 성공 여부) 반환, 구독 응답이 아니면 None
+///
+/// - Binance: `{"result":null,"id":1}` / `{"error":{...},"id":1}`
+/// - OKX: `{"event":"subscribe","arg":{...},"id":"1"}` / `{"event":"error",...}`
+/// - Bybit: `{"success":true,"op":"subscribe","req_id":"1",...}`
+fn parse_subscription_ack(text: &str) -> Option<(Option<u64>, bool)> {
+    // 시장 데이터 메시지는 크고 구독 응답은 작으므로 길이로 먼저 거름
+    if text.len() > 1024 {
+        return None;
     }
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let id_of = |v: &serde_json::Value| v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok()));
 
-    /// Bithumb WebSocket URL 생성
-    fn build_bithumb_websocket_url(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        // Bithumb은 연결 후 별도 구독 메시지로 심볼 지정
-        info!("🔗 Bithumb {} 세션 연결 준비: {} 심볼", endpoint.exchange_name, symbols.len());
-        for symbol in symbols {
-            debug!("   └─ 심볼: {}", symbol);
+    if value.get("result").is_some() || (value.get("error").is_some() && value.get("id").is_some()) {
+        let id = value.get("id").and_then(id_of);
+        return Some((id, value.get("error").is_none()));
+    }
+    if let Some(event) = value.get("event").and_then(|v| v.as_str()) {
+        if matches!(event, "subscribe" | "unsubscribe" | "error") {
+            return Some((value.get("id").and_then(id_of), event != "error"));
         }
-        Ok(endpoint.ws_url_base.clone())
     }
-
-    /// Coinbase WebSocket URL 생성
-    fn build_coinbase_websocket_url(&self, endpoint: &ExchangeEndpoint, symbols: &[String]) -> Result<String> {
-        // Coinbase는 연결 후 별도 구독 메시지로 심볼 지정
-        info!("🔗 Coinbase {} 세션 연결 준비: {} 심볼", endpoint.exchange_name, symbols.len());
-        for symbol in symbols {
-            debug!("   └─ 심볼: {}", symbol);
+    if let Some(op) = value.get("op").and_then(|v| v.as_str()) {
+        if matches!(op, "subscribe" | "unsubscribe") {
+            let success = value.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+            return Some((value.get("req_id").and_then(id_of), success));
         }
-        Ok(endpoint.ws_url_base.clone())
     }
+    None
+}
 
-/// 거래소별 구독 메시지 생성
-fn build_subscription_message(&self, exchange_name: &str, _symbols: &[String]) -> Result<Option<Message>> {
-        match exchange_name {
-            "BinanceSpot" | "BinanceFutures" => {
-                // Binance는 URL에서 구독을 처리하므로 별도 메시지 불필요
-                Ok(None)
-            },
-            _ => {
-                // 다른 거래소들은 나중에 구현
-                Ok(None)
+/// 연결 유지용 keepalive 프레임 (OKX는 텍스트 `ping`, Bybit은 `{"op":"ping"}`, 그 외는 WebSocket Ping)
+fn keepalive_frame(exchange_name: &str) -> Message {
+    match exchange_name {
+        name if name.starts_with("Okx") => Message::Text("ping".to_string()),
+        name if name.starts_with("Bybit") => Message::Text(r#"{"op":"ping"}"#.to_string()),
+        _ => Message::Ping(Vec::new()),
+    }
+}
+
+/// keepalive 응답 여부 (OKX `pong`, Bybit `"op":"ping"`/`"op":"pong"` 응답은 파서로 보내지 않음)
+fn is_keepalive_reply(text: &str) -> bool {
+    if text == "pong" {
+        return true;
+    }
+    if text.len() > 512 || !text.contains("\"op\"") {
+        return false;
+    }
+    serde_json::from_str::<serde_json::Value>(text).ok()
+        .and_then(|v| v.get("op").and_then(|op| op.as_str()).map(|op| matches!(op, "ping" | "pong")))
+        .unwrap_or(false)
+}
+
+/// `ping_interval_ms` 주기의 keepalive 타이머 (미지정/0이면 None, 첫 전송은 한 주기 뒤)
+fn keepalive_timer(ping_interval_ms: Option<u64>) -> Option<time::Interval> {
+    let period = Duration::from_millis(ping_interval_ms.filter(|ms| *ms > 0)?);
+    let mut timer = time::interval_at(time::Instant::now() + period, period);
+    timer.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+    Some(timer)
+}
+
+/// keepalive 타이머 대기 (타이머가 없으면 영원히 대기)
+async fn keepalive_tick(timer: &mut Option<time::Interval>) {
+    match timer {
+        Some(timer) => { timer.tick().await; }
+        None => std::future::pending().await,
+    }
+}
+
+/// 제어 메시지를 세션 심볼 목록에 반영하고 실제로 바뀐 (심볼, 구독 상태) 목록을 반환
+fn apply_control_to_session(session: &Mutex<SymbolSession>, control: &SessionControl) -> Vec<(String, u8)> {
+    let mut session = session.lock().unwrap();
+    let mut changed = Vec::new();
+    match control {
+        SessionControl::Subscribe(symbols) => {
+            for symbol in symbols {
+                if !session.symbols.contains(symbol) {
+                    session.symbols.push(symbol.clone());
+                    changed.push((symbol.clone(), SUBSCRIPTION_STATUS_SUBSCRIBED));
+                }
             }
         }
+        SessionControl::Unsubscribe(symbols) => {
+            for symbol in symbols {
+                if let Some(pos) = session.symbols.iter().position(|s| s == symbol) {
+                    session.symbols.remove(pos);
+                    changed.push((symbol.clone(), SUBSCRIPTION_STATUS_UNSUBSCRIBED));
+                }
+            }
+        }
+        SessionControl::Stop => {}
     }
+    session.is_btc_session = session.symbols.len() == 1 && session.symbols[0].starts_with("BTC^");
+    changed
 }
 
 impl ConnectionManager {
-    async fn send_connection_event(&self, exchange_name: &str, previous_status: u8, current_status: u8, retry_count: u32, error_code: u64) -> Result<()> {
+    /// 종료되는 세션의 모든 심볼에 대해 UNSUBSCRIBED 상태 이벤트 전송
+    async fn send_session_unsubscribed(&self, exchange_name: &str, session: &Mutex<SymbolSession>) {
+        let (symbols, port) = {
+            let session = session.lock().unwrap();
+            (session.symbols.clone(), session.port)
+        };
+        for symbol in &symbols {
+            let _ = self.send_subscription_events_to_port(exchange_name, symbol, SUBSCRIPTION_STATUS_UNSUBSCRIBED, port).await;
+        }
+    }
+
+    /// 심볼 구독 상태 이벤트를 세션 포트로 전송 (오더북/체결 구독 각각)
+    async fn send_subscription_events_to_port(&self, exchange_name: &str, symbol: &str, status: u8, port: u16) -> Result<()> {
         let exchange_id = infer_exchange_id_from_display(exchange_name);
-        let event = SystemEvent::ConnectionStatus(ConnectionStatus::new(exchange_id, previous_status, current_status, retry_count, error_code));
-        // 표시용 문자열(ex: BinanceSpot/BinanceFutures)을 그대로 헤더에 기록
-        let packet = self.packet_builder.build_event_packet_with_exchange(event, exchange_name)?;
-        // 심볼 세션 컨텍스트가 없으므로 레거시 포트로 전송
-        self.udp_broadcaster.send_packet(packet).await
+        for subscription_type in [SUBSCRIPTION_TYPE_ORDER_BOOK, SUBSCRIPTION_TYPE_TRADE] {
+            let event = SystemEvent::SubscriptionStatus(SubscriptionStatus::new(exchange_id, subscription_type, status, symbol));
+            let packet = self.packet_builder.build_event_packet_with_exchange(event, exchange_name)?;
+            self.udp_broadcaster.send_packet_to_port(packet, port).await?;
+        }
+        Ok(())
+    }
+
+    /// 오류 이벤트를 현재 설정의 모든 세션 포트로 전송 (세션 설정이 없으면 레거시 포트)
+    async fn send_error_event_to_session_ports(&self, error_type: u32, exchange_id: u16, severity: u16, error_details: u64) -> Result<()> {
+        let ports: BTreeSet<u16> = self.config().symbol_config.as_ref()
+            .map(|c| c.exchanges.values().flat_map(|g| g.sessions.iter().map(|s| s.port)).collect())
+            .unwrap_or_default();
+        let event = SystemEvent::ErrorEvent(ErrorEvent::new(error_type, exchange_id, severity, error_details));
+        if ports.is_empty() {
+            let packet = self.packet_builder.build_event_packet(event)?;
+            return self.udp_broadcaster.send_packet(packet).await;
+        }
+        for port in ports {
+            let packet = self.packet_builder.build_event_packet(event.clone())?;
+            self.udp_broadcaster.send_packet_to_port(packet, port).await?;
+        }
+        Ok(())
     }
 
     async fn send_connection_event_to_port(&self, exchange_name: &str, previous_status: u8, current_status: u8, retry_count: u32, error_code: u64, port: u16) -> Result<()> {
@@ -568,6 +1524,14 @@ impl Clone for ConnectionManager {
             data_parser: Arc::clone(&self.data_parser),
             packet_builder: Arc::clone(&self.packet_builder),
             udp_broadcaster: Arc::clone(&self.udp_broadcaster),
+            sessions: Arc::clone(&self.sessions),
+            runtime_session_counter: Arc::clone(&self.runtime_session_counter),
+            session_id_counter: Arc::clone(&self.session_id_counter),
+            exchange_slot_counters: Arc::clone(&self.exchange_slot_counters),
+            shutdown: self.shutdown.clone(),
+            journal: self.journal.clone(),
+            stage: Arc::clone(&self.stage),
+            depth_snapshots: Arc::clone(&self.depth_snapshots),
         }
     }
 }
@@ -575,7 +1539,7 @@ impl Clone for ConnectionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::ExchangeEndpoint;
+    use crate::config::{ExchangeEndpoint, KlineSource};
 
     #[test]
     fn test_backoff_delay_calculation() {
@@ -594,6 +1558,22 @@ mod tests {
         assert_eq!(manager.calculate_backoff_delay(10), Duration::from_secs(60)); // 최대치
     }
 
+    #[test]
+    fn test_exchange_slots_are_per_exchange() {
+        let config = Arc::new(Config::load().unwrap());
+        let data_parser = Arc::new(DataParser::new());
+        let packet_builder = Arc::new(PacketBuilder::new());
+        let udp_broadcaster = Arc::new(UdpMulticaster::new(&config.udp).unwrap());
+        let manager = ConnectionManager::new(config, data_parser, packet_builder, udp_broadcaster);
+
+        // 다른 거래소 세션이 끼어들어도 거래소별 순번은 0부터 연속
+        assert_eq!(manager.next_exchange_slot("BinanceSpot"), 0);
+        assert_eq!(manager.next_exchange_slot("OKX"), 0);
+        assert_eq!(manager.next_exchange_slot("OKX"), 1);
+        assert_eq!(manager.next_exchange_slot("BinanceSpot"), 1);
+        assert_eq!(manager.clone().next_exchange_slot("BinanceSpot"), 2);
+    }
+
     #[test]
     fn test_binance_combined_stream_url_spot() {
         let config = Arc::new(Config::load().unwrap());
@@ -608,8 +1588,15 @@ mod tests {
             timeout_ms: 5000,
             ping_interval_ms: Some(30000),
             enabled: true,
+            max_streams_per_connection: None,
+            max_subscriptions_per_connection: None,
+            max_url_length: None,
+            proxy: None,
+            bind_addrs: vec![],
+            resolve: vec![],
+            rest_url_base: None,
         };
-        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &vec!["BTC^USDT".into(), "ETH^USDT".into()]).unwrap();
+        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &["BTC^USDT".into(), "ETH^USDT".into()], SessionStreams::default()).unwrap();
         assert!(url.contains("wss://stream.binance.com:9443/stream?streams="));
         assert!(url.contains("btcusdt@trade/btcusdt@depth/ethusdt@trade/ethusdt@depth"));
     }
@@ -628,9 +1615,308 @@ mod tests {
             timeout_ms: 5000,
             ping_interval_ms: Some(30000),
             enabled: true,
+            max_streams_per_connection: None,
+            max_subscriptions_per_connection: None,
+            max_url_length: None,
+            proxy: None,
+            bind_addrs: vec![],
+            resolve: vec![],
+            rest_url_base: None,
         };
-        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &vec!["BTC^USDT".into(), "ETH^USDT".into()]).unwrap();
+        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &["BTC^USDT".into(), "ETH^USDT".into()], SessionStreams::default()).unwrap();
         assert!(url.contains("wss://fstream.binance.com/stream?streams="));
         assert!(url.contains("btcusdt@trade/btcusdt@depth@0ms/ethusdt@trade/ethusdt@depth@0ms"));
+        assert!(!url.contains("@kline_1m"));
+
+        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &["BTC^USDT".into()], SessionStreams { klines: KlineSource::Exchange, ticker: true, ..SessionStreams::default() }).unwrap();
+        assert!(url.ends_with("btcusdt@forceOrder/btcusdt@kline_1m/btcusdt@ticker"));
+
+        // 전체 시장 청산: 심볼별 forceOrder 대신 !forceOrder@arr, 심볼 없는 전용 세션 가능
+        let all = SessionStreams { liquidations: LiquidationScope::All, ..SessionStreams::default() };
+        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &["BTC^USDT".into()], all).unwrap();
+        assert!(url.ends_with("btcusdt@markPrice@1s/!forceOrder@arr"));
+        let url = manager.build_binance_websocket_url_from_endpoint(&endpoint, &[], all).unwrap();
+        assert_eq!(url, "wss://fstream.binance.com/stream?streams=!forceOrder@arr");
     }
-}
\ No newline at end of file
+
+    /// 프레임 하나로 만들어지는 구독 요청을 JSON으로 반환
+    fn single_frame(exchange_name: &str, symbols: &[String], streams: SessionStreams, subscribe: bool, request_id: u64) -> serde_json::Value {
+        let frames = build_subscription_frames(exchange_name, symbols, streams, subscribe, request_id).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].request_id, request_id);
+        serde_json::from_str(&frames[0].text).unwrap()
+    }
+
+    #[test]
+    fn test_binance_live_subscription_frame() {
+        let v = single_frame("BinanceFutures", &["SOL^USDT".into()], SessionStreams::default(), true, 7);
+        assert_eq!(v["method"], "SUBSCRIBE");
+        assert_eq!(v["id"], 7);
+        assert_eq!(v["params"][0], "solusdt@trade");
+        assert_eq!(v["params"][1], "solusdt@depth@0ms");
+        assert_eq!(v["params"].as_array().unwrap().len(), 4);
+
+        let v = single_frame("BinanceSpot", &["SOL^USDT".into()], SessionStreams::default(), false, 8);
+        assert_eq!(v["method"], "UNSUBSCRIBE");
+        assert_eq!(v["params"][1], "solusdt@depth");
+    }
+
+    #[test]
+    fn test_okx_and_bybit_live_subscription_frames() {
+        let v = single_frame("OkxSwap", &["BTC^USDT".into()], SessionStreams::default(), true, 3);
+        assert_eq!(v["op"], "subscribe");
+        assert_eq!(v["id"], "3");
+        assert_eq!(v["args"][0]["channel"], "trades");
+        assert_eq!(v["args"][0]["instId"], "BTC-USDT-SWAP");
+
+        let v = single_frame("BybitLinear", &["ETH^USDT".into()], SessionStreams::default(), false, 4);
+        assert_eq!(v["op"], "unsubscribe");
+        assert_eq!(v["req_id"], "4");
+        assert_eq!(v["args"][0], "publicTrade.ETHUSDT");
+        assert_eq!(v["args"][1], "orderbook.50.ETHUSDT");
+
+        // 실시간 구독 변경 미지원 거래소는 재연결로 대체
+        assert!(build_subscription_frames("UpbitSpot", &["BTC^KRW".into()], SessionStreams::default(), true, 1).is_none());
+        let upbit: serde_json::Value = serde_json::from_str(&build_upbit_subscription(&["BTC^KRW".into(), "USDT^KRW".into()], 1)).unwrap();
+        assert_eq!(upbit[0]["ticket"], "crypto-feeder-1");
+        assert_eq!((upbit[1]["type"].as_str(), upbit[2]["type"].as_str()), (Some("trade"), Some("orderbook")));
+        assert_eq!(upbit[2]["codes"], serde_json::json!(["KRW-BTC", "KRW-USDT"]));
+    }
+
+    #[test]
+    fn test_session_stream_subscription_topics() {
+        let v = single_frame("OkxSpot", &["BTC^USDT".into()], SessionStreams { ticker: true, ..SessionStreams::default() }, true, 1);
+        assert_eq!(v["args"][2]["channel"], "tickers");
+        assert_eq!(v["args"][2]["instId"], "BTC-USDT");
+
+        let v = single_frame("BybitSpot", &["ETH^USDT".into()], SessionStreams { klines: KlineSource::Exchange, ticker: false, ..SessionStreams::default() }, true, 2);
+        assert_eq!(v["args"][2], "kline.1.ETHUSDT");
+
+        // 로컬 집계는 추가 구독 없음
+        let v = single_frame("BinanceSpot", &["SOL^USDT".into()], SessionStreams { klines: KlineSource::Local, ticker: false, ..SessionStreams::default() }, true, 3);
+        assert_eq!(v["params"].as_array().unwrap().len(), 2);
+
+        // 미결제약정: OKX 파생은 open-interest 채널, Bybit 파생은 tickers 토픽, 현물은 추가 구독 없음
+        let open_interest = SessionStreams { open_interest: true, ..SessionStreams::default() };
+        let v = single_frame("OkxSwap", &["BTC^USDT".into()], open_interest, true, 4);
+        assert_eq!(v["args"][2]["channel"], "open-interest");
+        let v = single_frame("BybitLinear", &["ETH^USDT".into()], open_interest, true, 5);
+        assert_eq!(v["args"][2], "tickers.ETHUSDT");
+        let funding = SessionStreams { funding: true, ..SessionStreams::default() };
+        let v = single_frame("OkxSwap", &["BTC^USDT".into()], funding, true, 7);
+        assert_eq!(v["args"][2]["channel"], "funding-rate");
+        let v = single_frame("BybitSpot", &["ETH^USDT".into()], open_interest, true, 6);
+        assert_eq!(v["args"].as_array().unwrap().len(), 2);
+
+        // 청산: OKX 무기한은 instType 채널을 구독 요청에만 한 번, Bybit 파생은 심볼별 allLiquidation
+        let liquidations = SessionStreams { liquidations: LiquidationScope::Symbols, ..SessionStreams::default() };
+        let v = single_frame("OkxSwap", &["BTC^USDT".into(), "ETH^USDT".into()], liquidations, true, 8);
+        assert_eq!(v["args"].as_array().unwrap().len(), 5);
+        assert_eq!(v["args"][4], serde_json::json!({ "channel": "liquidation-orders", "instType": "SWAP" }));
+        let v = single_frame("OkxSwap", &["BTC^USDT".into()], liquidations, false, 9);
+        assert_eq!(v["args"].as_array().unwrap().len(), 2);
+        let v = single_frame("BybitLinear", &["ETH^USDT".into()], liquidations, true, 10);
+        assert_eq!(v["args"][2], "allLiquidation.ETHUSDT");
+        let all = SessionStreams { liquidations: LiquidationScope::All, ..SessionStreams::default() };
+        let v = single_frame("BinanceFutures", &["SOL^USDT".into()], all, true, 11);
+        assert_eq!(v["params"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_subscription_ack() {
+        assert_eq!(parse_subscription_ack(r#"{"result":null,"id":5}"#), Some((Some(5), true)));
+        assert_eq!(parse_subscription_ack(r#"{"error":{"code":2,"msg":"Invalid request"},"id":6}"#), Some((Some(6), false)));
+        assert_eq!(parse_subscription_ack(r#"{"event":"subscribe","arg":{"channel":"trades","instId":"BTC-USDT"},"id":"2","connId":"a"}"#), Some((Some(2), true)));
+        assert_eq!(parse_subscription_ack(r#"{"event":"error","msg":"bad","code":"60018","connId":"a"}"#), Some((None, false)));
+        assert_eq!(parse_subscription_ack(r#"{"success":true,"ret_msg":"","conn_id":"x","req_id":"9","op":"subscribe"}"#), Some((Some(9), true)));
+        // 시장 데이터 메시지는 구독 응답이 아님
+        assert_eq!(parse_subscription_ack(r#"{"stream":"btcusdt@trade","data":{"e":"trade"}}"#), None);
+    }
+
+    #[test]
+    fn test_subscription_status_after_every_ack() {
+        // OKX는 채널마다 응답하므로 trades/books 두 응답을 모두 받은 뒤에 상태 확정
+        let frames = build_subscription_frames("OkxSpot", &["BTC^USDT".into()], SessionStreams::default(), true, 1).unwrap();
+        assert_eq!(frames[0].expected_acks, 2);
+        let mut pending = vec![PendingSubscription::new(vec!["BTC^USDT".into()], true, &frames)];
+        assert_eq!(record_subscription_ack(&mut pending, Some(1), true), None);
+        assert_eq!(record_subscription_ack(&mut pending, Some(1), true), Some((vec!["BTC^USDT".to_string()], SUBSCRIPTION_STATUS_SUBSCRIBED)));
+        assert!(pending.is_empty());
+        // 대기 중이 아닌 요청의 응답은 무시
+        assert_eq!(record_subscription_ack(&mut pending, Some(1), true), None);
+
+        // 실패 응답은 요청을 끝내고 FAILED, ID 없는 응답은 가장 먼저 보낸 요청에 대응
+        let frames = build_subscription_frames("OkxSpot", &["ETH^USDT".into()], SessionStreams::default(), false, 2).unwrap();
+        pending.push(PendingSubscription::new(vec!["ETH^USDT".into()], false, &frames));
+        let frames = build_subscription_frames("OkxSpot", &["SOL^USDT".into()], SessionStreams::default(), true, 3).unwrap();
+        pending.push(PendingSubscription::new(vec!["SOL^USDT".into()], true, &frames));
+        assert_eq!(record_subscription_ack(&mut pending, None, false), Some((vec!["ETH^USDT".to_string()], SUBSCRIPTION_STATUS_FAILED)));
+        assert_eq!(record_subscription_ack(&mut pending, Some(3), true), None);
+        assert_eq!(record_subscription_ack(&mut pending, Some(3), true), Some((vec!["SOL^USDT".to_string()], SUBSCRIPTION_STATUS_SUBSCRIBED)));
+    }
+
+    #[test]
+    fn test_bybit_subscription_split_per_request() {
+        // 심볼 4개 x (publicTrade, orderbook, kline, tickers) = 16 토픽 -> 요청 2건 (10 + 6)
+        let symbols: Vec<String> = ["BTC^USDT", "ETH^USDT", "SOL^USDT", "XRP^USDT"].iter().map(|s| s.to_string()).collect();
+        let streams = SessionStreams { klines: KlineSource::Exchange, ticker: true, ..SessionStreams::default() };
+        let frames = build_subscription_frames("BybitSpot", &symbols, streams, true, 5).unwrap();
+        assert_eq!(frames.iter().map(|f| f.request_id).collect::<Vec<_>>(), vec![5, 6]);
+        let args: Vec<usize> = frames.iter()
+            .map(|f| serde_json::from_str::<serde_json::Value>(&f.text).unwrap()["args"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(args, vec![10, 6]);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&frames[1].text).unwrap()["req_id"], "6");
+
+        // 두 요청 모두 응답해야 상태 확정
+        let mut pending = vec![PendingSubscription::new(symbols.clone(), true, &frames)];
+        assert_eq!(record_subscription_ack(&mut pending, Some(6), true), None);
+        assert_eq!(record_subscription_ack(&mut pending, Some(5), true), Some((symbols, SUBSCRIPTION_STATUS_SUBSCRIBED)));
+    }
+
+    #[test]
+    fn test_keepalive_frames_and_replies() {
+        assert_eq!(keepalive_frame("OkxSwap"), Message::Text("ping".to_string()));
+        assert_eq!(keepalive_frame("BybitLinear"), Message::Text(r#"{"op":"ping"}"#.to_string()));
+        assert_eq!(keepalive_frame("BinanceSpot"), Message::Ping(Vec::new()));
+
+        assert!(is_keepalive_reply("pong"));
+        assert!(is_keepalive_reply(r#"{"success":true,"ret_msg":"pong","conn_id":"abc","op":"ping"}"#));
+        assert!(is_keepalive_reply(r#"{"req_id":"1","op":"pong","args":["1700000000000"]}"#));
+        assert!(!is_keepalive_reply(r#"{"success":true,"ret_msg":"","op":"subscribe"}"#));
+        assert!(!is_keepalive_reply(r#"{"arg":{"channel":"trades"},"data":[]}"#));
+
+        assert!(keepalive_timer(None).is_none());
+        assert!(keepalive_timer(Some(0)).is_none());
+    }
+
+    #[test]
+    fn test_apply_control_to_session() {
+        let session = Mutex::new(SymbolSession { symbols: vec!["BTC^USDT".into()], is_btc_session: true, port: 55559, streams: SessionStreams::default() });
+        let changed = apply_control_to_session(&session, &SessionControl::Subscribe(vec!["ETH^USDT".into(), "BTC^USDT".into()]));
+        assert_eq!(changed, vec![("ETH^USDT".to_string(), SUBSCRIPTION_STATUS_SUBSCRIBED)]);
+        assert!(!session.lock().unwrap().is_btc_session);
+
+        let changed = apply_control_to_session(&session, &SessionControl::Unsubscribe(vec!["ETH^USDT".into()]));
+        assert_eq!(changed, vec![("ETH^USDT".to_string(), SUBSCRIPTION_STATUS_UNSUBSCRIBED)]);
+        assert_eq!(session.lock().unwrap().symbols, vec!["BTC^USDT"]);
+        assert!(session.lock().unwrap().is_btc_session);
+    }
+
+    #[test]
+    fn test_replay_matches_live_session_path() {
+        use crate::journal::JournalRecord;
+        use crate::replay::{ReplayOptions, ReplaySpeed, Replayer};
+
+        let trade = |id: u32, ts: u64| format!(r#"{{"arg":{{"channel":"trades","instId":"BTC-USDT-SWAP"}},"data":[{{"instId":"BTC-USDT-SWAP","tradeId":"{}","px":"50000.5","sz":"2","side":"buy","ts":"{}"}}]}}"#, id, ts);
+        let funding = r#"{"arg":{"channel":"funding-rate","instId":"BTC-USDT-SWAP"},"data":[{"instType":"SWAP","instId":"BTC-USDT-SWAP","fundingRate":"0.0001","nextFundingRate":"","fundingTime":"1700006400000","ts":"1700000000000"}]}"#;
+        // 두 번째 체결이 다음 분으로 넘어가 로컬 1분봉이 확정되고, 세션에서 끈 펀딩비는 버려짐
+        let frames = [(1_700_000_000_100_000_000u64, trade(1, 1_700_000_000_000)), (1_700_000_030_000_000_000, funding.to_string()), (1_700_000_061_000_000_000, trade(2, 1_700_000_061_000))];
+        let streams = SessionStreams { klines: KlineSource::Local, ..SessionStreams::default() };
+        let config = Arc::new(Config::load().unwrap());
+
+        let data_parser = Arc::new(DataParser::new());
+        data_parser.register_exchange("OkxSwap");
+        let packet_builder = Arc::new(PacketBuilder::new());
+        let udp_broadcaster = Arc::new(UdpMulticaster::new(&config.udp).unwrap());
+        let manager = ConnectionManager::new(Arc::clone(&config), data_parser, Arc::clone(&packet_builder), udp_broadcaster);
+        let session = Mutex::new(SymbolSession { symbols: vec!["BTC^USDT".into()], is_btc_session: true, port: 55560, streams });
+        let mut live = Vec::new();
+        for (ts, frame) in &frames {
+            packet_builder.set_fixed_local_timestamp(Some(*ts));
+            let (port, packets) = manager.process_session_frame("OkxSwap", frame.clone().into_bytes(), &session, *ts).unwrap();
+            assert_eq!(port, 55560);
+            live.extend(packets.into_iter().map(|p| p.data));
+        }
+
+        let record = |ts: u64, session_meta: bool, data: Vec<u8>| JournalRecord {
+            recv_timestamp_ns: ts, exchange: "OkxSwap".into(), session: "0".into(), port: 55560, binary: false, session_meta, depth_snapshot: false, data,
+        };
+        let meta = SessionMeta { symbols: vec!["BTC^USDT".into()], streams };
+        let mut records = vec![record(1_700_000_000_000_000_000, true, serde_json::to_vec(&meta).unwrap())];
+        records.extend(frames.iter().map(|(ts, frame)| record(*ts, false, frame.clone().into_bytes())));
+        let options = ReplayOptions { inputs: vec![], speed: ReplaySpeed::AsFastAsPossible, deterministic: true };
+        let replayer = Replayer::new(config, Arc::new(DataParser::new()), Arc::new(PacketBuilder::new()), options);
+        let replayed: Vec<Vec<u8>> = records.iter().flat_map(|r| replayer.packets_for(r).unwrap()).map(|p| p.data).collect();
+
+        // 체결 2건 + 확정된 1분봉 1건
+        assert_eq!(live.len(), 3);
+        assert_eq!(replayed, live);
+    }
+
+    #[test]
+    fn test_parse_session_command() {
+        assert_eq!(SessionCommand::parse("add BinanceSpot 55555 ETH^USDT").unwrap(),
+                   SessionCommand::Add { exchange: "BinanceSpot".into(), port: 55555, symbol: "ETH^USDT".into() });
+        assert_eq!(SessionCommand::parse(" REMOVE OkxSwap 55556 BTC^USDT\n").unwrap(),
+                   SessionCommand::Remove { exchange: "OkxSwap".into(), port: 55556, symbol: "BTC^USDT".into() });
+        assert_eq!(SessionCommand::parse("move BinanceSpot ETH^USDT 55555 55556").unwrap(),
+                   SessionCommand::Move { exchange: "BinanceSpot".into(), symbol: "ETH^USDT".into(), from_port: 55555, to_port: 55556 });
+        assert!(SessionCommand::parse("add BinanceSpot 0 ETH^USDT").is_err());
+        assert!(SessionCommand::parse("add BinanceSpot 55555").is_err());
+        assert!(SessionCommand::parse("stop BinanceSpot").is_err());
+    }
+
+    /// 실제 연결 없이 제어 채널만 가진 세션을 레지스트리에 등록
+    fn register_session(manager: &ConnectionManager, port: u16, symbols: &[&str]) -> mpsc::UnboundedReceiver<SessionControl> {
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        let mut sessions = manager.sessions.lock().unwrap();
+        let id = sessions.len();
+        sessions.push(SessionHandle {
+            id,
+            exchange_name: "BinanceSpot".into(),
+            label: id.to_string(),
+            origin: None,
+            session: Arc::new(Mutex::new(SymbolSession { symbols: symbols.iter().map(|s| s.to_string()).collect(), is_btc_session: false, port, streams: SessionStreams::default() })),
+            control_tx,
+        });
+        control_rx
+    }
+
+    #[tokio::test]
+    async fn test_control_port_commands_reach_sessions() {
+        let config = Arc::new(Config::load().unwrap());
+        let udp_broadcaster = Arc::new(UdpMulticaster::new(&config.udp).unwrap());
+        let manager = ConnectionManager::new(config, Arc::new(DataParser::new()), Arc::new(PacketBuilder::new()), udp_broadcaster);
+        let mut first = register_session(&manager, 55561, &["BTC^USDT", "ETH^USDT"]);
+        let mut second = register_session(&manager, 55562, &["SOL^USDT", "XRP^USDT"]);
+
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let serving = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.serve_commands(server).await }
+        });
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let request = |line: &'static str| {
+            let client = &client;
+            async move {
+                client.send_to(line.as_bytes(), addr).await.unwrap();
+                let mut buf = [0u8; 256];
+                let len = time::timeout(Duration::from_secs(2), client.recv(&mut buf)).await.unwrap().unwrap();
+                String::from_utf8_lossy(&buf[..len]).into_owned()
+            }
+        };
+
+        assert_eq!(request("add BinanceSpot 55561 ADA^USDT").await, "OK");
+        assert!(matches!(first.try_recv(), Ok(SessionControl::Subscribe(s)) if s == ["ADA^USDT"]));
+        assert_eq!(request("move BinanceSpot ETH^USDT 55561 55562").await, "OK");
+        assert!(matches!(first.try_recv(), Ok(SessionControl::Unsubscribe(s)) if s == ["ETH^USDT"]));
+        assert!(matches!(second.try_recv(), Ok(SessionControl::Subscribe(s)) if s == ["ETH^USDT"]));
+
+        // 대상 포트에 이미 있는 심볼은 제거 전에 거부되어 기존 세션이 그대로 유지됨
+        assert!(request("move BinanceSpot BTC^USDT 55561 55561").await.starts_with("ERR"));
+        manager.sessions.lock().unwrap()[1].session.lock().unwrap().symbols.push("BTC^USDT".into());
+        assert!(request("move BinanceSpot BTC^USDT 55561 55562").await.starts_with("ERR"));
+        assert!(first.try_recv().is_err());
+        let closing = register_session(&manager, 55563, &["LTC^USDT"]);
+        drop(closing);
+        assert!(request("move BinanceSpot BTC^USDT 55561 55563").await.starts_with("ERR"));
+        assert!(first.try_recv().is_err());
+        assert!(request("remove BinanceSpot 55561 DOGE^USDT").await.starts_with("ERR"));
+        assert!(request("hello").await.starts_with("ERR"));
+
+        manager.shutdown.cancel();
+        time::timeout(Duration::from_secs(2), serving).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+//! 거래소 통합 최우선 호가(consolidated BBO) 집계기
+//! 여러 거래소 오더북 증분으로 거래소별 호가창을 유지하고, 표준 심볼(`A^B`)별 전체 거래소 최우선 매수/매도 호가를 계산
+//!
+//! - 같은 quote 통화의 거래소끼리 통합 (`X^USDT`는 `X^USDT`끼리)
+//! - `consolidated_bbo_krw_rate`를 주면 `X^KRW` 호가를 환율로 나눠 `X^{fx_quote}`에 함께 통합
+//! - `consolidated_bbo_stale_ms`보다 오래 갱신되지 않은 거래소 호가는 제외
+//! - 최우선 매수 ≥ 최우선 매도이면 locked(같음)/crossed(역전)로 표시
+//! - 계약 단위 수량(OKX 무기한 sz)은 참조 메타데이터의 계약당 기준 자산 수량(ctVal)으로 환산
+
+use crate::config::ConsolidatedBboConfig;
+use crate::data_parser::{OrderBookLevel, ParsedData, StandardizedOrderBookUpdate};
+use crate::venue_book::{price_key, VenueBook};
+use std::collections::{BTreeSet, HashMap};
+
+/// 통합 호가 패킷 헤더의 거래소 표시명
+pub const CONSOLIDATED_EXCHANGE: &str = "Consolidated";
+
+/// 환산 대상 quote 통화
+const KRW: &str = "KRW";
+
+/// 통합 호가 한쪽 (기여 거래소 포함)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueQuote {
+    pub exchange: String,
+    /// 통합 quote 통화 기준 가격 (KRW 환산 시 환산 후 가격)
+    pub price: f64,
+    /// 기준 자산 수량 (계약 단위 거래소는 환산 후 수량)
+    pub quantity: f64,
+    /// KRW 호가를 환율로 환산한 값인지
+    pub fx_converted: bool,
+}
+
+/// 심볼 하나의 통합 최우선 호가
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsolidatedQuote {
+    /// 통합 심볼 (KRW 환산 시 `X^{fx_quote}`)
+    pub symbol: String,
+    pub bid: Option<VenueQuote>,
+    pub ask: Option<VenueQuote>,
+    /// 호가가 유효한 거래소 수
+    pub venue_count: u16,
+    /// 계산 시각 (나노초)
+    pub timestamp: u64,
+}
+
+impl ConsolidatedQuote {
+    /// 최우선 매수 = 최우선 매도 (서로 다른 거래소)
+    pub fn is_locked(&self) -> bool {
+        matches!((&self.bid, &self.ask), (Some(b), Some(a)) if price_key(b.price) == price_key(a.price))
+    }
+
+    /// 최우선 매수 > 최우선 매도
+    pub fn is_crossed(&self) -> bool {
+        matches!((&self.bid, &self.ask), (Some(b), Some(a)) if price_key(b.price) > price_key(a.price))
+    }
+}
+
+/// 거래소 통합 최우선 호가 집계기
+///
+/// `ingest`로 오더북 증분을 반영하면 해당 통합 심볼이 변경 표시되고, `flush` 때 변경된 심볼의 통합 호가를 반환합니다.
+pub struct ConsolidatedBbo {
+    exchanges: Vec<String>,
+    krw_rate: Option<f64>,
+    fx_quote: String,
+    stale_ns: u64,
+    /// (거래소, 원본 심볼)별 계약당 기준 자산 수량 (없으면 수량이 이미 기준 자산 단위)
+    contract_values: HashMap<(String, String), f64>,
+    /// (거래소, 원본 심볼)별 호가창
+    books: HashMap<(String, String), VenueBook>,
+    /// 통합 심볼별 기여 (거래소, 원본 심볼) 목록
+    members: HashMap<String, BTreeSet<(String, String)>>,
+    dirty: BTreeSet<String>,
+}
+
+impl ConsolidatedBbo {
+    pub fn new(config: &ConsolidatedBboConfig) -> Self {
+        Self {
+            exchanges: config.exchanges.clone(),
+            krw_rate: config.krw_rate,
+            fx_quote: config.fx_quote.clone(),
+            stale_ns: config.stale_ms * 1_000_000,
+            contract_values: HashMap::new(),
+            books: HashMap::new(),
+            members: HashMap::new(),
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    /// 계약 단위 수량을 기준 자산 수량으로 환산할 (거래소, 심볼)별 계약당 수량 지정
+    pub fn with_contract_values(mut self, contract_values: HashMap<(String, String), f64>) -> Self {
+        self.contract_values = contract_values;
+        self
+    }
+
+    /// KRW 환산 환율 변경 (1 fx_quote = rate KRW, None이면 KRW는 KRW끼리만 통합)
+    pub fn set_krw_rate(&mut self, rate: Option<f64>) {
+        let rate = rate.filter(|r| r.is_finite() && *r > 0.0);
+        if rate != self.krw_rate {
+            self.krw_rate = rate;
+            let keys: Vec<_> = self.books.keys().cloned().collect();
+            for key in keys {
+                let (converted, _) = self.consolidated_symbol(&key.1);
+                self.members.entry(converted).or_default().insert(key);
+            }
+            self.dirty.extend(self.members.keys().cloned());
+        }
+    }
+
+    /// 원본 심볼의 통합 심볼과 가격 배율 (KRW 환산이면 1/환율)
+    fn consolidated_symbol(&self, symbol: &str) -> (String, Option<f64>) {
+        match (symbol.split_once('^'), self.krw_rate) {
+            (Some((base, KRW)), Some(rate)) => (format!("{}^{}", base, self.fx_quote), Some(1.0 / rate)),
+            _ => (symbol.to_string(), None),
+        }
+    }
+
+    /// 파싱된 데이터의 오더북 증분 반영 (`now_ns`는 수신 시각)
+    pub fn ingest(&mut self, data: &ParsedData, now_ns: u64) {
+        match data {
+            ParsedData::OrderBook(update) => self.on_order_book(update, now_ns),
+            ParsedData::Multi(items) => {
+                for item in items {
+                    self.ingest(item, now_ns);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_order_book(&mut self, update: &StandardizedOrderBookUpdate, now_ns: u64) {
+        if !self.exchanges.is_empty() && !self.exchanges.contains(&update.exchange) {
+            return;
+        }
+        let key = (update.exchange.clone(), update.symbol.clone());
+        self.books.entry(key.clone()).or_default().apply(update, now_ns);
+        // 환율 유무와 관계없이 원본/환산 심볼 양쪽에 등록해 두고 계산 시 현재 환율로 선택
+        let (converted, _) = self.consolidated_symbol(&update.symbol);
+        for symbol in [update.symbol.clone(), converted] {
+            self.members.entry(symbol.clone()).or_default().insert(key.clone());
+            self.dirty.insert(symbol);
+        }
+    }
+
+    /// 스냅샷을 기다리는 호가창인지 (추적하지 않는 (거래소, 심볼)이면 false)
+    pub fn awaits_snapshot(&self, exchange: &str, symbol: &str) -> bool {
+        self.books.get(&(exchange.to_string(), symbol.to_string())).is_some_and(|book| !book.is_synced())
+    }
+
+    /// 통합 심볼의 현재 통합 호가 (유효한 거래소가 없으면 None)
+    pub fn quote(&self, symbol: &str, now_ns: u64) -> Option<ConsolidatedQuote> {
+        let members = self.members.get(symbol)?;
+        let mut bid: Option<VenueQuote> = None;
+        let mut ask: Option<VenueQuote> = None;
+        let mut venue_count = 0u16;
+        for key in members {
+            let (target, factor) = self.consolidated_symbol(&key.1);
+            if target != symbol {
+                continue;
+            }
+            let Some(book) = self.books.get(key) else { continue };
+            if book.is_stale(now_ns, self.stale_ns) {
+                continue;
+            }
+            let contract_value = self.contract_values.get(key).copied().unwrap_or(1.0);
+            let venue_quote = |level: &OrderBookLevel| VenueQuote {
+                exchange: key.0.clone(),
+                price: level.price * factor.unwrap_or(1.0),
+                quantity: level.quantity * contract_value,
+                fx_converted: factor.is_some(),
+            };
+            let (best_bid, best_ask) = (book.best_bid().map(venue_quote), book.best_ask().map(venue_quote));
+            if best_bid.is_none() && best_ask.is_none() {
+                continue;
+            }
+            venue_count += 1;
+            if let Some(b) = best_bid {
+                if bid.as_ref().is_none_or(|cur| better(&b, cur, |p| p)) {
+                    bid = Some(b);
+                }
+            }
+            if let Some(a) = best_ask {
+                if ask.as_ref().is_none_or(|cur| better(&a, cur, |p| -p)) {
+                    ask = Some(a);
+                }
+            }
+        }
+        (venue_count > 0).then(|| ConsolidatedQuote { symbol: symbol.to_string(), bid, ask, venue_count, timestamp: now_ns })
+    }
+
+    /// 마지막 전송 이후 바뀐 심볼의 통합 호가 반환 (유효한 거래소가 없는 심볼은 제외)
+    pub fn flush(&mut self, now_ns: u64) -> Vec<ConsolidatedQuote> {
+        std::mem::take(&mut self.dirty).into_iter()
+            .filter_map(|symbol| self.quote(&symbol, now_ns))
+            .collect()
+    }
+}
+
+/// 후보 호가가 현재 호가보다 나은지 (가격 우선, 같은 가격이면 수량이 많은 쪽, 그다음 거래소 이름순)
+fn better(candidate: &VenueQuote, current: &VenueQuote, sign: impl Fn(i64) -> i64) -> bool {
+    let (c, p) = (sign(price_key(candidate.price)), sign(price_key(current.price)));
+    c > p || (c == p && (candidate.quantity > current.quantity
+        || (candidate.quantity == current.quantity && candidate.exchange < current.exchange)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venue_book::test_books::{diff, snapshot};
+
+    const MS: u64 = 1_000_000;
+
+    fn config(krw_rate: Option<f64>) -> ConsolidatedBboConfig {
+        ConsolidatedBboConfig { krw_rate, ..ConsolidatedBboConfig::default() }
+    }
+
+    #[test]
+    fn test_best_of_venues_with_contributors() {
+        let mut bbo = ConsolidatedBbo::new(&config(None));
+        bbo.ingest(&snapshot("BinanceSpot", "BTC^USDT", &[(100.0, 1.0), (99.0, 2.0)], &[(102.0, 1.0)]), 0);
+        bbo.ingest(&snapshot("OkxSpot", "BTC^USDT", &[(100.5, 0.5)], &[(101.5, 3.0)]), 0);
+
+        let quotes = bbo.flush(0);
+        assert_eq!(quotes.len(), 1);
+        let q = &quotes[0];
+        assert_eq!(q.venue_count, 2);
+        let (bid, ask) = (q.bid.as_ref().unwrap(), q.ask.as_ref().unwrap());
+        assert_eq!((bid.exchange.as_str(), bid.price), ("OkxSpot", 100.5));
+        assert_eq!((ask.exchange.as_str(), ask.price, ask.quantity), ("OkxSpot", 101.5, 3.0));
+        assert!(!q.is_locked() && !q.is_crossed());
+        assert!(bbo.flush(0).is_empty());
+
+        // 수량 0이면 호가 삭제 후 다음 거래소 호가가 최우선
+        bbo.ingest(&diff("OkxSpot", "BTC^USDT", &[(100.5, 0.0)], &[]), 0);
+        assert_eq!(bbo.flush(0)[0].bid.as_ref().unwrap().exchange, "BinanceSpot");
+    }
+
+    #[test]
+    fn test_locked_crossed_and_stale() {
+        let mut bbo = ConsolidatedBbo::new(&config(None));
+        bbo.ingest(&snapshot("BinanceSpot", "ETH^USDT", &[(10.0, 1.0)], &[(11.0, 1.0)]), 0);
+        bbo.ingest(&snapshot("BybitSpot", "ETH^USDT", &[(9.0, 1.0)], &[(10.0, 1.0)]), 0);
+        assert!(bbo.quote("ETH^USDT", 0).unwrap().is_locked());
+
+        bbo.ingest(&diff("BybitSpot", "ETH^USDT", &[], &[(8.5, 1.0)]), 0);
+        let q = bbo.quote("ETH^USDT", 0).unwrap();
+        assert!(q.is_crossed());
+        // 같은 거래소 안에서는 새 매도 호가 이상인 매수 호가가 지워짐
+        assert!(bbo.books[&("BybitSpot".to_string(), "ETH^USDT".to_string())].best_bid().is_none());
+
+        // 오래된 거래소는 제외
+        let stale = ConsolidatedBboConfig::default().stale_ms * MS;
+        bbo.ingest(&diff("BybitSpot", "ETH^USDT", &[], &[(12.0, 1.0)]), stale + MS);
+        let q = bbo.quote("ETH^USDT", stale + MS).unwrap();
+        assert_eq!((q.venue_count, q.bid), (1, None));
+    }
+
+    #[test]
+    fn test_krw_converted_into_fx_quote() {
+        let mut bbo = ConsolidatedBbo::new(&config(Some(1400.0)));
+        bbo.ingest(&snapshot("BinanceSpot", "BTC^USDT", &[(100.0, 1.0)], &[(101.0, 1.0)]), 0);
+        bbo.ingest(&snapshot("UpbitSpot", "BTC^KRW", &[(141_400.0, 1.0)], &[(142_800.0, 1.0)]), 0);
+
+        let q = bbo.quote("BTC^USDT", 0).unwrap();
+        let bid = q.bid.unwrap();
+        assert_eq!((bid.exchange.as_str(), bid.price, bid.fx_converted), ("UpbitSpot", 101.0, true));
+        assert_eq!(q.venue_count, 2);
+        assert!(q.ask.is_some_and(|a| !a.fx_converted));
+
+        // 환율을 끄면 KRW는 KRW끼리만 통합
+        bbo.set_krw_rate(None);
+        assert_eq!(bbo.quote("BTC^USDT", 0).unwrap().venue_count, 1);
+        assert_eq!(bbo.quote("BTC^KRW", 0).unwrap().bid.unwrap().price, 141_400.0);
+    }
+}
@@ -0,0 +1,1565 @@
+//! 멀티캐스트 프로토콜 소비자 라이브러리
+//! 피더가 전송하는 UDP 패킷을 수신/해석하기 위한 공용 모듈 (packet-decoder, udp-counter 등이 사용)
+//!
+//! - `MulticastSubscriber`: 그룹/인터페이스/포트 설정으로 멀티캐스트 가입 및 수신
+//! - `PacketView`, `HeaderView`, `ItemView`: unsafe 없이 수신 버퍼를 그대로 참조하는 LE 해석 뷰 (v1/v2 헤더)
+//! - `Message`: 시장 데이터(0~14), 종목 정의(50), 시스템 이벤트(100~105) 타입별 열거형
+//! - `ScaleBook`: 종목 정의/설정으로 학습한 종목별 가격/수량 스케일 (기본 10^8)
+//! - `MessageAssembler`: 분할 정보(message_id/조각 번호) 또는 is_last 기준 다중 패킷 메시지 재조립
+//! - `GapDetector`: 채널별 시퀀스 누락/중복 감지
+
+use crate::config::Config;
+use crate::errors::{CryptoFeederError, Result};
+use crate::events::{
+    ConnectionStatus, ErrorEvent, SubscriptionStatus, SystemHeartbeat, SystemShutdown, SystemStats,
+    MESSAGE_TYPE_CONNECTION_STATUS, MESSAGE_TYPE_ERROR_EVENT, MESSAGE_TYPE_SUBSCRIPTION_STATUS,
+    MESSAGE_TYPE_SYSTEM_HEARTBEAT, MESSAGE_TYPE_SYSTEM_SHUTDOWN, MESSAGE_TYPE_SYSTEM_STATS,
+};
+use crate::reference_data::{InstrumentDefinition, MESSAGE_TYPE_INSTRUMENT_DEFINITION};
+use crate::protocol::{
+    MESSAGE_TYPE_FUNDING_RATE, MESSAGE_TYPE_INDEX_PRICE, MESSAGE_TYPE_LIQUIDATION, MESSAGE_TYPE_MARK_PRICE,
+    MESSAGE_TYPE_ORDER_BOOK, MESSAGE_TYPE_TRADE_TICK, MESSAGE_TYPE_TRADE_TICK_WITH_ID, MESSAGE_TYPE_KLINE, MESSAGE_TYPE_TICKER_24H, MESSAGE_TYPE_OPEN_INTEREST, MESSAGE_TYPE_FUNDING_INFO, MESSAGE_TYPE_LIQUIDATION_DETAIL, MESSAGE_TYPE_CONSOLIDATED_BBO, MESSAGE_TYPE_KIMCHI_PREMIUM, MESSAGE_TYPE_TRADE_FLOW, FragmentHeader, InstrumentScale, KlineItem, Ticker24hItem, OpenInterestItem, FundingInfoItem, LiquidationDetailItem, ConsolidatedBboItem, KimchiPremiumItem, TradeFlowItem,
+    PriceValueItem, UpdateIdRange, WireFormat, PROTOCOL_VERSION_V1, PROTOCOL_VERSION_V2, V2_FLAG_LAST, V2_FLAG_REPLAY,
+    V2_FLAG_SNAPSHOT, V2_FLAG_CRC, V2_FLAG_FRAGMENT, V2_FLAG_UPDATE_IDS, verify_v2_crc,
+};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// v1 패킷 헤더 크기
+pub const HEADER_SIZE: usize = 67;
+/// v2 패킷 헤더 크기
+pub const HEADER_V2_SIZE: usize = 40;
+/// 가격/수량 아이템 크기 (오더북, 체결, 청산)
+pub const ITEM_SIZE: usize = 16;
+/// 체결 ID 포함 체결 아이템 크기
+pub const TRADE_ID_ITEM_SIZE: usize = 32;
+/// OHLCV 봉 아이템 크기
+pub const KLINE_ITEM_SIZE: usize = 64;
+/// 24시간 티커 아이템 크기
+pub const TICKER_ITEM_SIZE: usize = 64;
+/// 미결제약정 아이템 크기
+pub const OPEN_INTEREST_ITEM_SIZE: usize = 24;
+/// 펀딩 정보 아이템 크기
+pub const FUNDING_INFO_ITEM_SIZE: usize = 32;
+/// 청산 상세 아이템 크기
+pub const LIQUIDATION_DETAIL_ITEM_SIZE: usize = 40;
+/// 통합 최우선 호가 아이템 크기
+pub const CONSOLIDATED_BBO_ITEM_SIZE: usize = 40;
+/// 김치 프리미엄 아이템 크기
+pub const KIMCHI_PREMIUM_ITEM_SIZE: usize = 40;
+/// 체결 흐름 아이템 크기
+pub const TRADE_FLOW_ITEM_SIZE: usize = 40;
+/// 단일 값 아이템 크기 (인덱스/마크 가격, 펀딩비)
+pub const VALUE_ITEM_SIZE: usize = 8;
+/// 이벤트 페이로드 크기
+pub const EVENT_PAYLOAD_SIZE: usize = 16;
+/// 종목 정의 아이템 크기
+pub const INSTRUMENT_DEFINITION_SIZE: usize = 96;
+/// v2 분할 헤더 크기 (FRAGMENT 플래그가 있을 때 페이로드 앞)
+pub const FRAGMENT_HEADER_SIZE: usize = 8;
+/// v2 업데이트 ID 범위 크기 (UPDATE_IDS 플래그가 있을 때 분할 헤더 뒤)
+pub const UPDATE_ID_RANGE_SIZE: usize = 16;
+
+const FLAG_BIT: i64 = 1i64 << 63;
+
+/// 패킷 해석 오류
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error("패킷이 헤더보다 짧음: {actual} bytes")]
+    TooShort { actual: usize },
+    #[error("페이로드 길이 부족: message_type={message_type} 아이템 {count}개에 {actual} bytes")]
+    PayloadTooShort { message_type: u8, count: usize, actual: usize },
+    #[error("지원하지 않는 프로토콜 버전: {0}")]
+    UnsupportedVersion(u8),
+    #[error("v2 payload_length 불일치: 헤더 {declared} bytes, 수신 {actual} bytes")]
+    PayloadLengthMismatch { declared: usize, actual: usize },
+    #[error("v2 CRC32 불일치 (헤더 값 {expected:#010x})")]
+    CrcMismatch { expected: u32 },
+    #[error("v2 분할 헤더 오류: 페이로드 {actual} bytes")]
+    InvalidFragment { actual: usize },
+    #[error("v2 업데이트 ID 범위 오류: 페이로드 {actual} bytes")]
+    InvalidUpdateIds { actual: usize },
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn i64_at(bytes: &[u8], at: usize) -> i64 {
+    i64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+/// NUL 종료 문자열 (UTF-8이 아니면 빈 문자열)
+fn c_str(bytes: &[u8]) -> &str {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..end]).unwrap_or("")
+}
+
+/// 패킷 헤더 뷰 (v1 67바이트 / v2 40바이트, Little Endian)
+///
+/// v2 헤더에는 심볼/거래소 문자열이 없으므로 `symbol()`/`exchange()`는 빈 문자열이고,
+/// v1 헤더의 `venue_id()`/`instrument_id()`는 0입니다.
+#[derive(Debug, Clone, Copy)]
+pub struct HeaderView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> HeaderView<'a> {
+    pub fn new(bytes: &'a [u8]) -> std::result::Result<Self, DecodeError> {
+        let size = match bytes.first() {
+            Some(&PROTOCOL_VERSION_V1) => HEADER_SIZE,
+            Some(&PROTOCOL_VERSION_V2) => HEADER_V2_SIZE,
+            Some(&version) => return Err(DecodeError::UnsupportedVersion(version)),
+            None => return Err(DecodeError::TooShort { actual: 0 }),
+        };
+        if bytes.len() < size {
+            return Err(DecodeError::TooShort { actual: bytes.len() });
+        }
+        Ok(Self { bytes: &bytes[..size] })
+    }
+
+    pub fn protocol_version(&self) -> u8 { self.bytes[0] }
+    pub fn is_v2(&self) -> bool { self.protocol_version() == PROTOCOL_VERSION_V2 }
+    /// 헤더 크기 (페이로드 시작 위치)
+    pub fn len(&self) -> usize { self.bytes.len() }
+    pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+
+    /// v1: 피더 전역 시퀀스, v2: 채널(포트)별 시퀀스
+    pub fn sequence_number(&self) -> u64 { u64_at(self.bytes, if self.is_v2() { 16 } else { 1 }) }
+    pub fn exchange_timestamp(&self) -> u64 { u64_at(self.bytes, if self.is_v2() { 24 } else { 9 }) }
+    pub fn local_timestamp(&self) -> u64 { u64_at(self.bytes, if self.is_v2() { 32 } else { 17 }) }
+    pub fn message_type(&self) -> u8 { self.bytes[if self.is_v2() { 1 } else { 25 }] }
+
+    /// v1 형식의 flags_and_count (v2는 is_last/item_count로 구성)
+    pub fn flags_and_count(&self) -> u8 {
+        if self.is_v2() {
+            (if self.is_last() { 0b1000_0000 } else { 0 }) | (self.item_count() & 0b0111_1111)
+        } else {
+            self.bytes[26]
+        }
+    }
+
+    /// v2 형식의 flags (v1은 is_last만 반영)
+    pub fn flags(&self) -> u8 {
+        if self.is_v2() {
+            self.bytes[2]
+        } else if self.bytes[26] & 0b1000_0000 != 0 {
+            V2_FLAG_LAST
+        } else {
+            0
+        }
+    }
+
+    pub fn is_last(&self) -> bool { self.flags() & V2_FLAG_LAST != 0 }
+    pub fn is_snapshot(&self) -> bool { self.flags() & V2_FLAG_SNAPSHOT != 0 }
+    pub fn is_replay(&self) -> bool { self.flags() & V2_FLAG_REPLAY != 0 }
+    pub fn is_fragmented(&self) -> bool { self.flags() & V2_FLAG_FRAGMENT != 0 }
+    pub fn has_update_ids(&self) -> bool { self.flags() & V2_FLAG_UPDATE_IDS != 0 }
+    pub fn item_count(&self) -> u8 { if self.is_v2() { self.bytes[3] } else { self.bytes[26] & 0b0111_1111 } }
+    pub fn symbol(&self) -> &'a str { if self.is_v2() { "" } else { c_str(&self.bytes[27..47]) } }
+    pub fn exchange(&self) -> &'a str { if self.is_v2() { "" } else { c_str(&self.bytes[47..67]) } }
+    pub fn venue_id(&self) -> u16 { if self.is_v2() { u16::from_le_bytes([self.bytes[4], self.bytes[5]]) } else { 0 } }
+    pub fn instrument_id(&self) -> u32 { if self.is_v2() { u32_at(self.bytes, 8) } else { 0 } }
+
+    /// v2 헤더의 페이로드 길이
+    pub fn payload_length(&self) -> Option<usize> {
+        self.is_v2().then(|| u16::from_le_bytes([self.bytes[6], self.bytes[7]]) as usize)
+    }
+
+    /// v2 헤더의 CRC32 (CRC 플래그가 있을 때만)
+    pub fn crc32(&self) -> Option<u32> {
+        (self.is_v2() && self.flags() & V2_FLAG_CRC != 0).then(|| u32_at(self.bytes, 12))
+    }
+}
+
+/// 가격/수량 아이템 뷰 (16바이트, 수량 최상위 비트 = 타입별 플래그, 체결 ID 포함 체결은 32바이트)
+#[derive(Debug, Clone, Copy)]
+pub struct ItemView<'a> {
+    bytes: &'a [u8],
+    scale: InstrumentScale,
+}
+
+impl<'a> ItemView<'a> {
+    /// 스케일된 가격 (종목 스케일, 기본 10^8)
+    pub fn price_raw(&self) -> i64 { i64_at(self.bytes, 0) }
+    /// 스케일된 수량 (플래그 제외)
+    pub fn quantity_raw(&self) -> i64 { i64_at(self.bytes, 8) & !FLAG_BIT }
+    /// 오더북: is_ask, 체결: is_buyer_taker, 청산: is_sell
+    pub fn flag(&self) -> bool { i64_at(self.bytes, 8) & FLAG_BIT != 0 }
+    pub fn price(&self) -> f64 { self.scale.price(self.price_raw()) }
+    pub fn quantity(&self) -> f64 { self.scale.quantity(self.quantity_raw()) }
+    /// 거래소 체결 ID (체결 ID 포함 체결만, 거래소가 제공하지 않으면 0)
+    pub fn trade_id(&self) -> Option<u64> { (self.bytes.len() >= TRADE_ID_ITEM_SIZE).then(|| u64_at(self.bytes, 16)) }
+    /// 거래소 체결 시각 (Unix 나노초, 체결 ID 포함 체결만)
+    pub fn trade_time(&self) -> Option<u64> { (self.bytes.len() >= TRADE_ID_ITEM_SIZE).then(|| u64_at(self.bytes, 24)) }
+}
+
+/// 스케일을 적용한 OHLCV 봉
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kline {
+    /// 봉 시작 시각 (Unix 나노초)
+    pub start_time: u64,
+    pub interval_secs: u32,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trade_count: u32,
+    pub is_closed: bool,
+}
+
+impl Kline {
+    fn decode(items: &[u8], scale: InstrumentScale) -> Option<Self> {
+        let item = KlineItem::try_from(items).ok()?;
+        Some(Self {
+            start_time: item.start_time,
+            interval_secs: item.interval_secs,
+            open: scale.price(item.open),
+            high: scale.price(item.high),
+            low: scale.price(item.low),
+            close: scale.price(item.close),
+            volume: scale.quantity(item.volume()),
+            quote_volume: scale.price(item.quote_volume),
+            trade_count: item.trade_count,
+            is_closed: item.is_closed(),
+        })
+    }
+}
+
+/// 스케일을 적용한 24시간 티커 (변동률은 %, 항상 10^8)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ticker24h {
+    pub last_price: f64,
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub price_change_percent: f64,
+    pub trade_count: u64,
+}
+
+impl Ticker24h {
+    fn decode(items: &[u8], scale: InstrumentScale) -> Option<Self> {
+        let item = Ticker24hItem::try_from(items).ok()?;
+        Some(Self {
+            last_price: scale.price(item.last_price),
+            open_price: scale.price(item.open_price),
+            high_price: scale.price(item.high_price),
+            low_price: scale.price(item.low_price),
+            volume: scale.quantity(item.volume),
+            quote_volume: scale.price(item.quote_volume),
+            price_change_percent: InstrumentScale::DEFAULT.price(item.price_change_percent),
+            trade_count: item.trade_count,
+        })
+    }
+}
+
+/// 스케일을 적용한 미결제약정 (거래소가 제공하지 않는 값은 None)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpenInterest {
+    pub open_interest: f64,
+    pub open_interest_value: Option<f64>,
+    pub long_short_ratio: Option<f64>,
+}
+
+impl OpenInterest {
+    fn decode(items: &[u8], scale: InstrumentScale) -> Option<Self> {
+        let item = OpenInterestItem::try_from(items).ok()?;
+        let (value, ratio) = (item.open_interest_value, item.long_short_ratio);
+        Some(Self {
+            open_interest: scale.quantity(item.open_interest),
+            open_interest_value: (value != 0).then(|| scale.price(value)),
+            long_short_ratio: (ratio != 0).then(|| InstrumentScale::DEFAULT.price(ratio)),
+        })
+    }
+}
+
+/// 펀딩 정보 (비율은 항상 10^8, 거래소가 제공하지 않는 값은 None)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingInfo {
+    pub funding_rate: f64,
+    pub predicted_rate: Option<f64>,
+    /// 다음 정산 시각 (Unix 나노초)
+    pub next_funding_time: Option<u64>,
+    pub interval_secs: Option<u32>,
+}
+
+impl FundingInfo {
+    fn decode(items: &[u8]) -> Option<Self> {
+        let item = FundingInfoItem::try_from(items).ok()?;
+        let scale = InstrumentScale::DEFAULT;
+        let (next_funding_time, interval_secs) = (item.next_funding_time, item.interval_secs);
+        Some(Self {
+            funding_rate: scale.price(item.funding_rate),
+            predicted_rate: item.predicted_rate().map(|rate| scale.price(rate)),
+            next_funding_time: (next_funding_time != 0).then_some(next_funding_time),
+            interval_secs: (interval_secs != 0).then_some(interval_secs),
+        })
+    }
+}
+
+/// 스케일을 적용한 청산 상세
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidationDetail {
+    pub price: f64,
+    pub quantity: f64,
+    /// 청산 주문 방향 (true = 롱 포지션 청산)
+    pub is_sell: bool,
+    pub filled_quantity: f64,
+    /// `LIQUIDATION_STATUS_*`
+    pub order_status: u32,
+    /// 거래소 주문(체결) 시각 (Unix 나노초)
+    pub order_time: Option<u64>,
+}
+
+impl LiquidationDetail {
+    fn decode(items: &[u8], scale: InstrumentScale) -> Option<Self> {
+        let item = LiquidationDetailItem::try_from(items).ok()?;
+        let order_time = item.order_time;
+        Some(Self {
+            price: scale.price(item.price),
+            quantity: scale.quantity(item.quantity()),
+            is_sell: item.is_sell(),
+            filled_quantity: scale.quantity(item.filled_quantity),
+            order_status: item.order_status,
+            order_time: (order_time != 0).then_some(order_time),
+        })
+    }
+}
+
+/// 통합 호가 한쪽 (가격, 수량, 기여 거래소 venue_id)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BboSide {
+    pub price: f64,
+    pub quantity: f64,
+    pub venue_id: u16,
+    /// KRW 호가를 환율로 환산한 값인지
+    pub fx_converted: bool,
+}
+
+impl BboSide {
+    /// 기여 거래소 표시명 (고정 목록에 없으면 None)
+    pub fn venue_name(&self) -> Option<&'static str> {
+        known_venue_name(self.venue_id)
+    }
+}
+
+/// v2 venue_id의 거래소 표시명 (고정 목록에 없으면 None)
+fn known_venue_name(venue_id: u16) -> Option<&'static str> {
+    crate::instruments::KNOWN_VENUES.iter().find(|(_, id)| *id == venue_id).map(|(name, _)| *name)
+}
+
+/// 스케일을 적용한 거래소 통합 최우선 호가
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsolidatedBbo {
+    pub bid: Option<BboSide>,
+    pub ask: Option<BboSide>,
+    pub venue_count: u16,
+    pub locked: bool,
+    pub crossed: bool,
+}
+
+impl ConsolidatedBbo {
+    fn decode(items: &[u8], scale: InstrumentScale) -> Option<Self> {
+        use crate::protocol::{BBO_FLAG_ASK_FX, BBO_FLAG_BID_FX};
+        let item = ConsolidatedBboItem::try_from(items).ok()?;
+        let side = |price: i64, quantity: i64, venue_id: u16, fx_flag: u16| (price != 0).then(|| BboSide {
+            price: scale.price(price),
+            quantity: scale.quantity(quantity),
+            venue_id,
+            fx_converted: item.flags & fx_flag != 0,
+        });
+        Some(Self {
+            bid: side(item.bid_price, item.bid_quantity, item.bid_venue, BBO_FLAG_BID_FX),
+            ask: side(item.ask_price, item.ask_quantity, item.ask_venue, BBO_FLAG_ASK_FX),
+            venue_count: item.venue_count,
+            locked: item.is_locked(),
+            crossed: item.is_crossed(),
+        })
+    }
+}
+
+/// 김치 프리미엄 (KRW 심볼/거래소는 헤더에 있음)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KimchiPremium {
+    /// 비율 (0.03 = 3%)
+    pub premium: f64,
+    pub krw_price: f64,
+    pub foreign_price: f64,
+    /// 1 foreign quote = fx_rate KRW
+    pub fx_rate: f64,
+    pub foreign_venue_id: u16,
+    /// 환율이 시장 중간 가격인지 (false면 고정 환율)
+    pub market_fx: bool,
+}
+
+impl KimchiPremium {
+    fn decode(items: &[u8]) -> Option<Self> {
+        let item = KimchiPremiumItem::try_from(items).ok()?;
+        let scale = InstrumentScale::DEFAULT;
+        Some(Self {
+            premium: scale.price(item.premium),
+            krw_price: scale.price(item.krw_price),
+            foreign_price: scale.price(item.foreign_price),
+            fx_rate: scale.price(item.fx_rate),
+            foreign_venue_id: item.foreign_venue,
+            market_fx: item.is_market_fx(),
+        })
+    }
+
+    /// 해외 거래소 표시명 (고정 목록에 없으면 None)
+    pub fn foreign_venue_name(&self) -> Option<&'static str> {
+        known_venue_name(self.foreign_venue_id)
+    }
+}
+
+/// 아이템 순회자
+#[derive(Debug, Clone)]
+pub struct Items<'a> {
+    chunks: std::slice::ChunksExact<'a, u8>,
+    scale: InstrumentScale,
+}
+
+impl<'a> Items<'a> {
+    fn new(payload: &'a [u8], item_size: usize, scale: InstrumentScale) -> Self {
+        Self { chunks: payload.chunks_exact(item_size), scale }
+    }
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = ItemView<'a>;
+
+    fn next(&mut self) -> Option<ItemView<'a>> {
+        self.chunks.next().map(|bytes| ItemView { bytes, scale: self.scale })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Items<'_> {}
+
+/// 스케일을 적용한 체결 흐름 구간 하나
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeFlowWindow {
+    pub window_secs: u32,
+    pub trade_count: u32,
+    /// 체결이 없으면 0
+    pub vwap: f64,
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    /// (매수 - 매도) / (매수 + 매도)
+    pub imbalance: f64,
+}
+
+/// 체결 흐름 구간 순회자 (짧은 구간부터)
+#[derive(Debug, Clone)]
+pub struct TradeFlowWindows<'a> {
+    chunks: std::slice::ChunksExact<'a, u8>,
+    scale: InstrumentScale,
+}
+
+impl Iterator for TradeFlowWindows<'_> {
+    type Item = TradeFlowWindow;
+
+    fn next(&mut self) -> Option<TradeFlowWindow> {
+        let item = TradeFlowItem::try_from(self.chunks.next()?).ok()?;
+        Some(TradeFlowWindow {
+            window_secs: item.window_secs,
+            trade_count: item.trade_count,
+            vwap: self.scale.price(item.vwap),
+            buy_volume: self.scale.quantity(item.buy_volume),
+            sell_volume: self.scale.quantity(item.sell_volume),
+            imbalance: InstrumentScale::DEFAULT.price(item.imbalance),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+/// 종목 정의 순회자
+#[derive(Debug, Clone)]
+pub struct Definitions<'a> {
+    chunks: std::slice::ChunksExact<'a, u8>,
+}
+
+impl Iterator for Definitions<'_> {
+    type Item = InstrumentDefinition;
+
+    fn next(&mut self) -> Option<InstrumentDefinition> {
+        self.chunks.next().and_then(|bytes| InstrumentDefinition::try_from(bytes).ok())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+/// 메시지 타입별 해석 결과
+#[derive(Debug, Clone)]
+pub enum Message<'a> {
+    /// 아이템 플래그 = is_ask
+    OrderBook(Items<'a>),
+    /// 아이템 플래그 = is_buyer_taker (타입 6은 `trade_id`/`trade_time` 포함)
+    Trade(Items<'a>),
+    IndexPrice(f64),
+    MarkPrice(f64),
+    FundingRate(f64),
+    /// 아이템 플래그 = is_sell
+    Liquidation(Items<'a>),
+    Kline(Kline),
+    Ticker24h(Ticker24h),
+    OpenInterest(OpenInterest),
+    FundingInfo(FundingInfo),
+    LiquidationDetail(LiquidationDetail),
+    ConsolidatedBbo(ConsolidatedBbo),
+    KimchiPremium(KimchiPremium),
+    TradeFlow(TradeFlowWindows<'a>),
+    InstrumentDefinitions(Definitions<'a>),
+    Heartbeat(SystemHeartbeat),
+    ConnectionStatus(ConnectionStatus),
+    SubscriptionStatus(SubscriptionStatus),
+    SystemStats(SystemStats),
+    Error(ErrorEvent),
+    Shutdown(SystemShutdown),
+    Unknown(u8),
+}
+
+/// 메시지 타입별 아이템 크기 (알 수 없는 타입은 None)
+pub fn item_size(message_type: u8) -> Option<usize> {
+    match message_type {
+        MESSAGE_TYPE_ORDER_BOOK | MESSAGE_TYPE_TRADE_TICK | MESSAGE_TYPE_LIQUIDATION => Some(ITEM_SIZE),
+        MESSAGE_TYPE_TRADE_TICK_WITH_ID => Some(TRADE_ID_ITEM_SIZE),
+        MESSAGE_TYPE_KLINE => Some(KLINE_ITEM_SIZE),
+        MESSAGE_TYPE_TICKER_24H => Some(TICKER_ITEM_SIZE),
+        MESSAGE_TYPE_OPEN_INTEREST => Some(OPEN_INTEREST_ITEM_SIZE),
+        MESSAGE_TYPE_FUNDING_INFO => Some(FUNDING_INFO_ITEM_SIZE),
+        MESSAGE_TYPE_LIQUIDATION_DETAIL => Some(LIQUIDATION_DETAIL_ITEM_SIZE),
+        MESSAGE_TYPE_CONSOLIDATED_BBO => Some(CONSOLIDATED_BBO_ITEM_SIZE),
+        MESSAGE_TYPE_KIMCHI_PREMIUM => Some(KIMCHI_PREMIUM_ITEM_SIZE),
+        MESSAGE_TYPE_TRADE_FLOW => Some(TRADE_FLOW_ITEM_SIZE),
+        MESSAGE_TYPE_INDEX_PRICE | MESSAGE_TYPE_MARK_PRICE | MESSAGE_TYPE_FUNDING_RATE => Some(VALUE_ITEM_SIZE),
+        MESSAGE_TYPE_INSTRUMENT_DEFINITION => Some(INSTRUMENT_DEFINITION_SIZE),
+        MESSAGE_TYPE_SYSTEM_HEARTBEAT..=MESSAGE_TYPE_SYSTEM_SHUTDOWN => Some(EVENT_PAYLOAD_SIZE),
+        _ => None,
+    }
+}
+
+/// 메시지 타입과 아이템 바이트로 메시지 해석 (기본 스케일 10^8)
+pub fn decode_message(message_type: u8, items: &[u8]) -> Message<'_> {
+    decode_message_with_scale(message_type, items, InstrumentScale::DEFAULT)
+}
+
+/// 종목 스케일을 적용한 메시지 해석 (다중 패킷 재조립 결과에도 사용, 펀딩비는 항상 10^8)
+pub fn decode_message_with_scale(message_type: u8, items: &[u8], scale: InstrumentScale) -> Message<'_> {
+    let value = |scale: InstrumentScale| PriceValueItem::try_from(items).map(|v| scale.price(v.value)).unwrap_or(0.0);
+    let unknown = |_| Message::Unknown(message_type);
+    match message_type {
+        MESSAGE_TYPE_ORDER_BOOK => Message::OrderBook(Items::new(items, ITEM_SIZE, scale)),
+        MESSAGE_TYPE_TRADE_TICK => Message::Trade(Items::new(items, ITEM_SIZE, scale)),
+        MESSAGE_TYPE_TRADE_TICK_WITH_ID => Message::Trade(Items::new(items, TRADE_ID_ITEM_SIZE, scale)),
+        MESSAGE_TYPE_LIQUIDATION => Message::Liquidation(Items::new(items, ITEM_SIZE, scale)),
+        MESSAGE_TYPE_KLINE => Kline::decode(items, scale).map_or(Message::Unknown(message_type), Message::Kline),
+        MESSAGE_TYPE_TICKER_24H => Ticker24h::decode(items, scale).map_or(Message::Unknown(message_type), Message::Ticker24h),
+        MESSAGE_TYPE_OPEN_INTEREST => OpenInterest::decode(items, scale).map_or(Message::Unknown(message_type), Message::OpenInterest),
+        MESSAGE_TYPE_FUNDING_INFO => FundingInfo::decode(items).map_or(Message::Unknown(message_type), Message::FundingInfo),
+        MESSAGE_TYPE_LIQUIDATION_DETAIL => LiquidationDetail::decode(items, scale).map_or(Message::Unknown(message_type), Message::LiquidationDetail),
+        MESSAGE_TYPE_CONSOLIDATED_BBO => ConsolidatedBbo::decode(items, scale).map_or(Message::Unknown(message_type), Message::ConsolidatedBbo),
+        MESSAGE_TYPE_KIMCHI_PREMIUM => KimchiPremium::decode(items).map_or(Message::Unknown(message_type), Message::KimchiPremium),
+        MESSAGE_TYPE_TRADE_FLOW => Message::TradeFlow(TradeFlowWindows { chunks: items.chunks_exact(TRADE_FLOW_ITEM_SIZE), scale }),
+        MESSAGE_TYPE_INDEX_PRICE => Message::IndexPrice(value(scale)),
+        MESSAGE_TYPE_MARK_PRICE => Message::MarkPrice(value(scale)),
+        MESSAGE_TYPE_FUNDING_RATE => Message::FundingRate(value(InstrumentScale::DEFAULT)),
+        MESSAGE_TYPE_INSTRUMENT_DEFINITION => Message::InstrumentDefinitions(Definitions { chunks: items.chunks_exact(INSTRUMENT_DEFINITION_SIZE) }),
+        MESSAGE_TYPE_SYSTEM_HEARTBEAT => SystemHeartbeat::try_from(items).map_or_else(unknown, Message::Heartbeat),
+        MESSAGE_TYPE_CONNECTION_STATUS => ConnectionStatus::try_from(items).map_or_else(unknown, Message::ConnectionStatus),
+        MESSAGE_TYPE_SUBSCRIPTION_STATUS => SubscriptionStatus::try_from(items).map_or_else(unknown, Message::SubscriptionStatus),
+        MESSAGE_TYPE_SYSTEM_STATS => SystemStats::try_from(items).map_or_else(unknown, Message::SystemStats),
+        MESSAGE_TYPE_ERROR_EVENT => ErrorEvent::try_from(items).map_or_else(unknown, Message::Error),
+        MESSAGE_TYPE_SYSTEM_SHUTDOWN => SystemShutdown::try_from(items).map_or_else(unknown, Message::Shutdown),
+        t => Message::Unknown(t),
+    }
+}
+
+/// 패킷 전체 뷰 (헤더 + item_count만큼의 페이로드)
+#[derive(Debug, Clone, Copy)]
+pub struct PacketView<'a> {
+    pub header: HeaderView<'a>,
+    /// v2 분할 정보 (다중 패킷 메시지의 조각일 때)
+    pub fragment: Option<FragmentHeader>,
+    /// v2 오더북 증분의 거래소 업데이트 ID 범위
+    pub update_ids: Option<UpdateIdRange>,
+    payload: &'a [u8],
+}
+
+impl<'a> PacketView<'a> {
+    /// 헤더와 페이로드 길이 검증 (알 수 없는 타입은 페이로드 전체를 유지)
+    ///
+    /// v2는 payload_length만큼을 페이로드로 사용하고, CRC 플래그가 있으면 CRC32를 검증합니다.
+    /// FRAGMENT 플래그가 있으면 페이로드 앞 8바이트를 분할 정보로, UPDATE_IDS 플래그가 있으면
+    /// 그 뒤 16바이트를 업데이트 ID 범위로 분리합니다.
+    pub fn parse(bytes: &'a [u8]) -> std::result::Result<Self, DecodeError> {
+        let header = HeaderView::new(bytes)?;
+        let mut payload = &bytes[header.len()..];
+        let mut fragment = None;
+        let mut update_ids = None;
+        if let Some(declared) = header.payload_length() {
+            payload = payload.get(..declared).ok_or(DecodeError::PayloadLengthMismatch { declared, actual: payload.len() })?;
+            if let Some(expected) = header.crc32() {
+                if !verify_v2_crc(&bytes[..header.len() + declared], expected) {
+                    return Err(DecodeError::CrcMismatch { expected });
+                }
+            }
+            if header.is_fragmented() {
+                let parsed = FragmentHeader::decode(payload).ok().filter(FragmentHeader::is_valid)
+                    .ok_or(DecodeError::InvalidFragment { actual: payload.len() })?;
+                fragment = Some(parsed);
+                payload = &payload[FRAGMENT_HEADER_SIZE..];
+            }
+            if header.has_update_ids() {
+                let parsed = UpdateIdRange::decode(payload).map_err(|_| DecodeError::InvalidUpdateIds { actual: payload.len() })?;
+                update_ids = Some(parsed);
+                payload = &payload[UPDATE_ID_RANGE_SIZE..];
+            }
+        }
+        let payload = match item_size(header.message_type()) {
+            Some(size) => {
+                let count = header.item_count() as usize;
+                payload.get(..count * size).ok_or(DecodeError::PayloadTooShort {
+                    message_type: header.message_type(),
+                    count,
+                    actual: payload.len(),
+                })?
+            }
+            None => payload,
+        };
+        Ok(Self { header, fragment, update_ids, payload })
+    }
+
+    /// 헤더 이후 아이템 바이트
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    pub fn message(&self) -> Message<'a> {
+        decode_message(self.header.message_type(), self.payload)
+    }
+
+    /// 종목 스케일을 적용한 해석 (`ScaleBook::for_header`)
+    pub fn message_with(&self, scale: InstrumentScale) -> Message<'a> {
+        decode_message_with_scale(self.header.message_type(), self.payload, scale)
+    }
+}
+
+/// 재조립된 메시지 (첫 패킷 헤더 정보 + 전체 아이템 바이트)
+#[derive(Debug, Clone)]
+pub struct AssembledMessage {
+    /// 수신 채널 (포트)
+    pub channel: u16,
+    pub message_type: u8,
+    /// v2 헤더의 거래소/종목 ID (v1은 0)
+    pub venue_id: u16,
+    pub instrument_id: u32,
+    pub sequence_number: u64,
+    pub exchange_timestamp: u64,
+    pub local_timestamp: u64,
+    pub exchange: String,
+    pub symbol: String,
+    pub items: Vec<u8>,
+    /// 메시지를 구성한 패킷 수
+    pub packets: u32,
+    /// v2 분할 정보의 message_id (단일 패킷 또는 v1 메시지는 None)
+    pub message_id: Option<u32>,
+    /// v2 오더북 증분의 거래소 업데이트 ID 범위 (v1 또는 ID를 제공하지 않는 거래소는 None)
+    pub update_ids: Option<UpdateIdRange>,
+}
+
+impl AssembledMessage {
+    pub fn message(&self) -> Message<'_> {
+        decode_message(self.message_type, &self.items)
+    }
+
+    /// 종목 스케일을 적용한 해석 (`ScaleBook::for_message`)
+    pub fn message_with(&self, scale: InstrumentScale) -> Message<'_> {
+        decode_message_with_scale(self.message_type, &self.items, scale)
+    }
+}
+
+/// 종목별 가격/수량 스케일 (없으면 기본 10^8)
+///
+/// 참조 데이터 포트의 종목 정의(`learn`) 또는 config.ini의 `instrument_scales`로 등록합니다.
+/// v1 패킷은 심볼, v2 패킷은 instrument_id로 조회합니다.
+#[derive(Debug, Clone, Default)]
+pub struct ScaleBook {
+    by_symbol: HashMap<String, InstrumentScale>,
+    by_instrument_id: HashMap<u32, InstrumentScale>,
+}
+
+impl ScaleBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// config.ini의 `instrument_scales` 로드 (키가 없으면 빈 목록)
+    pub fn from_config_ini(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CryptoFeederError::Other(format!("설정 파일 읽기 실패 ({}): {}", path.display(), e)))?;
+        let map = Config::parse_ini(&content)?;
+        let by_symbol = map.get("instrument_scales")
+            .map(|v| InstrumentScale::parse_list(v))
+            .transpose()
+            .map_err(CryptoFeederError::Other)?
+            .unwrap_or_default();
+        Ok(Self { by_symbol, by_instrument_id: HashMap::new() })
+    }
+
+    pub fn insert(&mut self, symbol: &str, scale: InstrumentScale) {
+        self.by_symbol.insert(symbol.to_string(), scale);
+    }
+
+    /// 심볼별 스케일 (패킷 생성기 설정용)
+    pub fn symbol_scales(&self) -> &HashMap<String, InstrumentScale> {
+        &self.by_symbol
+    }
+
+    /// 종목 정의 메시지면 스케일을 등록하고 등록한 종목 수를 반환
+    pub fn learn(&mut self, message: &Message<'_>) -> usize {
+        let Message::InstrumentDefinitions(definitions) = message else { return 0 };
+        let mut learned = 0;
+        for definition in definitions.clone() {
+            self.by_symbol.insert(definition.symbol_str(), definition.scale());
+            self.by_instrument_id.insert(definition.instrument_id, definition.scale());
+            learned += 1;
+        }
+        learned
+    }
+
+    fn lookup(&self, symbol: &str, instrument_id: u32) -> InstrumentScale {
+        let scale = if instrument_id != 0 { self.by_instrument_id.get(&instrument_id) } else { self.by_symbol.get(symbol) };
+        scale.copied().unwrap_or_default()
+    }
+
+    pub fn for_header(&self, header: &HeaderView<'_>) -> InstrumentScale {
+        self.lookup(header.symbol(), header.instrument_id())
+    }
+
+    pub fn for_message(&self, message: &AssembledMessage) -> InstrumentScale {
+        self.lookup(&message.symbol, message.instrument_id)
+    }
+}
+
+/// 다중 패킷 메시지 재조립 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssemblyStats {
+    pub packets: u64,
+    pub messages: u64,
+    /// 헤더보다 짧거나 아이템 길이가 맞지 않는 패킷
+    pub malformed: u64,
+    /// is_last 패킷 전에 다른 메시지가 시작되어 버린 미완성 메시지
+    pub incomplete: u64,
+    /// 알 수 없는 메시지 타입
+    pub skipped: u64,
+    /// 미완성으로 버린 분할 메시지에서 받지 못한 조각 수
+    pub missing_fragments: u64,
+}
+
+/// 재조립 키: 채널, venue_id, instrument_id, 거래소, 심볼, 메시지 타입 (v1은 ID 0, v2는 문자열이 비어 있음)
+type AssemblyKey = (u16, u16, u32, String, String, u8);
+
+/// 분할 정보가 있는 메시지의 수신 중 조각
+struct PendingFragments {
+    message: AssembledMessage,
+    parts: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+impl PendingFragments {
+    fn missing(&self) -> u64 {
+        (self.parts.len() - self.received) as u64
+    }
+}
+
+/// 다중 패킷 메시지 재조립기 (채널/거래소/심볼/메시지 타입별)
+///
+/// v2 분할 정보가 있으면 message_id 단위로 조각 순서와 관계없이 모으고 빠진 조각 수를 집계하며,
+/// 분할 정보가 없는 패킷(v1, 구버전 피더)은 is_last 플래그 기준으로 이어 붙입니다.
+#[derive(Default)]
+pub struct MessageAssembler {
+    pending: HashMap<AssemblyKey, AssembledMessage>,
+    fragments: HashMap<AssemblyKey, PendingFragments>,
+    pub stats: AssemblyStats,
+}
+
+impl MessageAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 단일 채널 입력용 (채널 0)
+    pub fn push(&mut self, datagram: &[u8]) -> Option<AssembledMessage> {
+        self.push_from(0, datagram)
+    }
+
+    /// 데이터그램 하나를 넣고, 메시지가 완성되면 반환
+    pub fn push_from(&mut self, channel: u16, datagram: &[u8]) -> Option<AssembledMessage> {
+        self.stats.packets += 1;
+        let packet = match PacketView::parse(datagram) {
+            Ok(p) => p,
+            Err(_) => {
+                self.stats.malformed += 1;
+                return None;
+            }
+        };
+        let header = packet.header;
+        if item_size(header.message_type()).is_none() {
+            self.stats.skipped += 1;
+            return None;
+        }
+
+        let key = (
+            channel,
+            header.venue_id(),
+            header.instrument_id(),
+            header.exchange().to_string(),
+            header.symbol().to_string(),
+            header.message_type(),
+        );
+        if let Some(fragment) = packet.fragment {
+            return self.push_fragment(key, &packet, fragment);
+        }
+        // 같은 키의 이전 메시지가 다른 시각이면 미완성으로 폐기
+        if self.pending.get(&key).map(|p| p.exchange_timestamp != header.exchange_timestamp()).unwrap_or(false) {
+            self.pending.remove(&key);
+            self.stats.incomplete += 1;
+        }
+        let message = self.pending.entry(key.clone()).or_insert_with(|| new_message(channel, &header, &key, None, packet.update_ids));
+        message.items.extend_from_slice(packet.payload());
+        message.packets += 1;
+
+        if header.is_last() {
+            self.stats.messages += 1;
+            self.pending.remove(&key)
+        } else {
+            None
+        }
+    }
+
+    /// 분할 정보가 있는 조각 처리 (모든 조각이 모이면 조각 순서대로 이어 붙여 반환)
+    fn push_fragment(&mut self, key: AssemblyKey, packet: &PacketView<'_>, fragment: FragmentHeader) -> Option<AssembledMessage> {
+        let (message_id, index, total) = (fragment.message_id, fragment.fragment_index as usize, fragment.fragment_total as usize);
+        // 같은 키에서 다른 메시지가 시작되면 이전 메시지는 조각 유실로 폐기
+        if self.fragments.get(&key).map(|p| p.message.message_id != Some(message_id) || p.parts.len() != total).unwrap_or(false) {
+            let dropped = self.fragments.remove(&key).unwrap();
+            self.stats.incomplete += 1;
+            self.stats.missing_fragments += dropped.missing();
+        }
+        let header = packet.header;
+        let pending = self.fragments.entry(key.clone()).or_insert_with(|| PendingFragments {
+            message: new_message(key.0, &header, &key, Some(message_id), packet.update_ids),
+            parts: vec![None; total],
+            received: 0,
+        });
+        // 중복 조각은 무시
+        if pending.parts[index].is_none() {
+            pending.parts[index] = Some(packet.payload().to_vec());
+            pending.received += 1;
+            if index == 0 {
+                pending.message.sequence_number = header.sequence_number();
+                pending.message.local_timestamp = header.local_timestamp();
+            }
+        }
+        if pending.received < total {
+            return None;
+        }
+
+        let mut done = self.fragments.remove(&key).unwrap();
+        for part in done.parts.into_iter().flatten() {
+            done.message.items.extend_from_slice(&part);
+        }
+        done.message.packets = total as u32;
+        self.stats.messages += 1;
+        Some(done.message)
+    }
+
+    /// 입력 종료 시 남은 미완성 메시지 수 반영
+    pub fn finish(&mut self) {
+        self.stats.incomplete += (self.pending.len() + self.fragments.len()) as u64;
+        self.stats.missing_fragments += self.fragments.values().map(PendingFragments::missing).sum::<u64>();
+        self.pending.clear();
+        self.fragments.clear();
+    }
+}
+
+/// 헤더 정보로 빈 재조립 메시지 생성 (sequence_number/local_timestamp는 첫 패킷 기준)
+fn new_message(channel: u16, header: &HeaderView<'_>, key: &AssemblyKey, message_id: Option<u32>, update_ids: Option<UpdateIdRange>) -> AssembledMessage {
+    AssembledMessage {
+        channel,
+        message_type: header.message_type(),
+        venue_id: header.venue_id(),
+        instrument_id: header.instrument_id(),
+        sequence_number: header.sequence_number(),
+        exchange_timestamp: header.exchange_timestamp(),
+        local_timestamp: header.local_timestamp(),
+        exchange: key.3.clone(),
+        symbol: key.4.clone(),
+        items: Vec::new(),
+        packets: 0,
+        message_id,
+        update_ids,
+    }
+}
+
+/// 시퀀스 번호 확인 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceCheck {
+    /// 채널의 첫 패킷
+    First,
+    InOrder,
+    /// 누락 발생 (missing = 건너뛴 번호 수)
+    Gap { expected: u64, received: u64, missing: u64 },
+    /// 이미 지난 번호 (중복 또는 순서 뒤바뀜)
+    Stale { last: u64, received: u64 },
+    /// 시퀀스가 1로 돌아감 (피더 재시작)
+    Reset,
+}
+
+/// 채널별 시퀀스 누락 감지
+///
+/// v1 헤더의 sequence_number는 피더 프로세스 전체에서 하나씩 증가하므로, 여러 포트로 분산된 환경에서
+/// 포트를 채널로 사용하면 다른 포트로 간 패킷도 누락으로 집계됩니다. v2 헤더는 포트별 시퀀스를 사용합니다.
+#[derive(Debug, Default)]
+pub struct GapDetector<K: Eq + Hash> {
+    last: HashMap<K, u64>,
+    pub gaps: u64,
+    pub missing: u64,
+    pub stale: u64,
+    pub resets: u64,
+}
+
+impl<K: Eq + Hash> GapDetector<K> {
+    pub fn new() -> Self {
+        Self { last: HashMap::new(), gaps: 0, missing: 0, stale: 0, resets: 0 }
+    }
+
+    pub fn observe(&mut self, channel: K, sequence_number: u64) -> SequenceCheck {
+        let Some(last) = self.last.get_mut(&channel) else {
+            self.last.insert(channel, sequence_number);
+            return SequenceCheck::First;
+        };
+        let expected = last.wrapping_add(1);
+        let check = if sequence_number == expected {
+            SequenceCheck::InOrder
+        } else if sequence_number > expected {
+            let missing = sequence_number - expected;
+            self.gaps += 1;
+            self.missing += missing;
+            SequenceCheck::Gap { expected, received: sequence_number, missing }
+        } else if sequence_number <= 1 {
+            self.resets += 1;
+            SequenceCheck::Reset
+        } else {
+            self.stale += 1;
+            return SequenceCheck::Stale { last: *last, received: sequence_number };
+        };
+        *last = sequence_number;
+        check
+    }
+}
+
+/// 멀티캐스트 수신 설정
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberConfig {
+    pub group: Ipv4Addr,
+    pub interface: Ipv4Addr,
+    pub ports: Vec<u16>,
+    /// 소켓 수신 버퍼 크기 (0이면 OS 기본값)
+    pub recv_buffer_bytes: usize,
+}
+
+impl Default for SubscriberConfig {
+    fn default() -> Self {
+        Self { group: Ipv4Addr::new(239, 255, 1, 1), interface: Ipv4Addr::UNSPECIFIED, ports: vec![55555], recv_buffer_bytes: 4 << 20 }
+    }
+}
+
+impl SubscriberConfig {
+    /// config.ini의 multicast_addr/interface_addr/port 적용 (없는 키는 기본값)
+    pub fn from_config_ini(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| CryptoFeederError::Other(format!("설정 파일 읽기 실패 ({}): {}", path.display(), e)))?;
+        let map = Config::parse_ini(&content)?;
+        let mut config = Self::default();
+        let parse_err = |key: &str, value: &str| CryptoFeederError::Other(format!("잘못된 {} 값: {}", key, value));
+        if let Some(v) = map.get("multicast_addr") {
+            config.group = v.parse().map_err(|_| parse_err("multicast_addr", v))?;
+        }
+        if let Some(v) = map.get("interface_addr") {
+            config.interface = v.parse().map_err(|_| parse_err("interface_addr", v))?;
+        }
+        if let Some(v) = map.get("port") {
+            config.ports = vec![v.parse().map_err(|_| parse_err("port", v))?];
+        }
+        Ok(config)
+    }
+}
+
+/// 수신된 데이터그램
+#[derive(Debug, Clone)]
+pub struct Datagram {
+    pub port: u16,
+    pub source: SocketAddr,
+    pub recv_timestamp_ns: u64,
+    pub data: Vec<u8>,
+}
+
+impl Datagram {
+    pub fn packet(&self) -> std::result::Result<PacketView<'_>, DecodeError> {
+        PacketView::parse(&self.data)
+    }
+}
+
+/// 멀티캐스트 가입 소켓 생성 (reuseaddr로 같은 포트를 여러 소비자가 공유)
+pub fn join_multicast(group: Ipv4Addr, interface: Ipv4Addr, port: u16, recv_buffer_bytes: usize) -> std::io::Result<UdpSocket> {
+    let sock = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    sock.set_reuse_address(true)?;
+    if recv_buffer_bytes > 0 {
+        sock.set_recv_buffer_size(recv_buffer_bytes).ok();
+    }
+    sock.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)).into())?;
+    sock.join_multicast_v4(&group, &interface)?;
+    Ok(sock.into())
+}
+
+/// 포트별 수신 쓰레드를 두고 하나의 큐로 데이터그램을 전달하는 구독자
+pub struct MulticastSubscriber {
+    rx: mpsc::Receiver<Datagram>,
+    running: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl MulticastSubscriber {
+    pub fn join(config: &SubscriberConfig) -> std::io::Result<Self> {
+        let (tx, rx) = mpsc::sync_channel(65_536);
+        let running = Arc::new(AtomicBool::new(true));
+        let mut workers = Vec::new();
+        for &port in &config.ports {
+            let socket = join_multicast(config.group, config.interface, port, config.recv_buffer_bytes)?;
+            // 종료 확인을 위한 주기적 타임아웃
+            socket.set_read_timeout(Some(Duration::from_millis(200)))?;
+            let tx = tx.clone();
+            let running = running.clone();
+            let worker = std::thread::Builder::new()
+                .name(format!("mcast-{}", port))
+                .spawn(move || {
+                    let mut buf = vec![0u8; 65_536];
+                    while running.load(Ordering::Relaxed) {
+                        match socket.recv_from(&mut buf) {
+                            Ok((size, source)) => {
+                                let datagram = Datagram { port, source, recv_timestamp_ns: crate::journal::now_nanos(), data: buf[..size].to_vec() };
+                                if tx.send(datagram).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+                            Err(e) => log::warn!("⚠️ 포트 {} 수신 오류: {}", port, e),
+                        }
+                    }
+                })?;
+            workers.push(worker);
+        }
+        Ok(Self { rx, running, workers })
+    }
+
+    /// 다음 데이터그램 (모든 수신 쓰레드가 종료되면 None)
+    pub fn recv(&self) -> Option<Datagram> {
+        self.rx.recv().ok()
+    }
+
+    /// 제한 시간 내 다음 데이터그램
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Datagram> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for MulticastSubscriber {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// 테스트용 v1 패킷 (BinanceSpot BTC^USDT 헤더 뒤에 아이템 바이트를 이어 붙임, local_timestamp는 2023-11-14)
+#[cfg(test)]
+pub(crate) fn test_packet(message_type: u8, seq: u64, exchange_ts: u64, is_last: bool, items: &[Vec<u8>]) -> Vec<u8> {
+    let mut header = crate::protocol::PacketHeader::new();
+    header.sequence_number = seq;
+    header.exchange_timestamp = exchange_ts;
+    header.local_timestamp = 1_700_000_000_000_000_000;
+    header.message_type = message_type;
+    header.set_flags_and_count(is_last, items.len() as u8);
+    header.set_symbol("BTC^USDT");
+    header.set_exchange("BinanceSpot");
+    let mut bytes = header.to_bytes();
+    for item in items {
+        bytes.extend_from_slice(item);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{SystemEvent, SHUTDOWN_REASON_TIMER};
+    use crate::packet_builder::PacketBuilder;
+    use crate::protocol::{OrderBookItem, PacketHeader, PacketHeaderV2, TradeTickItem};
+
+    #[test]
+    fn test_views_match_packed_structs() {
+        let bytes = test_packet(MESSAGE_TYPE_TRADE_TICK, 42, 1_700_000_000_000, true, &[TradeTickItem::new(50_000.5, 0.25, true).unwrap().to_bytes()]);
+        let view = PacketView::parse(&bytes).unwrap();
+        assert_eq!(view.header.protocol_version(), 1);
+        assert_eq!(view.header.sequence_number(), 42);
+        assert_eq!(view.header.exchange_timestamp(), 1_700_000_000_000);
+        assert_eq!(view.header.local_timestamp(), 1_700_000_000_000_000_000);
+        assert_eq!(view.header.symbol(), "BTC^USDT");
+        assert_eq!(view.header.exchange(), "BinanceSpot");
+        assert!(view.header.is_last());
+        let Message::Trade(mut items) = view.message() else { panic!("trade expected") };
+        let item = items.next().unwrap();
+        assert_eq!(item.price(), 50_000.5);
+        assert_eq!(item.quantity(), 0.25);
+        assert!(item.flag());
+        assert!(items.next().is_none());
+
+        assert_eq!(PacketView::parse(&bytes[..40]).unwrap_err(), DecodeError::TooShort { actual: 40 });
+        assert!(matches!(PacketView::parse(&bytes[..HEADER_SIZE + 8]), Err(DecodeError::PayloadTooShort { .. })));
+    }
+
+    #[test]
+    fn test_decodes_system_events() {
+        let builder = PacketBuilder::new();
+        let heartbeat = builder.build_event_packet(SystemEvent::Heartbeat(SystemHeartbeat::new(90, 3, 1234))).unwrap();
+        let view = PacketView::parse(&heartbeat.data).unwrap();
+        assert_eq!(view.header.exchange(), "FEEDER");
+        let Message::Heartbeat(h) = view.message() else { panic!("heartbeat expected") };
+        let (uptime, active, total) = (h.uptime_seconds, h.active_connections, h.total_packets_sent);
+        assert_eq!((uptime, active, total), (90, 3, 1234));
+
+        let shutdown = builder.build_event_packet(SystemEvent::Shutdown(SystemShutdown::new(90, 1234, SHUTDOWN_REASON_TIMER))).unwrap();
+        let Message::Shutdown(s) = PacketView::parse(&shutdown.data).unwrap().message() else { panic!("shutdown expected") };
+        let (uptime, total, reason) = (s.uptime_seconds, s.total_packets_sent, s.reason);
+        assert_eq!((uptime, total, reason), (90, 1234, SHUTDOWN_REASON_TIMER));
+
+        let sub = builder.build_event_packet(SystemEvent::SubscriptionStatus(SubscriptionStatus::new(1, 2, 1, "ETH^USDT"))).unwrap();
+        let Message::SubscriptionStatus(s) = PacketView::parse(&sub.data).unwrap().message() else { panic!("subscription expected") };
+        assert_eq!(s.symbol_short, SubscriptionStatus::new(1, 2, 1, "ETH^USDT").symbol_short);
+
+        let err = builder.build_event_packet(SystemEvent::ErrorEvent(ErrorEvent::new(1, 3, 4, 99))).unwrap();
+        let Message::Error(e) = PacketView::parse(&err.data).unwrap().message() else { panic!("error expected") };
+        let (error_type, exchange_id, severity, details) = (e.error_type, e.exchange_id, e.severity, e.error_details);
+        assert_eq!((error_type, exchange_id, severity, details), (1, 3, 4, 99));
+    }
+
+    #[test]
+    fn test_reassembles_and_drops_incomplete() {
+        let mut assembler = MessageAssembler::new();
+        let bid = OrderBookItem::new(100.0, 1.0, false).unwrap().to_bytes();
+        let ask = OrderBookItem::new(101.0, 2.0, true).unwrap().to_bytes();
+        assert!(assembler.push_from(55555, &test_packet(MESSAGE_TYPE_ORDER_BOOK, 1, 10, false, std::slice::from_ref(&bid))).is_none());
+        // 다른 채널의 같은 심볼 메시지는 독립적으로 재조립
+        assert!(assembler.push_from(55556, &test_packet(MESSAGE_TYPE_ORDER_BOOK, 2, 10, false, std::slice::from_ref(&bid))).is_none());
+        let message = assembler.push_from(55555, &test_packet(MESSAGE_TYPE_ORDER_BOOK, 3, 10, true, &[ask])).unwrap();
+        assert_eq!((message.channel, message.sequence_number, message.packets), (55555, 1, 2));
+        let Message::OrderBook(items) = message.message() else { panic!("order book expected") };
+        assert_eq!(items.map(|i| i.flag()).collect::<Vec<_>>(), vec![false, true]);
+
+        // 마지막 패킷 유실 후 다음 메시지 시작
+        assert!(assembler.push_from(55556, &test_packet(MESSAGE_TYPE_ORDER_BOOK, 5, 11, true, &[bid])).is_some());
+        assert!(assembler.push(&[0u8; 10]).is_none());
+        assert_eq!(assembler.stats, AssemblyStats { packets: 5, messages: 2, malformed: 1, incomplete: 1, skipped: 0, missing_fragments: 0 });
+    }
+
+    #[test]
+    fn test_reassembles_fragments_out_of_order() {
+        use crate::data_parser::{OrderBookLevel, StandardizedOrderBookUpdate};
+        let builder = PacketBuilder::new().with_instruments(std::sync::Arc::new(crate::instruments::InstrumentRegistry::new()));
+        let book = |levels: usize, timestamp: u64| StandardizedOrderBookUpdate {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            bids: (0..levels).map(|i| OrderBookLevel { price: 100.0 - i as f64 * 0.01, quantity: 1.0 }).collect(),
+            asks: Vec::new(),
+            timestamp,
+            first_update_id: Some(timestamp * 100),
+            last_update_id: Some(timestamp * 100 + 9),
+            is_snapshot: false,
+        };
+        let to_v2 = |packets: Vec<crate::packet_builder::UdpPacket>| -> Vec<Vec<u8>> {
+            packets.iter().enumerate()
+                .map(|(i, p)| crate::udp_broadcaster::encode_v2_packet(p, i as u64 + 1, 0).unwrap().unwrap())
+                .collect()
+        };
+
+        // 200레벨 → 기본 1472바이트 상한에서 87개씩 3조각
+        let v2 = to_v2(builder.build_packets(crate::data_parser::ParsedData::OrderBook(book(200, 1))).unwrap());
+        assert_eq!(v2.len(), 3);
+        let fragment = PacketView::parse(&v2[1]).unwrap().fragment.unwrap();
+        assert_eq!(({ fragment.fragment_index }, { fragment.fragment_total }), (1, 3));
+
+        let mut assembler = MessageAssembler::new();
+        assert!(assembler.push(&v2[2]).is_none());
+        assert!(assembler.push(&v2[0]).is_none());
+        let message = assembler.push(&v2[1]).unwrap();
+        assert_eq!((message.packets, message.sequence_number, message.message_id), (3, 1, Some(fragment.message_id)));
+        // 업데이트 ID 범위는 모든 조각에 실려 재조립 메시지로 전달
+        assert_eq!(message.update_ids, Some(UpdateIdRange { first_update_id: 100, last_update_id: 109 }));
+        let Message::OrderBook(items) = message.message() else { panic!("order book expected") };
+        let prices: Vec<f64> = items.map(|i| i.price()).collect();
+        assert_eq!(prices.len(), 200);
+        assert!(prices.windows(2).all(|w| w[0] > w[1]));
+
+        // 가운데 조각 유실: 다음 메시지가 시작되면 빠진 조각 수와 함께 폐기
+        let lost = to_v2(builder.build_packets(crate::data_parser::ParsedData::OrderBook(book(200, 2))).unwrap());
+        assert!(assembler.push(&lost[0]).is_none());
+        assert!(assembler.push(&lost[2]).is_none());
+        let next = to_v2(builder.build_packets(crate::data_parser::ParsedData::OrderBook(book(100, 3))).unwrap());
+        assert!(assembler.push(&next[0]).is_none());
+        assert!(assembler.push(&next[1]).is_some());
+        assert_eq!((assembler.stats.incomplete, assembler.stats.missing_fragments), (1, 1));
+    }
+
+    #[test]
+    fn test_decodes_trades_with_ids() {
+        use crate::data_parser::{ParsedData, StandardizedTrade};
+        let trade = |trade_id| StandardizedTrade {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            price: 50000.0,
+            quantity: 0.5,
+            is_buyer_taker: true,
+            timestamp: 1_640_995_200_000_000_000,
+            trade_id,
+        };
+        let builder = PacketBuilder::new().with_trade_ids(true);
+        let packets = builder.build_trade_packets_batch(vec![trade(Some(42)), trade(None)]).unwrap();
+        let view = PacketView::parse(&packets[0].data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_TRADE_TICK_WITH_ID);
+        let Message::Trade(items) = view.message() else { panic!("trade expected") };
+        let items: Vec<_> = items.collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!((items[0].price(), items[0].quantity(), items[0].flag()), (50000.0, 0.5, true));
+        assert_eq!((items[0].trade_id(), items[0].trade_time()), (Some(42), Some(1_640_995_200_000_000_000)));
+        assert_eq!(items[1].trade_id(), Some(0));
+
+        // 기본 형식(타입 1)은 체결 ID가 없음
+        let packets = PacketBuilder::new().build_packets(ParsedData::Trade(trade(Some(42)))).unwrap();
+        let Message::Trade(mut items) = PacketView::parse(&packets[0].data).unwrap().message() else { panic!("trade expected") };
+        assert_eq!(items.next().unwrap().trade_id(), None);
+    }
+
+    #[test]
+    fn test_decodes_kline() {
+        use crate::data_parser::StandardizedKline;
+        let kline = StandardizedKline {
+            symbol: "ETH^USDT".into(),
+            exchange: "BybitSpot".into(),
+            interval_secs: 60,
+            start_time: 1_700_000_000_000_000_000,
+            open: 2000.0,
+            high: 2010.5,
+            low: 1995.25,
+            close: 2005.0,
+            volume: 12.5,
+            quote_volume: 25_050.0,
+            trade_count: 7,
+            is_closed: true,
+            timestamp: 1_700_000_060_000_000_000,
+        };
+        let packet = PacketBuilder::new().build_kline_packet(&kline).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_KLINE);
+        assert_eq!(view.header.exchange_timestamp(), kline.timestamp);
+        let Message::Kline(k) = view.message() else { panic!("kline expected") };
+        assert_eq!((k.open, k.high, k.low, k.close), (2000.0, 2010.5, 1995.25, 2005.0));
+        assert_eq!((k.volume, k.quote_volume, k.trade_count), (12.5, 25_050.0, 7));
+        assert_eq!((k.start_time, k.interval_secs, k.is_closed), (kline.start_time, 60, true));
+    }
+
+    #[test]
+    fn test_decodes_ticker() {
+        use crate::data_parser::StandardizedTicker;
+        let ticker = StandardizedTicker {
+            symbol: "BTC^USDT".into(),
+            exchange: "OkxSpot".into(),
+            last_price: 50_500.0,
+            open_price: 50_000.0,
+            high_price: 51_000.0,
+            low_price: 49_000.5,
+            volume: 1234.5,
+            quote_volume: 62_000_000.0,
+            price_change_percent: -1.25,
+            trade_count: 0,
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        let packet = PacketBuilder::new().build_ticker_packet(&ticker).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_TICKER_24H);
+        let Message::Ticker24h(t) = view.message() else { panic!("ticker expected") };
+        assert_eq!((t.last_price, t.open_price, t.high_price, t.low_price), (50_500.0, 50_000.0, 51_000.0, 49_000.5));
+        assert_eq!((t.volume, t.quote_volume, t.price_change_percent, t.trade_count), (1234.5, 62_000_000.0, -1.25, 0));
+    }
+
+    #[test]
+    fn test_decodes_open_interest() {
+        use crate::data_parser::StandardizedOpenInterest;
+        let oi = StandardizedOpenInterest {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceFutures".into(),
+            open_interest: 10_659.509,
+            open_interest_value: f64::NAN,
+            long_short_ratio: 1.8105,
+            timestamp: 1_589_437_530_011_000_000,
+        };
+        let packet = PacketBuilder::new().build_open_interest_packet(&oi).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_OPEN_INTEREST);
+        let Message::OpenInterest(decoded) = view.message() else { panic!("open interest expected") };
+        assert_eq!(decoded.open_interest, 10_659.509);
+        assert_eq!((decoded.open_interest_value, decoded.long_short_ratio), (None, Some(1.8105)));
+    }
+
+    #[test]
+    fn test_decodes_funding_info() {
+        use crate::data_parser::{ParsedData, StandardizedFunding};
+        let funding = StandardizedFunding {
+            symbol: "BTC^USDT".into(),
+            exchange: "OkxSwap".into(),
+            rate: 0.0001,
+            predicted_rate: 0.0,
+            next_funding_time: 1_700_006_400_000_000_000,
+            interval_secs: 28_800,
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        // 기본 형식(타입 4)은 현재 펀딩비만 전송
+        let packets = PacketBuilder::new().build_packets(ParsedData::FundingRate(funding.clone())).unwrap();
+        assert!(matches!(PacketView::parse(&packets[0].data).unwrap().message(), Message::FundingRate(rate) if rate == 0.0001));
+
+        let packets = PacketBuilder::new().with_funding_info(true).build_packets(ParsedData::FundingRate(funding)).unwrap();
+        let view = PacketView::parse(&packets[0].data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_FUNDING_INFO);
+        let Message::FundingInfo(info) = view.message() else { panic!("funding info expected") };
+        assert_eq!((info.funding_rate, info.predicted_rate), (0.0001, Some(0.0)));
+        assert_eq!((info.next_funding_time, info.interval_secs), (Some(1_700_006_400_000_000_000), Some(28_800)));
+    }
+
+    #[test]
+    fn test_decodes_liquidation_detail() {
+        use crate::data_parser::{LiquidationStatus, ParsedData, StandardizedLiquidation};
+        use crate::protocol::LIQUIDATION_STATUS_PARTIALLY_FILLED;
+        let liquidation = StandardizedLiquidation {
+            symbol: "ETH^USDT".into(),
+            exchange: "BinanceFutures".into(),
+            price: 1995.5,
+            quantity: 0.5,
+            is_sell: true,
+            filled_quantity: 0.3,
+            status: LiquidationStatus::PartiallyFilled,
+            order_time: 1_700_000_000_090_000_000,
+            timestamp: 1_700_000_000_100_000_000,
+        };
+        // 기본 형식(타입 5)은 가격/수량/방향만 전송
+        let packets = PacketBuilder::new().build_packets(ParsedData::Liquidation(liquidation.clone())).unwrap();
+        assert_eq!(PacketView::parse(&packets[0].data).unwrap().header.message_type(), MESSAGE_TYPE_LIQUIDATION);
+
+        let packets = PacketBuilder::new().with_liquidation_detail(true).build_packets(ParsedData::Liquidation(liquidation)).unwrap();
+        let view = PacketView::parse(&packets[0].data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_LIQUIDATION_DETAIL);
+        let Message::LiquidationDetail(detail) = view.message() else { panic!("liquidation detail expected") };
+        assert_eq!((detail.price, detail.quantity, detail.filled_quantity, detail.is_sell), (1995.5, 0.5, 0.3, true));
+        assert_eq!((detail.order_status, detail.order_time), (LIQUIDATION_STATUS_PARTIALLY_FILLED, Some(1_700_000_000_090_000_000)));
+    }
+
+    #[test]
+    fn test_decodes_consolidated_bbo() {
+        use crate::consolidated_bbo::{ConsolidatedQuote, VenueQuote};
+        let quote = ConsolidatedQuote {
+            symbol: "BTC^USDT".into(),
+            bid: Some(VenueQuote { exchange: "UpbitSpot".into(), price: 101.0, quantity: 0.5, fx_converted: true }),
+            ask: Some(VenueQuote { exchange: "BinanceSpot".into(), price: 100.5, quantity: 2.0, fx_converted: false }),
+            venue_count: 2,
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        let packet = PacketBuilder::new().build_consolidated_bbo_packet(&quote).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_CONSOLIDATED_BBO);
+        assert_eq!(view.header.exchange(), "Consolidated");
+        let Message::ConsolidatedBbo(bbo) = view.message() else { panic!("consolidated bbo expected") };
+        let (bid, ask) = (bbo.bid.unwrap(), bbo.ask.unwrap());
+        assert_eq!((bid.price, bid.quantity, bid.venue_name(), bid.fx_converted), (101.0, 0.5, Some("UpbitSpot"), true));
+        assert_eq!((ask.price, ask.venue_name(), ask.fx_converted), (100.5, Some("BinanceSpot"), false));
+        assert_eq!((bbo.venue_count, bbo.locked, bbo.crossed), (2, false, true));
+    }
+
+    #[test]
+    fn test_decodes_kimchi_premium() {
+        use crate::kimchi_premium::PremiumQuote;
+        let quote = PremiumQuote {
+            krw_exchange: "UpbitSpot".into(),
+            symbol: "BTC^KRW".into(),
+            foreign_exchange: "BinanceSpot".into(),
+            krw_price: 144_000.0,
+            foreign_price: 100.0,
+            fx_rate: 1400.0,
+            fx_from_market: true,
+            premium: 0.03,
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        let packet = PacketBuilder::new().build_kimchi_premium_packet(&quote).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_KIMCHI_PREMIUM);
+        assert_eq!((view.header.symbol(), view.header.exchange()), ("BTC^KRW", "UpbitSpot"));
+        let Message::KimchiPremium(k) = view.message() else { panic!("kimchi premium expected") };
+        assert_eq!((k.premium, k.krw_price, k.foreign_price, k.fx_rate), (0.03, 144_000.0, 100.0, 1400.0));
+        assert_eq!((k.foreign_venue_name(), k.market_fx), (Some("BinanceSpot"), true));
+    }
+
+    #[test]
+    fn test_decodes_trade_flow() {
+        use crate::trade_flow::{FlowSnapshot, FlowStats};
+        let snapshot = FlowSnapshot {
+            exchange: "BinanceSpot".into(),
+            symbol: "BTC^USDT".into(),
+            windows: vec![
+                FlowStats { window_secs: 1, trade_count: 0, vwap: 0.0, buy_volume: 0.0, sell_volume: 0.0 },
+                FlowStats { window_secs: 10, trade_count: 3, vwap: 106.0, buy_volume: 2.0, sell_volume: 3.0 },
+            ],
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        let packet = PacketBuilder::new().build_trade_flow_packet(&snapshot).unwrap();
+        let view = PacketView::parse(&packet.data).unwrap();
+        assert_eq!(view.header.message_type(), MESSAGE_TYPE_TRADE_FLOW);
+        assert_eq!(view.header.item_count(), 2);
+        let Message::TradeFlow(windows) = view.message() else { panic!("trade flow expected") };
+        let windows: Vec<_> = windows.collect();
+        assert_eq!((windows[0].window_secs, windows[0].trade_count, windows[0].imbalance), (1, 0, 0.0));
+        let w = windows[1];
+        assert_eq!((w.window_secs, w.trade_count, w.vwap, w.buy_volume, w.sell_volume, w.imbalance), (10, 3, 106.0, 2.0, 3.0, -0.2));
+    }
+
+    #[test]
+    fn test_parses_v2_header() {
+        let v1 = test_packet(MESSAGE_TYPE_ORDER_BOOK, 42, 10, true, &[OrderBookItem::new(100.0, 1.0, true).unwrap().to_bytes()]);
+        let v2_header = PacketHeaderV2::from_v1(&PacketHeader::try_from(v1.as_slice()).unwrap(), 1, 1);
+        let v1 = crate::packet_builder::UdpPacket { size: v1.len(), data: v1, fragment: None, update_ids: None, v2_header: Some(v2_header) };
+        let v2 = crate::udp_broadcaster::encode_v2_packet(&v1, 5, V2_FLAG_CRC | V2_FLAG_REPLAY).unwrap().unwrap();
+        assert_eq!(v2.len(), HEADER_V2_SIZE + ITEM_SIZE);
+
+        let view = PacketView::parse(&v2).unwrap();
+        assert!(view.header.is_v2());
+        assert_eq!(view.header.sequence_number(), 5);
+        assert_eq!((view.header.venue_id(), view.header.instrument_id()), (1, 1));
+        assert_eq!((view.header.exchange_timestamp(), view.header.local_timestamp()), (10, 1_700_000_000_000_000_000));
+        assert_eq!(view.header.flags_and_count(), 0b1000_0001);
+        assert!(view.header.is_replay() && !view.header.is_snapshot());
+        assert_eq!(view.header.symbol(), "");
+        let Message::OrderBook(mut items) = view.message() else { panic!("order book expected") };
+        assert_eq!(items.next().unwrap().price(), 100.0);
+
+        let mut corrupted = v2.clone();
+        corrupted[HEADER_V2_SIZE] ^= 0xFF;
+        assert!(matches!(PacketView::parse(&corrupted), Err(DecodeError::CrcMismatch { .. })));
+        assert!(matches!(PacketView::parse(&v2[..HEADER_V2_SIZE + 8]), Err(DecodeError::PayloadLengthMismatch { declared: 16, actual: 8 })));
+        assert_eq!(PacketView::parse(&[9u8; 80]).unwrap_err(), DecodeError::UnsupportedVersion(9));
+
+        let message = MessageAssembler::new().push_from(55555, &v2).unwrap();
+        assert_eq!((message.venue_id, message.instrument_id, message.sequence_number), (1, 1, 5));
+    }
+
+    #[test]
+    fn test_decodes_instrument_definitions() {
+        let mut symbol = [0u8; 20];
+        symbol[..8].copy_from_slice(b"BTC^USDT");
+        let definition = InstrumentDefinition {
+            instrument_id: 3, venue_id: 1, market_type: 1, price_scale: 8, quantity_scale: 8, reserved: [0; 3],
+            tick_size: 1_000_000, lot_size: 1_000, contract_multiplier: 100_000_000,
+            exchange: [0; 20], symbol, base: [0; 10], quote: [0; 10],
+        };
+        let packets = PacketBuilder::new().build_instrument_definition_packets(&[definition; 3]).unwrap();
+        let view = PacketView::parse(&packets[0].data).unwrap();
+        let Message::InstrumentDefinitions(items) = view.message() else { panic!("instrument definitions expected") };
+        let items: Vec<_> = items.collect();
+        assert_eq!(items, vec![definition; 3]);
+        assert_eq!(items[0].symbol_str(), "BTC^USDT");
+        assert_eq!(items[0].tick_size_f64(), 0.01);
+    }
+
+    #[test]
+    fn test_scale_book_descales_items() {
+        let shib = InstrumentScale::new(12, 4).unwrap();
+        let mut symbol = [0u8; 20];
+        symbol[..8].copy_from_slice(b"BTC^USDT");
+        let definition = InstrumentDefinition {
+            instrument_id: 9, venue_id: 1, market_type: 1, price_scale: 12, quantity_scale: 4, reserved: [0; 3],
+            tick_size: 0, lot_size: 0, contract_multiplier: 10_000,
+            exchange: [0; 20], symbol, base: [0; 10], quote: [0; 10],
+        };
+        let mut scales = ScaleBook::new();
+        let reference = PacketBuilder::new().build_instrument_definition_packets(&[definition]).unwrap();
+        assert_eq!(scales.learn(&PacketView::parse(&reference[0].data).unwrap().message()), 1);
+
+        let item = TradeTickItem::scaled(0.000012345678, 150_000_000.0, true, shib).unwrap();
+        let bytes = test_packet(MESSAGE_TYPE_TRADE_TICK, 1, 10, true, &[item.to_bytes()]);
+        let view = PacketView::parse(&bytes).unwrap();
+        assert_eq!(scales.for_header(&view.header), shib);
+        let Message::Trade(mut items) = view.message_with(scales.for_header(&view.header)) else { panic!("trade expected") };
+        let item = items.next().unwrap();
+        assert_eq!((item.price(), item.quantity()), (0.000012345678, 150_000_000.0));
+
+        // 모르는 종목은 기본 10^8
+        assert_eq!(ScaleBook::new().for_header(&view.header), InstrumentScale::DEFAULT);
+    }
+
+    #[test]
+    fn test_gap_detector_per_channel() {
+        let mut gaps = GapDetector::new();
+        assert_eq!(gaps.observe(55555u16, 10), SequenceCheck::First);
+        assert_eq!(gaps.observe(55556, 3), SequenceCheck::First);
+        assert_eq!(gaps.observe(55555, 11), SequenceCheck::InOrder);
+        assert_eq!(gaps.observe(55555, 15), SequenceCheck::Gap { expected: 12, received: 15, missing: 3 });
+        assert_eq!(gaps.observe(55555, 14), SequenceCheck::Stale { last: 15, received: 14 });
+        assert_eq!(gaps.observe(55556, 4), SequenceCheck::InOrder);
+        assert_eq!(gaps.observe(55555, 1), SequenceCheck::Reset);
+        assert_eq!(gaps.observe(55555, 2), SequenceCheck::InOrder);
+        assert_eq!((gaps.gaps, gaps.missing, gaps.stale, gaps.resets), (1, 3, 1, 1));
+    }
+
+    #[test]
+    fn test_subscriber_config_from_ini() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.ini");
+        std::fs::write(&path, "[udp]\nmulticast_addr=239.1.2.3\ninterface_addr=10.0.0.5\n").unwrap();
+        let config = SubscriberConfig::from_config_ini(&path).unwrap();
+        assert_eq!(config.group, Ipv4Addr::new(239, 1, 2, 3));
+        assert_eq!(config.interface, Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(config.ports, vec![55555]);
+    }
+}
@@ -1,5 +1,5 @@
-/// 데이터 파서 모듈
-/// simd-json을 사용하여 거래소별 JSON 데이터를 표준화된 구조체로 변환
+//! 데이터 파서 모듈
+//! simd-json을 사용하여 거래소별 JSON 데이터를 표준화된 구조체로 변환
 
 use crate::errors::{CryptoFeederError, Result};
 use crate::config::Config;
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use simd_json;
 use serde_json;
 use std::collections::HashMap;
+use std::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardizedTrade {
@@ -16,7 +17,10 @@ pub struct StandardizedTrade {
     pub price: f64,
     pub quantity: f64,
     pub is_buyer_taker: bool,
-    pub timestamp: u64, // nanoseconds since Unix epoch
+    pub timestamp: u64, // nanoseconds since Unix epoch (거래소 체결 시각)
+    /// 거래소 체결 ID (Binance trade `t` / aggTrade `a`)
+    #[serde(default)]
+    pub trade_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +38,176 @@ pub struct StandardizedOrderBookUpdate {
     pub bids: Vec<OrderBookLevel>,
     pub asks: Vec<OrderBookLevel>,
     pub timestamp: u64, // nanoseconds since Unix epoch
+    /// 거래소 업데이트 ID 범위 (Binance depth `U`/`u`)
+    ///
+    /// 직전 메시지의 `last_update_id + 1`보다 `first_update_id`가 크면 빠진 증분이 있습니다
+    /// (Binance 선물은 `pu + 1`, OKX는 `prevSeqId + 1`을 `first_update_id`로 사용).
+    #[serde(default)]
+    pub first_update_id: Option<u64>,
+    #[serde(default)]
+    pub last_update_id: Option<u64>,
+    /// 증분이 아닌 전체 호가 스냅샷 (Upbit orderbook, 이전 호가를 모두 대체)
+    #[serde(default)]
+    pub is_snapshot: bool,
+}
+
+/// OHLCV 봉 (거래소 kline 스트림 또는 로컬 체결 집계)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardizedKline {
+    pub symbol: String,
+    pub exchange: String,
+    /// 봉 길이 (초)
+    pub interval_secs: u32,
+    /// 봉 시작 시각 (nanoseconds since Unix epoch)
+    pub start_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// 기준 자산 거래량
+    pub volume: f64,
+    /// 호가 자산 거래대금
+    pub quote_volume: f64,
+    /// 체결 건수 (거래소가 제공하지 않으면 0)
+    pub trade_count: u64,
+    /// 봉 확정 여부
+    pub is_closed: bool,
+    pub timestamp: u64, // nanoseconds since Unix epoch (이벤트 시각)
+}
+
+/// 24시간 통계 티커
+///
+/// Bybit 파생상품 ticker 델타처럼 일부 필드만 오는 메시지는 빠진 필드를 NaN으로 두며,
+/// 티커 병합기(`ticker_conflator`)가 직전 값으로 채웁니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardizedTicker {
+    pub symbol: String,
+    pub exchange: String,
+    pub last_price: f64,
+    /// 24시간 전 가격
+    pub open_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    /// 24시간 기준 자산 거래량
+    pub volume: f64,
+    /// 24시간 호가 자산 거래대금
+    pub quote_volume: f64,
+    /// 24시간 변동률 (%)
+    pub price_change_percent: f64,
+    /// 24시간 체결 수 (거래소가 제공하지 않으면 0)
+    pub trade_count: u64,
+    pub timestamp: u64, // nanoseconds since Unix epoch (이벤트 시각)
+}
+
+impl StandardizedTicker {
+    /// 델타 티커의 값이 있는 필드만 덮어씀
+    pub fn merge_from(&mut self, update: &StandardizedTicker) {
+        let fields = [
+            (&mut self.last_price, update.last_price),
+            (&mut self.open_price, update.open_price),
+            (&mut self.high_price, update.high_price),
+            (&mut self.low_price, update.low_price),
+            (&mut self.volume, update.volume),
+            (&mut self.quote_volume, update.quote_volume),
+            (&mut self.price_change_percent, update.price_change_percent),
+        ];
+        for (dst, value) in fields {
+            if !value.is_nan() {
+                *dst = value;
+            }
+        }
+        if update.trade_count > 0 {
+            self.trade_count = update.trade_count;
+        }
+        self.timestamp = self.timestamp.max(update.timestamp);
+    }
+
+    /// 모든 가격/거래량 필드가 채워졌는지
+    pub fn is_complete(&self) -> bool {
+        [self.last_price, self.open_price, self.high_price, self.low_price, self.volume, self.quote_volume, self.price_change_percent]
+            .iter()
+            .all(|v| !v.is_nan())
+    }
+}
+
+/// 표준화된 펀딩 정보
+///
+/// 거래소가 제공하지 않는 값은 예상 펀딩비 NaN, 다음 정산 시각/정산 주기 0입니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardizedFunding {
+    pub symbol: String,
+    pub exchange: String,
+    /// 현재(다음 정산에 적용될) 펀딩비
+    pub rate: f64,
+    /// 그다음 정산의 예상 펀딩비 (OKX만 제공)
+    pub predicted_rate: f64,
+    /// 다음 정산 시각 (Unix 나노초)
+    pub next_funding_time: u64,
+    /// 정산 주기 (초)
+    pub interval_secs: u32,
+    pub timestamp: u64, // nanoseconds since Unix epoch (이벤트 시각)
+}
+
+/// 표준화된 미결제약정
+///
+/// 거래소가 제공하지 않는 값(명목 가치, 롱/숏 비율)은 NaN입니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardizedOpenInterest {
+    pub symbol: String,
+    pub exchange: String,
+    /// 미결제약정 (기준 자산 수량, Bybit 인버스는 계약 수)
+    pub open_interest: f64,
+    /// 미결제약정 명목 가치 (호가 자산)
+    pub open_interest_value: f64,
+    /// 롱/숏 계정 비율 (REST 조회 거래소만)
+    pub long_short_ratio: f64,
+    pub timestamp: u64, // nanoseconds since Unix epoch (거래소 집계 시각)
+}
+
+/// 청산 주문 상태 (거래소가 제공하지 않으면 Unknown)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiquidationStatus {
+    #[default]
+    Unknown,
+    New,
+    PartiallyFilled,
+    Filled,
+    /// 만료/취소 (미체결 잔량 소멸)
+    Expired,
+}
+
+impl LiquidationStatus {
+    /// Binance 주문 상태 문자열 (`NEW`, `PARTIALLY_FILLED`, `FILLED`, `EXPIRED`, `CANCELED`)
+    fn from_binance(status: &str) -> Self {
+        match status {
+            "NEW" => Self::New,
+            "PARTIALLY_FILLED" => Self::PartiallyFilled,
+            "FILLED" => Self::Filled,
+            "EXPIRED" | "CANCELED" => Self::Expired,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// 표준화된 청산
+///
+/// `is_sell`은 청산 주문 방향입니다 (true = 롱 포지션 청산). 누적 체결 수량을 제공하지 않는 거래소(OKX, Bybit)는
+/// 체결된 청산만 알리므로 주문 수량과 같게 채웁니다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardizedLiquidation {
+    pub symbol: String,
+    pub exchange: String,
+    /// 평균 체결가 (없으면 주문가/파산가)
+    pub price: f64,
+    /// 주문 수량 (OKX 파생은 계약 수)
+    pub quantity: f64,
+    pub is_sell: bool,
+    /// 누적 체결 수량
+    pub filled_quantity: f64,
+    pub status: LiquidationStatus,
+    /// 거래소 주문(체결) 시각 (Unix 나노초, 0 = 알 수 없음)
+    pub order_time: u64,
+    pub timestamp: u64, // nanoseconds since Unix epoch (이벤트 시각)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,8 +219,6 @@ pub struct OrderBookLevel {
 // Binance WebSocket 메시지 구조체
 #[derive(Debug, Deserialize)]
 struct BinanceDepthUpdate {
-    #[serde(rename = "e")]
-    event_type: String,
     #[serde(rename = "E")]
     event_time: u64,
     #[serde(rename = "s")]
@@ -55,6 +227,9 @@ struct BinanceDepthUpdate {
     first_update_id: u64,
     #[serde(rename = "u")]
     final_update_id: u64,
+    /// 직전 이벤트의 u (선물만 제공, 선물의 U는 직전 u + 1과 다를 수 있음)
+    #[serde(rename = "pu", default)]
+    prev_final_update_id: Option<u64>,
     #[serde(rename = "b")]
     bids: Vec<[String; 2]>, // [price, quantity]
     #[serde(rename = "a")]
@@ -63,10 +238,6 @@ struct BinanceDepthUpdate {
 
 #[derive(Debug, Deserialize)]
 struct BinanceTrade {
-    #[serde(rename = "e")]
-    event_type: String,
-    #[serde(rename = "E")]
-    event_time: u64,
     #[serde(rename = "s")]
     symbol: String,
     #[serde(rename = "t")]
@@ -75,10 +246,6 @@ struct BinanceTrade {
     price: String,
     #[serde(rename = "q")]
     quantity: String,
-    #[serde(rename = "b", default)]
-    buyer_order_id: Option<u64>,
-    #[serde(rename = "a", default)]
-    seller_order_id: Option<u64>,
     #[serde(rename = "T")]
     trade_time: u64,
     #[serde(rename = "m")]
@@ -87,10 +254,6 @@ struct BinanceTrade {
 
 #[derive(Debug, Deserialize)]
 struct BinanceAggTrade {
-    #[serde(rename = "e")]
-    event_type: String,
-    #[serde(rename = "E")]
-    event_time: u64,
     #[serde(rename = "s")]
     symbol: String,
     #[serde(rename = "a", default)]
@@ -105,9 +268,12 @@ struct BinanceAggTrade {
     is_buyer_market_maker: bool,
 }
 
+/// 거래소별 메시지 파서 함수
+type ParserFn = fn(&mut [u8]) -> Result<ParsedData>;
+
 pub struct DataParser {
     // 거래소별 파서 함수 맵
-    parsers: HashMap<String, fn(&mut [u8]) -> Result<ParsedData>>,
+    parsers: RwLock<HashMap<String, ParserFn>>,
 }
 
 #[derive(Debug)]
@@ -117,11 +283,30 @@ pub enum ParsedData {
     OrderBook(StandardizedOrderBookUpdate),
     IndexPrice { symbol: String, exchange: String, value: f64, timestamp: u64 },
     MarkPrice { symbol: String, exchange: String, value: f64, timestamp: u64 },
-    FundingRate { symbol: String, exchange: String, value: f64, timestamp: u64 },
-    Liquidation { symbol: String, exchange: String, price: f64, quantity: f64, is_sell: bool, timestamp: u64 },
+    FundingRate(StandardizedFunding),
+    Liquidation(StandardizedLiquidation),
+    Kline(StandardizedKline),
+    Ticker(StandardizedTicker),
+    OpenInterest(StandardizedOpenInterest),
     Multi(Vec<ParsedData>),
 }
 
+impl ParsedData {
+    /// 조건에 맞는 데이터만 남김 (Multi 내부 포함, 모두 걸러지면 빈 Multi)
+    pub fn retain(self, keep: &impl Fn(&ParsedData) -> bool) -> ParsedData {
+        match self {
+            ParsedData::Multi(items) => ParsedData::Multi(
+                items.into_iter()
+                    .map(|it| it.retain(keep))
+                    .filter(|it| !matches!(it, ParsedData::Multi(v) if v.is_empty()))
+                    .collect()
+            ),
+            other if keep(&other) => other,
+            _ => ParsedData::Multi(Vec::new()),
+        }
+    }
+}
+
 impl DataParser {
     pub fn new() -> Self {
         Self::new_with_config(None)
@@ -131,7 +316,7 @@ impl DataParser {
         let mut parsers = HashMap::new();
         
         // 기본 Binance 파서 등록 (하위 호환성)
-        let binance_parser = Self::parse_binance_message as fn(&mut [u8]) -> Result<ParsedData>;
+        let binance_parser = Self::parse_binance_message as ParserFn;
         parsers.insert("binance".to_string(), binance_parser);
         
         // symbol_config가 있으면 거기서 거래소 목록 가져오기
@@ -149,13 +334,23 @@ impl DataParser {
             warn!("Config가 제공되지 않아서 기본 파서만 사용");
         }
         
-        Self { parsers }
+        Self { parsers: RwLock::new(parsers) }
+    }
+
+    /// 설정 재로드로 추가된 거래소의 파서 등록 (이미 등록된 거래소는 무시)
+    pub fn register_exchange(&self, exchange_name: &str) {
+        self.parsers.write().unwrap()
+            .entry(exchange_name.to_string())
+            .or_insert_with(|| Self::get_parser_for_exchange(exchange_name));
     }
 
     /// 거래소명에 따라 적절한 파서 함수 반환
-    fn get_parser_for_exchange(exchange_name: &str) -> fn(&mut [u8]) -> Result<ParsedData> {
+    fn get_parser_for_exchange(exchange_name: &str) -> ParserFn {
         match exchange_name {
             name if name.starts_with("Binance") => Self::parse_binance_message,
+            name if name.starts_with("Okx") => Self::parse_okx_message,
+            name if name.starts_with("Bybit") => Self::parse_bybit_message,
+            name if name.starts_with("Upbit") => Self::parse_upbit_message,
             _ => Self::parse_default_message,
         }
     }
@@ -164,7 +359,7 @@ impl DataParser {
     pub fn parse_message(&self, exchange: &str, mut data: Vec<u8>) -> Result<ParsedData> {
         debug!("파싱 시작 - 거래소: {}, 데이터 크기: {} bytes", exchange, data.len());
         
-        let parser = self.parsers.get(exchange)
+        let parser = *self.parsers.read().unwrap().get(exchange)
             .ok_or_else(|| CryptoFeederError::JsonParseError(
                 format!("지원되지 않는 거래소: {}", exchange)
             ))?;
@@ -177,8 +372,11 @@ impl DataParser {
             ParsedData::OrderBook(mut ob) => { ob.exchange = exchange.to_string(); ParsedData::OrderBook(ob) }
             ParsedData::IndexPrice { symbol, exchange: _, value, timestamp } => ParsedData::IndexPrice { symbol, exchange: exchange.to_string(), value, timestamp },
             ParsedData::MarkPrice { symbol, exchange: _, value, timestamp } => ParsedData::MarkPrice { symbol, exchange: exchange.to_string(), value, timestamp },
-            ParsedData::FundingRate { symbol, exchange: _, value, timestamp } => ParsedData::FundingRate { symbol, exchange: exchange.to_string(), value, timestamp },
-            ParsedData::Liquidation { symbol, exchange: _, price, quantity, is_sell, timestamp } => ParsedData::Liquidation { symbol, exchange: exchange.to_string(), price, quantity, is_sell, timestamp },
+            ParsedData::FundingRate(mut f) => { f.exchange = exchange.to_string(); ParsedData::FundingRate(f) }
+            ParsedData::Liquidation(mut l) => { l.exchange = exchange.to_string(); ParsedData::Liquidation(l) }
+            ParsedData::Kline(mut k) => { k.exchange = exchange.to_string(); ParsedData::Kline(k) }
+            ParsedData::Ticker(mut t) => { t.exchange = exchange.to_string(); ParsedData::Ticker(t) }
+            ParsedData::OpenInterest(mut oi) => { oi.exchange = exchange.to_string(); ParsedData::OpenInterest(oi) }
             ParsedData::Multi(items) => {
                 let adj: Vec<ParsedData> = items.into_iter().map(|it| match it {
                     ParsedData::Trade(mut t) => { t.exchange = exchange.to_string(); ParsedData::Trade(t) }
@@ -186,8 +384,11 @@ impl DataParser {
                     ParsedData::OrderBook(mut ob) => { ob.exchange = exchange.to_string(); ParsedData::OrderBook(ob) }
                     ParsedData::IndexPrice { symbol, exchange: _, value, timestamp } => ParsedData::IndexPrice { symbol, exchange: exchange.to_string(), value, timestamp },
                     ParsedData::MarkPrice { symbol, exchange: _, value, timestamp } => ParsedData::MarkPrice { symbol, exchange: exchange.to_string(), value, timestamp },
-                    ParsedData::FundingRate { symbol, exchange: _, value, timestamp } => ParsedData::FundingRate { symbol, exchange: exchange.to_string(), value, timestamp },
-                    ParsedData::Liquidation { symbol, exchange: _, price, quantity, is_sell, timestamp } => ParsedData::Liquidation { symbol, exchange: exchange.to_string(), price, quantity, is_sell, timestamp },
+                    ParsedData::FundingRate(mut f) => { f.exchange = exchange.to_string(); ParsedData::FundingRate(f) }
+                    ParsedData::Liquidation(mut l) => { l.exchange = exchange.to_string(); ParsedData::Liquidation(l) }
+                    ParsedData::Kline(mut k) => { k.exchange = exchange.to_string(); ParsedData::Kline(k) }
+                    ParsedData::Ticker(mut t) => { t.exchange = exchange.to_string(); ParsedData::Ticker(t) }
+                    ParsedData::OpenInterest(mut oi) => { oi.exchange = exchange.to_string(); ParsedData::OpenInterest(oi) }
                     ParsedData::Multi(_) => ParsedData::Multi(Vec::new()),
                 }).collect();
                 ParsedData::Multi(adj)
@@ -212,6 +413,14 @@ impl DataParser {
             None => root,                       // 단일 스트림 포맷
         };
 
+        // 전체 시장 티커 배열 (!ticker@arr, !miniTicker@arr)
+        if let serde_json::Value::Array(events) = event_obj {
+            let tickers = events.iter()
+                .map(|event| Self::convert_binance_ticker(event).map(ParsedData::Ticker))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(ParsedData::Multi(tickers));
+        }
+
         let event_type = event_obj
             .get("e")
             .and_then(|v| v.as_str())
@@ -239,13 +448,24 @@ impl DataParser {
                 let mark_f = mark.parse::<f64>().map_err(|e| CryptoFeederError::JsonParseError(format!("mark 파싱 실패: {}", e)))?;
                 let index_f = index.parse::<f64>().map_err(|e| CryptoFeederError::JsonParseError(format!("index 파싱 실패: {}", e)))?;
                 let funding_f = funding.parse::<f64>().map_err(|e| CryptoFeederError::JsonParseError(format!("funding 파싱 실패: {}", e)))?;
+                // T: 다음 정산 시각 (ms). 정산 주기는 스트림에 없음
+                let next_funding_time = event_obj.get("T").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000;
                 Ok(ParsedData::Multi(vec![
                     ParsedData::IndexPrice { symbol: symbol_std.clone(), exchange: exchange.clone(), value: index_f, timestamp: ts },
                     ParsedData::MarkPrice { symbol: symbol_std.clone(), exchange: exchange.clone(), value: mark_f, timestamp: ts },
-                    ParsedData::FundingRate { symbol: symbol_std, exchange, value: funding_f, timestamp: ts },
+                    ParsedData::FundingRate(StandardizedFunding {
+                        symbol: symbol_std,
+                        exchange,
+                        rate: funding_f,
+                        predicted_rate: f64::NAN,
+                        next_funding_time,
+                        interval_secs: 0,
+                        timestamp: ts,
+                    }),
                 ]))
             }
             "forceOrder" => {
+                // 심볼별 @forceOrder와 전체 시장 !forceOrder@arr 모두 이벤트 하나씩 수신
                 let o = event_obj.get("o").ok_or_else(|| CryptoFeederError::JsonParseError("forceOrder:o 누락".into()))?;
                 let sym = o.get("s").and_then(|v| v.as_str()).ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
                 let side = o.get("S").and_then(|v| v.as_str()).unwrap_or("");
@@ -257,7 +477,18 @@ impl DataParser {
                 let price_f = price.parse::<f64>().unwrap_or(0.0);
                 let qty_f = qty.parse::<f64>().unwrap_or(0.0);
                 let is_sell = side.eq_ignore_ascii_case("SELL");
-                Ok(ParsedData::Liquidation { symbol: symbol_std, exchange, price: price_f, quantity: qty_f, is_sell, timestamp: ts })
+                Ok(ParsedData::Liquidation(StandardizedLiquidation {
+                    symbol: symbol_std,
+                    exchange,
+                    price: price_f,
+                    quantity: qty_f,
+                    is_sell,
+                    // z: 누적 체결 수량, X: 주문 상태, T: 주문 체결 시각 (ms)
+                    filled_quantity: o.get("z").and_then(|v| v.as_str()).and_then(|z| z.parse::<f64>().ok()).unwrap_or(qty_f),
+                    status: LiquidationStatus::from_binance(o.get("X").and_then(|v| v.as_str()).unwrap_or("")),
+                    order_time: o.get("T").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+                    timestamp: ts,
+                }))
             }
             "trade" => {
                 debug!("Binance 체결 데이터 파싱 중");
@@ -265,6 +496,32 @@ impl DataParser {
                     .map_err(|e| CryptoFeederError::JsonParseError(format!("체결 데이터 파싱 실패: {}", e)))?;
                 Ok(ParsedData::Trade(Self::convert_binance_trade(trade)?))
             }
+            "kline" => {
+                let k = event_obj.get("k").ok_or_else(|| CryptoFeederError::JsonParseError("kline:k 누락".into()))?;
+                let sym = k.get("s").or_else(|| event_obj.get("s")).and_then(|v| v.as_str())
+                    .ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
+                let interval = k.get("i").and_then(|v| v.as_str()).unwrap_or("");
+                let interval_secs = crate::bar_aggregator::parse_interval(interval)
+                    .ok_or_else(|| CryptoFeederError::JsonParseError(format!("지원되지 않는 kline 간격: {}", interval)))?;
+                Ok(ParsedData::Kline(StandardizedKline {
+                    symbol: Self::normalize_binance_symbol(sym),
+                    exchange: Self::normalize_exchange_name("binance", "spot"),
+                    interval_secs,
+                    start_time: k.get("t").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+                    open: Self::json_f64(k, "o")?,
+                    high: Self::json_f64(k, "h")?,
+                    low: Self::json_f64(k, "l")?,
+                    close: Self::json_f64(k, "c")?,
+                    volume: Self::json_f64(k, "v")?,
+                    quote_volume: Self::json_f64(k, "q")?,
+                    trade_count: k.get("n").and_then(|v| v.as_u64()).unwrap_or(0),
+                    is_closed: k.get("x").and_then(|v| v.as_bool()).unwrap_or(false),
+                    timestamp: event_obj.get("E").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+                }))
+            }
+            "24hrTicker" | "24hrMiniTicker" => {
+                Ok(ParsedData::Ticker(Self::convert_binance_ticker(&event_obj)?))
+            }
             "aggTrade" => {
                 debug!("Binance aggTrade 체결 데이터 파싱 중");
                 let trade: BinanceAggTrade = serde_json::from_value(event_obj)
@@ -316,6 +573,9 @@ impl DataParser {
             bids,
             asks,
             timestamp: update.event_time * 1_000_000, // milliseconds to nanoseconds
+            first_update_id: Some(update.prev_final_update_id.map_or(update.first_update_id, |pu| pu + 1)),
+            last_update_id: Some(update.final_update_id),
+            is_snapshot: false,
         })
     }
 
@@ -333,6 +593,7 @@ impl DataParser {
             quantity,
             is_buyer_taker: !trade.is_buyer_market_maker, // 바이낸스는 market maker 플래그 제공
             timestamp: trade.trade_time * 1_000_000, // milliseconds to nanoseconds
+            trade_id: Some(trade.trade_id),
         })
     }
 
@@ -351,27 +612,588 @@ impl DataParser {
             quantity,
             is_buyer_taker: !trade.is_buyer_market_maker,
             timestamp: trade.trade_time * 1_000_000,
+            trade_id: trade.agg_trade_id,
+        })
+    }
+
+    /// Binance 24hrTicker/24hrMiniTicker를 표준화된 티커로 변환 (미니 티커는 변동률을 직접 계산, 체결 수 0)
+    fn convert_binance_ticker(event: &serde_json::Value) -> Result<StandardizedTicker> {
+        let sym = event.get("s").and_then(|v| v.as_str())
+            .ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
+        let last_price = Self::json_f64(event, "c")?;
+        let open_price = Self::json_f64(event, "o")?;
+        let price_change_percent = match Self::json_f64(event, "P") {
+            Ok(pct) => pct,
+            Err(_) => change_percent(open_price, last_price),
+        };
+        Ok(StandardizedTicker {
+            symbol: Self::normalize_binance_symbol(sym),
+            exchange: Self::normalize_exchange_name("binance", "spot"),
+            last_price,
+            open_price,
+            high_price: Self::json_f64(event, "h")?,
+            low_price: Self::json_f64(event, "l")?,
+            volume: Self::json_f64(event, "v")?,
+            quote_volume: Self::json_f64(event, "q")?,
+            price_change_percent,
+            trade_count: event.get("n").and_then(|v| v.as_u64()).unwrap_or(0),
+            timestamp: event.get("E").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
         })
     }
 
-    /// 기본 메시지 파서 (다른 거래소용 - 현재는 로그만 출력)
+    /// 문자열(또는 숫자) 필드를 f64로 파싱
+    fn json_f64(obj: &serde_json::Value, key: &str) -> Result<f64> {
+        match obj.get(key) {
+            Some(serde_json::Value::String(s)) => s.parse::<f64>()
+                .map_err(|e| CryptoFeederError::JsonParseError(format!("{} 파싱 실패: {}", key, e))),
+            Some(v) => v.as_f64().ok_or_else(|| CryptoFeederError::JsonParseError(format!("{} 형식 오류", key))),
+            None => Err(CryptoFeederError::JsonParseError(format!("{} 누락", key))),
+        }
+    }
+
+    /// OKX tickers 채널 파싱
+    fn parse_okx_tickers(root: &serde_json::Value) -> Result<ParsedData> {
+        let mut tickers = Vec::new();
+        for t in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let inst_id = t.get("instId").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+            let parts: Vec<&str> = inst_id.split('-').collect();
+            let is_derivative = parts.len() > 2;
+            let last_price = Self::json_f64(t, "last")?;
+            let open_price = Self::json_f64(t, "open24h")?;
+            // 현물: vol24h=기준 자산, volCcy24h=호가 자산 / 파생: vol24h=계약 수, volCcy24h=기준 자산
+            let (volume, quote_volume) = if is_derivative {
+                let base = Self::json_f64(t, "volCcy24h")?;
+                (base, base * last_price) // 파생상품은 거래대금을 제공하지 않으므로 최종가 기준 추정
+            } else {
+                (Self::json_f64(t, "vol24h")?, Self::json_f64(t, "volCcy24h")?)
+            };
+            let ts = t.get("ts").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            tickers.push(ParsedData::Ticker(StandardizedTicker {
+                symbol: parts.iter().take(2).copied().collect::<Vec<_>>().join("^"),
+                exchange: Self::normalize_exchange_name("okx", if is_derivative { "swap" } else { "spot" }),
+                last_price,
+                open_price,
+                high_price: Self::json_f64(t, "high24h")?,
+                low_price: Self::json_f64(t, "low24h")?,
+                volume,
+                quote_volume,
+                price_change_percent: change_percent(open_price, last_price),
+                trade_count: 0,
+                timestamp: ts * 1_000_000,
+            }));
+        }
+        match tickers.len() {
+            0 => Err(CryptoFeederError::JsonParseError("tickers 데이터 없음".into())),
+            1 => Ok(tickers.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(tickers)),
+        }
+    }
+
+    /// OKX open-interest 채널 파싱 (oiCcy=기준 자산 수량, oiUsd=USD 명목 가치)
+    fn parse_okx_open_interest(root: &serde_json::Value) -> Result<ParsedData> {
+        let mut items = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let inst_id = d.get("instId").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+            let ts = d.get("ts").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            items.push(ParsedData::OpenInterest(StandardizedOpenInterest {
+                symbol: inst_id.split('-').take(2).collect::<Vec<_>>().join("^"),
+                exchange: Self::normalize_exchange_name("okx", "swap"),
+                open_interest: Self::json_f64(d, "oiCcy")?,
+                open_interest_value: Self::json_f64(d, "oiUsd").unwrap_or(f64::NAN),
+                long_short_ratio: f64::NAN,
+                timestamp: ts * 1_000_000,
+            }));
+        }
+        match items.len() {
+            0 => Err(CryptoFeederError::JsonParseError("open-interest 데이터 없음".into())),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(items)),
+        }
+    }
+
+    /// OKX funding-rate 채널 파싱
+    ///
+    /// fundingTime은 현재 펀딩비가 적용될 정산 시각, nextFundingTime은 그다음 정산 시각이며
+    /// 두 시각의 차이를 정산 주기로 사용합니다. nextFundingRate(예상 펀딩비)는 빈 문자열일 수 있습니다.
+    fn parse_okx_funding_rate(root: &serde_json::Value) -> Result<ParsedData> {
+        let ms = |d: &serde_json::Value, key: &str| d.get(key).and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+        let mut items = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let inst_id = d.get("instId").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+            let (funding_time, next_funding_time) = (ms(d, "fundingTime"), ms(d, "nextFundingTime"));
+            items.push(ParsedData::FundingRate(StandardizedFunding {
+                symbol: inst_id.split('-').take(2).collect::<Vec<_>>().join("^"),
+                exchange: Self::normalize_exchange_name("okx", "swap"),
+                rate: Self::json_f64(d, "fundingRate")?,
+                predicted_rate: Self::json_f64(d, "nextFundingRate").unwrap_or(f64::NAN),
+                next_funding_time: funding_time * 1_000_000,
+                interval_secs: (next_funding_time.saturating_sub(funding_time) / 1000) as u32,
+                timestamp: ms(d, "ts") * 1_000_000,
+            }));
+        }
+        match items.len() {
+            0 => Err(CryptoFeederError::JsonParseError("funding-rate 데이터 없음".into())),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(items)),
+        }
+    }
+
+    /// OKX liquidation-orders 채널 파싱 (instType 단위 구독이므로 모든 종목의 청산이 들어옴)
+    ///
+    /// side는 청산 주문 방향(sell = 롱 포지션 청산), bkPx는 파산가, sz는 계약 수입니다.
+    fn parse_okx_liquidations(root: &serde_json::Value) -> Result<ParsedData> {
+        let mut items = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let inst_id = d.get("instId").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+            for detail in d.get("details").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+                let quantity = Self::json_f64(detail, "sz")?;
+                let ts = detail.get("ts").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0) * 1_000_000;
+                items.push(ParsedData::Liquidation(StandardizedLiquidation {
+                    symbol: inst_id.split('-').take(2).collect::<Vec<_>>().join("^"),
+                    exchange: Self::normalize_exchange_name("okx", "swap"),
+                    price: Self::json_f64(detail, "bkPx")?,
+                    quantity,
+                    is_sell: detail.get("side").and_then(|v| v.as_str()) == Some("sell"),
+                    filled_quantity: quantity,
+                    status: LiquidationStatus::Unknown,
+                    order_time: ts,
+                    timestamp: ts,
+                }));
+            }
+        }
+        match items.len() {
+            0 => Err(CryptoFeederError::JsonParseError("liquidation-orders 데이터 없음".into())),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(items)),
+        }
+    }
+
+    /// 호가 배열 파싱 (`[[가격, 수량, ...], ...]`, OKX는 뒤에 주문 수 등의 필드가 더 붙음)
+    fn json_levels(obj: &serde_json::Value, key: &str) -> Result<Vec<OrderBookLevel>> {
+        let rows = obj.get(key).and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]);
+        rows.iter().map(|row| {
+            let field = |idx: usize| -> Result<f64> {
+                row.get(idx).and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())
+                    .ok_or_else(|| CryptoFeederError::JsonParseError(format!("{} 호가 필드 {} 파싱 실패", key, idx)))
+            };
+            Ok(OrderBookLevel { price: field(0)?, quantity: field(1)? })
+        }).collect()
+    }
+
+    /// OKX trades 채널 파싱 (side는 taker 방향, 파생상품의 sz는 계약 수)
+    ///
+    /// `{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId","tradeId","px","sz","side","ts"}]}`
+    fn parse_okx_trades(root: &serde_json::Value) -> Result<ParsedData> {
+        let mut trades = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let inst_id = d.get("instId").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+            let parts: Vec<&str> = inst_id.split('-').collect();
+            let ts = d.get("ts").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            trades.push(StandardizedTrade {
+                symbol: parts.iter().take(2).copied().collect::<Vec<_>>().join("^"),
+                exchange: Self::normalize_exchange_name("okx", if parts.len() > 2 { "swap" } else { "spot" }),
+                price: Self::json_f64(d, "px")?,
+                quantity: Self::json_f64(d, "sz")?,
+                is_buyer_taker: d.get("side").and_then(|v| v.as_str()) == Some("buy"),
+                timestamp: ts * 1_000_000,
+                trade_id: d.get("tradeId").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()),
+            });
+        }
+        let first = trades.first()
+            .ok_or_else(|| CryptoFeederError::JsonParseError("trades 데이터 없음".into()))?;
+        Ok(ParsedData::TradeBatch(StandardizedTradeBatch {
+            symbol: first.symbol.clone(),
+            exchange: first.exchange.clone(),
+            exchange_timestamp: trades.iter().map(|t| t.timestamp).max().unwrap_or(0),
+            trades,
+        }))
+    }
+
+    /// OKX books / books5 / bbo-tbt / books-l2-tbt 채널 파싱
+    ///
+    /// `action`이 `update`인 메시지만 증분이고, 나머지(`snapshot`, action이 없는 books5/bbo-tbt)는 전체 스냅샷입니다.
+    /// 업데이트 ID는 `prevSeqId + 1 ..= seqId`로 옮겨 직전 메시지의 seqId와 이어지는지 확인할 수 있게 합니다.
+    fn parse_okx_books(root: &serde_json::Value) -> Result<ParsedData> {
+        let inst_id = root.get("arg").and_then(|a| a.get("instId")).and_then(|v| v.as_str())
+            .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+        let parts: Vec<&str> = inst_id.split('-').collect();
+        let is_snapshot = root.get("action").and_then(|v| v.as_str()) != Some("update");
+        let mut books = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let seq_id = d.get("seqId").and_then(|v| v.as_i64()).filter(|id| *id >= 0);
+            let prev_seq_id = d.get("prevSeqId").and_then(|v| v.as_i64()).filter(|id| *id >= 0);
+            let ts = d.get("ts").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            books.push(ParsedData::OrderBook(StandardizedOrderBookUpdate {
+                symbol: parts.iter().take(2).copied().collect::<Vec<_>>().join("^"),
+                exchange: Self::normalize_exchange_name("okx", if parts.len() > 2 { "swap" } else { "spot" }),
+                bids: Self::json_levels(d, "bids")?,
+                asks: Self::json_levels(d, "asks")?,
+                timestamp: ts * 1_000_000,
+                first_update_id: if is_snapshot { None } else { prev_seq_id.map(|id| id as u64 + 1) },
+                last_update_id: seq_id.map(|id| id as u64),
+                is_snapshot,
+            }));
+        }
+        match books.len() {
+            0 => Err(CryptoFeederError::JsonParseError("books 데이터 없음".into())),
+            1 => Ok(books.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(books)),
+        }
+    }
+
+    /// OKX 메시지 파싱 (trades/books/candle/tickers/open-interest/funding-rate/liquidation-orders 채널 표준화)
+    ///
+    /// 구독 응답 등 `data`가 없는 이벤트와 처리하지 않는 채널은 오류로 반환합니다.
+    fn parse_okx_message(data: &mut [u8]) -> Result<ParsedData> {
+        let root = match simd_json::from_slice::<serde_json::Value>(data) {
+            Ok(root) => root,
+            Err(_) => return Self::parse_default_message(data),
+        };
+        let arg = root.get("arg");
+        let channel = arg.and_then(|a| a.get("channel")).and_then(|v| v.as_str()).unwrap_or("");
+        if root.get("data").is_none() {
+            let event = root.get("event").and_then(|v| v.as_str()).unwrap_or("");
+            return Err(CryptoFeederError::JsonParseError(format!("OKX 데이터 메시지 아님: event={} channel={}", event, channel)));
+        }
+        if channel == "trades" {
+            return Self::parse_okx_trades(&root);
+        }
+        if channel.starts_with("books") || channel == "bbo-tbt" {
+            return Self::parse_okx_books(&root);
+        }
+        if channel == "tickers" {
+            return Self::parse_okx_tickers(&root);
+        }
+        if channel == "open-interest" {
+            return Self::parse_okx_open_interest(&root);
+        }
+        if channel == "funding-rate" {
+            return Self::parse_okx_funding_rate(&root);
+        }
+        if channel == "liquidation-orders" {
+            return Self::parse_okx_liquidations(&root);
+        }
+        let Some(interval) = channel.strip_prefix("candle") else {
+            return Err(CryptoFeederError::JsonParseError(format!("지원되지 않는 OKX 채널: {}", channel)));
+        };
+        let interval_secs = crate::bar_aggregator::parse_interval(interval)
+            .ok_or_else(|| CryptoFeederError::JsonParseError(format!("지원되지 않는 candle 간격: {}", channel)))?;
+        let inst_id = arg.and_then(|a| a.get("instId")).and_then(|v| v.as_str())
+            .ok_or_else(|| CryptoFeederError::JsonParseError("instId 누락".into()))?;
+        // BTC-USDT / BTC-USDT-SWAP → BTC^USDT
+        let parts: Vec<&str> = inst_id.split('-').collect();
+        let symbol = parts.iter().take(2).copied().collect::<Vec<_>>().join("^");
+        let is_derivative = parts.len() > 2;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+
+        let mut klines = Vec::new();
+        for row in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            // [ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]
+            let field = |idx: usize| -> Result<f64> {
+                row.get(idx).and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())
+                    .ok_or_else(|| CryptoFeederError::JsonParseError(format!("candle 필드 {} 파싱 실패", idx)))
+            };
+            let start_ms = row.get(0).and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("candle 시작 시각 파싱 실패".into()))?;
+            klines.push(ParsedData::Kline(StandardizedKline {
+                symbol: symbol.clone(),
+                exchange: Self::normalize_exchange_name("okx", if is_derivative { "swap" } else { "spot" }),
+                interval_secs,
+                start_time: start_ms * 1_000_000,
+                open: field(1)?,
+                high: field(2)?,
+                low: field(3)?,
+                close: field(4)?,
+                // 파생상품의 vol은 계약 수이므로 기준 자산 수량(volCcy) 사용
+                volume: if is_derivative { field(6)? } else { field(5)? },
+                quote_volume: field(7)?,
+                trade_count: 0,
+                is_closed: row.get(8).and_then(|v| v.as_str()) == Some("1"),
+                timestamp: now,
+            }));
+        }
+        match klines.len() {
+            0 => Err(CryptoFeederError::JsonParseError("candle 데이터 없음".into())),
+            1 => Ok(klines.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(klines)),
+        }
+    }
+
+    /// Bybit tickers 토픽 파싱 (파생상품 델타는 빠진 필드를 NaN으로 둠)
+    fn parse_bybit_ticker(sym: &str, root: &serde_json::Value) -> Result<ParsedData> {
+        let t = root.get("data").ok_or_else(|| CryptoFeederError::JsonParseError("tickers 데이터 없음".into()))?;
+        let field = |key: &str| -> Result<f64> {
+            match t.get(key) {
+                None => Ok(f64::NAN),
+                Some(_) => Self::json_f64(t, key),
+            }
+        };
+        let timestamp = root.get("ts").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000;
+        let ticker = ParsedData::Ticker(StandardizedTicker {
+            symbol: Self::normalize_binance_symbol(sym),
+            exchange: Self::normalize_exchange_name("bybit", "spot"),
+            last_price: field("lastPrice")?,
+            open_price: field("prevPrice24h")?,
+            high_price: field("highPrice24h")?,
+            low_price: field("lowPrice24h")?,
+            volume: field("volume24h")?,
+            quote_volume: field("turnover24h")?,
+            // price24hPcnt는 비율 (0.01 = 1%)
+            price_change_percent: field("price24hPcnt")? * 100.0,
+            trade_count: 0,
+            timestamp,
+        });
+        // 파생상품 ticker는 미결제약정/펀딩비를 함께 제공 (델타는 바뀐 경우에만 포함)
+        let mut items = vec![ticker];
+        if t.get("openInterest").is_some() {
+            items.push(ParsedData::OpenInterest(StandardizedOpenInterest {
+                symbol: Self::normalize_binance_symbol(sym),
+                exchange: Self::normalize_exchange_name("bybit", "linear"),
+                open_interest: field("openInterest")?,
+                open_interest_value: field("openInterestValue")?,
+                long_short_ratio: f64::NAN,
+                timestamp,
+            }));
+        }
+        if t.get("fundingRate").is_some() {
+            let integer = |key: &str| t.get(key).and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
+            items.push(ParsedData::FundingRate(StandardizedFunding {
+                symbol: Self::normalize_binance_symbol(sym),
+                exchange: Self::normalize_exchange_name("bybit", "linear"),
+                rate: field("fundingRate")?,
+                predicted_rate: f64::NAN,
+                next_funding_time: integer("nextFundingTime") * 1_000_000,
+                interval_secs: (integer("fundingIntervalHour") * 3_600) as u32,
+                timestamp,
+            }));
+        }
+        Ok(if items.len() == 1 { items.pop().unwrap() } else { ParsedData::Multi(items) })
+    }
+
+    /// Bybit publicTrade 토픽 파싱 (S는 taker 방향)
+    ///
+    /// `{"topic":"publicTrade.BTCUSDT","ts":..,"data":[{"T","s","S","v","p","i"}]}`, 파생상품의 체결 ID(i)는 UUID라 생략됩니다.
+    fn parse_bybit_trades(root: &serde_json::Value) -> Result<ParsedData> {
+        let mut trades = Vec::new();
+        for d in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let sym = d.get("s").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
+            trades.push(StandardizedTrade {
+                symbol: Self::normalize_binance_symbol(sym),
+                exchange: Self::normalize_exchange_name("bybit", "spot"),
+                price: Self::json_f64(d, "p")?,
+                quantity: Self::json_f64(d, "v")?,
+                is_buyer_taker: d.get("S").and_then(|v| v.as_str()) == Some("Buy"),
+                timestamp: d.get("T").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+                trade_id: d.get("i").and_then(|v| v.as_str()).and_then(|s| s.parse::<u64>().ok()),
+            });
+        }
+        let first = trades.first()
+            .ok_or_else(|| CryptoFeederError::JsonParseError("publicTrade 데이터 없음".into()))?;
+        Ok(ParsedData::TradeBatch(StandardizedTradeBatch {
+            symbol: first.symbol.clone(),
+            exchange: first.exchange.clone(),
+            exchange_timestamp: root.get("ts").and_then(|v| v.as_u64()).map(|ms| ms * 1_000_000)
+                .unwrap_or_else(|| trades.iter().map(|t| t.timestamp).max().unwrap_or(0)),
+            trades,
+        }))
+    }
+
+    /// Bybit orderbook.{depth}.{symbol} 토픽 파싱 (type: snapshot / delta)
+    ///
+    /// delta의 업데이트 ID(u)는 1씩 증가하므로 한 메시지의 범위는 `u..=u`입니다. 수량 0은 호가 삭제입니다.
+    fn parse_bybit_orderbook(root: &serde_json::Value) -> Result<ParsedData> {
+        let d = root.get("data").ok_or_else(|| CryptoFeederError::JsonParseError("orderbook 데이터 없음".into()))?;
+        let sym = d.get("s").and_then(|v| v.as_str())
+            .ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
+        let is_snapshot = root.get("type").and_then(|v| v.as_str()) == Some("snapshot");
+        let update_id = d.get("u").and_then(|v| v.as_u64());
+        Ok(ParsedData::OrderBook(StandardizedOrderBookUpdate {
+            symbol: Self::normalize_binance_symbol(sym),
+            exchange: Self::normalize_exchange_name("bybit", "spot"),
+            bids: Self::json_levels(d, "b")?,
+            asks: Self::json_levels(d, "a")?,
+            timestamp: root.get("ts").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+            first_update_id: if is_snapshot { None } else { update_id },
+            last_update_id: update_id,
+            is_snapshot,
+        }))
+    }
+
+    /// Bybit allLiquidation / liquidation(구버전) 토픽 파싱
+    ///
+    /// - allLiquidation: `data: [{"T", "s", "S", "v", "p"}]`
+    /// - liquidation: `data: {"updatedTime", "symbol", "side", "size", "price"}`
+    ///
+    /// side는 청산된 포지션 방향이므로(Buy = 롱 포지션 청산) 청산 주문 방향은 반대입니다.
+    fn parse_bybit_liquidations(root: &serde_json::Value) -> Result<ParsedData> {
+        let rows = match root.get("data") {
+            Some(serde_json::Value::Array(rows)) => rows.iter().collect::<Vec<_>>(),
+            Some(row) => vec![row],
+            None => Vec::new(),
+        };
+        let mut items = Vec::new();
+        for row in rows {
+            let field = |new: &str, old: &str| row.get(new).or_else(|| row.get(old));
+            let sym = field("s", "symbol").and_then(|v| v.as_str())
+                .ok_or_else(|| CryptoFeederError::JsonParseError("symbol 누락".into()))?;
+            let quantity = Self::json_f64(row, if row.get("v").is_some() { "v" } else { "size" })?;
+            let order_time = field("T", "updatedTime").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000;
+            items.push(ParsedData::Liquidation(StandardizedLiquidation {
+                symbol: Self::normalize_binance_symbol(sym),
+                exchange: Self::normalize_exchange_name("bybit", "linear"),
+                price: Self::json_f64(row, if row.get("p").is_some() { "p" } else { "price" })?,
+                quantity,
+                is_sell: field("S", "side").and_then(|v| v.as_str()) == Some("Buy"),
+                filled_quantity: quantity,
+                status: LiquidationStatus::Unknown,
+                order_time,
+                timestamp: root.get("ts").and_then(|v| v.as_u64()).map(|ms| ms * 1_000_000).unwrap_or(order_time),
+            }));
+        }
+        match items.len() {
+            0 => Err(CryptoFeederError::JsonParseError("liquidation 데이터 없음".into())),
+            1 => Ok(items.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(items)),
+        }
+    }
+
+    /// Bybit 메시지 파싱 (publicTrade/orderbook/kline/tickers/liquidation 토픽 표준화)
+    ///
+    /// 구독 응답(`op`) 등 토픽이 없는 메시지와 처리하지 않는 토픽은 오류로 반환합니다.
+    fn parse_bybit_message(data: &mut [u8]) -> Result<ParsedData> {
+        let root = match simd_json::from_slice::<serde_json::Value>(data) {
+            Ok(root) => root,
+            Err(_) => return Self::parse_default_message(data),
+        };
+        let topic = root.get("topic").and_then(|v| v.as_str()).unwrap_or("");
+        if topic.starts_with("publicTrade.") {
+            return Self::parse_bybit_trades(&root);
+        }
+        if topic.starts_with("orderbook.") {
+            return Self::parse_bybit_orderbook(&root);
+        }
+        if let Some(sym) = topic.strip_prefix("tickers.") {
+            return Self::parse_bybit_ticker(sym, &root);
+        }
+        if topic.starts_with("allLiquidation.") || topic.starts_with("liquidation.") {
+            return Self::parse_bybit_liquidations(&root);
+        }
+        if !topic.starts_with("kline.") {
+            let op = root.get("op").and_then(|v| v.as_str()).unwrap_or("");
+            return Err(CryptoFeederError::JsonParseError(format!("지원되지 않는 Bybit 메시지: op={} topic={}", op, topic)));
+        }
+        // kline.{interval}.{symbol}
+        let sym = topic.rsplit('.').next().unwrap_or("");
+        let event_ts = root.get("ts").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000;
+
+        let mut klines = Vec::new();
+        for k in root.get("data").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]) {
+            let interval = k.get("interval").and_then(|v| v.as_str()).unwrap_or("");
+            let interval_secs = Self::parse_bybit_interval(interval)
+                .ok_or_else(|| CryptoFeederError::JsonParseError(format!("지원되지 않는 kline 간격: {}", interval)))?;
+            let timestamp = k.get("timestamp").and_then(|v| v.as_u64()).map(|ms| ms * 1_000_000).unwrap_or(event_ts);
+            klines.push(ParsedData::Kline(StandardizedKline {
+                symbol: Self::normalize_binance_symbol(sym),
+                exchange: Self::normalize_exchange_name("bybit", "spot"),
+                interval_secs,
+                start_time: k.get("start").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+                open: Self::json_f64(k, "open")?,
+                high: Self::json_f64(k, "high")?,
+                low: Self::json_f64(k, "low")?,
+                close: Self::json_f64(k, "close")?,
+                volume: Self::json_f64(k, "volume")?,
+                quote_volume: Self::json_f64(k, "turnover")?,
+                trade_count: 0,
+                is_closed: k.get("confirm").and_then(|v| v.as_bool()).unwrap_or(false),
+                timestamp,
+            }));
+        }
+        match klines.len() {
+            0 => Err(CryptoFeederError::JsonParseError("kline 데이터 없음".into())),
+            1 => Ok(klines.pop().unwrap()),
+            _ => Ok(ParsedData::Multi(klines)),
+        }
+    }
+
+    /// Bybit kline 간격 (숫자는 분 단위, D/W는 일/주)
+    fn parse_bybit_interval(interval: &str) -> Option<u32> {
+        match interval {
+            "D" => Some(86_400),
+            "W" => Some(7 * 86_400),
+            minutes => minutes.parse::<u32>().ok().filter(|m| *m > 0).map(|m| m * 60),
+        }
+    }
+
+    /// Upbit 메시지 파싱 (trade/orderbook 타입, 그 외는 오류)
+    ///
+    /// - trade: `{"type":"trade","code":"KRW-BTC","trade_price":..,"trade_volume":..,"ask_bid":"BID","trade_timestamp":..,"sequential_id":..}`
+    /// - orderbook: `{"type":"orderbook","code":"KRW-BTC","timestamp":..,"orderbook_units":[{"ask_price":..,"bid_price":..,"ask_size":..,"bid_size":..}]}` (전체 스냅샷)
+    fn parse_upbit_message(data: &mut [u8]) -> Result<ParsedData> {
+        let root = match simd_json::from_slice::<serde_json::Value>(data) {
+            Ok(root) => root,
+            Err(_) => return Self::parse_default_message(data),
+        };
+        let code = root.get("code").and_then(|v| v.as_str()).unwrap_or("");
+        let symbol = Self::normalize_upbit_code(code)
+            .ok_or_else(|| CryptoFeederError::JsonParseError(format!("Upbit 마켓 코드 오류: {}", code)))?;
+        let ms = |key: &str| root.get(key).and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000;
+        match root.get("type").and_then(|v| v.as_str()) {
+            Some("trade") => Ok(ParsedData::Trade(StandardizedTrade {
+                symbol,
+                exchange: "UpbitSpot".to_string(),
+                price: Self::json_f64(&root, "trade_price")?,
+                quantity: Self::json_f64(&root, "trade_volume")?,
+                // ask_bid: BID = 매수 체결 (매수자가 taker)
+                is_buyer_taker: root.get("ask_bid").and_then(|v| v.as_str()) == Some("BID"),
+                timestamp: ms("trade_timestamp"),
+                trade_id: root.get("sequential_id").and_then(|v| v.as_u64()),
+            })),
+            Some("orderbook") => {
+                let units = root.get("orderbook_units").and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]);
+                let mut bids = Vec::with_capacity(units.len());
+                let mut asks = Vec::with_capacity(units.len());
+                for unit in units {
+                    bids.push(OrderBookLevel { price: Self::json_f64(unit, "bid_price")?, quantity: Self::json_f64(unit, "bid_size")? });
+                    asks.push(OrderBookLevel { price: Self::json_f64(unit, "ask_price")?, quantity: Self::json_f64(unit, "ask_size")? });
+                }
+                Ok(ParsedData::OrderBook(StandardizedOrderBookUpdate {
+                    symbol,
+                    exchange: "UpbitSpot".to_string(),
+                    bids,
+                    asks,
+                    timestamp: ms("timestamp"),
+                    first_update_id: None,
+                    last_update_id: None,
+                    is_snapshot: true,
+                }))
+            }
+            _ => Self::parse_default_message(data),
+        }
+    }
+
+    /// Upbit 마켓 코드를 표준 형식으로 변환 (KRW-BTC -> BTC^KRW, quote가 앞)
+    fn normalize_upbit_code(code: &str) -> Option<String> {
+        let (quote, base) = code.split_once('-')?;
+        (!quote.is_empty() && !base.is_empty()).then(|| format!("{}^{}", base.to_uppercase(), quote.to_uppercase()))
+    }
+
+    /// 기본 메시지 파서 (파서가 없는 거래소 및 표준화하지 않는 메시지용)
+    ///
+    /// 더미 데이터를 만들지 않고 오류를 반환하므로 pong·구독 응답·미지원 채널은 하위 단계로 전달되지 않습니다.
     fn parse_default_message(data: &mut [u8]) -> Result<ParsedData> {
         let raw_text = String::from_utf8_lossy(data);
-        debug!("기본 파서로 메시지 처리 중 (아직 구현되지 않음): {}", 
-               &raw_text[..std::cmp::min(100, raw_text.len())]);
-        
-        // 임시로 더미 데이터 반환 (실제로는 거래소별 파싱 로직 필요)
-        Ok(ParsedData::Trade(StandardizedTrade {
-            symbol: "UNKNOWN^USDT".to_string(),
-            exchange: "Unknown".to_string(),
-            price: 0.0,
-            quantity: 0.0,
-            is_buyer_taker: false,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_nanos() as u64,
-        }))
+        let preview: String = raw_text.chars().take(100).collect();
+        debug!("지원되지 않는 메시지: {}", preview);
+        Err(CryptoFeederError::JsonParseError(format!("지원되지 않는 메시지: {}", preview)))
     }
 
     /// Binance 심볼을 표준 형식으로 변환 (BTCUSDT -> BTC^USDT)
@@ -434,6 +1256,11 @@ impl DataParser {
     }
 }
 
+/// 시가 대비 변동률 (%, 시가가 0이면 0)
+fn change_percent(open: f64, last: f64) -> f64 {
+    if open == 0.0 { 0.0 } else { (last - open) / open * 100.0 }
+}
+
 impl Default for DataParser {
     fn default() -> Self {
         Self::new()
@@ -462,7 +1289,7 @@ mod tests {
     #[test]
     fn test_parser_creation() {
         let parser = DataParser::new();
-        assert!(parser.parsers.contains_key("binance"));
+        assert!(parser.parsers.read().unwrap().contains_key("binance"));
     }
 
     #[test]
@@ -483,6 +1310,7 @@ mod tests {
                 assert_eq!(t.exchange, "BinanceSpot");
                 assert!(t.is_buyer_taker); // m=false => buyer taker
                 assert_eq!(t.price, 50000.0);
+                assert_eq!(t.trade_id, Some(1));
             }
             _ => panic!("unexpected parse result"),
         }
@@ -507,8 +1335,331 @@ mod tests {
                 assert_eq!(ob.exchange, "BinanceSpot");
                 assert_eq!(ob.bids.len(), 1);
                 assert_eq!(ob.asks.len(), 1);
+                assert_eq!((ob.first_update_id, ob.last_update_id), (Some(100), Some(110)));
             }
             _ => panic!("unexpected parse result"),
         }
+
+        // 선물은 pu(직전 이벤트의 u) + 1을 시작 ID로 사용
+        let json = r#"{"e":"depthUpdate","E":1700000001000,"T":1700000000990,"s":"ETHUSDT","U":157,"u":160,"pu":149,
+            "b":[["3000.10","1.0"]],"a":[]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::OrderBook(ob)) => assert_eq!((ob.first_update_id, ob.last_update_id), (Some(150), Some(160))),
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_exchange_klines() {
+        let json = r#"{"stream":"btcusdt@kline_1m","data":{"e":"kline","E":1700000030000,"s":"BTCUSDT",
+            "k":{"t":1700000000000,"T":1700000059999,"s":"BTCUSDT","i":"1m","o":"100.0","c":"101.5","h":"102.0","l":"99.5",
+                 "v":"12.5","n":42,"x":true,"q":"1260.0"}}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::Kline(k)) => {
+                assert_eq!((k.symbol.as_str(), k.interval_secs, k.trade_count), ("BTC^USDT", 60, 42));
+                assert_eq!(k.start_time, 1_700_000_000_000_000_000);
+                assert_eq!((k.open, k.high, k.low, k.close), (100.0, 102.0, 99.5, 101.5));
+                assert!(k.is_closed);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"arg":{"channel":"candle1m","instId":"BTC-USDT-SWAP"},
+            "data":[["1700000000000","100","102","99","101","250","2.5","252.5","0"]]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::Kline(k)) => {
+                assert_eq!(k.symbol, "BTC^USDT");
+                assert_eq!((k.volume, k.quote_volume), (2.5, 252.5));
+                assert!(!k.is_closed);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"topic":"kline.5.ETHUSDT","ts":1700000300100,"type":"snapshot","data":[
+            {"start":1700000000000,"end":1700000299999,"interval":"5","open":"2000","close":"2010","high":"2020","low":"1990",
+             "volume":"3","turnover":"6015","confirm":true,"timestamp":1700000300000}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Kline(k)) => {
+                assert_eq!((k.symbol.as_str(), k.interval_secs), ("ETH^USDT", 300));
+                assert_eq!(k.quote_volume, 6015.0);
+                assert!(k.is_closed);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_tickers() {
+        let json = r#"{"stream":"btcusdt@ticker","data":{"e":"24hrTicker","E":1700000000000,"s":"BTCUSDT","p":"500.0","P":"1.010",
+            "c":"50000.0","o":"49500.0","h":"50500.0","l":"49000.0","v":"1000.5","q":"50000000.0","n":12345}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::Ticker(t)) => {
+                assert_eq!((t.symbol.as_str(), t.last_price, t.open_price), ("BTC^USDT", 50000.0, 49500.0));
+                assert_eq!((t.price_change_percent, t.trade_count), (1.01, 12345));
+                assert!(t.is_complete());
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        // 미니 티커 배열은 변동률을 직접 계산
+        let json = r#"{"stream":"!miniTicker@arr","data":[
+            {"e":"24hrMiniTicker","E":1700000000000,"s":"ETHUSDT","c":"2020","o":"2000","h":"2050","l":"1990","v":"10","q":"20100"},
+            {"e":"24hrMiniTicker","E":1700000000000,"s":"SOLUSDT","c":"99","o":"100","h":"101","l":"98","v":"5","q":"495"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::Multi(items)) => {
+                assert_eq!(items.len(), 2);
+                let ParsedData::Ticker(t) = &items[0] else { panic!("ticker expected") };
+                assert!((t.price_change_percent - 1.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"arg":{"channel":"tickers","instId":"ETH-USDT"},"data":[{"instType":"SPOT","instId":"ETH-USDT",
+            "last":"2020","open24h":"2000","high24h":"2050","low24h":"1990","vol24h":"10","volCcy24h":"20100","ts":"1700000000000"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::Ticker(t)) => {
+                assert_eq!((t.symbol.as_str(), t.volume, t.quote_volume), ("ETH^USDT", 10.0, 20100.0));
+                assert_eq!(t.timestamp, 1_700_000_000_000_000_000);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        // Bybit 파생상품 델타: 빠진 필드는 NaN
+        let json = r#"{"topic":"tickers.BTCUSDT","type":"delta","ts":1700000000100,"data":{"symbol":"BTCUSDT","lastPrice":"50100","price24hPcnt":"0.0121"}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Ticker(t)) => {
+                assert_eq!(t.last_price, 50100.0);
+                assert!((t.price_change_percent - 1.21).abs() < 1e-9);
+                assert!(t.volume.is_nan());
+                assert!(!t.is_complete());
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_open_interest() {
+        let json = r#"{"arg":{"channel":"open-interest","instId":"BTC-USDT-SWAP"},"data":[{"instType":"SWAP","instId":"BTC-USDT-SWAP",
+            "oi":"500000","oiCcy":"5000","oiUsd":"250000000","ts":"1700000000000"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::OpenInterest(oi)) => {
+                assert_eq!((oi.symbol.as_str(), oi.open_interest, oi.open_interest_value), ("BTC^USDT", 5000.0, 250_000_000.0));
+                assert!(oi.long_short_ratio.is_nan());
+                assert_eq!(oi.timestamp, 1_700_000_000_000_000_000);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        // Bybit 파생상품 ticker는 티커와 미결제약정으로 분리
+        let json = r#"{"topic":"tickers.BTCUSDT","type":"delta","ts":1700000000100,"data":{"symbol":"BTCUSDT","lastPrice":"50100",
+            "openInterest":"1200.5","openInterestValue":"60145050"}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Multi(items)) => {
+                assert!(matches!(&items[0], ParsedData::Ticker(t) if t.last_price == 50100.0));
+                match &items[1] {
+                    ParsedData::OpenInterest(oi) => assert_eq!((oi.open_interest, oi.open_interest_value), (1200.5, 60_145_050.0)),
+                    other => panic!("open interest expected: {:?}", other),
+                }
+                let only_oi = ParsedData::Multi(items).retain(&|d| !matches!(d, ParsedData::Ticker(_)));
+                assert!(matches!(only_oi, ParsedData::Multi(v) if v.len() == 1));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_funding_details() {
+        let json = r#"{"stream":"btcusdt@markPrice@1s","data":{"e":"markPriceUpdate","E":1700000000000,"s":"BTCUSDT",
+            "p":"50000.0","i":"49990.0","P":"50010.0","r":"0.00010000","T":1700006400000}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::Multi(items)) => match &items[2] {
+                ParsedData::FundingRate(f) => {
+                    assert_eq!((f.rate, f.next_funding_time), (0.0001, 1_700_006_400_000_000_000));
+                    assert!(f.predicted_rate.is_nan());
+                }
+                other => panic!("funding expected: {:?}", other),
+            },
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"arg":{"channel":"funding-rate","instId":"BTC-USDT-SWAP"},"data":[{"instType":"SWAP","instId":"BTC-USDT-SWAP",
+            "fundingRate":"0.0001","nextFundingRate":"-0.00005","fundingTime":"1700006400000","nextFundingTime":"1700035200000","ts":"1700000000000"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::FundingRate(f)) => {
+                assert_eq!((f.symbol.as_str(), f.rate, f.predicted_rate), ("BTC^USDT", 0.0001, -0.00005));
+                assert_eq!((f.next_funding_time, f.interval_secs), (1_700_006_400_000_000_000, 28_800));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":1700000000100,"data":{"symbol":"BTCUSDT","lastPrice":"50100",
+            "fundingRate":"0.0002","nextFundingTime":"1700006400000","fundingIntervalHour":"4"}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Multi(items)) => match &items[1] {
+                ParsedData::FundingRate(f) => assert_eq!((f.rate, f.interval_secs), (0.0002, 14_400)),
+                other => panic!("funding expected: {:?}", other),
+            },
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_liquidations() {
+        let json = r#"{"stream":"!forceOrder@arr","data":{"e":"forceOrder","E":1700000000100,"o":{"s":"ETHUSDT","S":"SELL","o":"LIMIT",
+            "f":"IOC","q":"0.5","p":"1990.0","ap":"1995.5","X":"PARTIALLY_FILLED","l":"0.2","z":"0.3","T":1700000000090}}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_binance_message(&mut bytes) {
+            Ok(ParsedData::Liquidation(l)) => {
+                assert_eq!((l.symbol.as_str(), l.price, l.quantity, l.is_sell), ("ETH^USDT", 1995.5, 0.5, true));
+                assert_eq!((l.filled_quantity, l.status, l.order_time), (0.3, LiquidationStatus::PartiallyFilled, 1_700_000_000_090_000_000));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"arg":{"channel":"liquidation-orders","instType":"SWAP"},"data":[{"details":[{"bkLoss":"0","bkPx":"0.007831",
+            "ccy":"","posSide":"short","side":"buy","sz":"13","ts":"1692266434010"}],"instFamily":"IOST-USDT","instId":"IOST-USDT-SWAP",
+            "instType":"SWAP","uly":"IOST-USDT"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::Liquidation(l)) => {
+                assert_eq!((l.symbol.as_str(), l.price, l.quantity, l.is_sell), ("IOST^USDT", 0.007831, 13.0, false));
+                assert_eq!((l.status, l.order_time), (LiquidationStatus::Unknown, 1_692_266_434_010_000_000));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        // Bybit는 청산된 포지션 방향 (Buy = 롱 청산 = 매도 주문)
+        let json = r#"{"topic":"allLiquidation.ROSEUSDT","type":"snapshot","ts":1739502303204,"data":[
+            {"T":1739502302929,"s":"ROSEUSDT","S":"Buy","v":"20000","p":"0.04499"},
+            {"T":1739502302930,"s":"ROSEUSDT","S":"Sell","v":"100","p":"0.04500"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Multi(items)) => match (&items[0], &items[1]) {
+                (ParsedData::Liquidation(a), ParsedData::Liquidation(b)) => {
+                    assert_eq!((a.symbol.as_str(), a.quantity, a.filled_quantity, a.is_sell), ("ROSE^USDT", 20000.0, 20000.0, true));
+                    assert!(!b.is_sell);
+                    assert_eq!(a.order_time, 1_739_502_302_929_000_000);
+                }
+                other => panic!("liquidations expected: {:?}", other),
+            },
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"topic":"liquidation.BTCUSDT","type":"snapshot","ts":1673251091822,"data":{"updatedTime":1673251091822,
+            "symbol":"BTCUSDT","side":"Sell","size":"0.003","price":"43511.70"}}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::Liquidation(l)) => assert_eq!((l.price, l.quantity, l.is_sell), (43511.7, 0.003, false)),
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+    #[test]
+    fn test_parse_upbit_messages() {
+        let json = r#"{"type":"trade","code":"KRW-BTC","timestamp":1676965262177,"trade_timestamp":1676965262139,
+            "trade_price":31883000,"trade_volume":0.03103806,"ask_bid":"BID","sequential_id":1676965262139000,"stream_type":"REALTIME"}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_upbit_message(&mut bytes) {
+            Ok(ParsedData::Trade(t)) => {
+                assert_eq!((t.symbol.as_str(), t.price, t.quantity, t.is_buyer_taker), ("BTC^KRW", 31_883_000.0, 0.03103806, true));
+                assert_eq!((t.timestamp, t.trade_id), (1_676_965_262_139_000_000, Some(1_676_965_262_139_000)));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"type":"orderbook","code":"KRW-USDT","timestamp":1704867306396,"total_ask_size":1.0,"total_bid_size":2.0,
+            "orderbook_units":[{"ask_price":1322,"bid_price":1321,"ask_size":100.5,"bid_size":200.0},
+                               {"ask_price":1323,"bid_price":1320,"ask_size":10.0,"bid_size":20.0}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_upbit_message(&mut bytes) {
+            Ok(ParsedData::OrderBook(ob)) => {
+                assert_eq!(ob.symbol, "USDT^KRW");
+                assert!(ob.is_snapshot);
+                assert_eq!((ob.bids[0].price, ob.asks[0].price, ob.asks[0].quantity), (1321.0, 1322.0, 100.5));
+                assert_eq!(ob.timestamp, 1_704_867_306_396_000_000);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_okx_bybit_trades_and_books() {
+        let json = r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"130639474",
+            "px":"42219.9","sz":"0.12060306","side":"buy","ts":"1630048897897","count":"3"}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::TradeBatch(b)) => {
+                let t = &b.trades[0];
+                assert_eq!((b.symbol.as_str(), t.price, t.quantity, t.is_buyer_taker), ("BTC^USDT", 42219.9, 0.12060306, true));
+                assert_eq!((t.timestamp, t.trade_id), (1_630_048_897_897_000_000, Some(130_639_474)));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"arg":{"channel":"books","instId":"BTC-USDT-SWAP"},"action":"update","data":[{"asks":[["8476.98","0","0","0"]],
+            "bids":[["8476.5","12","0","1"]],"ts":"1597026383085","checksum":-855196043,"prevSeqId":123455,"seqId":123457}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_okx_message(&mut bytes) {
+            Ok(ParsedData::OrderBook(ob)) => {
+                assert_eq!((ob.symbol.as_str(), ob.is_snapshot), ("BTC^USDT", false));
+                assert_eq!((ob.bids[0].price, ob.bids[0].quantity, ob.asks[0].quantity), (8476.5, 12.0, 0.0));
+                assert_eq!((ob.first_update_id, ob.last_update_id), (Some(123_456), Some(123_457)));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[
+            {"T":1672304486865,"s":"BTCUSDT","S":"Sell","v":"0.001","p":"16578.50","L":"PlusTick","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::TradeBatch(b)) => {
+                let t = &b.trades[0];
+                assert_eq!((t.symbol.as_str(), t.price, t.quantity, t.is_buyer_taker), ("BTC^USDT", 16578.5, 0.001, false));
+                assert_eq!((t.timestamp, t.trade_id, b.exchange_timestamp), (1_672_304_486_865_000_000, None, 1_672_304_486_868_000_000));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+
+        let json = r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1672304484978,"data":{"s":"BTCUSDT",
+            "b":[["16493.50","0.006"],["16493.00","0.100"]],"a":[["16611.00","0.029"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#;
+        let mut bytes = json.as_bytes().to_vec();
+        match DataParser::parse_bybit_message(&mut bytes) {
+            Ok(ParsedData::OrderBook(ob)) => {
+                assert!(ob.is_snapshot);
+                assert_eq!((ob.bids.len(), ob.asks[0].price), (2, 16611.0));
+                assert_eq!((ob.first_update_id, ob.last_update_id), (None, Some(18_521_288)));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unhandled_frames_are_errors() {
+        let frames = [
+            ("OkxSpot", "pong"),
+            ("OkxSpot", r#"{"event":"subscribe","arg":{"channel":"trades","instId":"BTC-USDT"},"connId":"a4d3ae55"}"#),
+            ("OkxSpot", r#"{"arg":{"channel":"mark-price","instId":"BTC-USDT"},"data":[{"markPx":"1"}]}"#),
+            ("BybitSpot", r#"{"success":true,"ret_msg":"pong","conn_id":"x","op":"ping"}"#),
+            ("BybitSpot", r#"{"success":true,"ret_msg":"","conn_id":"x","req_id":"1","op":"subscribe"}"#),
+            ("BybitSpot", r#"{"topic":"lt.EOS3LUSDT","type":"snapshot","data":{}}"#),
+            ("UpbitSpot", r#"{"status":"UP"}"#),
+        ];
+        let parser = DataParser::new();
+        for (exchange, frame) in frames {
+            parser.register_exchange(exchange);
+            assert!(parser.parse_message(exchange, frame.as_bytes().to_vec()).is_err(), "{} {}", exchange, frame);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! 오더북 REST 스냅샷 조회기
+//! WebSocket으로 오더북 스냅샷을 주지 않는 거래소(Binance 현물/선물)의 파생 데이터용 호가창을 REST depth 스냅샷으로 시작
+//!
+//! - 대상: 파생 데이터 계산기(통합 최우선 호가, 김치 프리미엄, 합성 지수)의 호가창이 스냅샷을 기다리는 (거래소, 심볼)
+//! - 첫 증분을 받았을 때와 업데이트 ID가 끊겼을 때 조회하며, 같은 심볼은 `SNAPSHOT_RETRY` 안에 다시 조회하지 않음
+//! - 스냅샷은 계산기의 호가창에만 반영 (세션 포트로는 전송하지 않음)
+//! - REST 기본 URL은 endpoint.ini `rest_url_base`로 재정의 가능 (로컬 스텁 연결용)
+
+use crate::config::Config;
+use crate::data_parser::{OrderBookLevel, StandardizedOrderBookUpdate};
+use crate::errors::{CryptoFeederError, Result};
+use crate::http_client;
+use crate::ws_connect::ConnectOptions;
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// REST 조회 제한 시간
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 같은 (거래소, 심볼)의 스냅샷 재조회 최소 간격 (요청 가중치 제한 보호)
+pub const SNAPSHOT_RETRY: Duration = Duration::from_secs(5);
+
+/// 조회할 호가 수 (파생 데이터 호가창 한쪽 최대 호가 수와 같음)
+const SNAPSHOT_LIMIT: u32 = 1000;
+
+/// REST 스냅샷이 필요한 거래소의 기본 REST URL
+pub fn default_rest_base(exchange: &str) -> Option<&'static str> {
+    match exchange {
+        "BinanceSpot" => Some("https://api.binance.com"),
+        "BinanceFutures" => Some("https://fapi.binance.com"),
+        _ => None,
+    }
+}
+
+fn depth_path(exchange: &str) -> &'static str {
+    if exchange == "BinanceFutures" { "/fapi/v1/depth" } else { "/api/v3/depth" }
+}
+
+/// Binance depth 응답을 스냅샷으로 변환
+///
+/// `{"lastUpdateId":1027024,"bids":[["4.00000000","431.00000000"]],"asks":[["4.00000200","12.00000000"]]}`
+/// (선물은 `E`/`T` 시각을 함께 제공)
+pub fn parse_depth_snapshot(exchange: &str, symbol: &str, body: &Value) -> Result<StandardizedOrderBookUpdate> {
+    let levels = |key: &str| -> Result<Vec<OrderBookLevel>> {
+        body.get(key).and_then(|v| v.as_array()).map(|a| a.as_slice()).unwrap_or(&[]).iter()
+            .map(|row| {
+                let field = |idx: usize| row.get(idx).and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok())
+                    .ok_or_else(|| CryptoFeederError::JsonParseError(format!("depth {} 필드 {} 파싱 실패", key, idx)));
+                Ok(OrderBookLevel { price: field(0)?, quantity: field(1)? })
+            })
+            .collect()
+    };
+    let last_update_id = body.get("lastUpdateId").and_then(|v| v.as_u64())
+        .ok_or_else(|| CryptoFeederError::JsonParseError("lastUpdateId 누락".into()))?;
+    Ok(StandardizedOrderBookUpdate {
+        symbol: symbol.to_string(),
+        exchange: exchange.to_string(),
+        bids: levels("bids")?,
+        asks: levels("asks")?,
+        timestamp: body.get("E").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+        first_update_id: None,
+        last_update_id: Some(last_update_id),
+        is_snapshot: true,
+    })
+}
+
+/// (거래소, 심볼) 하나의 depth 스냅샷 조회
+pub async fn fetch(config: &Config, exchange: &str, symbol: &str) -> Result<StandardizedOrderBookUpdate> {
+    let endpoint = config.endpoint_config.as_ref().and_then(|e| e.get_exchange_endpoint(exchange));
+    let base = endpoint.and_then(|e| e.rest_url_base.clone())
+        .or_else(|| default_rest_base(exchange).map(str::to_string))
+        .ok_or_else(|| CryptoFeederError::Other(format!("{} REST URL 없음", exchange)))?;
+    let options = ConnectOptions::for_session(endpoint, 0)?;
+    let url = format!("{}{}?symbol={}&limit={}", base, depth_path(exchange), symbol.replace('^', ""), SNAPSHOT_LIMIT);
+    let body = http_client::get(&url::Url::parse(&url)?, &options, FETCH_TIMEOUT).await?;
+    let body = serde_json::from_str::<Value>(&body).map_err(|e| CryptoFeederError::JsonParseError(e.to_string()))?;
+    parse_depth_snapshot(exchange, symbol, &body)
+}
+
+/// (거래소, 심볼)별 마지막 스냅샷 요청 시각 (재조회 간격 제한용)
+#[derive(Debug, Default)]
+pub struct SnapshotRequests {
+    requested_at: HashMap<(String, String), u64>,
+}
+
+impl SnapshotRequests {
+    /// 지금 조회해도 되는지 (true면 요청 시각을 기록)
+    pub fn try_request(&mut self, exchange: &str, symbol: &str, now_ns: u64) -> bool {
+        let retry_ns = SNAPSHOT_RETRY.as_nanos() as u64;
+        let at = self.requested_at.entry((exchange.to_string(), symbol.to_string())).or_insert(0);
+        if *at != 0 && now_ns.saturating_sub(*at) < retry_ns {
+            return false;
+        }
+        *at = now_ns;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_depth_snapshot() {
+        let body: Value = serde_json::from_str(r#"{"lastUpdateId":1027024,"E":1589436922972,"T":1589436922959,
+            "bids":[["4.00000000","431.00000000"]],"asks":[["4.00000200","12.00000000"],["4.00000300","1.0"]]}"#).unwrap();
+        let snapshot = parse_depth_snapshot("BinanceFutures", "BTC^USDT", &body).unwrap();
+        assert!(snapshot.is_snapshot);
+        assert_eq!((snapshot.first_update_id, snapshot.last_update_id), (None, Some(1_027_024)));
+        assert_eq!((snapshot.bids[0].quantity, snapshot.asks.len()), (431.0, 2));
+        assert_eq!(snapshot.timestamp, 1_589_436_922_972_000_000);
+        assert!(parse_depth_snapshot("BinanceSpot", "BTC^USDT", &serde_json::json!({"bids": []})).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_requests_are_rate_limited() {
+        let mut requests = SnapshotRequests::default();
+        let retry_ns = SNAPSHOT_RETRY.as_nanos() as u64;
+        assert!(requests.try_request("BinanceSpot", "BTC^USDT", 1));
+        assert!(!requests.try_request("BinanceSpot", "BTC^USDT", retry_ns));
+        assert!(requests.try_request("BinanceSpot", "ETH^USDT", retry_ns));
+        assert!(requests.try_request("BinanceSpot", "BTC^USDT", retry_ns + 1));
+    }
+}
@@ -2,8 +2,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum CryptoFeederError {
+    /// tungstenite 오류는 크기가 커서 박싱 (모든 `Result`의 Err 크기를 줄이기 위함)
     #[error("WebSocket 연결 오류: {0}")]
-    WebSocketError(#[from] tokio_tungstenite::tungstenite::Error),
+    WebSocketError(Box<tokio_tungstenite::tungstenite::Error>),
     
     #[error("JSON 파싱 오류: {0}")]
     JsonParseError(String),
@@ -24,4 +25,10 @@ pub enum CryptoFeederError {
     Other(String),
 }
 
+impl From<tokio_tungstenite::tungstenite::Error> for CryptoFeederError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocketError(Box::new(e))
+    }
+}
+
 pub type Result<T> = std::result::Result<T, CryptoFeederError>;
\ No newline at end of file
@@ -1,8 +1,9 @@
-/// 이벤트 패킷 구조체 및 관리
-/// event_packet.md 명세에 따라 구현
+//! 이벤트 패킷 구조체 및 관리
+//! event_packet.md 명세에 따라 구현
 
 use std::mem;
 use serde::{Serialize, Deserialize};
+use crate::protocol::{impl_try_from_wire, WireFormat, WireReader};
 
 // 이벤트 메시지 타입 상수
 pub const MESSAGE_TYPE_SYSTEM_HEARTBEAT: u8 = 100;
@@ -10,6 +11,7 @@ pub const MESSAGE_TYPE_CONNECTION_STATUS: u8 = 101;
 pub const MESSAGE_TYPE_SUBSCRIPTION_STATUS: u8 = 102;
 pub const MESSAGE_TYPE_SYSTEM_STATS: u8 = 103;
 pub const MESSAGE_TYPE_ERROR_EVENT: u8 = 104;
+pub const MESSAGE_TYPE_SYSTEM_SHUTDOWN: u8 = 105;
 
 // 거래소 ID 상수
 pub const EXCHANGE_ID_BINANCE: u16 = 1;
@@ -26,8 +28,31 @@ pub const CONNECTION_STATUS_CONNECTED: u8 = 2;
 pub const CONNECTION_STATUS_RECONNECTING: u8 = 3;
 pub const CONNECTION_STATUS_FAILED: u8 = 4;
 
+// 구독 타입 상수
+pub const SUBSCRIPTION_TYPE_ORDER_BOOK: u8 = 1;
+pub const SUBSCRIPTION_TYPE_TRADE: u8 = 2;
+
+// 구독 상태 상수
+pub const SUBSCRIPTION_STATUS_FAILED: u8 = 0;
+pub const SUBSCRIPTION_STATUS_SUBSCRIBED: u8 = 1;
+pub const SUBSCRIPTION_STATUS_UNSUBSCRIBED: u8 = 2;
+
+// 종료 사유 상수
+pub const SHUTDOWN_REASON_SIGNAL: u32 = 1; // SIGINT/SIGTERM
+pub const SHUTDOWN_REASON_TIMER: u32 = 2; // 실행 시간 타이머 만료
+pub const SHUTDOWN_REASON_MANAGER_EXIT: u32 = 3; // 연결 관리자 종료
+
+// 오류 타입 상수
+pub const ERROR_TYPE_CONFIG_RELOAD_REJECTED: u32 = 1;
+
+// 오류 심각도 상수
+pub const ERROR_SEVERITY_INFO: u16 = 1;
+pub const ERROR_SEVERITY_WARNING: u16 = 2;
+pub const ERROR_SEVERITY_ERROR: u16 = 3;
+pub const ERROR_SEVERITY_CRITICAL: u16 = 4;
+
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SystemHeartbeat {
     pub uptime_seconds: u64,
     pub active_connections: u32,
@@ -35,7 +60,7 @@ pub struct SystemHeartbeat {
 }
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConnectionStatus {
     pub exchange_id: u16,
     pub previous_status: u8,
@@ -45,7 +70,7 @@ pub struct ConnectionStatus {
 }
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SubscriptionStatus {
     pub exchange_id: u16,
     pub subscription_type: u8,
@@ -54,7 +79,7 @@ pub struct SubscriptionStatus {
 }
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SystemStats {
     pub cpu_usage_percent: u32,
     pub memory_usage_mb: u32,
@@ -63,7 +88,7 @@ pub struct SystemStats {
 }
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ErrorEvent {
     pub error_type: u32,
     pub exchange_id: u16,
@@ -71,6 +96,13 @@ pub struct ErrorEvent {
     pub error_details: u64,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemShutdown {
+    pub uptime_seconds: u64,
+    pub total_packets_sent: u32,
+    pub reason: u32,
+}
+
 // 이벤트 타입 enum
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SystemEvent {
@@ -79,6 +111,7 @@ pub enum SystemEvent {
     SubscriptionStatus(SubscriptionStatus),
     SystemStats(SystemStats),
     ErrorEvent(ErrorEvent),
+    Shutdown(SystemShutdown),
 }
 
 impl SystemHeartbeat {
@@ -90,11 +123,8 @@ impl SystemHeartbeat {
         }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 }
 
@@ -109,11 +139,8 @@ impl ConnectionStatus {
         }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 }
 
@@ -132,11 +159,8 @@ impl SubscriptionStatus {
         }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 }
 
@@ -150,11 +174,8 @@ impl SystemStats {
         }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 }
 
@@ -168,12 +189,23 @@ impl ErrorEvent {
         }
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl SystemShutdown {
+    pub fn new(uptime_seconds: u64, total_packets_sent: u32, reason: u32) -> Self {
+        Self {
+            uptime_seconds,
+            total_packets_sent,
+            reason,
         }
     }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
 }
 
 impl SystemEvent {
@@ -184,6 +216,7 @@ impl SystemEvent {
             SystemEvent::SubscriptionStatus(_) => MESSAGE_TYPE_SUBSCRIPTION_STATUS,
             SystemEvent::SystemStats(_) => MESSAGE_TYPE_SYSTEM_STATS,
             SystemEvent::ErrorEvent(_) => MESSAGE_TYPE_ERROR_EVENT,
+            SystemEvent::Shutdown(_) => MESSAGE_TYPE_SYSTEM_SHUTDOWN,
         }
     }
 
@@ -194,6 +227,7 @@ impl SystemEvent {
             SystemEvent::SubscriptionStatus(s) => s.to_bytes(),
             SystemEvent::SystemStats(s) => s.to_bytes(),
             SystemEvent::ErrorEvent(e) => e.to_bytes(),
+            SystemEvent::Shutdown(s) => s.to_bytes(),
         }
     }
 
@@ -227,6 +261,109 @@ impl SystemEvent {
     }
 }
 
+impl WireFormat for SystemHeartbeat {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "SystemHeartbeat";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.uptime_seconds }.to_le_bytes());
+        dst.extend_from_slice(&{ self.active_connections }.to_le_bytes());
+        dst.extend_from_slice(&{ self.total_packets_sent }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { uptime_seconds: r.u64(), active_connections: r.u32(), total_packets_sent: r.u32() }
+    }
+}
+
+impl WireFormat for ConnectionStatus {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "ConnectionStatus";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.exchange_id }.to_le_bytes());
+        dst.push(self.previous_status);
+        dst.push(self.current_status);
+        dst.extend_from_slice(&{ self.retry_count }.to_le_bytes());
+        dst.extend_from_slice(&{ self.error_code }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            exchange_id: r.u16(),
+            previous_status: r.u8(),
+            current_status: r.u8(),
+            retry_count: r.u32(),
+            error_code: r.u64(),
+        }
+    }
+}
+
+impl WireFormat for SubscriptionStatus {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "SubscriptionStatus";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.exchange_id }.to_le_bytes());
+        dst.push(self.subscription_type);
+        dst.push(self.status);
+        dst.extend_from_slice(&self.symbol_short);
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { exchange_id: r.u16(), subscription_type: r.u8(), status: r.u8(), symbol_short: r.array() }
+    }
+}
+
+impl WireFormat for SystemStats {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "SystemStats";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.cpu_usage_percent }.to_le_bytes());
+        dst.extend_from_slice(&{ self.memory_usage_mb }.to_le_bytes());
+        dst.extend_from_slice(&{ self.packets_per_second }.to_le_bytes());
+        dst.extend_from_slice(&{ self.bytes_per_second }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { cpu_usage_percent: r.u32(), memory_usage_mb: r.u32(), packets_per_second: r.u32(), bytes_per_second: r.u32() }
+    }
+}
+
+impl WireFormat for ErrorEvent {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "ErrorEvent";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.error_type }.to_le_bytes());
+        dst.extend_from_slice(&{ self.exchange_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.severity }.to_le_bytes());
+        dst.extend_from_slice(&{ self.error_details }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { error_type: r.u32(), exchange_id: r.u16(), severity: r.u16(), error_details: r.u64() }
+    }
+}
+
+impl WireFormat for SystemShutdown {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "SystemShutdown";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.uptime_seconds }.to_le_bytes());
+        dst.extend_from_slice(&{ self.total_packets_sent }.to_le_bytes());
+        dst.extend_from_slice(&{ self.reason }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { uptime_seconds: r.u64(), total_packets_sent: r.u32(), reason: r.u32() }
+    }
+}
+
+impl_try_from_wire!(SystemHeartbeat, ConnectionStatus, SubscriptionStatus, SystemStats, ErrorEvent, SystemShutdown);
+
 // 컴파일 타임에 구조체 크기 검증
 const _: () = assert!(mem::size_of::<SystemHeartbeat>() == 16);
 const _: () = assert!(mem::size_of::<ConnectionStatus>() == 16);
@@ -291,4 +428,68 @@ mod tests {
         assert_eq!(event.get_message_type(), MESSAGE_TYPE_CONNECTION_STATUS);
         assert_eq!(event.get_exchange(), "binance");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shutdown_event() {
+        let event = SystemEvent::Shutdown(SystemShutdown::new(60, 100, SHUTDOWN_REASON_SIGNAL));
+        assert_eq!(event.get_message_type(), MESSAGE_TYPE_SYSTEM_SHUTDOWN);
+        assert_eq!(event.get_exchange(), "FEEDER");
+        let bytes = event.get_payload_bytes();
+        assert_eq!(bytes, [60, 0, 0, 0, 0, 0, 0, 0, 100, 0, 0, 0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_event_wire_layout() {
+        let bytes = ConnectionStatus::new(EXCHANGE_ID_OKX, CONNECTION_STATUS_CONNECTED, CONNECTION_STATUS_RECONNECTING, 7, 0x0A0B).to_bytes();
+        assert_eq!(bytes, [2, 0, 2, 3, 7, 0, 0, 0, 0x0B, 0x0A, 0, 0, 0, 0, 0, 0]);
+        let bytes = SubscriptionStatus::new(EXCHANGE_ID_BYBIT, SUBSCRIPTION_TYPE_TRADE, SUBSCRIPTION_STATUS_SUBSCRIBED, "ETHUSDT").to_bytes();
+        assert_eq!(&bytes[..4], &[3, 0, 2, 1]);
+        assert_eq!(&bytes[4..11], b"ETHUSDT");
+        assert_eq!(
+            SystemHeartbeat::try_from(&bytes[..15]),
+            Err(crate::protocol::WireError::TooShort { structure: "SystemHeartbeat", expected: 16, actual: 15 })
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_heartbeat_round_trip(uptime: u64, active: u32, total: u32) {
+            let event = SystemHeartbeat::new(uptime, active, total);
+            proptest::prop_assert_eq!(SystemHeartbeat::try_from(event.to_bytes().as_slice()).unwrap(), event);
+        }
+
+        #[test]
+        fn prop_connection_status_round_trip(exchange_id: u16, previous: u8, current: u8, retry: u32, code: u64) {
+            let event = ConnectionStatus::new(exchange_id, previous, current, retry, code);
+            proptest::prop_assert_eq!(ConnectionStatus::try_from(event.to_bytes().as_slice()).unwrap(), event);
+        }
+
+        #[test]
+        fn prop_subscription_status_round_trip(exchange_id: u16, subscription_type: u8, status: u8, symbol_short: [u8; 12]) {
+            let event = SubscriptionStatus { exchange_id, subscription_type, status, symbol_short };
+            proptest::prop_assert_eq!(SubscriptionStatus::try_from(event.to_bytes().as_slice()).unwrap(), event);
+        }
+
+        #[test]
+        fn prop_system_stats_round_trip(cpu: u32, memory: u32, pps: u32, bps: u32) {
+            let event = SystemStats::new(cpu, memory, pps, bps);
+            proptest::prop_assert_eq!(SystemStats::try_from(event.to_bytes().as_slice()).unwrap(), event);
+        }
+
+        #[test]
+        fn prop_error_event_round_trip(error_type: u32, exchange_id: u16, severity: u16, details: u64) {
+            let event = ErrorEvent::new(error_type, exchange_id, severity, details);
+            proptest::prop_assert_eq!(ErrorEvent::try_from(event.to_bytes().as_slice()).unwrap(), event);
+        }
+
+        #[test]
+        fn prop_event_bytes_round_trip(bytes: [u8; 16]) {
+            proptest::prop_assert_eq!(SystemHeartbeat::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+            proptest::prop_assert_eq!(ConnectionStatus::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+            proptest::prop_assert_eq!(SubscriptionStatus::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+            proptest::prop_assert_eq!(SystemStats::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+            proptest::prop_assert_eq!(ErrorEvent::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+            proptest::prop_assert_eq!(SystemShutdown::try_from(&bytes[..]).unwrap().to_bytes(), bytes);
+        }
+    }
+}
@@ -0,0 +1,519 @@
+//! 연구용 피드 데이터 변환
+//! consumer 모듈로 재조립한 메시지를 메시지 타입별 테이블(CSV/Parquet)로 기록
+//!
+//! 테이블 (가격/수량은 종목 스케일 해제 - 기본 10^8, 타임스탬프는 Unix epoch ns):
+//! - `order_book`: 오더북 레벨 (is_ask, level, price, quantity)
+//! - `trade`: 체결 (is_buyer_taker, price, quantity)
+//! - `index_price`, `mark_price`, `funding_rate`: 단일 값 (value)
+//! - `funding_info`: 펀딩 정보 (funding_rate, predicted_rate, next_funding_time, interval_secs - 거래소가 제공하지 않으면 null)
+//! - `liquidation`: 청산 (is_sell, price, quantity)
+//! - `liquidation_detail`: 청산 상세 (is_sell, price, quantity, filled_quantity, order_status(`LIQUIDATION_STATUS_*`), order_time - 없으면 null)
+//!
+//! 공통 컬럼: exchange_timestamp, local_timestamp, exchange, symbol, sequence_number(메시지 첫 패킷)
+//! 파티션 사용 시 `{out}/{table}/date=YYYYMMDD/exchange={거래소}/part-00000.{csv|parquet}` (date는 local_timestamp UTC 기준)
+
+use crate::consumer::{AssembledMessage, Message, ScaleBook};
+use crate::protocol::InstrumentScale;
+use crate::errors::{CryptoFeederError, Result};
+use parquet::basic::Compression;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Parquet row group 크기 (행 수)
+const PARQUET_ROW_GROUP_ROWS: usize = 128 * 1024;
+
+/// 테이블 컬럼 타입
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Timestamp,
+    Int64,
+    Double,
+    Boolean,
+    Utf8,
+    /// null 허용 (값이 없으면 `Cell::Null`)
+    OptionalTimestamp,
+    OptionalInt64,
+    OptionalDouble,
+}
+
+impl ColumnType {
+    /// (값 타입, null 허용 여부)
+    fn split(self) -> (Self, bool) {
+        match self {
+            Self::OptionalTimestamp => (Self::Timestamp, true),
+            Self::OptionalInt64 => (Self::Int64, true),
+            Self::OptionalDouble => (Self::Double, true),
+            other => (other, false),
+        }
+    }
+}
+
+/// 컬럼 값 (행 단위 입력)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Int64(i64),
+    Double(f64),
+    Boolean(bool),
+    Utf8(String),
+    Null,
+}
+
+/// 변환 테이블 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Table {
+    OrderBook,
+    Trade,
+    IndexPrice,
+    MarkPrice,
+    FundingRate,
+    FundingInfo,
+    Liquidation,
+    LiquidationDetail,
+}
+
+const COMMON_COLUMNS: [(&str, ColumnType); 5] = [
+    ("exchange_timestamp", ColumnType::Timestamp),
+    ("local_timestamp", ColumnType::Timestamp),
+    ("exchange", ColumnType::Utf8),
+    ("symbol", ColumnType::Utf8),
+    ("sequence_number", ColumnType::Int64),
+];
+
+impl Table {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OrderBook => "order_book",
+            Self::Trade => "trade",
+            Self::IndexPrice => "index_price",
+            Self::MarkPrice => "mark_price",
+            Self::FundingRate => "funding_rate",
+            Self::FundingInfo => "funding_info",
+            Self::Liquidation => "liquidation",
+            Self::LiquidationDetail => "liquidation_detail",
+        }
+    }
+
+    fn specific_columns(&self) -> &'static [(&'static str, ColumnType)] {
+        match self {
+            Self::OrderBook => &[("is_ask", ColumnType::Boolean), ("level", ColumnType::Int64), ("price", ColumnType::Double), ("quantity", ColumnType::Double)],
+            Self::Trade => &[("is_buyer_taker", ColumnType::Boolean), ("price", ColumnType::Double), ("quantity", ColumnType::Double)],
+            Self::IndexPrice | Self::MarkPrice | Self::FundingRate => &[("value", ColumnType::Double)],
+            Self::FundingInfo => &[("funding_rate", ColumnType::Double), ("predicted_rate", ColumnType::OptionalDouble), ("next_funding_time", ColumnType::OptionalTimestamp), ("interval_secs", ColumnType::OptionalInt64)],
+            Self::Liquidation => &[("is_sell", ColumnType::Boolean), ("price", ColumnType::Double), ("quantity", ColumnType::Double)],
+            Self::LiquidationDetail => &[("is_sell", ColumnType::Boolean), ("price", ColumnType::Double), ("quantity", ColumnType::Double), ("filled_quantity", ColumnType::Double), ("order_status", ColumnType::Int64), ("order_time", ColumnType::OptionalTimestamp)],
+        }
+    }
+
+    fn columns(&self) -> Vec<(&'static str, ColumnType)> {
+        COMMON_COLUMNS.iter().chain(self.specific_columns()).copied().collect()
+    }
+
+    fn parquet_schema(&self) -> String {
+        let fields: Vec<String> = self.columns().iter().map(|(name, ty)| {
+            let (ty, nullable) = ty.split();
+            let repetition = if nullable { "OPTIONAL" } else { "REQUIRED" };
+            match ty {
+                ColumnType::Timestamp => format!("{} INT64 {} (TIMESTAMP(NANOS,true));", repetition, name),
+                ColumnType::Double => format!("{} DOUBLE {};", repetition, name),
+                ColumnType::Boolean => format!("{} BOOLEAN {};", repetition, name),
+                ColumnType::Utf8 => format!("{} BYTE_ARRAY {} (UTF8);", repetition, name),
+                _ => format!("{} INT64 {};", repetition, name),
+            }
+        }).collect();
+        format!("message {} {{ {} }}", self.name(), fields.join(" "))
+    }
+}
+
+/// 재조립된 메시지를 종목 스케일로 해석하여 테이블 행으로 변환 (시스템 이벤트 등은 None)
+pub fn message_rows(message: &AssembledMessage, scale: InstrumentScale) -> Option<(Table, Vec<Vec<Cell>>)> {
+    let row = |extra: Vec<Cell>| {
+        let mut row = vec![
+            Cell::Int64(message.exchange_timestamp as i64),
+            Cell::Int64(message.local_timestamp as i64),
+            Cell::Utf8(message.exchange.clone()),
+            Cell::Utf8(message.symbol.clone()),
+            Cell::Int64(message.sequence_number as i64),
+        ];
+        row.extend(extra);
+        row
+    };
+    let value_row = |value: f64| vec![row(vec![Cell::Double(value)])];
+
+    let (table, rows) = match message.message_with(scale) {
+        Message::OrderBook(items) => {
+            // bid(가격 내림차순) 후 ask(가격 오름차순) 순서로 전송되므로 면별 순번을 레벨로 사용
+            let (mut bid_level, mut ask_level) = (0i64, 0i64);
+            let rows = items.map(|item| {
+                let level = if item.flag() { &mut ask_level } else { &mut bid_level };
+                *level += 1;
+                row(vec![Cell::Boolean(item.flag()), Cell::Int64(*level - 1), Cell::Double(item.price()), Cell::Double(item.quantity())])
+            }).collect();
+            (Table::OrderBook, rows)
+        }
+        Message::Trade(items) => (Table::Trade, items.map(|item| row(vec![
+            Cell::Boolean(item.flag()), Cell::Double(item.price()), Cell::Double(item.quantity()),
+        ])).collect()),
+        Message::Liquidation(items) => (Table::Liquidation, items.map(|item| row(vec![
+            Cell::Boolean(item.flag()), Cell::Double(item.price()), Cell::Double(item.quantity()),
+        ])).collect()),
+        Message::LiquidationDetail(detail) => (Table::LiquidationDetail, vec![row(vec![
+            Cell::Boolean(detail.is_sell),
+            Cell::Double(detail.price),
+            Cell::Double(detail.quantity),
+            Cell::Double(detail.filled_quantity),
+            Cell::Int64(detail.order_status as i64),
+            detail.order_time.map_or(Cell::Null, |t| Cell::Int64(t as i64)),
+        ])]),
+        Message::IndexPrice(value) => (Table::IndexPrice, value_row(value)),
+        Message::MarkPrice(value) => (Table::MarkPrice, value_row(value)),
+        Message::FundingRate(value) => (Table::FundingRate, value_row(value)),
+        Message::FundingInfo(info) => (Table::FundingInfo, vec![row(vec![
+            Cell::Double(info.funding_rate),
+            info.predicted_rate.map_or(Cell::Null, Cell::Double),
+            info.next_funding_time.map_or(Cell::Null, |t| Cell::Int64(t as i64)),
+            info.interval_secs.map_or(Cell::Null, |secs| Cell::Int64(secs as i64)),
+        ])]),
+        _ => return None,
+    };
+    Some((table, rows))
+}
+
+/// 출력 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "parquet" => Some(Self::Parquet),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+fn export_err(context: &str, e: impl std::fmt::Display) -> CryptoFeederError {
+    CryptoFeederError::Other(format!("{}: {}", context, e))
+}
+
+/// 컬럼 값 버퍼 (Parquet row group 단위로 비움)
+enum ColumnValues {
+    Int64(Vec<i64>),
+    Double(Vec<f64>),
+    Boolean(Vec<bool>),
+    Utf8(Vec<ByteArray>),
+}
+
+/// 컬럼 버퍼 (null 허용 컬럼은 행별 definition level 포함, 0 = null)
+struct ColumnBuffer {
+    values: ColumnValues,
+    def_levels: Option<Vec<i16>>,
+}
+
+/// 파티션 하나의 출력 파일
+enum PartitionSink {
+    Csv(BufWriter<File>),
+    Parquet { writer: SerializedFileWriter<File>, columns: Vec<ColumnBuffer>, rows: usize },
+}
+
+impl PartitionSink {
+    fn open(path: &Path, table: Table, format: ExportFormat) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| export_err("출력 디렉터리 생성 실패", e))?;
+        }
+        let file = File::create(path).map_err(|e| export_err(&format!("출력 파일 생성 실패 ({})", path.display()), e))?;
+        match format {
+            ExportFormat::Csv => {
+                let mut out = BufWriter::with_capacity(1 << 20, file);
+                let header: Vec<&str> = table.columns().iter().map(|(name, _)| *name).collect();
+                writeln!(out, "{}", header.join(",")).map_err(|e| export_err("CSV 쓰기 실패", e))?;
+                Ok(Self::Csv(out))
+            }
+            ExportFormat::Parquet => {
+                let schema = Arc::new(parse_message_type(&table.parquet_schema()).map_err(|e| export_err("Parquet 스키마 오류", e))?);
+                let props = Arc::new(WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+                let writer = SerializedFileWriter::new(file, schema, props).map_err(|e| export_err("Parquet 파일 생성 실패", e))?;
+                let columns = table.columns().iter().map(|(_, ty)| {
+                    let (ty, nullable) = ty.split();
+                    let values = match ty {
+                        ColumnType::Double => ColumnValues::Double(Vec::new()),
+                        ColumnType::Boolean => ColumnValues::Boolean(Vec::new()),
+                        ColumnType::Utf8 => ColumnValues::Utf8(Vec::new()),
+                        _ => ColumnValues::Int64(Vec::new()),
+                    };
+                    ColumnBuffer { values, def_levels: nullable.then(Vec::new) }
+                }).collect();
+                Ok(Self::Parquet { writer, columns, rows: 0 })
+            }
+        }
+    }
+
+    fn write_row(&mut self, row: &[Cell]) -> Result<()> {
+        match self {
+            Self::Csv(out) => {
+                let fields: Vec<String> = row.iter().map(|cell| match cell {
+                    Cell::Int64(v) => v.to_string(),
+                    Cell::Double(v) => v.to_string(),
+                    Cell::Boolean(v) => v.to_string(),
+                    Cell::Utf8(v) if v.contains([',', '"', '\n']) => format!("\"{}\"", v.replace('"', "\"\"")),
+                    Cell::Utf8(v) => v.clone(),
+                    Cell::Null => String::new(),
+                }).collect();
+                writeln!(out, "{}", fields.join(",")).map_err(|e| export_err("CSV 쓰기 실패", e))
+            }
+            Self::Parquet { columns, rows, .. } => {
+                for (column, cell) in columns.iter_mut().zip(row) {
+                    if let Some(levels) = column.def_levels.as_mut() {
+                        levels.push(if *cell == Cell::Null { 0 } else { 1 });
+                    }
+                    match (&mut column.values, cell) {
+                        (_, Cell::Null) if column.def_levels.is_some() => {}
+                        (ColumnValues::Int64(v), Cell::Int64(x)) => v.push(*x),
+                        (ColumnValues::Double(v), Cell::Double(x)) => v.push(*x),
+                        (ColumnValues::Boolean(v), Cell::Boolean(x)) => v.push(*x),
+                        (ColumnValues::Utf8(v), Cell::Utf8(x)) => v.push(ByteArray::from(x.as_str())),
+                        _ => return Err(CryptoFeederError::Other("컬럼 타입 불일치".to_string())),
+                    }
+                }
+                *rows += 1;
+                if *rows >= PARQUET_ROW_GROUP_ROWS {
+                    self.flush_row_group()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 버퍼된 행을 Parquet row group으로 기록
+    fn flush_row_group(&mut self) -> Result<()> {
+        let Self::Parquet { writer, columns, rows } = self else { return Ok(()) };
+        if *rows == 0 {
+            return Ok(());
+        }
+        let err = |e| export_err("Parquet 쓰기 실패", e);
+        let mut row_group = writer.next_row_group().map_err(err)?;
+        for column in columns.iter_mut() {
+            let mut out = row_group.next_column().map_err(err)?
+                .ok_or_else(|| CryptoFeederError::Other("Parquet 컬럼 수 불일치".to_string()))?;
+            let def_levels = column.def_levels.as_deref();
+            match &mut column.values {
+                ColumnValues::Int64(v) => { out.typed::<Int64Type>().write_batch(v, def_levels, None).map_err(err)?; v.clear(); }
+                ColumnValues::Double(v) => { out.typed::<DoubleType>().write_batch(v, def_levels, None).map_err(err)?; v.clear(); }
+                ColumnValues::Boolean(v) => { out.typed::<BoolType>().write_batch(v, def_levels, None).map_err(err)?; v.clear(); }
+                ColumnValues::Utf8(v) => { out.typed::<ByteArrayType>().write_batch(v, def_levels, None).map_err(err)?; v.clear(); }
+            }
+            if let Some(levels) = column.def_levels.as_mut() {
+                levels.clear();
+            }
+            out.close().map_err(err)?;
+        }
+        row_group.close().map_err(err)?;
+        *rows = 0;
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<()> {
+        self.flush_row_group()?;
+        match self {
+            Self::Csv(mut out) => out.flush().map_err(|e| export_err("CSV flush 실패", e)),
+            Self::Parquet { writer, .. } => writer.close().map(|_| ()).map_err(|e| export_err("Parquet 닫기 실패", e)),
+        }
+    }
+}
+
+/// 메시지 타입별 테이블 기록기 (선택적으로 날짜/거래소 파티션)
+pub struct TableExporter {
+    out_dir: PathBuf,
+    format: ExportFormat,
+    partition: bool,
+    sinks: HashMap<(Table, String, String), PartitionSink>,
+    rows: HashMap<Table, u64>,
+    scales: ScaleBook,
+}
+
+impl TableExporter {
+    pub fn new(out_dir: impl Into<PathBuf>, format: ExportFormat, partition: bool) -> Self {
+        Self { out_dir: out_dir.into(), format, partition, sinks: HashMap::new(), rows: HashMap::new(), scales: ScaleBook::new() }
+    }
+
+    /// 초기 종목 스케일 (입력에 종목 정의가 있으면 그 값으로 갱신)
+    pub fn with_scales(mut self, scales: ScaleBook) -> Self {
+        self.scales = scales;
+        self
+    }
+
+    fn partition_path(&self, table: Table, date: &str, exchange: &str) -> PathBuf {
+        let file = format!("part-00000.{}", self.format.extension());
+        if self.partition {
+            self.out_dir.join(table.name()).join(format!("date={}", date)).join(format!("exchange={}", exchange)).join(file)
+        } else {
+            self.out_dir.join(format!("{}.{}", table.name(), self.format.extension()))
+        }
+    }
+
+    /// 재조립된 메시지 기록
+    pub fn write_message(&mut self, message: &AssembledMessage) -> Result<()> {
+        self.scales.learn(&message.message());
+        let Some((table, rows)) = message_rows(message, self.scales.for_message(message)) else { return Ok(()) };
+        let (date, exchange) = if self.partition {
+            let date = chrono::DateTime::from_timestamp_nanos(message.local_timestamp as i64).format("%Y%m%d").to_string();
+            (date, message.exchange.replace(['/', '\\'], "_"))
+        } else {
+            (String::new(), String::new())
+        };
+        let key = (table, date, exchange);
+        if !self.sinks.contains_key(&key) {
+            let path = self.partition_path(table, &key.1, &key.2);
+            self.sinks.insert(key.clone(), PartitionSink::open(&path, table, self.format)?);
+        }
+        let sink = self.sinks.get_mut(&key).unwrap();
+        for row in &rows {
+            sink.write_row(row)?;
+        }
+        *self.rows.entry(table).or_insert(0) += rows.len() as u64;
+        Ok(())
+    }
+
+    /// 모든 파일을 닫고 테이블별 행 수 반환
+    pub fn finish(self) -> Result<Vec<(&'static str, u64)>> {
+        for (_, sink) in self.sinks {
+            sink.close()?;
+        }
+        let mut rows: Vec<(&'static str, u64)> = self.rows.into_iter().map(|(t, n)| (t.name(), n)).collect();
+        rows.sort();
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consumer::{test_packet, MessageAssembler};
+    use crate::protocol::{OrderBookItem, TradeTickItem, MESSAGE_TYPE_ORDER_BOOK, MESSAGE_TYPE_TRADE_TICK};
+
+    #[test]
+    fn test_order_book_rows_from_reassembled_message() {
+        let mut assembler = MessageAssembler::new();
+        let first = test_packet(MESSAGE_TYPE_ORDER_BOOK, 1, 10, false, &[OrderBookItem::new(100.0, 1.0, false).unwrap().to_bytes(), OrderBookItem::new(99.0, 2.0, false).unwrap().to_bytes()]);
+        let last = test_packet(MESSAGE_TYPE_ORDER_BOOK, 2, 10, true, &[OrderBookItem::new(101.0, 3.0, true).unwrap().to_bytes()]);
+        assert!(assembler.push(&first).is_none());
+        let message = assembler.push(&last).unwrap();
+        assert_eq!(message.sequence_number, 1);
+        assert_eq!(message.exchange, "BinanceSpot");
+
+        let (table, rows) = message_rows(&message, InstrumentScale::DEFAULT).unwrap();
+        assert_eq!(table, Table::OrderBook);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][5..], [Cell::Boolean(false), Cell::Int64(1), Cell::Double(99.0), Cell::Double(2.0)]);
+        assert_eq!(rows[2][5..], [Cell::Boolean(true), Cell::Int64(0), Cell::Double(101.0), Cell::Double(3.0)]);
+    }
+
+    #[test]
+    fn test_funding_info_rows_with_nulls() {
+        use crate::data_parser::{ParsedData, StandardizedFunding};
+        use crate::packet_builder::PacketBuilder;
+        let funding = StandardizedFunding {
+            symbol: "BTC^USDT".into(),
+            exchange: "BybitLinear".into(),
+            rate: 0.0001,
+            predicted_rate: 0.0,
+            next_funding_time: 1_700_006_400_000_000_000,
+            interval_secs: 0,
+            timestamp: 1_700_000_000_000_000_000,
+        };
+        let packets = PacketBuilder::new().with_funding_info(true).build_packets(ParsedData::FundingRate(funding)).unwrap();
+        let message = MessageAssembler::new().push(&packets[0].data).unwrap();
+
+        let (table, rows) = message_rows(&message, InstrumentScale::DEFAULT).unwrap();
+        assert_eq!(table, Table::FundingInfo);
+        assert_eq!(rows[0][5..], [Cell::Double(0.0001), Cell::Double(0.0), Cell::Int64(1_700_006_400_000_000_000), Cell::Null]);
+
+        let dir = tempfile::tempdir().unwrap();
+        for format in [ExportFormat::Csv, ExportFormat::Parquet] {
+            let mut exporter = TableExporter::new(dir.path(), format, false);
+            exporter.write_message(&message).unwrap();
+            assert_eq!(exporter.finish().unwrap(), vec![("funding_info", 1)]);
+        }
+        let csv = fs::read_to_string(dir.path().join("funding_info.csv")).unwrap();
+        assert!(csv.lines().nth(1).unwrap().ends_with(",BybitLinear,BTC^USDT,1,0.0001,0,1700006400000000000,"));
+
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::{Field, RowAccessor};
+        let reader = SerializedFileReader::new(File::open(dir.path().join("funding_info.parquet")).unwrap()).unwrap();
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert_eq!(row.get_double(5).unwrap(), 0.0001);
+        assert_eq!(row.get_long(7).unwrap(), 1_700_006_400_000_000_000);
+        assert_eq!(row.get_column_iter().nth(8).map(|(_, field)| field), Some(&Field::Null));
+    }
+
+    #[test]
+    fn test_liquidation_detail_rows() {
+        use crate::data_parser::{LiquidationStatus, ParsedData, StandardizedLiquidation};
+        use crate::packet_builder::PacketBuilder;
+        use crate::protocol::LIQUIDATION_STATUS_PARTIALLY_FILLED;
+        let liquidation = StandardizedLiquidation {
+            symbol: "ETH^USDT".into(),
+            exchange: "BinanceFutures".into(),
+            price: 1995.5,
+            quantity: 0.5,
+            is_sell: true,
+            filled_quantity: 0.3,
+            status: LiquidationStatus::PartiallyFilled,
+            order_time: 1_700_000_000_090_000_000,
+            timestamp: 1_700_000_000_100_000_000,
+        };
+        let packets = PacketBuilder::new().with_liquidation_detail(true).build_packets(ParsedData::Liquidation(liquidation)).unwrap();
+        let message = MessageAssembler::new().push(&packets[0].data).unwrap();
+
+        let (table, rows) = message_rows(&message, InstrumentScale::DEFAULT).unwrap();
+        assert_eq!(table, Table::LiquidationDetail);
+        assert_eq!(rows[0][5..], [
+            Cell::Boolean(true), Cell::Double(1995.5), Cell::Double(0.5), Cell::Double(0.3),
+            Cell::Int64(LIQUIDATION_STATUS_PARTIALLY_FILLED as i64), Cell::Int64(1_700_000_000_090_000_000),
+        ]);
+    }
+
+    #[test]
+    fn test_export_csv_and_parquet_partitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut assembler = MessageAssembler::new();
+        let message = assembler.push(&test_packet(MESSAGE_TYPE_TRADE_TICK, 1, 10, true, &[
+            TradeTickItem::new(50_000.0, 0.5, true).unwrap().to_bytes(),
+            TradeTickItem::new(49_999.5, 1.25, false).unwrap().to_bytes(),
+        ])).unwrap();
+
+        for format in [ExportFormat::Csv, ExportFormat::Parquet] {
+            let mut exporter = TableExporter::new(dir.path(), format, true);
+            exporter.write_message(&message).unwrap();
+            assert_eq!(exporter.finish().unwrap(), vec![("trade", 2)]);
+        }
+
+        let partition = dir.path().join("trade/date=20231114/exchange=BinanceSpot");
+        let csv = fs::read_to_string(partition.join("part-00000.csv")).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "exchange_timestamp,local_timestamp,exchange,symbol,sequence_number,is_buyer_taker,price,quantity");
+        assert_eq!(lines[2], "10,1700000000000000000,BinanceSpot,BTC^USDT,1,false,49999.5,1.25");
+
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        let reader = SerializedFileReader::new(File::open(partition.join("part-00000.parquet")).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        assert_eq!(reader.metadata().file_metadata().schema_descr().num_columns(), 8);
+    }
+}
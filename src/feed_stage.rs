@@ -0,0 +1,346 @@
+//! 메시지 처리 단계
+//! 실시간 세션 수신 루프와 저널 리플레이가 함께 쓰는 프레임 한 건의 처리와 주기 발행
+//!
+//! - 프레임 처리: 파싱 → 세션 필터(`session_wants`) → 티커 병합 → 로컬 봉 집계 → 파생 데이터 반영 → 패킷 생성
+//! - 주기 발행: 봉 마감, 병합 티커, 통합 최우선 호가, 김치 프리미엄, 자체 인덱스, 체결 흐름
+//! - 시각은 호출자가 넘긴 수신 시각을 사용하므로 같은 저널을 재생하면 실시간과 같은 패킷이 나옴
+
+use crate::bar_aggregator::BarAggregator;
+use crate::composite_index::CompositeIndex;
+use crate::config::{Config, KlineSource, LiquidationScope, SessionStreams};
+use crate::consolidated_bbo::ConsolidatedBbo;
+use crate::data_parser::{DataParser, ParsedData};
+use crate::depth_snapshot;
+use crate::errors::Result;
+use crate::kimchi_premium::KimchiPremium;
+use crate::packet_builder::{PacketBuilder, UdpPacket};
+use crate::reference_data::MetadataStore;
+use crate::ticker_conflator::{split_tickers, TickerConflator};
+use crate::trade_flow::TradeFlow;
+use log::{info, warn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 주기 발행 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Publisher {
+    /// 유예 시간이 지난 로컬 봉 확정 (세션 포트)
+    Bars,
+    /// 바뀐 병합 티커 (세션 포트)
+    Tickers,
+    ConsolidatedBbo,
+    KimchiPremium,
+    CompositeIndex,
+    TradeFlow,
+}
+
+impl Publisher {
+    /// 로그 표시명
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Bars => "봉",
+            Self::Tickers => "티커",
+            Self::ConsolidatedBbo => "통합 호가",
+            Self::KimchiPremium => "김치 프리미엄",
+            Self::CompositeIndex => "자체 인덱스",
+            Self::TradeFlow => "체결 흐름",
+        }
+    }
+}
+
+/// 세션 프레임의 필터 입력 (세션이 없는 기본 경로는 필터 없이 파싱/패킷 생성만 함)
+pub struct SessionFilter<'a> {
+    pub port: u16,
+    pub streams: SessionStreams,
+    /// 세션 구독 심볼인지
+    pub in_session: &'a dyn Fn(&str) -> bool,
+}
+
+/// 프레임 한 건의 처리 결과
+#[derive(Default)]
+pub struct StageOutput {
+    /// 세션 포트로 보낼 패킷 (데이터, 확정된 로컬 봉, 즉시 전송 티커 순)
+    pub packets: Vec<UdpPacket>,
+    /// REST 스냅샷을 기다리는 파생 데이터 호가창 (거래소, 심볼)
+    pub awaiting_snapshots: Vec<(String, String)>,
+}
+
+/// 실시간/리플레이 공용 메시지 처리 단계
+pub struct FeedStage {
+    data_parser: Arc<DataParser>,
+    packet_builder: Arc<PacketBuilder>,
+    // `klines=local` 세션의 체결 기반 봉 집계기 (포트별 키)
+    bar_aggregator: Mutex<BarAggregator>,
+    // 24시간 티커 병합기 (포트별 키)
+    ticker_conflator: Mutex<TickerConflator>,
+    // 거래소 통합 최우선 호가 집계기 (consolidated_bbo_port가 0이면 None)
+    consolidated_bbo: Option<Mutex<ConsolidatedBbo>>,
+    // 김치 프리미엄 계산기 (kimchi_port가 0이면 None)
+    kimchi_premium: Option<Mutex<KimchiPremium>>,
+    // 자체 인덱스 계산기 (index_config.ini가 없거나 port가 0이면 None)
+    composite_index: Option<Mutex<CompositeIndex>>,
+    // 체결 흐름 분석기 (trade_flow_port가 0이면 None)
+    trade_flow: Option<Mutex<TradeFlow>>,
+    /// 활성 주기 발행과 간격
+    publishers: Vec<(Publisher, Duration)>,
+    /// 전용 포트 발행(통합 호가, 김치 프리미엄, 자체 인덱스, 체결 흐름)의 포트 조회용 설정
+    config: Arc<Config>,
+}
+
+impl FeedStage {
+    pub fn new(config: Arc<Config>, data_parser: Arc<DataParser>, packet_builder: Arc<PacketBuilder>) -> Self {
+        let mut publishers = vec![(Publisher::Bars, Duration::from_secs(1))];
+        if config.ticker_interval_ms != 0 {
+            publishers.push((Publisher::Tickers, Duration::from_millis(config.ticker_interval_ms)));
+        }
+        // 계약 단위 수량을 보내는 종목(OKX 무기한)은 거래소 간 통합/체결량 집계 전에 기준 자산 수량으로 환산
+        let contract_values = if config.consolidated_bbo.port != 0 || config.trade_flow.port != 0 {
+            MetadataStore::load_configured(&config.reference).contract_values()
+        } else {
+            Default::default()
+        };
+        let consolidated_bbo = (config.consolidated_bbo.port != 0).then(|| {
+            let settings = &config.consolidated_bbo;
+            info!("📊 통합 최우선 호가 발행: 포트 {}, {}ms 간격", settings.port, settings.interval_ms);
+            publishers.push((Publisher::ConsolidatedBbo, Duration::from_millis(settings.interval_ms)));
+            Mutex::new(ConsolidatedBbo::new(settings).with_contract_values(contract_values.clone()))
+        });
+        let kimchi_premium = (config.kimchi_premium.port != 0).then(|| {
+            let settings = &config.kimchi_premium;
+            info!("🇰🇷 김치 프리미엄 발행: 포트 {}, {}ms 간격, 환율 {:?}", settings.port, settings.interval_ms, settings.fx_rate);
+            publishers.push((Publisher::KimchiPremium, Duration::from_millis(settings.interval_ms)));
+            Mutex::new(KimchiPremium::new(settings))
+        });
+        let composite_index = config.index_config.as_ref().filter(|index| index.port != 0).map(|settings| {
+            info!("📐 자체 인덱스 발행: 포트 {}, {}ms 간격, {}개 심볼 ({})", settings.port, settings.interval_ms, settings.indices.len(), settings.exchange);
+            publishers.push((Publisher::CompositeIndex, Duration::from_millis(settings.interval_ms)));
+            Mutex::new(CompositeIndex::new(settings))
+        });
+        let trade_flow = (config.trade_flow.port != 0).then(|| {
+            let settings = &config.trade_flow;
+            info!("🌊 체결 흐름 발행: 포트 {}, {}ms 간격, 구간 {:?}초", settings.port, settings.interval_ms, settings.windows_secs);
+            publishers.push((Publisher::TradeFlow, Duration::from_millis(settings.interval_ms)));
+            Mutex::new(TradeFlow::new(settings).with_contract_values(contract_values))
+        });
+        Self {
+            data_parser,
+            packet_builder,
+            bar_aggregator: Mutex::new(BarAggregator::new(config.bar_intervals.clone())),
+            ticker_conflator: Mutex::new(TickerConflator::new(config.ticker_interval_ms)),
+            consolidated_bbo,
+            kimchi_premium,
+            composite_index,
+            trade_flow,
+            publishers,
+            config,
+        }
+    }
+
+    /// 활성 주기 발행과 간격
+    pub fn publishers(&self) -> &[(Publisher, Duration)] {
+        &self.publishers
+    }
+
+    /// 수신 프레임 한 건 처리 (`recv_timestamp_ns`는 파생 데이터 계산 기준 시각)
+    pub fn process(&self, exchange: &str, data: Vec<u8>, session: Option<&SessionFilter>, recv_timestamp_ns: u64) -> Result<StageOutput> {
+        let parsed = self.data_parser.parse_message(exchange, data)?;
+        let Some(session) = session else {
+            return Ok(StageOutput { packets: self.packet_builder.build_packets(parsed)?, awaiting_snapshots: Vec::new() });
+        };
+        let parsed = parsed.retain(&|d| session_wants(session.streams, d, session.in_session));
+        let (parsed, tickers) = split_tickers(parsed);
+        let ready_tickers: Vec<_> = if tickers.is_empty() || !session.streams.ticker {
+            Vec::new()
+        } else {
+            let mut conflator = self.ticker_conflator.lock().unwrap();
+            tickers.into_iter().filter_map(|t| conflator.update(session.port, t)).collect()
+        };
+        let closed_bars = if session.streams.klines == KlineSource::Local {
+            self.bar_aggregator.lock().unwrap().ingest(session.port, &parsed)
+        } else {
+            Vec::new()
+        };
+        self.ingest_derived(&parsed, recv_timestamp_ns);
+        let mut awaiting_snapshots = Vec::new();
+        self.collect_awaiting_snapshots(&parsed, &mut awaiting_snapshots);
+
+        let mut packets = self.packet_builder.build_packets(parsed)?;
+        for kline in &closed_bars {
+            packets.push(self.packet_builder.build_kline_packet(kline)?);
+        }
+        for ticker in &ready_tickers {
+            packets.push(self.packet_builder.build_ticker_packet(ticker)?);
+        }
+        Ok(StageOutput { packets, awaiting_snapshots })
+    }
+
+    /// 파생 데이터 계산기(통합 최우선 호가, 김치 프리미엄, 자체 인덱스, 체결 흐름)에 반영
+    pub fn ingest_derived(&self, data: &ParsedData, now_ns: u64) {
+        if let Some(consolidated_bbo) = &self.consolidated_bbo {
+            consolidated_bbo.lock().unwrap().ingest(data, now_ns);
+        }
+        if let Some(kimchi_premium) = &self.kimchi_premium {
+            kimchi_premium.lock().unwrap().ingest(data, now_ns);
+        }
+        if let Some(composite_index) = &self.composite_index {
+            composite_index.lock().unwrap().ingest(data, now_ns);
+        }
+        if let Some(trade_flow) = &self.trade_flow {
+            trade_flow.lock().unwrap().ingest(data, now_ns);
+        }
+    }
+
+    /// WebSocket 스냅샷이 없는 거래소(Binance)의 오더북 중 파생 데이터 호가창이 스냅샷을 기다리는 것
+    fn collect_awaiting_snapshots(&self, data: &ParsedData, out: &mut Vec<(String, String)>) {
+        match data {
+            ParsedData::OrderBook(update) => {
+                let key = (update.exchange.clone(), update.symbol.clone());
+                if depth_snapshot::default_rest_base(&update.exchange).is_some()
+                    && self.awaits_snapshot(&update.exchange, &update.symbol)
+                    && !out.contains(&key)
+                {
+                    out.push(key);
+                }
+            }
+            ParsedData::Multi(items) => {
+                for item in items {
+                    self.collect_awaiting_snapshots(item, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn awaits_snapshot(&self, exchange: &str, symbol: &str) -> bool {
+        self.consolidated_bbo.as_ref().is_some_and(|c| c.lock().unwrap().awaits_snapshot(exchange, symbol))
+            || self.kimchi_premium.as_ref().is_some_and(|k| k.lock().unwrap().awaits_snapshot(exchange, symbol))
+            || self.composite_index.as_ref().is_some_and(|i| i.lock().unwrap().awaits_snapshot(exchange, symbol))
+    }
+
+    /// 주기 발행 한 번 (`now_ns` 기준으로 확정/계산한 항목을 (포트, 패킷) 목록으로 반환)
+    ///
+    /// 패킷 생성 실패는 항목을 표시한 경고만 남기고 다음 항목을 계속 처리합니다.
+    pub fn publish(&self, publisher: Publisher, now_ns: u64) -> Vec<(u16, UdpPacket)> {
+        let config = &self.config;
+        match publisher {
+            Publisher::Bars => self.build_each(
+                publisher,
+                self.bar_aggregator.lock().unwrap().close_due(now_ns),
+                |builder, kline| builder.build_kline_packet(kline).map(|p| vec![p]),
+                |kline| format!("{} {} {}s", kline.exchange, kline.symbol, kline.interval_secs),
+            ),
+            Publisher::Tickers => self.build_each(
+                publisher,
+                self.ticker_conflator.lock().unwrap().flush(),
+                |builder, ticker| builder.build_ticker_packet(ticker).map(|p| vec![p]),
+                |ticker| format!("{} {}", ticker.exchange, ticker.symbol),
+            ),
+            Publisher::ConsolidatedBbo => self.build_each(
+                publisher,
+                self.consolidated_bbo.as_ref().map(|c| c.lock().unwrap().flush(now_ns)).unwrap_or_default()
+                    .into_iter().map(|q| (config.consolidated_bbo.port, q)).collect(),
+                |builder, quote| builder.build_consolidated_bbo_packet(quote).map(|p| vec![p]),
+                |quote| quote.symbol.clone(),
+            ),
+            Publisher::KimchiPremium => self.build_each(
+                publisher,
+                self.kimchi_premium.as_ref().map(|k| k.lock().unwrap().flush(now_ns)).unwrap_or_default()
+                    .into_iter().map(|q| (config.kimchi_premium.port, q)).collect(),
+                |builder, quote| builder.build_kimchi_premium_packet(quote).map(|p| vec![p]),
+                |quote| format!("{}@{}", quote.symbol, quote.krw_exchange),
+            ),
+            Publisher::CompositeIndex => {
+                let port = config.index_config.as_ref().map_or(0, |index| index.port);
+                self.build_each(
+                    publisher,
+                    self.composite_index.as_ref().map(|i| i.lock().unwrap().flush(now_ns)).unwrap_or_default()
+                        .into_iter().map(|v| (port, v)).collect(),
+                    |builder, value| builder.build_packets(value.to_parsed()),
+                    |value| value.symbol.clone(),
+                )
+            }
+            Publisher::TradeFlow => self.build_each(
+                publisher,
+                self.trade_flow.as_ref().map(|f| f.lock().unwrap().flush(now_ns)).unwrap_or_default()
+                    .into_iter().map(|s| (config.trade_flow.port, s)).collect(),
+                |builder, snapshot| builder.build_trade_flow_packet(snapshot).map(|p| vec![p]),
+                |snapshot| format!("{}@{}", snapshot.symbol, snapshot.exchange),
+            ),
+        }
+    }
+
+    fn build_each<T>(
+        &self,
+        publisher: Publisher,
+        items: Vec<(u16, T)>,
+        build: fn(&PacketBuilder, &T) -> Result<Vec<UdpPacket>>,
+        label: fn(&T) -> String,
+    ) -> Vec<(u16, UdpPacket)> {
+        let mut out = Vec::new();
+        for (port, item) in items {
+            match build(&self.packet_builder, &item) {
+                Ok(packets) => out.extend(packets.into_iter().map(|p| (port, p))),
+                Err(e) => warn!("⚠️ {} 패킷 생성 실패 ({}): {}", publisher.label(), label(&item), e),
+            }
+        }
+        out
+    }
+}
+
+/// 세션에서 켠 스트림의 데이터인지 (공유 토픽으로 함께 들어온 데이터를 거름, Binance markPrice의 펀딩비는 항상 전송)
+///
+/// `liquidations=on` 세션의 청산은 세션 심볼(`in_session`)만 남깁니다.
+pub fn session_wants(streams: SessionStreams, data: &ParsedData, in_session: &dyn Fn(&str) -> bool) -> bool {
+    match data {
+        ParsedData::OpenInterest(_) => streams.open_interest,
+        ParsedData::FundingRate(f) => streams.funding || f.exchange.starts_with("Binance"),
+        ParsedData::Liquidation(l) => streams.liquidations != LiquidationScope::Symbols || in_session(&l.symbol),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_wants_filters_shared_topics() {
+        use crate::data_parser::StandardizedFunding;
+        let funding = |exchange: &str| ParsedData::FundingRate(StandardizedFunding {
+            symbol: "BTC^USDT".into(),
+            exchange: exchange.into(),
+            rate: 0.0001,
+            predicted_rate: f64::NAN,
+            next_funding_time: 0,
+            interval_secs: 0,
+            timestamp: 0,
+        });
+        let any = |_: &str| true;
+        let ticker_only = SessionStreams { ticker: true, ..SessionStreams::default() };
+        assert!(!session_wants(ticker_only, &funding("BybitLinear"), &any));
+        assert!(session_wants(ticker_only, &funding("BinanceFutures"), &any));
+        assert!(session_wants(SessionStreams { funding: true, ..ticker_only }, &funding("BybitLinear"), &any));
+    }
+
+    #[test]
+    fn test_session_wants_filters_market_wide_liquidations() {
+        use crate::data_parser::{LiquidationStatus, StandardizedLiquidation};
+        let liquidation = |symbol: &str| ParsedData::Liquidation(StandardizedLiquidation {
+            symbol: symbol.into(),
+            exchange: "OkxSwap".into(),
+            price: 1.0,
+            quantity: 1.0,
+            is_sell: true,
+            filled_quantity: 1.0,
+            status: LiquidationStatus::Unknown,
+            order_time: 0,
+            timestamp: 0,
+        });
+        let in_session = |symbol: &str| symbol == "BTC^USDT";
+        let on = SessionStreams { liquidations: LiquidationScope::Symbols, ..SessionStreams::default() };
+        assert!(session_wants(on, &liquidation("BTC^USDT"), &in_session));
+        assert!(!session_wants(on, &liquidation("IOST^USDT"), &in_session));
+        let all = SessionStreams { liquidations: LiquidationScope::All, ..SessionStreams::default() };
+        assert!(session_wants(all, &liquidation("IOST^USDT"), &in_session));
+    }
+}
@@ -0,0 +1,163 @@
+//! 간단한 HTTP(S) GET 클라이언트
+//! 거래소 REST 메타데이터(exchangeInfo 등) 조회용
+//!
+//! - 연결은 WebSocket과 같은 `ConnectOptions`(프록시/송신 IP/DNS 재정의)를 적용
+//! - `Connection: close` 요청 후 응답 전체를 읽어 Content-Length/chunked 본문을 해석
+//! - 압축은 요청하지 않음 (`Accept-Encoding: identity`)
+
+use crate::errors::{CryptoFeederError, Result};
+use crate::ws_connect::{connect_tcp, ConnectOptions};
+use log::debug;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use url::Url;
+
+/// 응답 최대 크기 (exchangeInfo 전체 목록 수용)
+const MAX_RESPONSE_BYTES: usize = 64 * 1024 * 1024;
+
+/// URL에 GET 요청 후 200 응답 본문을 문자열로 반환
+pub async fn get(url: &Url, options: &ConnectOptions, timeout: Duration) -> Result<String> {
+    tokio::time::timeout(timeout, get_inner(url, options)).await
+        .map_err(|_| CryptoFeederError::Other(format!("HTTP 요청 시간 초과 ({}ms): {}", timeout.as_millis(), url)))?
+}
+
+async fn get_inner(url: &Url, options: &ConnectOptions) -> Result<String> {
+    let host = url.host_str()
+        .ok_or_else(|| CryptoFeederError::Other(format!("URL에 호스트가 없습니다: {}", url)))?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: crypto-feeder\r\nAccept: application/json\r\nAccept-Encoding: identity\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+
+    let stream = connect_tcp(url, options).await?;
+    let raw = match url.scheme() {
+        "https" => {
+            let connector = native_tls::TlsConnector::new()
+                .map_err(|e| CryptoFeederError::Other(format!("TLS 초기화 실패: {}", e)))?;
+            let tls = tokio_native_tls::TlsConnector::from(connector).connect(host, stream).await
+                .map_err(|e| CryptoFeederError::Other(format!("TLS 핸드셰이크 실패 {}: {}", host, e)))?;
+            exchange(tls, &request).await?
+        }
+        "http" => exchange(stream, &request).await?,
+        other => return Err(CryptoFeederError::Other(format!("지원하지 않는 스킴: {}", other))),
+    };
+
+    let (status, body) = parse_response(&raw)?;
+    debug!("🌍 HTTP GET {} → {} ({} bytes)", url, status, body.len());
+    if status != 200 {
+        return Err(CryptoFeederError::Other(format!("HTTP {} 응답: {}", status, url)));
+    }
+    String::from_utf8(body).map_err(|_| CryptoFeederError::Other(format!("UTF-8이 아닌 응답 본문: {}", url)))
+}
+
+/// 요청 전송 후 연결이 닫힐 때까지 응답 수신
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, request: &str) -> Result<Vec<u8>> {
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buf[..n]);
+        if raw.len() > MAX_RESPONSE_BYTES {
+            return Err(CryptoFeederError::Other(format!("HTTP 응답이 너무 큽니다 (>{} bytes)", MAX_RESPONSE_BYTES)));
+        }
+    }
+    Ok(raw)
+}
+
+/// HTTP/1.1 응답 해석 → (상태 코드, 본문)
+pub fn parse_response(raw: &[u8]) -> Result<(u16, Vec<u8>)> {
+    let malformed = |what: &str| CryptoFeederError::Other(format!("잘못된 HTTP 응답: {}", what));
+    let header_end = raw.windows(4).position(|w| w == b"\r\n\r\n").ok_or_else(|| malformed("헤더 끝 없음"))?;
+    let head = std::str::from_utf8(&raw[..header_end]).map_err(|_| malformed("헤더 인코딩"))?;
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines.next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| malformed("상태 줄"))?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse::<usize>().map_err(|_| malformed("Content-Length"))?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        }
+    }
+
+    if chunked {
+        return Ok((status, decode_chunked(body).ok_or_else(|| malformed("chunked 본문"))?));
+    }
+    match content_length {
+        Some(len) if body.len() < len => Err(malformed("본문이 Content-Length보다 짧음")),
+        Some(len) => Ok((status, body[..len].to_vec())),
+        None => Ok((status, body.to_vec())),
+    }
+}
+
+/// chunked 전송 본문 복원 (trailer는 무시)
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size_field = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size_field.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_content_length_and_chunked() {
+        let (status, body) = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello, trailing").unwrap();
+        assert_eq!((status, body.as_slice()), (200, &b"hello"[..]));
+
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n3;ext=1\r\n:1}\r\n0\r\n\r\n";
+        let (_, body) = parse_response(chunked).unwrap();
+        assert_eq!(body, b"{\"a\":1}");
+
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort").is_err());
+        assert!(parse_response(b"garbage").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_from_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let body = if request.starts_with("GET /api/v3/exchangeInfo?x=1 HTTP/1.1") { "{\"ok\":true}" } else { "bad" };
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let url = Url::parse(&format!("http://{}/api/v3/exchangeInfo?x=1", addr)).unwrap();
+        let body = get(&url, &ConnectOptions::default(), Duration::from_secs(5)).await.unwrap();
+        assert_eq!(body, "{\"ok\":true}");
+    }
+}
@@ -0,0 +1,229 @@
+//! 거래소(venue)/종목(instrument) 숫자 ID 레지스트리
+//! v2 헤더의 venue_id/instrument_id 배정 (udp_packet.md 6장)
+//!
+//! - venue_id: 표준 거래소 표시명별 고정 ID, 목록에 없는 거래소는 1000번부터 순서대로 배정
+//! - instrument_id: symbol_config.ini의 전체 심볼을 정렬해 1부터 배정, 이후 처음 보는 심볼은 뒤에 추가
+//! - 이벤트 패킷의 `FEEDER`/`SYSTEM`은 0
+//! - ID 파일(`reference_id_file`)을 사용하면 심볼 추가/삭제 후에도 기존 ID 유지
+
+use crate::config::Config;
+use crate::errors::{CryptoFeederError, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+
+/// 표준 거래소 표시명별 고정 venue_id
+pub const KNOWN_VENUES: &[(&str, u16)] = &[
+    ("BinanceSpot", 1),
+    ("BinanceFutures", 2),
+    ("BybitSpot", 3),
+    ("BybitLinear", 4),
+    ("BybitInverse", 5),
+    ("OkxSpot", 6),
+    ("OkxSwap", 7),
+    ("OkxFutures", 8),
+    ("UpbitSpot", 9),
+    ("BithumbSpot", 10),
+    ("CoinbaseSpot", 11),
+];
+
+/// 고정 목록의 venue_id (목록에 없으면 0)
+pub fn known_venue_id(exchange: &str) -> u16 {
+    KNOWN_VENUES.iter().find(|(name, _)| *name == exchange).map(|(_, id)| *id).unwrap_or(0)
+}
+
+/// 목록에 없는 거래소의 첫 venue_id
+pub const DYNAMIC_VENUE_ID_START: u16 = 1000;
+
+/// 피더 자체 이벤트용 거래소/심볼 (ID 0)
+const SYSTEM_VENUE: &str = "FEEDER";
+const SYSTEM_SYMBOL: &str = "SYSTEM";
+
+struct IdTable<T> {
+    ids: HashMap<String, T>,
+    next: T,
+}
+
+pub struct InstrumentRegistry {
+    venues: RwLock<IdTable<u16>>,
+    instruments: RwLock<IdTable<u32>>,
+}
+
+impl InstrumentRegistry {
+    pub fn new() -> Self {
+        let venues = KNOWN_VENUES.iter().map(|(name, id)| (name.to_string(), *id)).collect();
+        Self {
+            venues: RwLock::new(IdTable { ids: venues, next: DYNAMIC_VENUE_ID_START }),
+            instruments: RwLock::new(IdTable { ids: HashMap::new(), next: 1 }),
+        }
+    }
+
+    /// symbol_config.ini의 심볼로 instrument_id를 미리 배정 (같은 설정이면 재시작해도 같은 ID)
+    pub fn from_config(config: &Config) -> Self {
+        let registry = Self::new();
+        registry.seed_from_config(config);
+        registry
+    }
+
+    /// ID 파일의 배정을 먼저 복원한 뒤 설정의 새 심볼을 뒤에 추가하고 파일을 갱신
+    pub fn from_config_with_id_file(config: &Config, path: &Path) -> Result<Self> {
+        let registry = Self::from_config_with_id_file_read_only(config, path)?;
+        registry.save_ids(path)?;
+        Ok(registry)
+    }
+
+    /// `from_config_with_id_file`과 같은 배정이지만 ID 파일을 갱신하지 않음 (캡처 필터 등 읽기 전용 도구용)
+    pub fn from_config_with_id_file_read_only(config: &Config, path: &Path) -> Result<Self> {
+        let registry = Self::new();
+        if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            let mut table = registry.instruments.write().unwrap();
+            for (line_no, line) in content.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let parsed = line.split_once('=')
+                    .and_then(|(id, symbol)| Some((id.trim().parse::<u32>().ok().filter(|id| *id > 0)?, symbol.trim())));
+                let Some((id, symbol)) = parsed else {
+                    return Err(CryptoFeederError::Other(format!("{}:{} ID 파일 형식 오류: '{}' (ID=심볼)", path.display(), line_no + 1, line)));
+                };
+                table.ids.insert(symbol.to_string(), id);
+                table.next = table.next.max(id + 1);
+            }
+        }
+        registry.seed_from_config(config);
+        Ok(registry)
+    }
+
+    /// 배정된 instrument_id를 `ID=심볼` 형식으로 저장
+    pub fn save_ids(&self, path: &Path) -> Result<()> {
+        let mut content = String::from("# instrument_id 배정 (자동 생성, 기존 ID는 변경하지 마세요)\n");
+        for (id, symbol) in self.instruments() {
+            content.push_str(&format!("{}={}\n", id, symbol));
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn seed_from_config(&self, config: &Config) {
+        if let Some(symbol_config) = &config.symbol_config {
+            let symbols: BTreeSet<&String> = symbol_config.exchanges.values()
+                .flat_map(|group| group.sessions.iter())
+                .flat_map(|session| session.symbols.iter())
+                .collect();
+            for symbol in symbols {
+                self.instrument_id(symbol);
+            }
+        }
+    }
+
+    /// 거래소 표시명의 venue_id (처음 보는 거래소는 새로 배정)
+    pub fn venue_id(&self, exchange: &str) -> u16 {
+        if exchange.is_empty() || exchange == SYSTEM_VENUE {
+            return 0;
+        }
+        if let Some(id) = self.venues.read().unwrap().ids.get(exchange) {
+            return *id;
+        }
+        let mut table = self.venues.write().unwrap();
+        if let Some(id) = table.ids.get(exchange) {
+            return *id;
+        }
+        let id = table.next;
+        table.next = table.next.saturating_add(1);
+        table.ids.insert(exchange.to_string(), id);
+        id
+    }
+
+    /// 표준 심볼(A^B)의 instrument_id (처음 보는 심볼은 새로 배정)
+    pub fn instrument_id(&self, symbol: &str) -> u32 {
+        if symbol.is_empty() || symbol == SYSTEM_SYMBOL {
+            return 0;
+        }
+        if let Some(id) = self.instruments.read().unwrap().ids.get(symbol) {
+            return *id;
+        }
+        let mut table = self.instruments.write().unwrap();
+        if let Some(id) = table.ids.get(symbol) {
+            return *id;
+        }
+        let id = table.next;
+        table.next += 1;
+        table.ids.insert(symbol.to_string(), id);
+        id
+    }
+
+    /// 배정된 (venue_id, 거래소) 목록 (ID 오름차순)
+    pub fn venues(&self) -> Vec<(u16, String)> {
+        let mut list: Vec<(u16, String)> = self.venues.read().unwrap().ids.iter()
+            .map(|(exchange, id)| (*id, exchange.clone()))
+            .collect();
+        list.sort();
+        list
+    }
+
+    /// 배정된 (instrument_id, 심볼) 목록 (ID 오름차순)
+    pub fn instruments(&self) -> Vec<(u32, String)> {
+        let mut list: Vec<(u32, String)> = self.instruments.read().unwrap().ids.iter()
+            .map(|(symbol, id)| (*id, symbol.clone()))
+            .collect();
+        list.sort();
+        list
+    }
+}
+
+impl Default for InstrumentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_venue_ids() {
+        let registry = InstrumentRegistry::new();
+        assert_eq!(registry.venue_id("BinanceFutures"), 2);
+        assert_eq!(registry.venue_id("FEEDER"), 0);
+        assert_eq!(registry.venue_id("KrakenSpot"), DYNAMIC_VENUE_ID_START);
+        assert_eq!(registry.venue_id("KrakenSpot"), DYNAMIC_VENUE_ID_START);
+        assert_eq!(registry.venue_id("GateSpot"), DYNAMIC_VENUE_ID_START + 1);
+    }
+
+    #[test]
+    fn test_instrument_ids_are_stable_and_appended() {
+        let registry = InstrumentRegistry::new();
+        assert_eq!(registry.instrument_id("ETH^USDT"), 1);
+        assert_eq!(registry.instrument_id("BTC^USDT"), 2);
+        assert_eq!(registry.instrument_id("ETH^USDT"), 1);
+        assert_eq!(registry.instrument_id("SYSTEM"), 0);
+        assert_eq!(registry.instruments(), vec![(1, "ETH^USDT".to_string()), (2, "BTC^USDT".to_string())]);
+    }
+
+    #[test]
+    fn test_id_file_keeps_previous_assignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ids").join("instrument_ids.txt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "# comment\n7=SOL^USDT\n3=BTC^USDT\n").unwrap();
+
+        let mut config = crate::config::Config::load().unwrap();
+        config.symbol_config = None;
+        let registry = InstrumentRegistry::from_config_with_id_file(&config, &path).unwrap();
+        assert_eq!(registry.instrument_id("BTC^USDT"), 3);
+        assert_eq!(registry.instrument_id("ETH^USDT"), 8);
+        registry.save_ids(&path).unwrap();
+
+        let reloaded = InstrumentRegistry::from_config_with_id_file(&config, &path).unwrap();
+        assert_eq!(reloaded.instruments(), registry.instruments());
+
+        std::fs::write(&path, "abc=BTC^USDT\n").unwrap();
+        assert!(InstrumentRegistry::from_config_with_id_file(&config, &path).is_err());
+    }
+}
@@ -0,0 +1,539 @@
+//! 원시 WebSocket 메시지 저널
+//! 수신 시각(ns), 거래소, 세션, UDP 포트, 원본 프레임을 길이 접두 바이너리로 세션별 파일에 기록 (일자/크기 기준 교체)
+//!
+//! 수신 루프는 lock-free 큐(ArrayQueue)에 레코드를 넣기만 하고, 파일 쓰기는 별도 쓰레드에서 처리합니다.
+//! 큐가 가득 차면 레코드를 버리고 드롭 카운터만 증가시켜 UDP 전송 지연을 만들지 않습니다.
+//!
+//! 파일 형식 (Little Endian):
+//! - 파일 헤더: `CFWSJ002` (8바이트)
+//! - 레코드: `record_len: u32` (이후 바이트 수) | `recv_timestamp_ns: u64` | `flags: u8` (bit0 = 바이너리 프레임, bit1 = 세션 메타데이터, bit2 = 오더북 REST 스냅샷)
+//!   | `port: u16` (세션 UDP 포트, 0 = 기본 포트) | `exchange_len: u8` | exchange | `session_len: u8` | session | payload
+//! - 세션 메타데이터 레코드: payload는 `SessionMeta` JSON (구독 심볼, 부가 스트림). 연결 직후와 실시간 구독 변경 시 기록되며,
+//!   리플레이는 이 값으로 실시간 경로와 같은 세션 필터를 적용
+//! - 오더북 REST 스냅샷 레코드: payload는 `StandardizedOrderBookUpdate` JSON (세션 "depth", 포트 0).
+//!   파생 데이터 호가창을 시작한 스냅샷이며, 리플레이는 같은 시점에 파생 데이터 계산기에 반영
+//! - `CFWSJ001` 파일은 `port` 필드가 없으며 읽을 때 0으로 간주
+
+use crate::config::{JournalConfig, SessionStreams};
+use crate::data_parser::StandardizedOrderBookUpdate;
+use crate::errors::{CryptoFeederError, Result};
+use crossbeam_queue::ArrayQueue;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 저널 파일 매직 헤더
+pub const JOURNAL_MAGIC: &[u8; 8] = b"CFWSJ002";
+/// 포트 필드가 없는 초기 형식
+pub const JOURNAL_MAGIC_V1: &[u8; 8] = b"CFWSJ001";
+/// 레코드 flags: 바이너리 프레임
+pub const JOURNAL_FLAG_BINARY: u8 = 0x01;
+/// 레코드 flags: 세션 메타데이터 (WebSocket 프레임이 아님)
+pub const JOURNAL_FLAG_SESSION_META: u8 = 0x02;
+/// 레코드 flags: 오더북 REST 스냅샷 (WebSocket 프레임이 아님)
+pub const JOURNAL_FLAG_DEPTH_SNAPSHOT: u8 = 0x04;
+/// 저널 파일 확장자
+pub const JOURNAL_EXTENSION: &str = "wsj";
+
+/// 저널 레코드 한 건 (WebSocket 프레임 하나)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalRecord {
+    pub recv_timestamp_ns: u64,
+    pub exchange: String,
+    pub session: String,
+    pub port: u16,
+    pub binary: bool,
+    /// 세션 메타데이터 레코드 (`data`는 `SessionMeta` JSON)
+    pub session_meta: bool,
+    /// 오더북 REST 스냅샷 레코드 (`data`는 `StandardizedOrderBookUpdate` JSON)
+    pub depth_snapshot: bool,
+    pub data: Vec<u8>,
+}
+
+/// 세션 메타데이터 (기록 시점의 구독 심볼과 부가 스트림)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMeta {
+    pub symbols: Vec<String>,
+    pub streams: SessionStreams,
+}
+
+impl JournalRecord {
+    /// 레코드를 길이 접두 바이너리로 인코딩하여 `out`에 추가
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        let exchange = truncate_255(self.exchange.as_bytes());
+        let session = truncate_255(self.session.as_bytes());
+        let record_len = 8 + 1 + 2 + 1 + exchange.len() + 1 + session.len() + self.data.len();
+        out.reserve(4 + record_len);
+        out.extend_from_slice(&(record_len as u32).to_le_bytes());
+        out.extend_from_slice(&self.recv_timestamp_ns.to_le_bytes());
+        let mut flags = 0;
+        if self.binary {
+            flags |= JOURNAL_FLAG_BINARY;
+        }
+        if self.session_meta {
+            flags |= JOURNAL_FLAG_SESSION_META;
+        }
+        if self.depth_snapshot {
+            flags |= JOURNAL_FLAG_DEPTH_SNAPSHOT;
+        }
+        out.push(flags);
+        out.extend_from_slice(&self.port.to_le_bytes());
+        out.push(exchange.len() as u8);
+        out.extend_from_slice(exchange);
+        out.push(session.len() as u8);
+        out.extend_from_slice(session);
+        out.extend_from_slice(&self.data);
+    }
+
+    /// 길이 접두를 제외한 레코드 본문 디코딩
+    pub fn decode_body(body: &[u8]) -> Result<Self> {
+        Self::decode_body_versioned(body, true)
+    }
+
+    fn decode_body_versioned(body: &[u8], has_port: bool) -> Result<Self> {
+        let err = || CryptoFeederError::SerializationError(format!("저널 레코드 손상 ({} bytes)", body.len()));
+        let fixed = if has_port { 12 } else { 10 };
+        if body.len() < fixed {
+            return Err(err());
+        }
+        let recv_timestamp_ns = u64::from_le_bytes(body[0..8].try_into().unwrap());
+        let (binary, session_meta) = (body[8] & JOURNAL_FLAG_BINARY != 0, body[8] & JOURNAL_FLAG_SESSION_META != 0);
+        let depth_snapshot = body[8] & JOURNAL_FLAG_DEPTH_SNAPSHOT != 0;
+        let port = if has_port { u16::from_le_bytes([body[9], body[10]]) } else { 0 };
+        let mut pos = fixed - 1;
+        let exchange_len = *body.get(pos).ok_or_else(err)? as usize;
+        pos += 1;
+        let exchange = body.get(pos..pos + exchange_len).ok_or_else(err)?;
+        pos += exchange_len;
+        let session_len = *body.get(pos).ok_or_else(err)? as usize;
+        pos += 1;
+        let session = body.get(pos..pos + session_len).ok_or_else(err)?;
+        pos += session_len;
+        Ok(Self {
+            recv_timestamp_ns,
+            exchange: String::from_utf8_lossy(exchange).into_owned(),
+            session: String::from_utf8_lossy(session).into_owned(),
+            port,
+            binary,
+            session_meta,
+            depth_snapshot,
+            data: body[pos..].to_vec(),
+        })
+    }
+
+    /// 세션 메타데이터 레코드의 내용 (프레임 레코드거나 손상되었으면 None)
+    pub fn meta(&self) -> Option<SessionMeta> {
+        if !self.session_meta {
+            return None;
+        }
+        serde_json::from_slice(&self.data).ok()
+    }
+
+    /// 오더북 REST 스냅샷 레코드의 내용 (다른 레코드거나 손상되었으면 None)
+    pub fn depth_snapshot(&self) -> Option<StandardizedOrderBookUpdate> {
+        if !self.depth_snapshot {
+            return None;
+        }
+        serde_json::from_slice(&self.data).ok()
+    }
+}
+
+fn truncate_255(bytes: &[u8]) -> &[u8] {
+    &bytes[..bytes.len().min(255)]
+}
+
+/// 현재 시각 (Unix epoch 나노초)
+pub fn now_nanos() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64
+}
+
+/// 저널 통계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JournalStats {
+    pub records_written: u64,
+    pub bytes_written: u64,
+    pub records_dropped: u64,
+}
+
+/// 세션별 원시 프레임 저널 (백그라운드 쓰레드가 파일 기록)
+pub struct FrameJournal {
+    queue: Arc<ArrayQueue<JournalRecord>>,
+    running: Arc<AtomicBool>,
+    dropped: AtomicU64,
+    written: Arc<AtomicU64>,
+    bytes: Arc<AtomicU64>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl FrameJournal {
+    /// 저널 쓰레드 시작
+    pub fn start(config: &JournalConfig) -> Result<Self> {
+        fs::create_dir_all(&config.dir).map_err(|e| CryptoFeederError::Other(
+            format!("저널 디렉터리 생성 실패 ({}): {}", config.dir, e)
+        ))?;
+
+        let queue = Arc::new(ArrayQueue::new(config.queue_capacity.max(1)));
+        let running = Arc::new(AtomicBool::new(true));
+        let written = Arc::new(AtomicU64::new(0));
+        let bytes = Arc::new(AtomicU64::new(0));
+
+        let mut writer = JournalWriter::new(PathBuf::from(&config.dir), config.max_file_bytes);
+        let worker_queue = queue.clone();
+        let worker_running = running.clone();
+        let worker_written = written.clone();
+        let worker_bytes = bytes.clone();
+        let worker = std::thread::Builder::new()
+            .name("ws-journal".to_string())
+            .spawn(move || {
+                let mut idle_rounds = 0u32;
+                loop {
+                    let mut wrote = false;
+                    while let Some(record) = worker_queue.pop() {
+                        match writer.write(&record) {
+                            Ok(n) => {
+                                worker_written.fetch_add(1, Ordering::Relaxed);
+                                worker_bytes.fetch_add(n as u64, Ordering::Relaxed);
+                            }
+                            Err(e) => error!("❌ 저널 기록 실패: {}", e),
+                        }
+                        wrote = true;
+                    }
+                    if wrote {
+                        idle_rounds = 0;
+                        continue;
+                    }
+                    if !worker_running.load(Ordering::Acquire) && worker_queue.is_empty() {
+                        break;
+                    }
+                    // 유휴 시 버퍼를 비워 장애 시 손실 구간 최소화
+                    if idle_rounds == 0 {
+                        writer.flush_all();
+                    }
+                    idle_rounds = idle_rounds.saturating_add(1);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                writer.flush_all();
+            })
+            .map_err(|e| CryptoFeederError::Other(format!("저널 쓰레드 생성 실패: {}", e)))?;
+
+        info!("📼 원시 메시지 저널 시작: dir={} max_file={}MB queue={}",
+              config.dir, config.max_file_bytes / (1024 * 1024), config.queue_capacity);
+
+        Ok(Self {
+            queue,
+            running,
+            dropped: AtomicU64::new(0),
+            written,
+            bytes,
+            worker: Mutex::new(Some(worker)),
+        })
+    }
+
+    /// 수신 프레임 기록 요청 (큐에 넣기만 하며 가득 차면 드롭)
+    pub fn record(&self, recv_timestamp_ns: u64, exchange: &str, session: &str, port: u16, binary: bool, data: &[u8]) {
+        self.push(JournalRecord {
+            recv_timestamp_ns,
+            exchange: exchange.to_string(),
+            session: session.to_string(),
+            port,
+            binary,
+            session_meta: false,
+            depth_snapshot: false,
+            data: data.to_vec(),
+        });
+    }
+
+    /// 세션 메타데이터 기록 요청 (연결 직후/구독 변경 시)
+    pub fn record_session_meta(&self, recv_timestamp_ns: u64, exchange: &str, session: &str, port: u16, meta: &SessionMeta) {
+        self.push(JournalRecord {
+            recv_timestamp_ns,
+            exchange: exchange.to_string(),
+            session: session.to_string(),
+            port,
+            binary: false,
+            session_meta: true,
+            depth_snapshot: false,
+            data: serde_json::to_vec(meta).unwrap_or_default(),
+        });
+    }
+
+    /// 파생 데이터 호가창에 반영한 오더북 REST 스냅샷 기록 요청
+    pub fn record_depth_snapshot(&self, recv_timestamp_ns: u64, exchange: &str, snapshot: &StandardizedOrderBookUpdate) {
+        self.push(JournalRecord {
+            recv_timestamp_ns,
+            exchange: exchange.to_string(),
+            session: "depth".to_string(),
+            port: 0,
+            binary: false,
+            session_meta: false,
+            depth_snapshot: true,
+            data: serde_json::to_vec(snapshot).unwrap_or_default(),
+        });
+    }
+
+    fn push(&self, record: JournalRecord) {
+        if self.queue.push(record).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!("⚠️ 저널 큐 포화로 레코드 드롭 (누적 {}건)", dropped);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> JournalStats {
+        JournalStats {
+            records_written: self.written.load(Ordering::Relaxed),
+            bytes_written: self.bytes.load(Ordering::Relaxed),
+            records_dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 남은 레코드를 모두 기록하고 쓰레드 종료
+    pub fn shutdown(&self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(worker) = self.worker.lock().unwrap().take() {
+            let _ = worker.join();
+            let stats = self.stats();
+            info!("📼 저널 종료: 기록 {}건 ({} bytes), 드롭 {}건",
+                  stats.records_written, stats.bytes_written, stats.records_dropped);
+        }
+    }
+}
+
+impl Drop for FrameJournal {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// 세션별 현재 저널 파일
+struct SessionFile {
+    date: String,
+    seq: u32,
+    bytes: u64,
+    out: BufWriter<File>,
+}
+
+/// 세션별 파일 관리 및 일자/크기 기준 교체
+struct JournalWriter {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    files: HashMap<(String, String), SessionFile>,
+    buf: Vec<u8>,
+}
+
+impl JournalWriter {
+    fn new(dir: PathBuf, max_file_bytes: u64) -> Self {
+        Self { dir, max_file_bytes: max_file_bytes.max(1024), files: HashMap::new(), buf: Vec::with_capacity(64 * 1024) }
+    }
+
+    fn write(&mut self, record: &JournalRecord) -> Result<usize> {
+        self.buf.clear();
+        record.encode(&mut self.buf);
+        let date = chrono::DateTime::from_timestamp_nanos(record.recv_timestamp_ns as i64).format("%Y%m%d").to_string();
+        let key = (record.exchange.clone(), record.session.clone());
+
+        let rotate = match self.files.get(&key) {
+            Some(f) => f.date != date || f.bytes + self.buf.len() as u64 > self.max_file_bytes,
+            None => true,
+        };
+        if rotate {
+            let next_seq = self.files.get(&key).filter(|f| f.date == date).map(|f| f.seq + 1).unwrap_or(0);
+            if let Some(mut old) = self.files.remove(&key) {
+                let _ = old.out.flush();
+            }
+            let file = self.open_file(&key.0, &key.1, &date, next_seq)?;
+            self.files.insert(key.clone(), file);
+        }
+
+        let file = self.files.get_mut(&key).unwrap();
+        file.out.write_all(&self.buf).map_err(|e| CryptoFeederError::Other(format!("저널 쓰기 실패: {}", e)))?;
+        file.bytes += self.buf.len() as u64;
+        Ok(self.buf.len())
+    }
+
+    /// 사용되지 않은 다음 순번으로 새 파일 생성 (재시작 시 기존 파일 보존)
+    fn open_file(&self, exchange: &str, session: &str, date: &str, mut seq: u32) -> Result<SessionFile> {
+        let day_dir = self.dir.join(date);
+        fs::create_dir_all(&day_dir).map_err(|e| CryptoFeederError::Other(format!("저널 디렉터리 생성 실패: {}", e)))?;
+        let session = session.replace(['/', '\\'], "_");
+        loop {
+            let path = day_dir.join(format!("{}_s{}_{}_{:03}.{}", exchange, session, date, seq, JOURNAL_EXTENSION));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    let mut out = BufWriter::with_capacity(256 * 1024, file);
+                    out.write_all(JOURNAL_MAGIC).map_err(|e| CryptoFeederError::Other(format!("저널 헤더 쓰기 실패: {}", e)))?;
+                    info!("📼 저널 파일 생성: {}", path.display());
+                    return Ok(SessionFile { date: date.to_string(), seq, bytes: JOURNAL_MAGIC.len() as u64, out });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => seq += 1,
+                Err(e) => return Err(CryptoFeederError::Other(format!("저널 파일 생성 실패 ({}): {}", path.display(), e))),
+            }
+        }
+    }
+
+    fn flush_all(&mut self) {
+        for file in self.files.values_mut() {
+            if let Err(e) = file.out.flush() {
+                error!("❌ 저널 flush 실패: {}", e);
+            }
+        }
+    }
+}
+
+/// 저널 파일 순차 읽기
+pub struct JournalReader<R: Read> {
+    input: R,
+    has_port: bool,
+}
+
+impl JournalReader<BufReader<File>> {
+    /// 저널 파일 열기 (매직 헤더 확인)
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|e| CryptoFeederError::Other(format!("저널 파일 열기 실패 ({}): {}", path.display(), e)))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> JournalReader<R> {
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic).map_err(|e| CryptoFeederError::SerializationError(format!("저널 헤더 읽기 실패: {}", e)))?;
+        let has_port = match &magic {
+            m if m == JOURNAL_MAGIC => true,
+            m if m == JOURNAL_MAGIC_V1 => false,
+            _ => return Err(CryptoFeederError::SerializationError("저널 파일이 아닙니다 (매직 불일치)".to_string())),
+        };
+        Ok(Self { input, has_port })
+    }
+}
+
+impl<R: Read> Iterator for JournalReader<R> {
+    type Item = Result<JournalRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut len = [0u8; 4];
+        match self.input.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(CryptoFeederError::SerializationError(format!("저널 읽기 실패: {}", e)))),
+        }
+        let mut body = vec![0u8; u32::from_le_bytes(len) as usize];
+        if let Err(e) = self.input.read_exact(&mut body) {
+            // 비정상 종료로 잘린 마지막 레코드
+            return Some(Err(CryptoFeederError::SerializationError(format!("저널 레코드가 잘렸습니다: {}", e))));
+        }
+        Some(JournalRecord::decode_body_versioned(&body, self.has_port))
+    }
+}
+
+/// 디렉터리(하위 포함)의 저널 파일 목록 (경로순 정렬)
+pub fn list_journal_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(d) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&d) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().map(|e| e == JOURNAL_EXTENSION).unwrap_or(false) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ts: u64, session: &str, data: &[u8]) -> JournalRecord {
+        JournalRecord { recv_timestamp_ns: ts, exchange: "BinanceSpot".into(), session: session.into(), port: 55555, binary: false, session_meta: false, depth_snapshot: false, data: data.to_vec() }
+    }
+
+    #[test]
+    fn test_record_encode_decode_roundtrip() {
+        let original = JournalRecord { binary: true, ..record(1_700_000_000_123_456_789, "0.1", b"\x00\x01raw") };
+        let mut buf = Vec::new();
+        original.encode(&mut buf);
+        let len = u32::from_le_bytes(buf[0..4].try_into().unwrap()) as usize;
+        assert_eq!(len, buf.len() - 4);
+        assert_eq!(JournalRecord::decode_body(&buf[4..]).unwrap(), original);
+        assert!(JournalRecord::decode_body(&buf[4..12]).is_err());
+
+        let snapshot = JournalRecord { depth_snapshot: true, ..record(1, "depth", br#"{"symbol":"BTC^USDT"}"#) };
+        buf.clear();
+        snapshot.encode(&mut buf);
+        let decoded = JournalRecord::decode_body(&buf[4..]).unwrap();
+        assert!(decoded.depth_snapshot && !decoded.session_meta && decoded.meta().is_none());
+    }
+
+    #[test]
+    fn test_reads_v1_journal_without_port() {
+        let mut file = JOURNAL_MAGIC_V1.to_vec();
+        let body = [&7u64.to_le_bytes()[..], &[0, 3], b"Okx", &[1], b"0", b"{}"].concat();
+        file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        file.extend_from_slice(&body);
+        let records: Vec<JournalRecord> = JournalReader::new(&file[..]).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(records, vec![JournalRecord { recv_timestamp_ns: 7, exchange: "Okx".into(), session: "0".into(), port: 0, binary: false, session_meta: false, depth_snapshot: false, data: b"{}".to_vec() }]);
+    }
+
+    #[test]
+    fn test_journal_writes_per_session_files_and_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = JournalConfig { enabled: true, dir: dir.path().to_string_lossy().into_owned(), max_file_bytes: 1024 * 1024, queue_capacity: 1024 };
+        let journal = FrameJournal::start(&config).unwrap();
+        // 2023-11-14 UTC
+        let ts = 1_700_000_000_000_000_000u64;
+        journal.record(ts, "BinanceSpot", "0", 55555, false, br#"{"e":"trade"}"#);
+        journal.record(ts + 1, "BinanceSpot", "1", 55556, false, br#"{"e":"depthUpdate"}"#);
+        journal.record(ts + 2, "BinanceSpot", "0", 55555, true, b"\x01\x02");
+        let meta = SessionMeta { symbols: vec!["BTC^USDT".into()], streams: SessionStreams { ticker: true, ..Default::default() } };
+        journal.record_session_meta(ts + 3, "BinanceSpot", "0", 55555, &meta);
+        journal.shutdown();
+        assert_eq!(journal.stats().records_written, 4);
+
+        let files = list_journal_files(dir.path());
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("20231114/BinanceSpot_s0_20231114_000.wsj"));
+        let records: Vec<JournalRecord> = JournalReader::open(&files[0]).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].data, br#"{"e":"trade"}"#);
+        assert!(records[1].binary);
+        assert_eq!(records[1].port, 55555);
+        assert_eq!((records[0].meta(), records[2].meta()), (None, Some(meta)));
+    }
+
+    #[test]
+    fn test_journal_rotates_by_size_and_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = JournalWriter::new(dir.path().to_path_buf(), 1024);
+        let ts = 1_700_000_000_000_000_000u64;
+        for i in 0..4 {
+            writer.write(&record(ts + i, "0", &[b'x'; 400])).unwrap();
+        }
+        // 다음 날
+        writer.write(&record(ts + 86_400_000_000_000, "0", b"next day")).unwrap();
+        writer.flush_all();
+
+        let files = list_journal_files(dir.path());
+        let names: Vec<String> = files.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, vec![
+            "BinanceSpot_s0_20231114_000.wsj",
+            "BinanceSpot_s0_20231114_001.wsj",
+            "BinanceSpot_s0_20231115_000.wsj",
+        ]);
+        let total: usize = files.iter().map(|f| JournalReader::open(f).unwrap().count()).sum();
+        assert_eq!(total, 5);
+    }
+}
@@ -0,0 +1,196 @@
+//! 김치 프리미엄 계산기
+//! KRW 거래소(`X^KRW`) 중간 가격과 해외 거래소(`X^USDT`) 중간 가격을 KRW 환율로 비교
+//!
+//! - premium = KRW 중간 가격 / (해외 중간 가격 × 환율) − 1
+//! - 환율은 `kimchi_fx_rate`의 고정값 또는 시장(기본 UpbitSpot `USDT^KRW`) 중간 가격
+//! - 해외 가격은 `kimchi_foreign_exchanges` 순서대로 첫 번째 유효한 거래소 사용
+//! - `kimchi_stale_ms`보다 오래 갱신되지 않은 호가(환율 시장 포함)는 제외
+
+use crate::config::{FxRateSource, KimchiPremiumConfig};
+use crate::data_parser::{ParsedData, StandardizedOrderBookUpdate};
+use crate::venue_book::VenueBook;
+use std::collections::BTreeMap;
+
+/// KRW quote 통화
+const KRW: &str = "KRW";
+
+/// KRW 거래소 심볼 하나의 김치 프리미엄
+#[derive(Debug, Clone, PartialEq)]
+pub struct PremiumQuote {
+    /// KRW 거래소 (패킷 헤더 거래소)
+    pub krw_exchange: String,
+    /// KRW 심볼 (`X^KRW`)
+    pub symbol: String,
+    /// 비교에 사용한 해외 거래소
+    pub foreign_exchange: String,
+    pub krw_price: f64,
+    /// 해외 거래소 가격 (foreign_quote 기준)
+    pub foreign_price: f64,
+    /// 1 foreign_quote = fx_rate KRW
+    pub fx_rate: f64,
+    /// 환율이 시장 가격에서 온 값인지 (false면 고정 환율)
+    pub fx_from_market: bool,
+    /// 비율 (0.03 = 3% 프리미엄, 음수면 역프리미엄)
+    pub premium: f64,
+    /// 계산 시각 (나노초)
+    pub timestamp: u64,
+}
+
+/// 김치 프리미엄 계산기
+///
+/// `ingest`로 KRW/해외/환율 시장 오더북을 반영하고, `flush` 때 유효한 모든 KRW 심볼의 프리미엄을 반환합니다.
+pub struct KimchiPremium {
+    config: KimchiPremiumConfig,
+    stale_ns: u64,
+    /// (거래소, 심볼)별 호가창
+    books: BTreeMap<(String, String), VenueBook>,
+}
+
+impl KimchiPremium {
+    pub fn new(config: &KimchiPremiumConfig) -> Self {
+        Self {
+            config: config.clone(),
+            stale_ns: config.stale_ms * 1_000_000,
+            books: BTreeMap::new(),
+        }
+    }
+
+    /// 파싱된 데이터의 오더북 반영 (`now_ns`는 수신 시각)
+    pub fn ingest(&mut self, data: &ParsedData, now_ns: u64) {
+        match data {
+            ParsedData::OrderBook(update) => self.on_order_book(update, now_ns),
+            ParsedData::Multi(items) => {
+                for item in items {
+                    self.ingest(item, now_ns);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_order_book(&mut self, update: &StandardizedOrderBookUpdate, now_ns: u64) {
+        if !self.is_tracked(&update.exchange, &update.symbol) {
+            return;
+        }
+        self.books.entry((update.exchange.clone(), update.symbol.clone())).or_default().apply(update, now_ns);
+    }
+
+    /// 스냅샷을 기다리는 호가창인지 (추적하지 않는 (거래소, 심볼)이면 false)
+    pub fn awaits_snapshot(&self, exchange: &str, symbol: &str) -> bool {
+        self.books.get(&(exchange.to_string(), symbol.to_string())).is_some_and(|book| !book.is_synced())
+    }
+
+    /// 계산에 필요한 (거래소, 심볼)인지
+    fn is_tracked(&self, exchange: &str, symbol: &str) -> bool {
+        if let FxRateSource::Market { exchange: fx_exchange, symbol: fx_symbol } = &self.config.fx_rate {
+            if fx_exchange == exchange && fx_symbol == symbol {
+                return true;
+            }
+        }
+        let quote = symbol.split_once('^').map(|(_, q)| q);
+        let is = |list: &[String]| list.iter().any(|e| e == exchange);
+        (quote == Some(KRW) && is(&self.config.krw_exchanges))
+            || (quote == Some(self.config.foreign_quote.as_str()) && is(&self.config.foreign_exchanges))
+    }
+
+    /// 유효한 호가창의 중간 가격
+    fn fresh_mid(&self, exchange: &str, symbol: &str, now_ns: u64) -> Option<f64> {
+        let book = self.books.get(&(exchange.to_string(), symbol.to_string()))?;
+        if book.is_stale(now_ns, self.stale_ns) {
+            return None;
+        }
+        book.mid()
+    }
+
+    /// 현재 환율과 시장 출처 여부 (시장 호가가 없거나 오래되면 None)
+    pub fn fx_rate(&self, now_ns: u64) -> Option<(f64, bool)> {
+        match &self.config.fx_rate {
+            FxRateSource::Fixed(rate) => Some((*rate, false)),
+            FxRateSource::Market { exchange, symbol } => self.fresh_mid(exchange, symbol, now_ns).map(|mid| (mid, true)),
+        }
+    }
+
+    /// 유효한 모든 KRW 심볼의 김치 프리미엄 (환율이 없으면 빈 목록)
+    pub fn flush(&self, now_ns: u64) -> Vec<PremiumQuote> {
+        let Some((fx_rate, fx_from_market)) = self.fx_rate(now_ns) else { return Vec::new() };
+        let foreign_quote = &self.config.foreign_quote;
+        self.books.keys()
+            .filter(|(exchange, _)| self.config.krw_exchanges.contains(exchange))
+            .filter_map(|(krw_exchange, symbol)| {
+                let (base, KRW) = symbol.split_once('^')? else { return None };
+                // 환율 시장 자체(USDT^KRW)는 비교 대상이 아님
+                if base == foreign_quote {
+                    return None;
+                }
+                let krw_price = self.fresh_mid(krw_exchange, symbol, now_ns)?;
+                let foreign_symbol = format!("{}^{}", base, foreign_quote);
+                let (foreign_exchange, foreign_price) = self.config.foreign_exchanges.iter()
+                    .find_map(|e| self.fresh_mid(e, &foreign_symbol, now_ns).map(|mid| (e.clone(), mid)))?;
+                Some(PremiumQuote {
+                    krw_exchange: krw_exchange.clone(),
+                    symbol: symbol.clone(),
+                    foreign_exchange,
+                    krw_price,
+                    foreign_price,
+                    fx_rate,
+                    fx_from_market,
+                    premium: krw_price / (foreign_price * fx_rate) - 1.0,
+                    timestamp: now_ns,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venue_book::test_books::snapshot;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_premium_with_market_fx() {
+        let mut kimchi = KimchiPremium::new(&KimchiPremiumConfig::default());
+        kimchi.ingest(&snapshot("BinanceSpot", "BTC^USDT", &[(99.0, 1.0)], &[(101.0, 1.0)]), 0);
+        kimchi.ingest(&snapshot("UpbitSpot", "BTC^KRW", &[(143_000.0, 1.0)], &[(145_000.0, 1.0)]), 0);
+        // 환율 시장이 없으면 계산하지 않음
+        assert!(kimchi.flush(0).is_empty());
+
+        kimchi.ingest(&ParsedData::Multi(vec![snapshot("UpbitSpot", "USDT^KRW", &[(1395.0, 1.0)], &[(1405.0, 1.0)])]), 0);
+        let quotes = kimchi.flush(0);
+        assert_eq!(quotes.len(), 1);
+        let q = &quotes[0];
+        assert_eq!((q.krw_exchange.as_str(), q.foreign_exchange.as_str()), ("UpbitSpot", "BinanceSpot"));
+        assert_eq!((q.krw_price, q.foreign_price, q.fx_rate, q.fx_from_market), (144_000.0, 100.0, 1400.0, true));
+        assert!((q.premium - (144_000.0 / 140_000.0 - 1.0)).abs() < 1e-12);
+
+        // 환율 시장이 오래되면 제외
+        let stale = KimchiPremiumConfig::default().stale_ms * MS;
+        kimchi.ingest(&snapshot("BinanceSpot", "BTC^USDT", &[(99.0, 1.0)], &[(101.0, 1.0)]), stale + MS);
+        kimchi.ingest(&snapshot("UpbitSpot", "BTC^KRW", &[(143_000.0, 1.0)], &[(145_000.0, 1.0)]), stale + MS);
+        assert!(kimchi.flush(stale + MS).is_empty());
+    }
+
+    #[test]
+    fn test_fixed_fx_and_foreign_fallback() {
+        let config = KimchiPremiumConfig {
+            fx_rate: FxRateSource::Fixed(1000.0),
+            foreign_exchanges: vec!["OkxSpot".into(), "BinanceSpot".into()],
+            ..KimchiPremiumConfig::default()
+        };
+        let mut kimchi = KimchiPremium::new(&config);
+        kimchi.ingest(&snapshot("BinanceSpot", "ETH^USDT", &[(9.9, 1.0)], &[(10.1, 1.0)]), 0);
+        kimchi.ingest(&snapshot("BithumbSpot", "ETH^KRW", &[(9_400.0, 1.0)], &[(9_600.0, 1.0)]), 0);
+        // 추적 대상이 아닌 거래소는 무시
+        kimchi.ingest(&snapshot("BybitSpot", "ETH^USDT", &[(1.0, 1.0)], &[(1.1, 1.0)]), 0);
+
+        let q = &kimchi.flush(0)[0];
+        assert_eq!((q.foreign_exchange.as_str(), q.fx_from_market), ("BinanceSpot", false));
+        assert!((q.premium + 0.05).abs() < 1e-12);
+
+        // 앞 순서 해외 거래소가 유효하면 우선
+        kimchi.ingest(&snapshot("OkxSpot", "ETH^USDT", &[(9.4, 1.0)], &[(9.6, 1.0)]), 0);
+        assert_eq!(kimchi.flush(0)[0].foreign_exchange, "OkxSpot");
+    }
+}
@@ -6,4 +6,25 @@ pub mod udp_broadcaster;
 pub mod protocol;
 pub mod errors;
 pub mod events;
+pub mod session_planner;
+pub mod config_reload;
+pub mod ws_connect;
+pub mod journal;
+pub mod replay;
+pub mod instruments;
+pub mod consumer;
+pub mod capture;
+pub mod feed_export;
 
+pub mod http_client;
+pub mod reference_data;
+pub mod bar_aggregator;
+pub mod ticker_conflator;
+pub mod open_interest_poller;
+pub mod consolidated_bbo;
+pub mod venue_book;
+pub mod kimchi_premium;
+pub mod composite_index;
+pub mod trade_flow;
+pub mod depth_snapshot;
+pub mod feed_stage;
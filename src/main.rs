@@ -5,20 +5,17 @@ use std::time::Duration;
 use tokio::signal;
 use tokio::runtime::Builder as TokioRuntimeBuilder;
 
-mod config;
-mod connection_manager;
-mod data_parser;
-mod packet_builder;
-mod udp_broadcaster;
-mod protocol;
-mod errors;
-mod events;
-
-use config::Config;
-use connection_manager::ConnectionManager;
-use data_parser::DataParser;
-use packet_builder::PacketBuilder;
-use udp_broadcaster::UdpMulticaster;
+use crypto_feeder::config::Config;
+use crypto_feeder::connection_manager::ConnectionManager;
+use crypto_feeder::data_parser::DataParser;
+use crypto_feeder::instruments::InstrumentRegistry;
+use crypto_feeder::journal::FrameJournal;
+use crypto_feeder::packet_builder::PacketBuilder;
+use crypto_feeder::reference_data::ReferenceDataPublisher;
+use crypto_feeder::open_interest_poller::OpenInterestPoller;
+use crypto_feeder::replay::{ReplayOptions, ReplaySpeed};
+use crypto_feeder::events::{SHUTDOWN_REASON_MANAGER_EXIT, SHUTDOWN_REASON_SIGNAL, SHUTDOWN_REASON_TIMER};
+use crypto_feeder::udp_broadcaster::UdpMulticaster;
 
 fn main() -> Result<()> {
     // 설정 로드 (런타임 쓰레드 수를 적용하기 위함)
@@ -57,6 +54,11 @@ fn main() -> Result<()> {
 }
 
 async fn async_main(config: Arc<Config>) -> Result<()> {
+    // 리플레이 모드: WebSocket 대신 기록된 저널로 파이프라인 구동
+    if let Some(options) = parse_replay_args(&std::env::args().skip(1).collect::<Vec<_>>())? {
+        return run_replay_mode(config, options).await;
+    }
+
     // 명령행 인수에서 타이머 설정 읽기
     let timeout_seconds = parse_timeout_arg();
 
@@ -107,19 +109,62 @@ async fn async_main(config: Arc<Config>) -> Result<()> {
         info!("⚠️ endpoint.ini 파일이 없음. 기본 엔드포인트 사용");
     }
 
-    // 컴포넌트 초기화
-    let udp_broadcaster = Arc::new(UdpMulticaster::new(&config.udp)?);
-    let packet_builder = Arc::new(PacketBuilder::new());
+    // 컴포넌트 초기화 (ID 파일이 있으면 기존 instrument_id 유지)
+    let instruments = Arc::new(match &config.reference.id_file {
+        Some(path) => InstrumentRegistry::from_config_with_id_file(&config, std::path::Path::new(path))?,
+        None => InstrumentRegistry::from_config(&config),
+    });
+    let udp_broadcaster = Arc::new(UdpMulticaster::new(&config.udp)?.with_protocol(config.protocol.clone()));
+    let packet_builder = Arc::new(PacketBuilder::new()
+        .with_instruments(instruments.clone())
+        .with_v2_only(config.protocol.all_v2())
+        .with_scales(config.instrument_scales.clone())
+        .with_trade_ids(config.protocol.trade_ids)
+        .with_funding_info(config.protocol.funding_info)
+        .with_liquidation_detail(config.protocol.liquidation_detail)
+        .with_max_packet_size(config.udp.max_datagram_size()));
     let data_parser = Arc::new(DataParser::new_with_config(Some(&config)));
-    let connection_manager = ConnectionManager::new(
+    let mut connection_manager = ConnectionManager::new(
         config.clone(),
         data_parser.clone(),
         packet_builder.clone(),
         udp_broadcaster.clone(),
     );
 
+    // 원시 메시지 저널 (선택)
+    let journal = if config.journal.enabled {
+        let journal = Arc::new(FrameJournal::start(&config.journal)?);
+        connection_manager = connection_manager.with_journal(journal.clone());
+        Some(journal)
+    } else {
+        None
+    };
+
     info!("🔧 모든 컴포넌트 초기화 완료");
 
+    // 설정 핫 리로드 (SIGHUP + 선택적 파일 변경 감지)
+    spawn_config_reloader(connection_manager.clone(), config.config_watch_interval_secs);
+
+    // 실행 중 세션 명령 (심볼 추가/제거/이동, control_port가 0이면 끔)
+    if config.control_port != 0 {
+        let socket = tokio::net::UdpSocket::bind(("127.0.0.1", config.control_port)).await?;
+        info!("🎛️ 세션 명령 수신 대기: 127.0.0.1:{}", config.control_port);
+        let manager_for_commands = connection_manager.clone();
+        tokio::spawn(async move { manager_for_commands.serve_commands(socket).await });
+    }
+
+    // 종목 참조 데이터 발행 (전용 포트, 핫 리로드된 심볼 반영)
+    if config.reference.enabled {
+        let publisher = ReferenceDataPublisher::new(&config, instruments.clone(), packet_builder.clone(), udp_broadcaster.clone()).await;
+        let manager_for_reference = connection_manager.clone();
+        publisher.spawn(move || manager_for_reference.config());
+    }
+
+    // REST 전용 거래소 미결제약정 조회 (`open_interest=on` 세션 포트로 전송)
+    let manager_for_open_interest = connection_manager.clone();
+    OpenInterestPoller::new(&config, packet_builder.clone(), udp_broadcaster.clone())
+        .spawn(move || manager_for_open_interest.config());
+
     // (중복 제거) 메트릭스 태스크는 아래 블록 하나만 유지
 
     // 메트릭스 태스크 (UDP 전송량/pps/CPU)
@@ -157,7 +202,10 @@ async fn async_main(config: Arc<Config>) -> Result<()> {
     }
 
     // 연결 시작
-    let connection_handle = tokio::spawn(async move {
+    let shutdown = connection_manager.shutdown_token();
+    let manager_for_shutdown = connection_manager.clone();
+    let started_at = std::time::Instant::now();
+    let mut connection_handle = tokio::spawn(async move {
         if let Err(e) = connection_manager.run().await {
             error!("연결 관리자 치명적 오류: {}", e);
             std::process::exit(1); // 연결 실패 시 프로그램 종료
@@ -166,38 +214,203 @@ async fn async_main(config: Arc<Config>) -> Result<()> {
 
     info!("🌐 WebSocket 연결 시작됨");
 
-    // 종료 신호 대기 또는 연결 관리자 종료 대기 (선택적 타이머)
-    if let Some(timeout) = timeout_seconds {
-        tokio::select! {
-            _ = signal::ctrl_c() => {
-                info!("🛑 종료 신호 수신, 프로그램을 정리하는 중...");
+    // 종료 신호(SIGINT/SIGTERM) 대기 또는 연결 관리자 종료 대기 (선택적 타이머)
+    let timer = async {
+        match timeout_seconds {
+            Some(timeout) => tokio::time::sleep(Duration::from_secs(timeout)).await,
+            None => std::future::pending::<()>().await,
+        }
+    };
+    let mut manager_finished = false;
+    let shutdown_reason;
+    tokio::select! {
+        signal_name = shutdown_signal() => {
+            info!("🛑 종료 신호({}) 수신, 프로그램을 정리하는 중...", signal_name);
+            shutdown_reason = SHUTDOWN_REASON_SIGNAL;
+        }
+        _ = timer => {
+            info!("⏰ {}초 타이머 만료, 프로그램 자동 종료", timeout_seconds.unwrap_or_default());
+            shutdown_reason = SHUTDOWN_REASON_TIMER;
+        }
+        result = &mut connection_handle => {
+            manager_finished = true;
+            shutdown_reason = SHUTDOWN_REASON_MANAGER_EXIT;
+            match result {
+                Ok(_) => info!("🔚 연결 관리자가 정상 종료됨"),
+                Err(e) => error!("❌ 연결 관리자 오류: {}", e),
             }
-            _ = tokio::time::sleep(Duration::from_secs(timeout)) => {
-                info!("⏰ {}초 타이머 만료, 프로그램 자동 종료", timeout);
+        }
+    }
+
+    // 세션 정리: Close 프레임 전송, 수신된 메시지 UDP 전송, DISCONNECTED 이벤트
+    shutdown.cancel();
+    if !manager_finished {
+        match tokio::time::timeout(Duration::from_secs(SHUTDOWN_TIMEOUT_SECS), &mut connection_handle).await {
+            Ok(_) => info!("🔌 모든 세션 정리 완료"),
+            Err(_) => {
+                warn!("⚠️ {}초 내에 세션 정리가 끝나지 않아 강제 종료", SHUTDOWN_TIMEOUT_SECS);
+                connection_handle.abort();
             }
-            result = connection_handle => {
-                match result {
-                    Ok(_) => info!("🔚 연결 관리자가 정상 종료됨"),
-                    Err(e) => error!("❌ 연결 관리자 오류: {}", e),
-                }
+        }
+    }
+
+    // 각 포트로 종료 이벤트(SystemShutdown) 전송
+    let uptime = started_at.elapsed();
+    if let Err(e) = manager_for_shutdown.send_shutdown_events(uptime.as_secs(), shutdown_reason).await {
+        warn!("⚠️ 종료 이벤트 전송 실패: {}", e);
+    }
+
+    // 최종 통계
+    let stats = udp_broadcaster.get_stats();
+    let secs = uptime.as_secs_f64().max(1.0);
+    info!(
+        "📊 최종 통계: uptime={:.1}s packets={} bytes={} avg_pps={:.1} avg_kbps={:.1} avg_packet={:.1}B",
+        uptime.as_secs_f64(), stats.packets_sent, stats.bytes_sent,
+        stats.packets_sent as f64 / secs, (stats.bytes_sent as f64 / secs) / 1024.0, stats.average_packet_size()
+    );
+
+    // 저널에 남은 레코드 기록
+    if let Some(journal) = &journal {
+        journal.shutdown();
+    }
+
+    info!("✅ CryptoFeeder 종료 완료");
+    Ok(())
+}
+
+/// 저널 리플레이 실행 후 종료
+async fn run_replay_mode(config: Arc<Config>, options: ReplayOptions) -> Result<()> {
+    let instruments = Arc::new(InstrumentRegistry::from_config(&config));
+    let udp_broadcaster = UdpMulticaster::new(&config.udp)?
+        .with_protocol(config.protocol.clone())
+        .with_replay_flag();
+    let packet_builder = Arc::new(PacketBuilder::new()
+        .with_instruments(instruments)
+        .with_v2_only(config.protocol.all_v2())
+        .with_scales(config.instrument_scales.clone())
+        .with_trade_ids(config.protocol.trade_ids)
+        .with_funding_info(config.protocol.funding_info)
+        .with_liquidation_detail(config.protocol.liquidation_detail)
+        .with_max_packet_size(config.udp.max_datagram_size()));
+    let data_parser = Arc::new(DataParser::new_with_config(Some(&config)));
+
+    let stats = crypto_feeder::replay::run_replay(options, Arc::clone(&config), data_parser, packet_builder, &udp_broadcaster).await?;
+    let udp_stats = udp_broadcaster.get_stats();
+    info!("📊 리플레이 통계: records={} packets={} bytes={} parse_skipped={} send_errors={}",
+          stats.records, udp_stats.packets_sent, udp_stats.bytes_sent, stats.parse_errors, stats.send_errors);
+    Ok(())
+}
+
+/// 리플레이 인수 파싱 (`--replay`가 없으면 None)
+/// 사용법: crypto-feeder --replay <파일|디렉터리>... [--speed max|realtime|<N>x] [--deterministic]
+fn parse_replay_args(args: &[String]) -> Result<Option<ReplayOptions>> {
+    let Some(start) = args.iter().position(|a| a == "--replay") else {
+        return Ok(None);
+    };
+    let mut options = ReplayOptions { inputs: Vec::new(), speed: ReplaySpeed::AsFastAsPossible, deterministic: false };
+    let mut iter = args[start + 1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--speed" => {
+                let value = iter.next().ok_or_else(|| anyhow::anyhow!("--speed 값이 필요합니다"))?;
+                options.speed = ReplaySpeed::parse(value)
+                    .ok_or_else(|| anyhow::anyhow!("잘못된 재생 속도: '{}' (max, realtime, <N>x)", value))?;
             }
+            "--deterministic" => options.deterministic = true,
+            flag if flag.starts_with("--") => return Err(anyhow::anyhow!("알 수 없는 리플레이 옵션: {}", flag)),
+            path => options.inputs.push(path.into()),
         }
-    } else {
+    }
+    if options.inputs.is_empty() {
+        return Err(anyhow::anyhow!("--replay에 저널 파일 또는 디렉터리를 지정하세요"));
+    }
+    Ok(Some(options))
+}
+
+/// 종료 시 세션 정리 대기 최대 시간 (초)
+const SHUTDOWN_TIMEOUT_SECS: u64 = 10;
+
+/// SIGINT(Ctrl+C) 또는 SIGTERM 수신까지 대기하고 수신한 신호 이름을 반환합니다.
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        let mut terminate = match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("⚠️ SIGTERM 핸들러 등록 실패: {}", e);
+                let _ = signal::ctrl_c().await;
+                return "SIGINT";
+            }
+        };
         tokio::select! {
-            _ = signal::ctrl_c() => {
-                info!("🛑 종료 신호 수신, 프로그램을 정리하는 중...");
+            _ = signal::ctrl_c() => "SIGINT",
+            _ = terminate.recv() => "SIGTERM",
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// symbol_config.ini/endpoint.ini 재로드 태스크를 시작합니다.
+/// SIGHUP 수신 시 항상 재로드하며, interval_secs > 0이면 파일 수정 시각을 주기적으로 확인합니다.
+fn spawn_config_reloader(manager: ConnectionManager, interval_secs: u64) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut hangup = match signal::unix::signal(signal::unix::SignalKind::hangup()) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                warn!("⚠️ SIGHUP 핸들러 등록 실패: {}", e);
+                None
             }
-            result = connection_handle => {
-                match result {
-                    Ok(_) => info!("🔚 연결 관리자가 정상 종료됨"),
-                    Err(e) => error!("❌ 연결 관리자 오류: {}", e),
+        };
+
+        if interval_secs > 0 {
+            info!("👀 설정 파일 변경 감지 활성화: {}초 간격", interval_secs);
+        }
+        let mut last_modified = config_files_modified();
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+
+        loop {
+            #[cfg(unix)]
+            let sighup = async {
+                match hangup.as_mut() {
+                    Some(s) => { s.recv().await; }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+            #[cfg(not(unix))]
+            let sighup = std::future::pending::<()>();
+
+            tokio::select! {
+                _ = sighup => {
+                    info!("📨 SIGHUP 수신, 설정 재로드");
+                }
+                _ = ticker.tick(), if interval_secs > 0 => {
+                    let modified = config_files_modified();
+                    if modified == last_modified {
+                        continue;
+                    }
+                    info!("📝 설정 파일 변경 감지, 설정 재로드");
                 }
             }
+
+            last_modified = config_files_modified();
+            // 실패 시 기존 설정 유지 (오류 로그와 ErrorEvent는 ConnectionManager에서 처리)
+            let _ = manager.reload_config().await;
         }
-    }
-    
-    info!("✅ CryptoFeeder 종료 완료");
-    Ok(())
+    });
+}
+
+/// symbol_config.ini/endpoint.ini의 수정 시각
+fn config_files_modified() -> Vec<Option<std::time::SystemTime>> {
+    ["symbol_config.ini", "endpoint.ini"].iter()
+        .map(|name| crypto_feeder::config::config_file_path(name)
+            .and_then(|p| std::fs::metadata(p).ok())
+            .and_then(|m| m.modified().ok()))
+        .collect()
 }
 
 /// 명령행 인수에서 타이머 설정을 파싱합니다.
@@ -0,0 +1,242 @@
+//! 미결제약정 REST 조회기
+//! WebSocket으로 미결제약정을 제공하지 않는 거래소(Binance 선물)를 주기적으로 조회하여 세션 포트로 전송
+//!
+//! - 대상: symbol_config.ini에서 `open_interest=on`인 세션의 심볼 (세션 포트로 전송)
+//! - REST 기본 URL은 endpoint.ini `rest_url_base`로 재정의 가능 (로컬 스텁 연결용)
+//! - 롱/숏 계정 비율을 함께 조회하며, 비율 조회가 실패하면 비율 없이 전송
+
+use crate::config::Config;
+use crate::data_parser::StandardizedOpenInterest;
+use crate::errors::{CryptoFeederError, Result};
+use crate::http_client;
+use crate::packet_builder::PacketBuilder;
+use crate::udp_broadcaster::UdpMulticaster;
+use crate::ws_connect::ConnectOptions;
+
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// REST 조회 제한 시간
+const POLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 롱/숏 비율 집계 구간
+const LONG_SHORT_PERIOD: &str = "5m";
+
+/// REST로만 미결제약정을 제공하는 거래소의 기본 REST URL
+pub fn default_rest_base(exchange: &str) -> Option<&'static str> {
+    match exchange {
+        "BinanceFutures" => Some("https://fapi.binance.com"),
+        _ => None,
+    }
+}
+
+/// 조회 대상 (거래소, 심볼, 세션 포트)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PollTarget {
+    pub exchange: String,
+    pub symbol: String,
+    pub port: u16,
+}
+
+/// `open_interest=on` 세션 중 REST 조회 거래소의 심볼 목록 (거래소, 심볼, 포트 순)
+pub fn poll_targets(config: &Config) -> Vec<PollTarget> {
+    let Some(symbol_config) = &config.symbol_config else { return Vec::new() };
+    let targets: BTreeSet<PollTarget> = symbol_config.exchanges.iter()
+        .filter(|(name, _)| default_rest_base(name).is_some())
+        .flat_map(|(name, group)| {
+            group.sessions.iter()
+                .filter(|s| s.streams.open_interest)
+                .flat_map(move |s| s.symbols.iter().map(move |symbol| PollTarget {
+                    exchange: name.clone(),
+                    symbol: symbol.clone(),
+                    port: s.port,
+                }))
+        })
+        .collect();
+    targets.into_iter().collect()
+}
+
+/// 문자열("10659.509") 또는 숫자 JSON 값
+fn number(value: &Value, key: &str) -> Result<f64> {
+    let field = value.get(key).ok_or_else(|| CryptoFeederError::JsonParseError(format!("{} 누락", key)))?;
+    field.as_f64()
+        .or_else(|| field.as_str()?.parse().ok())
+        .ok_or_else(|| CryptoFeederError::JsonParseError(format!("{} 형식 오류", key)))
+}
+
+/// Binance openInterest 응답과 (선택) 롱/숏 비율 응답으로 미결제약정 생성
+///
+/// - openInterest: `{"openInterest":"10659.509","symbol":"BTCUSDT","time":1589437530011}`
+/// - globalLongShortAccountRatio: `[{"symbol":"BTCUSDT","longShortRatio":"1.8105",...,"timestamp":1583139600000}]`
+pub fn parse_open_interest(target: &PollTarget, open_interest: &Value, ratio: Option<&Value>) -> Result<StandardizedOpenInterest> {
+    let long_short_ratio = ratio
+        .and_then(|r| r.as_array()?.last().cloned())
+        .and_then(|r| number(&r, "longShortRatio").ok())
+        .unwrap_or(f64::NAN);
+    Ok(StandardizedOpenInterest {
+        symbol: target.symbol.clone(),
+        exchange: target.exchange.clone(),
+        open_interest: number(open_interest, "openInterest")?,
+        open_interest_value: f64::NAN,
+        long_short_ratio,
+        timestamp: open_interest.get("time").and_then(|v| v.as_u64()).unwrap_or(0) * 1_000_000,
+    })
+}
+
+/// 대상 하나의 미결제약정과 롱/숏 비율 조회
+pub async fn fetch(config: &Config, target: &PollTarget) -> Result<StandardizedOpenInterest> {
+    let endpoint = config.endpoint_config.as_ref().and_then(|e| e.get_exchange_endpoint(&target.exchange));
+    let base = endpoint.and_then(|e| e.rest_url_base.clone())
+        .or_else(|| default_rest_base(&target.exchange).map(str::to_string))
+        .ok_or_else(|| CryptoFeederError::Other(format!("{} REST URL 없음", target.exchange)))?;
+    let options = ConnectOptions::for_session(endpoint, 0)?;
+    let symbol = target.symbol.replace('^', "");
+
+    let get_json = |url: String| {
+        let options = options.clone();
+        async move {
+            let body = http_client::get(&url::Url::parse(&url)?, &options, POLL_TIMEOUT).await?;
+            serde_json::from_str::<Value>(&body).map_err(|e| CryptoFeederError::JsonParseError(e.to_string()))
+        }
+    };
+    let open_interest = get_json(format!("{}/fapi/v1/openInterest?symbol={}", base, symbol)).await?;
+    let ratio = get_json(format!("{}/futures/data/globalLongShortAccountRatio?symbol={}&period={}&limit=1", base, symbol, LONG_SHORT_PERIOD)).await;
+    if let Err(e) = &ratio {
+        debug!("📈 {} {} 롱/숏 비율 조회 실패: {}", target.exchange, target.symbol, e);
+    }
+    parse_open_interest(target, &open_interest, ratio.as_ref().ok())
+}
+
+/// 미결제약정을 주기적으로 조회하여 세션 포트로 전송하는 조회기
+pub struct OpenInterestPoller {
+    interval_secs: u64,
+    packet_builder: Arc<PacketBuilder>,
+    broadcaster: Arc<UdpMulticaster>,
+}
+
+impl OpenInterestPoller {
+    pub fn new(config: &Config, packet_builder: Arc<PacketBuilder>, broadcaster: Arc<UdpMulticaster>) -> Self {
+        Self { interval_secs: config.open_interest_poll_secs, packet_builder, broadcaster }
+    }
+
+    /// 현재 설정의 전체 대상을 한 번 조회하여 전송하고 전송한 수를 반환 (실패한 대상은 경고 후 건너뜀)
+    pub async fn poll(&self, config: &Config) -> usize {
+        let mut sent = 0;
+        for target in poll_targets(config) {
+            let result = async {
+                let oi = fetch(config, &target).await?;
+                let packet = self.packet_builder.build_open_interest_packet(&oi)?;
+                self.broadcaster.send_packet_to_port(packet, target.port).await
+            }.await;
+            match result {
+                Ok(()) => sent += 1,
+                Err(e) => warn!("⚠️ {} {} 미결제약정 조회 실패: {}", target.exchange, target.symbol, e),
+            }
+        }
+        sent
+    }
+
+    /// 주기적 조회 태스크 시작 (`config_source`로 핫 리로드된 최신 설정 사용, 주기 0이면 시작 안 함)
+    pub fn spawn(self, config_source: impl Fn() -> Arc<Config> + Send + 'static) {
+        if self.interval_secs == 0 {
+            return;
+        }
+        info!("📈 미결제약정 REST 조회 시작: {}초 간격", self.interval_secs);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(self.interval_secs));
+            loop {
+                ticker.tick().await;
+                let sent = self.poll(&config_source()).await;
+                debug!("📈 미결제약정 {}개 전송", sent);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{EndpointConfig, ExchangeEndpoint, ExchangeSymbolGroup, SessionStreams, SymbolConfig, SymbolSession, test_session};
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config_with(sessions: Vec<(&str, SymbolSession)>) -> Config {
+        let mut config = Config::load().unwrap();
+        let mut exchanges: HashMap<String, ExchangeSymbolGroup> = HashMap::new();
+        for (exchange, session) in sessions {
+            exchanges.entry(exchange.to_string())
+                .or_insert_with(|| ExchangeSymbolGroup { exchange_name: exchange.to_string(), sessions: Vec::new() })
+                .sessions.push(session);
+        }
+        config.symbol_config = Some(SymbolConfig { exchanges });
+        config
+    }
+
+    fn oi_session(port: u16, symbols: &[&str], open_interest: bool) -> SymbolSession {
+        SymbolSession { streams: SessionStreams { open_interest, ..SessionStreams::default() }, ..test_session(port, symbols) }
+    }
+
+    #[test]
+    fn test_poll_targets() {
+        let config = config_with(vec![
+            ("BinanceFutures", oi_session(55567, &["ETH^USDT", "BTC^USDT"], true)),
+            ("BinanceFutures", oi_session(55568, &["SOL^USDT"], false)),
+            // WebSocket으로 제공하는 거래소는 조회 대상 아님
+            ("OkxSwap", oi_session(55570, &["BTC^USDT"], true)),
+        ]);
+        let targets = poll_targets(&config);
+        assert_eq!(targets.len(), 2);
+        assert_eq!((targets[0].symbol.as_str(), targets[0].port), ("BTC^USDT", 55567));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_local_stub() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let body = if request.starts_with("GET /fapi/v1/openInterest?symbol=BTCUSDT ") {
+                    r#"{"openInterest":"10659.509","symbol":"BTCUSDT","time":1589437530011}"#
+                } else if request.starts_with("GET /futures/data/globalLongShortAccountRatio?symbol=BTCUSDT&period=5m&limit=1 ") {
+                    r#"[{"symbol":"BTCUSDT","longShortRatio":"1.8105","longAccount":"0.6442","shortAccount":"0.3558","timestamp":1583139600000}]"#
+                } else {
+                    "bad"
+                };
+                let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let mut config = config_with(vec![("BinanceFutures", oi_session(55567, &["BTC^USDT"], true))]);
+        let endpoint = ExchangeEndpoint {
+            exchange_name: "BinanceFutures".to_string(),
+            ws_url_base: "wss://fstream.binance.com/ws/".to_string(),
+            timeout_ms: 5000,
+            ping_interval_ms: None,
+            enabled: true,
+            max_streams_per_connection: None,
+            max_subscriptions_per_connection: None,
+            max_url_length: None,
+            proxy: None,
+            bind_addrs: vec![],
+            resolve: vec![],
+            rest_url_base: Some(format!("http://{}", addr)),
+        };
+        config.endpoint_config = Some(EndpointConfig { exchanges: HashMap::from([("BinanceFutures".to_string(), endpoint)]) });
+
+        let target = poll_targets(&config).remove(0);
+        let oi = fetch(&config, &target).await.unwrap();
+        assert_eq!((oi.exchange.as_str(), oi.symbol.as_str()), ("BinanceFutures", "BTC^USDT"));
+        assert_eq!((oi.open_interest, oi.long_short_ratio), (10659.509, 1.8105));
+        assert!(oi.open_interest_value.is_nan());
+        assert_eq!(oi.timestamp, 1_589_437_530_011_000_000);
+    }
+}
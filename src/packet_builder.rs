@@ -1,33 +1,176 @@
-/// UDP 패킷 생성기
-/// 표준화된 내부 데이터 구조체를 UDP 바이너리 패킷으로 직렬화
+//! UDP 패킷 생성기
+//! 표준화된 내부 데이터 구조체를 UDP 바이너리 패킷으로 직렬화
 
-use crate::data_parser::{ParsedData, StandardizedTrade, StandardizedOrderBookUpdate, StandardizedTradeBatch};
-use crate::protocol::{PacketHeader, OrderBookItem, TradeTickItem, PriceValueItem, FundingRateItem, MESSAGE_TYPE_ORDER_BOOK, MESSAGE_TYPE_TRADE_TICK, MESSAGE_TYPE_INDEX_PRICE, MESSAGE_TYPE_MARK_PRICE, MESSAGE_TYPE_FUNDING_RATE, MESSAGE_TYPE_LIQUIDATION, PRICE_SCALE, QUANTITY_SCALE};
+use crate::data_parser::{ParsedData, StandardizedTrade, StandardizedOrderBookUpdate, StandardizedTradeBatch, StandardizedKline, StandardizedTicker, StandardizedOpenInterest, StandardizedFunding, StandardizedLiquidation, LiquidationStatus};
+use crate::protocol::{PacketHeader, PacketHeaderV2, PROTOCOL_VERSION_V2, V2_FLAG_SNAPSHOT, FragmentHeader, UpdateIdRange, WireFormat, MAX_ITEM_COUNT, OrderBookItem, TradeTickItem, TradeTickIdItem, MESSAGE_TYPE_TRADE_TICK_WITH_ID, PriceValueItem, FundingRateItem, LiquidationItem, KlineItem, MESSAGE_TYPE_KLINE, Ticker24hItem, MESSAGE_TYPE_TICKER_24H, OpenInterestItem, MESSAGE_TYPE_OPEN_INTEREST, FundingInfoItem, MESSAGE_TYPE_FUNDING_INFO, FUNDING_FLAG_PREDICTED_RATE, LiquidationDetailItem, MESSAGE_TYPE_LIQUIDATION_DETAIL, ConsolidatedBboItem, MESSAGE_TYPE_CONSOLIDATED_BBO, BBO_FLAG_LOCKED, BBO_FLAG_CROSSED, BBO_FLAG_BID_FX, BBO_FLAG_ASK_FX, KimchiPremiumItem, MESSAGE_TYPE_KIMCHI_PREMIUM, KIMCHI_FLAG_MARKET_FX, TradeFlowItem, MESSAGE_TYPE_TRADE_FLOW, LIQUIDATION_STATUS_UNKNOWN, LIQUIDATION_STATUS_NEW, LIQUIDATION_STATUS_PARTIALLY_FILLED, LIQUIDATION_STATUS_FILLED, LIQUIDATION_STATUS_EXPIRED, InstrumentScale, ScaleError, MESSAGE_TYPE_ORDER_BOOK, MESSAGE_TYPE_TRADE_TICK, MESSAGE_TYPE_INDEX_PRICE, MESSAGE_TYPE_MARK_PRICE, MESSAGE_TYPE_FUNDING_RATE, MESSAGE_TYPE_LIQUIDATION};
+use crate::consolidated_bbo::{ConsolidatedQuote, VenueQuote, CONSOLIDATED_EXCHANGE};
 use crate::events::SystemEvent;
+use crate::kimchi_premium::PremiumQuote;
+use crate::trade_flow::FlowSnapshot;
+use crate::instruments::{known_venue_id, InstrumentRegistry};
+use crate::reference_data::{InstrumentDefinition, MESSAGE_TYPE_INSTRUMENT_DEFINITION};
 use crate::errors::{CryptoFeederError, Result};
 
 use log::{debug, warn};
 use crossbeam_queue::ArrayQueue;
-use std::collections::HashMap;
-use std::sync::{atomic::{AtomicU64, Ordering}};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, atomic::{AtomicU32, AtomicU64, Ordering}};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// 기본 패킷 크기 상한 (MTU 1500 - IPv4/UDP 헤더 28바이트)
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 1472;
+
 pub struct PacketBuilder {
     sequence_counter: AtomicU64,
+    // 다중 패킷 메시지 ID (분할 정보의 message_id)
+    message_counter: AtomicU32,
+    // 패킷 크기 상한 (v1 헤더 포함, config.ini의 mtu에서 결정)
+    max_packet_size: usize,
     payload_pool: PayloadPool,
+    // 고정 local_timestamp (0이면 현재 시각 사용, 리플레이 결정적 모드용)
+    fixed_local_timestamp: AtomicU64,
+    // 종목(표준 심볼)별 스케일 지수 (없으면 10^8)
+    scales: HashMap<String, InstrumentScale>,
+    // 체결을 체결 ID/체결 시각 포함 형식(message_type 6)으로 전송
+    trade_ids: bool,
+    // 펀딩비를 예상 펀딩비/다음 정산 시각/정산 주기 포함 형식(message_type 10)으로 전송
+    funding_info: bool,
+    // 청산을 주문 상태/누적 체결 수량/주문 시각 포함 형식(message_type 11)으로 전송
+    liquidation_detail: bool,
+    // v2 헤더용 venue_id/instrument_id 레지스트리 (없으면 v2 헤더를 만들지 않음)
+    instruments: Option<Arc<InstrumentRegistry>>,
+    // 모든 포트가 v2 (v2 헤더 기준으로 패킷을 나누고 data도 v2로 직렬화)
+    v2_only: bool,
 }
 
 pub struct UdpPacket {
+    /// v1 패킷 (모든 포트가 v2인 빌더에서는 시퀀스 0인 v2 헤더 + 아이템, 분할/업데이트 ID 확장 헤더는 전송 시 추가)
     pub data: Vec<u8>,
     pub size: usize,
+    /// 여러 패킷으로 나뉜 메시지의 분할 정보 (v2 전송 시 FragmentHeader로 기록, 단일 패킷이면 None)
+    pub fragment: Option<FragmentHeader>,
+    /// 오더북 증분의 거래소 업데이트 ID 범위 (v2 전송 시 UpdateIdRange 헤더 확장으로 기록)
+    pub update_ids: Option<UpdateIdRange>,
+    /// v2 포트 전송용 헤더 (생성 시 ID를 채워 두고 시퀀스/CRC/확장 플래그는 전송 시 기록, 레지스트리가 없으면 None)
+    pub v2_header: Option<PacketHeaderV2>,
+}
+
+impl UdpPacket {
+    /// 헤더를 제외한 아이템 바이트 (data의 버전에 따라 v1/v2 헤더 크기만큼 건너뜀)
+    pub fn items(&self) -> &[u8] {
+        let header_size = match self.data.first() {
+            Some(&PROTOCOL_VERSION_V2) => PacketHeaderV2::WIRE_SIZE,
+            _ => PacketHeader::WIRE_SIZE,
+        };
+        self.data.get(header_size..).unwrap_or(&[])
+    }
 }
 
 impl PacketBuilder {
     pub fn new() -> Self {
         Self {
             sequence_counter: AtomicU64::new(1),
+            message_counter: AtomicU32::new(1),
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
             payload_pool: PayloadPool::with_capacity(1024, 1400),
+            fixed_local_timestamp: AtomicU64::new(0),
+            scales: HashMap::new(),
+            trade_ids: false,
+            funding_info: false,
+            liquidation_detail: false,
+            instruments: None,
+            v2_only: false,
+        }
+    }
+
+    /// v2 헤더의 venue_id/instrument_id 레지스트리 설정 (v2 포트가 있을 때 필요)
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = Some(instruments);
+        self
+    }
+
+    /// 모든 포트가 v2인지 (`ProtocolConfig::all_v2`, `with_instruments`와 함께 설정해야 적용)
+    ///
+    /// v1 포트가 없으면 v1 item_count 7비트 한도(127) 대신 v2 헤더와 MTU 기준으로 패킷당 아이템 수를 정합니다.
+    pub fn with_v2_only(mut self, enabled: bool) -> Self {
+        self.v2_only = enabled;
+        self
+    }
+
+    /// v2로만 직렬화하는지 (레지스트리가 있어야 v2 헤더를 만들 수 있음)
+    fn is_v2_only(&self) -> bool {
+        self.v2_only && self.instruments.is_some()
+    }
+
+    /// 종목별 스케일 지수 설정 (config.ini의 instrument_scales)
+    pub fn with_scales(mut self, scales: HashMap<String, InstrumentScale>) -> Self {
+        self.scales = scales;
+        self
+    }
+
+    /// 체결 ID 포함 체결 형식 사용 여부 (config.ini의 trade_ids_enabled)
+    pub fn with_trade_ids(mut self, enabled: bool) -> Self {
+        self.trade_ids = enabled;
+        self
+    }
+
+    /// 펀딩 정보 형식 사용 여부 (config.ini의 protocol_funding_info)
+    pub fn with_funding_info(mut self, enabled: bool) -> Self {
+        self.funding_info = enabled;
+        self
+    }
+
+    /// 청산 상세 형식 사용 여부 (config.ini의 protocol_liquidation_detail)
+    pub fn with_liquidation_detail(mut self, enabled: bool) -> Self {
+        self.liquidation_detail = enabled;
+        self
+    }
+
+    /// 체결 메시지 타입과 아이템 크기
+    fn trade_format(&self) -> (u8, usize) {
+        if self.trade_ids {
+            (MESSAGE_TYPE_TRADE_TICK_WITH_ID, TradeTickIdItem::WIRE_SIZE)
+        } else {
+            (MESSAGE_TYPE_TRADE_TICK, TradeTickItem::WIRE_SIZE)
+        }
+    }
+
+    /// 체결 하나를 설정된 형식의 아이템으로 버퍼 끝에 추가
+    fn append_trade_item(&self, trade: &StandardizedTrade, scale: InstrumentScale, dst: &mut Vec<u8>) -> std::result::Result<(), ScaleError> {
+        if self.trade_ids {
+            TradeTickIdItem::scaled(trade.price, trade.quantity, trade.is_buyer_taker, trade.trade_id, trade.timestamp, scale)?.append_to_vec(dst);
+        } else {
+            TradeTickItem::scaled(trade.price, trade.quantity, trade.is_buyer_taker, scale)?.append_to_vec(dst);
         }
+        Ok(())
+    }
+
+    /// 패킷 크기 상한 설정 (`UdpConfig::max_datagram_size`, 점보 프레임이면 더 크게)
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+
+    /// 패킷당 아이템 수 (최소 1)
+    ///
+    /// v1 포트가 있으면 v1 헤더 기준 크기 상한과 v1 item_count 7비트 한도(127) 중 작은 값,
+    /// 모든 포트가 v2이면 v2 헤더와 확장 헤더(분할 정보 + 업데이트 ID 범위)를 뺀 크기 상한과 v2 item_count 한도(255) 중 작은 값입니다.
+    pub fn items_per_packet(&self, item_size: usize) -> usize {
+        if self.is_v2_only() {
+            let overhead = PacketHeaderV2::WIRE_SIZE + FragmentHeader::WIRE_SIZE + UpdateIdRange::WIRE_SIZE;
+            (self.max_packet_size.saturating_sub(overhead) / item_size).clamp(1, u8::MAX as usize)
+        } else {
+            (self.max_packet_size.saturating_sub(PacketHeader::WIRE_SIZE) / item_size).clamp(1, MAX_ITEM_COUNT)
+        }
+    }
+
+    /// 심볼의 스케일 (설정이 없으면 기본 10^8)
+    pub fn scale_for(&self, symbol: &str) -> InstrumentScale {
+        self.scales.get(symbol).copied().unwrap_or_default()
     }
 
     /// 파싱된 데이터로부터 UDP 패킷들 생성
@@ -37,24 +180,30 @@ impl PacketBuilder {
             ParsedData::TradeBatch(batch) => self.build_trade_batch_packets(batch),
             ParsedData::OrderBook(order_book) => self.build_order_book_packets(order_book),
             ParsedData::IndexPrice { symbol, exchange, value, timestamp } => {
-                let scaled = (value * PRICE_SCALE as f64) as i64;
+                let scaled = self.scale_for(&symbol).scale_price(value).map_err(|e| scale_error(&exchange, &symbol, e))?;
                 let pkt = self.build_single_value_packet(&symbol, &exchange, MESSAGE_TYPE_INDEX_PRICE, timestamp, scaled)?;
                 Ok(vec![pkt])
             }
             ParsedData::MarkPrice { symbol, exchange, value, timestamp } => {
-                let scaled = (value * PRICE_SCALE as f64) as i64;
+                let scaled = self.scale_for(&symbol).scale_price(value).map_err(|e| scale_error(&exchange, &symbol, e))?;
                 let pkt = self.build_single_value_packet(&symbol, &exchange, MESSAGE_TYPE_MARK_PRICE, timestamp, scaled)?;
                 Ok(vec![pkt])
             }
-            ParsedData::FundingRate { symbol, exchange, value, timestamp } => {
-                let scaled = (value * PRICE_SCALE as f64) as i64;
-                let pkt = self.build_single_value_packet(&symbol, &exchange, MESSAGE_TYPE_FUNDING_RATE, timestamp, scaled)?;
+            ParsedData::FundingRate(funding) if self.funding_info => Ok(vec![self.build_funding_info_packet(&funding)?]),
+            ParsedData::FundingRate(funding) => {
+                // 펀딩비는 가격이 아닌 비율이므로 항상 10^8
+                let scaled = InstrumentScale::DEFAULT.scale_price(funding.rate).map_err(|e| scale_error(&funding.exchange, &funding.symbol, e))?;
+                let pkt = self.build_single_value_packet(&funding.symbol, &funding.exchange, MESSAGE_TYPE_FUNDING_RATE, funding.timestamp, scaled)?;
                 Ok(vec![pkt])
             }
-            ParsedData::Liquidation { symbol, exchange, price, quantity, is_sell, timestamp } => {
-                let pkt = self.build_liquidation_packet(&symbol, &exchange, timestamp, price, quantity, is_sell)?;
+            ParsedData::Liquidation(liquidation) if self.liquidation_detail => Ok(vec![self.build_liquidation_detail_packet(&liquidation)?]),
+            ParsedData::Liquidation(l) => {
+                let pkt = self.build_liquidation_packet(&l.symbol, &l.exchange, l.timestamp, l.price, l.quantity, l.is_sell)?;
                 Ok(vec![pkt])
             }
+            ParsedData::Kline(kline) => Ok(vec![self.build_kline_packet(&kline)?]),
+            ParsedData::Ticker(ticker) => Ok(vec![self.build_ticker_packet(&ticker)?]),
+            ParsedData::OpenInterest(oi) => Ok(vec![self.build_open_interest_packet(&oi)?]),
             ParsedData::Multi(items) => {
                 let mut out = Vec::new();
                 for it in items {
@@ -100,11 +249,11 @@ impl PacketBuilder {
             return Ok(vec![]);
         }
 
-        // 타임스탬프별로 trade들을 그룹핑
-        let mut trade_groups: HashMap<u64, Vec<StandardizedTrade>> = HashMap::new();
+        // 타임스탬프별로 trade들을 그룹핑 (타임스탬프 오름차순으로 패킷 생성)
+        let mut trade_groups: BTreeMap<u64, Vec<StandardizedTrade>> = BTreeMap::new();
         
         for trade in trades {
-            trade_groups.entry(trade.timestamp).or_insert_with(Vec::new).push(trade);
+            trade_groups.entry(trade.timestamp).or_default().push(trade);
         }
 
         let mut all_packets = Vec::new();
@@ -127,34 +276,40 @@ impl PacketBuilder {
             });
 
             // 심볼과 거래소가 다른 trade들은 별도 처리
-            let mut symbol_groups: HashMap<(String, String), Vec<&StandardizedTrade>> = HashMap::new();
+            let mut symbol_groups: BTreeMap<(String, String), Vec<&StandardizedTrade>> = BTreeMap::new();
             
             for trade in &trades_in_group {
                 let key = (trade.symbol.clone(), trade.exchange.clone());
-                symbol_groups.entry(key).or_insert_with(Vec::new).push(trade);
+                symbol_groups.entry(key).or_default().push(trade);
             }
 
             // 각 심볼-거래소 조합별로 패킷 생성
             for ((symbol, exchange), symbol_trades) in symbol_groups {
                 let mut trade_items = Vec::new();
+                let scale = self.scale_for(&symbol);
+                let (message_type, item_size) = self.trade_format();
                 
                 for trade in &symbol_trades {
-                    let trade_item = TradeTickItem::new(trade.price, trade.quantity, trade.is_buyer_taker);
-                    trade_items.push(trade_item.to_bytes());
+                    let mut trade_item = Vec::with_capacity(item_size);
+                    self.append_trade_item(trade, scale, &mut trade_item)
+                        .map_err(|e| scale_error(&exchange, &symbol, e))?;
+                    trade_items.push(trade_item);
                 }
 
-                // 패킷 크기 제한을 고려하여 청크로 분할 (최대 50개 trade/패킷)
-                let chunks: Vec<_> = trade_items.chunks(50).collect();
+                // 패킷 크기 상한에 맞춰 청크로 분할
+                let chunks: Vec<_> = trade_items.chunks(self.items_per_packet(item_size)).collect();
                 let total_chunks = chunks.len();
+                let message_id = self.message_id_for(total_chunks)?;
 
                 for (chunk_index, chunk) in chunks.into_iter().enumerate() {
                     let mut header = PacketHeader::new();
-                    self.setup_header(&mut header, &symbol, &exchange, MESSAGE_TYPE_TRADE_TICK, timestamp);
+                    self.setup_header(&mut header, &symbol, &exchange, message_type, timestamp);
                     
                     let is_last = chunk_index == total_chunks - 1;
                     header.set_flags_and_count(is_last, chunk.len() as u8);
 
-                    let packet = self.create_packet(header, chunk.to_vec())?;
+                    let mut packet = self.create_packet(header, chunk.to_vec())?;
+                    packet.fragment = fragment_of(message_id, chunk_index, total_chunks);
                     all_packets.push(packet);
                 }
 
@@ -180,25 +335,29 @@ impl PacketBuilder {
             }
         });
 
-        // 80개씩 청크 분할하여 바로 평탄화 직렬화
-        let chunks: Vec<_> = trades.chunks(80).collect();
+        // 패킷 크기 상한에 맞춰 청크 분할하여 바로 평탄화 직렬화
+        let (message_type, item_size) = self.trade_format();
+        let chunks: Vec<_> = trades.chunks(self.items_per_packet(item_size)).collect();
         let total_chunks = chunks.len();
+        let message_id = self.message_id_for(total_chunks)?;
+        let scale = self.scale_for(&batch.symbol);
 
         let mut packets = Vec::new();
         for (chunk_index, chunk) in chunks.into_iter().enumerate() {
             let mut header = PacketHeader::new();
             // 배치의 공통 메타데이터 사용
-            self.setup_header(&mut header, &batch.symbol, &batch.exchange, MESSAGE_TYPE_TRADE_TICK, batch.exchange_timestamp);
+            self.setup_header(&mut header, &batch.symbol, &batch.exchange, message_type, batch.exchange_timestamp);
             let is_last = chunk_index == total_chunks - 1;
             header.set_flags_and_count(is_last, chunk.len() as u8);
             // 평탄화된 아이템 버퍼 조립 (풀 이용)
-            let needed = chunk.len() * std::mem::size_of::<TradeTickItem>();
+            let needed = chunk.len() * item_size;
             let mut buf = self.payload_pool.acquire_buffer(needed);
             for t in chunk {
-                let item = TradeTickItem::new(t.price, t.quantity, t.is_buyer_taker);
-                item.append_to_vec(&mut buf);
+                self.append_trade_item(t, scale, &mut buf)
+                    .map_err(|e| scale_error(&batch.exchange, &batch.symbol, e))?;
             }
-            let packet = self.create_packet_from_flat(header, buf)?;
+            let mut packet = self.create_packet_from_flat(header, chunk.len(), buf)?;
+            packet.fragment = fragment_of(message_id, chunk_index, total_chunks);
             packets.push(packet);
         }
         Ok(packets)
@@ -210,6 +369,11 @@ impl PacketBuilder {
     fn build_order_book_packets(&self, order_book: StandardizedOrderBookUpdate) -> Result<Vec<UdpPacket>> {
         let mut packets = Vec::new();
         let mut all_items: Vec<OrderBookItem> = Vec::new();
+        let scale = self.scale_for(&order_book.symbol);
+        let to_item = |price: f64, quantity: f64, is_ask: bool| {
+            OrderBookItem::scaled(price, quantity, is_ask, scale)
+                .map_err(|e| scale_error(&order_book.exchange, &order_book.symbol, e))
+        };
 
         // Bids 정렬 (가격 내림차순) 후 OrderBookItem으로 변환
         let mut bids = order_book.bids;
@@ -217,7 +381,7 @@ impl PacketBuilder {
         
         for bid in bids {
             if bid.quantity > 0.0 { // 수량이 0인 항목은 제외
-                all_items.push(OrderBookItem::new(bid.price, bid.quantity, false)); // false = bid
+                all_items.push(to_item(bid.price, bid.quantity, false)?); // false = bid
             }
         }
 
@@ -227,7 +391,7 @@ impl PacketBuilder {
         
         for ask in asks {
             if ask.quantity > 0.0 { // 수량이 0인 항목은 제외
-                all_items.push(OrderBookItem::new(ask.price, ask.quantity, true)); // true = ask
+                all_items.push(to_item(ask.price, ask.quantity, true)?); // true = ask
             }
         }
 
@@ -237,9 +401,13 @@ impl PacketBuilder {
             return Ok(vec![]);
         }
 
-        // 80개씩 청크로 나누어 패킷 생성 (MTU 안전 범위)
-        let chunks: Vec<_> = all_items.chunks(80).collect();
+        // 패킷 크기 상한(MTU 기준)에 맞춰 청크로 나누어 패킷 생성
+        let chunks: Vec<_> = all_items.chunks(self.items_per_packet(OrderBookItem::WIRE_SIZE)).collect();
         let total_chunks = chunks.len();
+        let message_id = self.message_id_for(total_chunks)?;
+        // 거래소 업데이트 ID 범위는 모든 조각에 동일하게 기록
+        let update_ids = order_book.first_update_id.zip(order_book.last_update_id)
+            .map(|(first_update_id, last_update_id)| UpdateIdRange { first_update_id, last_update_id });
 
         for (chunk_index, chunk) in chunks.into_iter().enumerate() {
             let mut header = PacketHeader::new();
@@ -250,12 +418,14 @@ impl PacketBuilder {
             header.set_flags_and_count(is_last, chunk.len() as u8);
 
             // 평탄화된 아이템 버퍼 조립 (풀 이용)
-            let needed = chunk.len() * std::mem::size_of::<OrderBookItem>();
+            let needed = std::mem::size_of_val(chunk);
             let mut buf = self.payload_pool.acquire_buffer(needed);
             for ob in chunk {
                 ob.append_to_vec(&mut buf);
             }
-            let packet = self.create_packet_from_flat(header, buf)?;
+            let mut packet = self.create_packet_from_flat(header, chunk.len(), buf)?;
+            packet.fragment = fragment_of(message_id, chunk_index, total_chunks);
+            packet.update_ids = update_ids;
             packets.push(packet);
         }
 
@@ -273,18 +443,10 @@ impl PacketBuilder {
 
         let item_bytes = match message_type {
             MESSAGE_TYPE_INDEX_PRICE | MESSAGE_TYPE_MARK_PRICE => {
-                let item = PriceValueItem { value: value_scaled };
-                unsafe {
-                    let ptr = &item as *const PriceValueItem as *const u8;
-                    std::slice::from_raw_parts(ptr, std::mem::size_of::<PriceValueItem>()).to_vec()
-                }
+                PriceValueItem { value: value_scaled }.to_bytes()
             }
             MESSAGE_TYPE_FUNDING_RATE => {
-                let item = FundingRateItem { value: value_scaled };
-                unsafe {
-                    let ptr = &item as *const FundingRateItem as *const u8;
-                    std::slice::from_raw_parts(ptr, std::mem::size_of::<FundingRateItem>()).to_vec()
-                }
+                FundingRateItem { value: value_scaled }.to_bytes()
             }
             _ => return Err(CryptoFeederError::Other("잘못된 단일 값 message_type".to_string()))
         };
@@ -298,20 +460,229 @@ impl PacketBuilder {
         self.setup_header(&mut header, symbol, exchange, MESSAGE_TYPE_LIQUIDATION, exchange_timestamp);
         header.set_flags_and_count(true, 1);
 
-        let scaled_price = (price * PRICE_SCALE as f64) as i64;
-        let scaled_qty = (qty * QUANTITY_SCALE as f64) as i64;
-        let quantity_with_flags = if is_sell { scaled_qty | (1i64 << 63) } else { scaled_qty };
+        let item_bytes = LiquidationItem::scaled(price, qty, is_sell, self.scale_for(symbol))
+            .map_err(|e| scale_error(exchange, symbol, e))?
+            .to_bytes();
+
+        self.create_packet(header, vec![item_bytes])
+    }
+
+    /// 청산 상세 패킷 생성 (주문 상태/누적 체결 수량/거래소 주문 시각 포함)
+    pub fn build_liquidation_detail_packet(&self, liquidation: &StandardizedLiquidation) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &liquidation.symbol, &liquidation.exchange, MESSAGE_TYPE_LIQUIDATION_DETAIL, liquidation.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let mut item = LiquidationDetailItem::scaled(liquidation.price, liquidation.quantity, liquidation.filled_quantity, liquidation.is_sell, self.scale_for(&liquidation.symbol))
+            .map_err(|e| scale_error(&liquidation.exchange, &liquidation.symbol, e))?;
+        item.order_time = liquidation.order_time;
+        item.order_status = match liquidation.status {
+            LiquidationStatus::Unknown => LIQUIDATION_STATUS_UNKNOWN,
+            LiquidationStatus::New => LIQUIDATION_STATUS_NEW,
+            LiquidationStatus::PartiallyFilled => LIQUIDATION_STATUS_PARTIALLY_FILLED,
+            LiquidationStatus::Filled => LIQUIDATION_STATUS_FILLED,
+            LiquidationStatus::Expired => LIQUIDATION_STATUS_EXPIRED,
+        };
 
-        #[repr(C, packed)]
-        struct LiquidationItemLocal { price: i64, quantity_with_flags: i64 }
+        self.create_packet(header, vec![item.to_bytes()])
+    }
 
-        let item = LiquidationItemLocal { price: scaled_price, quantity_with_flags };
-        let item_bytes = unsafe {
-            let ptr = &item as *const LiquidationItemLocal as *const u8;
-            std::slice::from_raw_parts(ptr, std::mem::size_of::<LiquidationItemLocal>()).to_vec()
+    /// OHLCV 봉 패킷 생성 (봉 하나당 단일 아이템, exchange_timestamp는 이벤트 시각)
+    pub fn build_kline_packet(&self, kline: &StandardizedKline) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &kline.symbol, &kline.exchange, MESSAGE_TYPE_KLINE, kline.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let scale = self.scale_for(&kline.symbol);
+        let to_error = |e| scale_error(&kline.exchange, &kline.symbol, e);
+        let volume = scale.scale_quantity(kline.volume).map_err(to_error)?;
+        let item = KlineItem {
+            open: scale.scale_price(kline.open).map_err(to_error)?,
+            high: scale.scale_price(kline.high).map_err(to_error)?,
+            low: scale.scale_price(kline.low).map_err(to_error)?,
+            close: scale.scale_price(kline.close).map_err(to_error)?,
+            volume_with_flags: if kline.is_closed { volume | (1i64 << 63) } else { volume },
+            quote_volume: scale.scale_price(kline.quote_volume).map_err(to_error)?,
+            start_time: kline.start_time,
+            trade_count: kline.trade_count.min(u32::MAX as u64) as u32,
+            interval_secs: kline.interval_secs,
         };
 
-        self.create_packet(header, vec![item_bytes])
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 24시간 티커 패킷 생성 (티커 하나당 단일 아이템, 변동률은 비율이므로 항상 10^8)
+    pub fn build_ticker_packet(&self, ticker: &StandardizedTicker) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &ticker.symbol, &ticker.exchange, MESSAGE_TYPE_TICKER_24H, ticker.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let scale = self.scale_for(&ticker.symbol);
+        let to_error = |e| scale_error(&ticker.exchange, &ticker.symbol, e);
+        let item = Ticker24hItem {
+            last_price: scale.scale_price(ticker.last_price).map_err(to_error)?,
+            open_price: scale.scale_price(ticker.open_price).map_err(to_error)?,
+            high_price: scale.scale_price(ticker.high_price).map_err(to_error)?,
+            low_price: scale.scale_price(ticker.low_price).map_err(to_error)?,
+            volume: scale.scale_quantity(ticker.volume).map_err(to_error)?,
+            quote_volume: scale.scale_price(ticker.quote_volume).map_err(to_error)?,
+            price_change_percent: InstrumentScale::DEFAULT.scale_price(ticker.price_change_percent).map_err(to_error)?,
+            trade_count: ticker.trade_count,
+        };
+
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 미결제약정 패킷 생성 (거래소가 제공하지 않는 명목 가치/롱숏 비율은 0, 롱/숏 비율은 항상 10^8)
+    pub fn build_open_interest_packet(&self, oi: &StandardizedOpenInterest) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &oi.symbol, &oi.exchange, MESSAGE_TYPE_OPEN_INTEREST, oi.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let scale = self.scale_for(&oi.symbol);
+        let to_error = |e| scale_error(&oi.exchange, &oi.symbol, e);
+        let open_interest_value = if oi.open_interest_value.is_nan() { 0 } else { scale.scale_price(oi.open_interest_value).map_err(to_error)? };
+        let long_short_ratio = if oi.long_short_ratio.is_nan() { 0 } else { InstrumentScale::DEFAULT.scale_price(oi.long_short_ratio).map_err(to_error)? };
+        let item = OpenInterestItem {
+            open_interest: scale.scale_quantity(oi.open_interest).map_err(to_error)?,
+            open_interest_value,
+            long_short_ratio,
+        };
+
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 펀딩 정보 패킷 생성 (비율은 항상 10^8, 예상 펀딩비가 없으면 flags 비트0 해제)
+    pub fn build_funding_info_packet(&self, funding: &StandardizedFunding) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &funding.symbol, &funding.exchange, MESSAGE_TYPE_FUNDING_INFO, funding.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let to_error = |e| scale_error(&funding.exchange, &funding.symbol, e);
+        let (predicted_rate, flags) = if funding.predicted_rate.is_nan() {
+            (0, 0)
+        } else {
+            (InstrumentScale::DEFAULT.scale_price(funding.predicted_rate).map_err(to_error)?, FUNDING_FLAG_PREDICTED_RATE)
+        };
+        let item = FundingInfoItem {
+            funding_rate: InstrumentScale::DEFAULT.scale_price(funding.rate).map_err(to_error)?,
+            predicted_rate,
+            next_funding_time: funding.next_funding_time,
+            interval_secs: funding.interval_secs,
+            flags,
+        };
+
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 거래소 통합 최우선 호가 패킷 생성 (거래소는 `Consolidated`, 없는 쪽 호가는 0)
+    pub fn build_consolidated_bbo_packet(&self, quote: &ConsolidatedQuote) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &quote.symbol, CONSOLIDATED_EXCHANGE, MESSAGE_TYPE_CONSOLIDATED_BBO, quote.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let scale = self.scale_for(&quote.symbol);
+        let to_error = |e| scale_error(CONSOLIDATED_EXCHANGE, &quote.symbol, e);
+        let side = |q: &Option<VenueQuote>| -> Result<(i64, i64, u16)> {
+            match q {
+                Some(q) => Ok((scale.scale_price(q.price).map_err(to_error)?, scale.scale_quantity(q.quantity).map_err(to_error)?, known_venue_id(&q.exchange))),
+                None => Ok((0, 0, 0)),
+            }
+        };
+        let (bid_price, bid_quantity, bid_venue) = side(&quote.bid)?;
+        let (ask_price, ask_quantity, ask_venue) = side(&quote.ask)?;
+        let flag = |on: bool, bit: u16| if on { bit } else { 0 };
+        let item = ConsolidatedBboItem {
+            bid_price,
+            bid_quantity,
+            ask_price,
+            ask_quantity,
+            bid_venue,
+            ask_venue,
+            venue_count: quote.venue_count,
+            flags: flag(quote.is_locked(), BBO_FLAG_LOCKED)
+                | flag(quote.is_crossed(), BBO_FLAG_CROSSED)
+                | flag(quote.bid.as_ref().is_some_and(|q| q.fx_converted), BBO_FLAG_BID_FX)
+                | flag(quote.ask.as_ref().is_some_and(|q| q.fx_converted), BBO_FLAG_ASK_FX),
+        };
+
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 김치 프리미엄 패킷 생성 (KRW 심볼/거래소 헤더, 모든 값 10^8)
+    pub fn build_kimchi_premium_packet(&self, quote: &PremiumQuote) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &quote.symbol, &quote.krw_exchange, MESSAGE_TYPE_KIMCHI_PREMIUM, quote.timestamp);
+        header.set_flags_and_count(true, 1);
+
+        let to_error = |e| scale_error(&quote.krw_exchange, &quote.symbol, e);
+        let scale = |value: f64| InstrumentScale::DEFAULT.scale_price(value).map_err(to_error);
+        let item = KimchiPremiumItem {
+            premium: scale(quote.premium)?,
+            krw_price: scale(quote.krw_price)?,
+            foreign_price: scale(quote.foreign_price)?,
+            fx_rate: scale(quote.fx_rate)?,
+            foreign_venue: known_venue_id(&quote.foreign_exchange),
+            flags: if quote.fx_from_market { KIMCHI_FLAG_MARKET_FX } else { 0 },
+            reserved: 0,
+        };
+
+        self.create_packet(header, vec![item.to_bytes()])
+    }
+
+    /// 체결 흐름 패킷 생성 (구간당 아이템 하나, vwap/체결량은 종목 스케일)
+    pub fn build_trade_flow_packet(&self, snapshot: &FlowSnapshot) -> Result<UdpPacket> {
+        let mut header = PacketHeader::new();
+        self.setup_header(&mut header, &snapshot.symbol, &snapshot.exchange, MESSAGE_TYPE_TRADE_FLOW, snapshot.timestamp);
+        header.set_flags_and_count(true, snapshot.windows.len().min(MAX_ITEM_COUNT) as u8);
+
+        let scale = self.scale_for(&snapshot.symbol);
+        let to_error = |e| scale_error(&snapshot.exchange, &snapshot.symbol, e);
+        let items = snapshot.windows.iter().take(MAX_ITEM_COUNT)
+            .map(|stats| Ok(TradeFlowItem {
+                vwap: scale.scale_price(stats.vwap).map_err(to_error)?,
+                buy_volume: scale.scale_quantity(stats.buy_volume).map_err(to_error)?,
+                sell_volume: scale.scale_quantity(stats.sell_volume).map_err(to_error)?,
+                imbalance: InstrumentScale::DEFAULT.scale_price(stats.imbalance()).map_err(to_error)?,
+                window_secs: stats.window_secs,
+                trade_count: stats.trade_count,
+            }.to_bytes()))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.create_packet(header, items)
+    }
+
+    /// 종목 정의 패킷 생성 (패킷 크기 상한 기준 분할, 마지막 패킷에 is_last)
+    pub fn build_instrument_definition_packets(&self, definitions: &[InstrumentDefinition]) -> Result<Vec<UdpPacket>> {
+        let timestamp = self.get_current_timestamp_nanos();
+        let chunks: Vec<_> = definitions.chunks(self.items_per_packet(InstrumentDefinition::WIRE_SIZE)).collect();
+        let total_chunks = chunks.len();
+        let message_id = self.message_id_for(total_chunks)?;
+
+        let mut packets = Vec::with_capacity(total_chunks);
+        for (chunk_index, chunk) in chunks.into_iter().enumerate() {
+            let mut header = PacketHeader::new();
+            self.setup_header(&mut header, "SYSTEM", "FEEDER", MESSAGE_TYPE_INSTRUMENT_DEFINITION, timestamp);
+            header.set_flags_and_count(chunk_index == total_chunks - 1, chunk.len() as u8);
+            let items = chunk.iter().copied().map(InstrumentDefinition::to_bytes).collect();
+            let mut packet = self.create_packet(header, items)?;
+            packet.fragment = fragment_of(message_id, chunk_index, total_chunks);
+            packets.push(packet);
+        }
+        Ok(packets)
+    }
+
+    /// 여러 패킷으로 나뉘는 메시지에 message_id 배정 (단일 패킷이면 None)
+    fn message_id_for(&self, total_chunks: usize) -> Result<Option<u32>> {
+        if total_chunks <= 1 {
+            return Ok(None);
+        }
+        if total_chunks > u16::MAX as usize {
+            return Err(CryptoFeederError::SerializationError(
+                format!("메시지 분할 수가 한도를 초과함: {} 패킷", total_chunks)
+            ));
+        }
+        Ok(Some(self.message_counter.fetch_add(1, Ordering::Relaxed)))
     }
 
     /// 패킷 헤더 기본 설정
@@ -328,46 +699,58 @@ impl PacketBuilder {
 
     /// 헤더와 아이템들로 최종 패킷 생성
     fn create_packet(&self, header: PacketHeader, item_bytes: Vec<Vec<u8>>) -> Result<UdpPacket> {
-        let header_bytes = header.to_bytes();
-        let items_total: usize = item_bytes.iter().map(|b| b.len()).sum();
-        let mut packet_data = Vec::with_capacity(header_bytes.len() + items_total);
-        packet_data.extend_from_slice(&header_bytes);
-        for item in item_bytes {
-            packet_data.extend_from_slice(&item);
-        }
+        let item_count = item_bytes.len();
+        self.create_packet_from_flat(header, item_count, item_bytes.concat())
+    }
+
+    /// 평탄화된 아이템 바이트로 최종 패킷 생성 (추가 할당 최소화)
+    ///
+    /// `item_count`는 v2 헤더에 그대로 기록됩니다 (v1 헤더의 7비트 item_count는 127을 넘지 못함).
+    fn create_packet_from_flat(&self, header: PacketHeader, item_count: usize, items_flat: Vec<u8>) -> Result<UdpPacket> {
+        let v2_header = self.v2_header_for(&header, item_count);
+        let packet_data = match v2_header.filter(|_| self.is_v2_only()) {
+            Some(v2) => v2.encode_packet(&items_flat)?,
+            None => {
+                let header_bytes = header.to_bytes();
+                let mut packet_data = Vec::with_capacity(header_bytes.len() + items_flat.len());
+                packet_data.extend_from_slice(&header_bytes);
+                packet_data.extend_from_slice(&items_flat);
+                packet_data
+            }
+        };
 
-        // MTU 안전 범위 검증
-        if packet_data.len() > 1472 {
+        // 패킷 크기 상한 검증
+        if packet_data.len() > self.max_packet_size {
             return Err(CryptoFeederError::SerializationError(
-                format!("패킷 크기가 MTU를 초과함: {} bytes", packet_data.len())
+                format!("패킷 크기가 상한을 초과함: {} > {} bytes", packet_data.len(), self.max_packet_size)
             ));
         }
 
-        Ok(UdpPacket {
-            size: packet_data.len(),
-            data: packet_data,
-        })
+        Ok(UdpPacket { size: packet_data.len(), data: packet_data, fragment: None, update_ids: None, v2_header })
     }
 
-    /// 평탄화된 아이템 바이트로 최종 패킷 생성 (추가 할당 최소화)
-    fn create_packet_from_flat(&self, header: PacketHeader, items_flat: Vec<u8>) -> Result<UdpPacket> {
-        let header_bytes = header.to_bytes();
-        let mut packet_data = Vec::with_capacity(header_bytes.len() + items_flat.len());
-        packet_data.extend_from_slice(&header_bytes);
-        packet_data.extend_from_slice(&items_flat);
-
-        // MTU 안전 범위 검증
-        if packet_data.len() > 1472 {
-            return Err(CryptoFeederError::SerializationError(
-                format!("패킷 크기가 MTU를 초과함: {} bytes", packet_data.len())
-            ));
+    /// v1 헤더에 대응하는 v2 헤더 (ID는 레지스트리에서 한 번만 조회, 종목 정의는 전체 목록 재전송이므로 스냅샷)
+    fn v2_header_for(&self, header: &PacketHeader, item_count: usize) -> Option<PacketHeaderV2> {
+        let instruments = self.instruments.as_ref()?;
+        let mut v2 = PacketHeaderV2::from_v1(header, instruments.venue_id(header.exchange_str()), instruments.instrument_id(header.symbol_str()));
+        v2.item_count = item_count.min(u8::MAX as usize) as u8;
+        if header.message_type == MESSAGE_TYPE_INSTRUMENT_DEFINITION {
+            v2.flags |= V2_FLAG_SNAPSHOT;
         }
+        Some(v2)
+    }
 
-        Ok(UdpPacket { size: packet_data.len(), data: packet_data })
+    /// 헤더 local_timestamp 고정 (리플레이 결정적 모드, None이면 현재 시각 사용)
+    pub fn set_fixed_local_timestamp(&self, timestamp_ns: Option<u64>) {
+        self.fixed_local_timestamp.store(timestamp_ns.unwrap_or(0), Ordering::Relaxed);
     }
 
-    /// 현재 시간을 나노초 단위로 반환
+    /// 현재 시간을 나노초 단위로 반환 (고정값이 설정된 경우 그 값)
     fn get_current_timestamp_nanos(&self) -> u64 {
+        let fixed = self.fixed_local_timestamp.load(Ordering::Relaxed);
+        if fixed != 0 {
+            return fixed;
+        }
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -380,6 +763,16 @@ impl PacketBuilder {
     }
 }
 
+/// 청크 위치의 분할 정보 (message_id가 없으면 단일 패킷 메시지)
+fn fragment_of(message_id: Option<u32>, chunk_index: usize, total_chunks: usize) -> Option<FragmentHeader> {
+    message_id.map(|id| FragmentHeader::new(id, chunk_index as u16, total_chunks as u16))
+}
+
+/// 스케일 변환 실패를 패킷 생성 오류로 변환 (해당 메시지 전체를 전송하지 않음)
+fn scale_error(exchange: &str, symbol: &str, error: ScaleError) -> CryptoFeederError {
+    CryptoFeederError::SerializationError(format!("{} {} 스케일 변환 실패: {}", exchange, symbol, error))
+}
+
 /// 고정 크기 Vec<u8> 슬랩 풀 (모듈 스코프)
 pub struct PayloadPool {
     queue: ArrayQueue<Vec<u8>>,
@@ -442,6 +835,7 @@ mod tests {
             quantity: 1.5,
             is_buyer_taker: true,
             timestamp: 1640995200000000000, // 2022-01-01 00:00:00 UTC in nanoseconds
+            trade_id: None,
         };
 
         let packets = builder.build_trade_packets(trade).unwrap();
@@ -465,6 +859,7 @@ mod tests {
             quantity: 1.0,
             is_buyer_taker: false,
             timestamp: 1640995200000000000,
+            trade_id: None,
         };
         
         let _packets1 = builder.build_trade_packets(trade1).unwrap();
@@ -478,6 +873,7 @@ mod tests {
             quantity: 2.0,
             is_buyer_taker: true,
             timestamp: 1640995260000000000,
+            trade_id: None,
         };
         
         let _packets2 = builder.build_trade_packets(trade2).unwrap();
@@ -488,17 +884,120 @@ mod tests {
     fn test_trade_batch_building_ws_message_unit() {
         let builder = PacketBuilder::new();
         // 하나의 WS 메시지에 3건 체결이 들어온 상황을 가정
-        let t1 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.5, quantity: 1000.0, is_buyer_taker: true, timestamp: 1 };
-        let t2 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.49, quantity: 2000.0, is_buyer_taker: false, timestamp: 1 };
-        let t3 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.51, quantity: 3000.0, is_buyer_taker: true, timestamp: 1 };
+        let t1 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.5, quantity: 1000.0, is_buyer_taker: true, timestamp: 1, trade_id: None };
+        let t2 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.49, quantity: 2000.0, is_buyer_taker: false, timestamp: 1, trade_id: None };
+        let t3 = StandardizedTrade { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), price: 0.51, quantity: 3000.0, is_buyer_taker: true, timestamp: 1, trade_id: None };
 
         let batch = StandardizedTradeBatch { symbol: "XRP^USDT".into(), exchange: "BinanceSpot".into(), exchange_timestamp: 1, trades: vec![t1, t2, t3] };
         let packets = builder.build_trade_batch_packets(batch).unwrap();
         assert_eq!(packets.len(), 1); // 3건이므로 1패킷
         // 헤더 검사
-        let header = PacketHeader::from_bytes(&packets[0].data[..std::mem::size_of::<PacketHeader>()]);
+        let header = PacketHeader::try_from(packets[0].data.as_slice()).unwrap();
         assert_eq!(header.item_count(), 3);
         assert!(header.is_last());
         assert_eq!(header.message_type, MESSAGE_TYPE_TRADE_TICK);
     }
+
+    #[test]
+    fn test_instrument_scale_applied_and_overflow_rejected() {
+        let mut scales = HashMap::new();
+        scales.insert("SHIB^USDT".to_string(), InstrumentScale::new(12, 0).unwrap());
+        let builder = PacketBuilder::new().with_scales(scales);
+        let trade = |symbol: &str, price: f64| StandardizedTrade {
+            symbol: symbol.into(), exchange: "BinanceSpot".into(), price, quantity: 1000.0, is_buyer_taker: true, timestamp: 1, trade_id: None,
+        };
+
+        let packets = builder.build_trade_packets(trade("SHIB^USDT", 0.000012345678)).unwrap();
+        let item = TradeTickItem::try_from(&packets[0].data[67..83]).unwrap();
+        assert_eq!({ item.price }, 12_345_678);
+        assert_eq!(item.quantity(), 1000);
+
+        // 10^8 기본 스케일로 i64 범위를 넘는 가격은 잘리지 않고 오류
+        assert!(builder.build_trade_packets(trade("BTC^USDT", 1e12)).is_err());
+    }
+
+    #[test]
+    fn test_v2_only_packets_sized_by_mtu() {
+        let builder = PacketBuilder::new()
+            .with_instruments(Arc::new(InstrumentRegistry::new()))
+            .with_v2_only(true)
+            .with_max_packet_size(8972);
+        let book = StandardizedOrderBookUpdate {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            bids: (0..300).map(|i| crate::data_parser::OrderBookLevel { price: 100.0 - i as f64 * 0.01, quantity: 1.0 }).collect(),
+            asks: Vec::new(),
+            timestamp: 1,
+            first_update_id: Some(10),
+            last_update_id: Some(20),
+            is_snapshot: false,
+        };
+        let packets = builder.build_packets(ParsedData::OrderBook(book)).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets.iter().map(|p| p.v2_header.unwrap().item_count).collect::<Vec<_>>(), vec![255, 45]);
+
+        // data는 v2 헤더 + 아이템이고, 확장 헤더를 붙여 전송해도 크기 상한 이내
+        let first = &packets[0];
+        assert_eq!(first.data[0], PROTOCOL_VERSION_V2);
+        assert_eq!(first.items().len(), 255 * OrderBookItem::WIRE_SIZE);
+        let wire = crate::udp_broadcaster::encode_v2_packet(first, 1, 0).unwrap().unwrap();
+        assert!(wire.len() <= 8972);
+        let header = PacketHeaderV2::try_from(wire.as_slice()).unwrap();
+        assert_eq!((header.item_count, { header.sequence_number }), (255, 1));
+        assert!(header.is_fragmented() && header.has_update_ids() && !header.is_last());
+    }
+
+    #[test]
+    fn test_v2_header_filled_at_build() {
+        let instruments = Arc::new(InstrumentRegistry::new());
+        let builder = PacketBuilder::new().with_instruments(instruments.clone());
+        let trade = StandardizedTrade { symbol: "ETH^USDT".into(), exchange: "OkxSpot".into(), price: 10.0, quantity: 1.0, is_buyer_taker: true, timestamp: 5, trade_id: None };
+        let packet = &builder.build_trade_packets(trade).unwrap()[0];
+        let v2 = packet.v2_header.unwrap();
+        let v1 = PacketHeader::try_from(packet.data.as_slice()).unwrap();
+        assert_eq!(({ v2.venue_id }, { v2.instrument_id }), (instruments.venue_id("OkxSpot"), instruments.instrument_id("ETH^USDT")));
+        assert_eq!((v2.message_type, v2.item_count, { v2.exchange_timestamp }, { v2.local_timestamp }), (MESSAGE_TYPE_TRADE_TICK, 1, 5, { v1.local_timestamp }));
+        assert!(v2.is_last() && !v2.is_snapshot());
+        assert_eq!({ v2.sequence_number }, 0);
+
+        // 종목 정의는 스냅샷, 레지스트리가 없으면 v2 헤더 없음
+        let definitions = builder.build_instrument_definition_packets(&[InstrumentDefinition::try_from(&[0u8; 96][..]).unwrap()]).unwrap();
+        assert!(definitions[0].v2_header.unwrap().is_snapshot());
+        assert!(PacketBuilder::new().build_event_packet(SystemEvent::Heartbeat(crate::events::SystemHeartbeat::new(1, 1, 1))).unwrap().v2_header.is_none());
+    }
+
+    #[test]
+    fn test_packet_size_follows_mtu() {
+        let builder = PacketBuilder::new();
+        assert_eq!(builder.items_per_packet(16), 87);
+        assert_eq!(builder.items_per_packet(InstrumentDefinition::WIRE_SIZE), 14);
+        // 점보 프레임은 v1 item_count 7비트 한도까지
+        let jumbo = PacketBuilder::new().with_max_packet_size(8972);
+        assert_eq!(jumbo.items_per_packet(16), MAX_ITEM_COUNT);
+        // 모든 포트가 v2이면 v2 헤더 + 확장 헤더(24바이트) 기준, v2 item_count 한도(255)까지
+        let v2 = |max_packet_size| PacketBuilder::new()
+            .with_instruments(Arc::new(InstrumentRegistry::new()))
+            .with_v2_only(true)
+            .with_max_packet_size(max_packet_size);
+        assert_eq!(v2(1472).items_per_packet(16), 88);
+        assert_eq!(v2(8972).items_per_packet(16), 255);
+        // 레지스트리 없이는 v2로 직렬화할 수 없으므로 v1 한도 유지
+        assert_eq!(PacketBuilder::new().with_v2_only(true).with_max_packet_size(8972).items_per_packet(16), MAX_ITEM_COUNT);
+
+        let trades: Vec<StandardizedTrade> = (0..300)
+            .map(|i| StandardizedTrade { symbol: "BTC^USDT".into(), exchange: "BinanceSpot".into(), price: 100.0 + i as f64, quantity: 1.0, is_buyer_taker: true, timestamp: 1, trade_id: None })
+            .collect();
+        let packets = jumbo.build_trade_packets_batch(trades.clone()).unwrap();
+        assert_eq!(packets.len(), 3);
+        assert!(packets.iter().all(|p| p.size <= 8972));
+        let fragments: Vec<_> = packets.iter().map(|p| p.fragment.unwrap()).collect();
+        assert!(fragments.iter().enumerate().all(|(i, f)| { f.fragment_index } as usize == i && { f.fragment_total } == 3));
+        assert!(fragments.iter().all(|f| { f.message_id } == { fragments[0].message_id }));
+
+        // 다음 다중 패킷 메시지는 새 message_id, 단일 패킷 메시지는 분할 정보 없음
+        assert_eq!(builder.build_trade_packets_batch(trades.clone()).unwrap().len(), 4);
+        let next = jumbo.build_trade_packets_batch(trades).unwrap();
+        assert_ne!({ next[0].fragment.unwrap().message_id }, { fragments[0].message_id });
+        assert!(builder.build_packets(ParsedData::Trade(StandardizedTrade { symbol: "BTC^USDT".into(), exchange: "BinanceSpot".into(), price: 1.0, quantity: 1.0, is_buyer_taker: true, timestamp: 1, trade_id: None })).unwrap()[0].fragment.is_none());
+    }
 }
\ No newline at end of file
@@ -1,11 +1,15 @@
-/// UDP 패킷 프로토콜 구조체 정의
-/// udp_packet_detail/udp_packet.md 명세에 따라 구현
+//! UDP 패킷 프로토콜 구조체 정의
+//! udp_packet_detail/udp_packet.md 명세에 따라 구현
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::mem;
 
+use crate::errors::CryptoFeederError;
+
 // C와 동일한 메모리 레이아웃을 보장합니다.
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PacketHeader {
     pub protocol_version: u8,      // 1B
     pub sequence_number: u64,      // 8B
@@ -18,14 +22,14 @@ pub struct PacketHeader {
 } // 총 67 바이트
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct OrderBookItem {
     pub price: i64,                // 8B, Scaled by 10^8
     pub quantity_with_flags: i64,  // 8B, quantity + is_ask flag
 } // 총 16 바이트
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct TradeTickItem {
     pub price: i64,                // 8B, Scaled by 10^8
     pub quantity_with_flags: i64,  // 8B, quantity + is_buyer_taker flag
@@ -38,6 +42,15 @@ pub const MESSAGE_TYPE_INDEX_PRICE: u8 = 2;    // 새: Index Price
 pub const MESSAGE_TYPE_MARK_PRICE: u8 = 3;     // 새: Mark Price
 pub const MESSAGE_TYPE_FUNDING_RATE: u8 = 4;   // 새: Funding Rate
 pub const MESSAGE_TYPE_LIQUIDATION: u8 = 5;    // 새: Liquidation
+pub const MESSAGE_TYPE_TRADE_TICK_WITH_ID: u8 = 6; // 체결 ID/체결 시각 포함 체결
+pub const MESSAGE_TYPE_KLINE: u8 = 7;          // OHLCV 봉 (거래소 kline 또는 체결 기반 로컬 집계)
+pub const MESSAGE_TYPE_TICKER_24H: u8 = 8;     // 24시간 통계 티커 (거래소 ticker 채널, 주기 병합)
+pub const MESSAGE_TYPE_OPEN_INTEREST: u8 = 9;  // 미결제약정 + 롱/숏 계정 비율 (파생상품)
+pub const MESSAGE_TYPE_FUNDING_INFO: u8 = 10;  // 펀딩비 + 예상 펀딩비/다음 정산 시각/정산 주기 (protocol_funding_info=true일 때 4 대신)
+pub const MESSAGE_TYPE_LIQUIDATION_DETAIL: u8 = 11; // 청산 + 주문 상태/누적 체결 수량/주문 시각 (protocol_liquidation_detail=true일 때 5 대신)
+pub const MESSAGE_TYPE_CONSOLIDATED_BBO: u8 = 12; // 거래소 통합 최우선 호가 (consolidated_bbo_port로 전송)
+pub const MESSAGE_TYPE_KIMCHI_PREMIUM: u8 = 13; // 김치 프리미엄 (kimchi_port로 전송)
+pub const MESSAGE_TYPE_TRADE_FLOW: u8 = 14;    // 체결 흐름 분석 (trade_flow_port로 전송, 구간당 아이템 하나)
 
 // 스케일링 상수
 pub const PRICE_SCALE: i64 = 100_000_000; // 10^8
@@ -45,24 +58,230 @@ pub const QUANTITY_SCALE: i64 = 100_000_000; // 10^8
 pub const FUNDING_RATE_SCALE: i64 = 100_000_000; // 10^8 (펀딩 비율도 동일 스케일 적용)
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct PriceValueItem {
     pub value: i64,                // 8B, Scaled by 10^8 (가격용)
 } // 총 8 바이트
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct FundingRateItem {
     pub value: i64,                // 8B, Scaled by 10^8 (비율용)
 } // 총 8 바이트
 
 #[repr(C, packed)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct LiquidationItem {
     pub price: i64,                // 8B, Scaled by 10^8
     pub quantity_with_flags: i64,  // 8B, quantity + is_sell flag
 } // 총 16 바이트
 
+/// 체결 ID와 거래소 체결 시각을 포함한 체결 아이템 (message_type 6)
+///
+/// A/B 라인 간 중복 제거와 거래소 REST 체결 이력 대조용입니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TradeTickIdItem {
+    pub price: i64,                // 8B, 종목 스케일 적용
+    pub quantity_with_flags: i64,  // 8B, quantity + is_buyer_taker flag
+    pub trade_id: u64,             // 8B, 거래소 체결 ID (Binance trade `t` / aggTrade `a`, 없으면 0)
+    pub trade_time: u64,           // 8B, 거래소 체결 시각 (Unix 나노초)
+} // 총 32 바이트
+
+/// OHLCV 봉 아이템 (message_type 7, 패킷당 1개)
+///
+/// 거래소 kline 스트림 또는 체결 스트림을 로컬에서 집계한 봉입니다. 가격과 거래대금은 종목 가격 스케일,
+/// 거래량은 종목 수량 스케일을 적용합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KlineItem {
+    pub open: i64,                 // 8B, 시가
+    pub high: i64,                 // 8B, 고가
+    pub low: i64,                  // 8B, 저가
+    pub close: i64,                // 8B, 종가 (미확정 봉은 현재가)
+    pub volume_with_flags: i64,    // 8B, 거래량 + is_closed flag
+    pub quote_volume: i64,         // 8B, 거래대금 (quote 통화, 가격 스케일)
+    pub start_time: u64,           // 8B, 봉 시작 시각 (Unix 나노초)
+    pub trade_count: u32,          // 4B, 체결 수 (거래소가 제공하지 않으면 0)
+    pub interval_secs: u32,        // 4B, 봉 길이 (초)
+} // 총 64 바이트
+
+/// 24시간 통계 티커 아이템 (message_type 8, 패킷당 1개)
+///
+/// 거래소 ticker 채널의 롤링 24시간 통계입니다. 가격과 거래대금은 종목 가격 스케일, 거래량은 수량 스케일,
+/// 변동률은 종목과 무관하게 10^8 스케일(퍼센트)을 적용합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Ticker24hItem {
+    pub last_price: i64,           // 8B, 최종 체결가
+    pub open_price: i64,           // 8B, 24시간 전 가격
+    pub high_price: i64,           // 8B, 24시간 고가
+    pub low_price: i64,            // 8B, 24시간 저가
+    pub volume: i64,               // 8B, 24시간 기준 자산 거래량
+    pub quote_volume: i64,         // 8B, 24시간 거래대금 (quote 통화, 가격 스케일)
+    pub price_change_percent: i64, // 8B, 24시간 변동률 (%, 10^8 고정)
+    pub trade_count: u64,          // 8B, 24시간 체결 수 (거래소가 제공하지 않으면 0)
+} // 총 64 바이트
+
+/// 미결제약정 아이템 (message_type 9, 패킷당 1개)
+///
+/// 미결제약정은 종목 수량 스케일, 명목 가치는 가격 스케일, 롱/숏 비율은 10^8 고정입니다.
+/// 거래소가 제공하지 않는 값은 0으로 전송합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OpenInterestItem {
+    pub open_interest: i64,        // 8B, 미결제약정 (기준 자산 수량)
+    pub open_interest_value: i64,  // 8B, 미결제약정 명목 가치 (quote 통화, 0 = 알 수 없음)
+    pub long_short_ratio: i64,     // 8B, 롱/숏 계정 비율 (10^8 고정, 0 = 알 수 없음)
+} // 총 24 바이트
+
+/// `FundingInfoItem::flags` 비트: 예상 펀딩비 제공 여부
+pub const FUNDING_FLAG_PREDICTED_RATE: u32 = 1;
+
+/// 펀딩 정보 아이템 (message_type 10, 패킷당 1개)
+///
+/// 비율은 종목과 무관하게 10^8 스케일입니다. 예상 펀딩비는 0도 유효한 값이므로 `flags`로 제공 여부를 구분하고,
+/// 알 수 없는 다음 정산 시각/정산 주기는 0으로 전송합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FundingInfoItem {
+    pub funding_rate: i64,         // 8B, 현재(다음 정산에 적용될) 펀딩비 (10^8 고정)
+    pub predicted_rate: i64,       // 8B, 그다음 정산의 예상 펀딩비 (10^8 고정, flags 비트0)
+    pub next_funding_time: u64,    // 8B, 다음 정산 시각 (Unix 나노초, 0 = 알 수 없음)
+    pub interval_secs: u32,        // 4B, 정산 주기 (초, 0 = 알 수 없음)
+    pub flags: u32,                // 4B, FUNDING_FLAG_*
+} // 총 32 바이트
+
+// `LiquidationDetailItem::order_status` 값
+pub const LIQUIDATION_STATUS_UNKNOWN: u32 = 0;
+pub const LIQUIDATION_STATUS_NEW: u32 = 1;
+pub const LIQUIDATION_STATUS_PARTIALLY_FILLED: u32 = 2;
+pub const LIQUIDATION_STATUS_FILLED: u32 = 3;
+pub const LIQUIDATION_STATUS_EXPIRED: u32 = 4; // 만료/취소 (미체결 잔량 소멸)
+
+/// 청산 상세 아이템 (message_type 11, 패킷당 1개)
+///
+/// 가격은 종목 가격 스케일, 수량은 수량 스케일입니다. 거래소가 주문 상태를 주지 않으면(OKX, Bybit)
+/// `LIQUIDATION_STATUS_UNKNOWN`, 누적 체결 수량은 주문 수량과 같게 전송합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LiquidationDetailItem {
+    pub price: i64,                // 8B, 평균 체결가 (없으면 주문가/파산가)
+    pub quantity_with_flags: i64,  // 8B, 주문 수량 + is_sell flag
+    pub filled_quantity: i64,      // 8B, 누적 체결 수량
+    pub order_time: u64,           // 8B, 거래소 주문(체결) 시각 (Unix 나노초, 0 = 알 수 없음)
+    pub order_status: u32,         // 4B, LIQUIDATION_STATUS_*
+    pub reserved: u32,             // 4B, 0
+} // 총 40 바이트
+
+// `ConsolidatedBboItem::flags` 비트
+pub const BBO_FLAG_LOCKED: u16 = 1;     // 최우선 매수 = 최우선 매도 (거래소 간)
+pub const BBO_FLAG_CROSSED: u16 = 2;    // 최우선 매수 > 최우선 매도 (거래소 간)
+pub const BBO_FLAG_BID_FX: u16 = 4;     // 매수 호가가 KRW 환산 값
+pub const BBO_FLAG_ASK_FX: u16 = 8;     // 매도 호가가 KRW 환산 값
+
+/// 거래소 통합 최우선 호가 아이템 (message_type 12, 패킷당 1개)
+///
+/// 헤더 심볼은 통합 심볼, 거래소는 `Consolidated`입니다. 가격/수량은 통합 심볼의 종목 스케일이며,
+/// 한쪽 호가가 없으면 해당 가격/수량/거래소를 0으로 전송합니다. 거래소는 v2 venue_id(고정 목록, 0 = 없음)입니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ConsolidatedBboItem {
+    pub bid_price: i64,            // 8B, 전체 거래소 최우선 매수 호가
+    pub bid_quantity: i64,         // 8B, 해당 거래소의 매수 잔량
+    pub ask_price: i64,            // 8B, 전체 거래소 최우선 매도 호가
+    pub ask_quantity: i64,         // 8B, 해당 거래소의 매도 잔량
+    pub bid_venue: u16,            // 2B, 최우선 매수 거래소 venue_id
+    pub ask_venue: u16,            // 2B, 최우선 매도 거래소 venue_id
+    pub venue_count: u16,          // 2B, 통합에 포함된(갱신이 오래되지 않은) 거래소 수
+    pub flags: u16,                // 2B, BBO_FLAG_*
+} // 총 40 바이트
+
+// `KimchiPremiumItem::flags` 비트
+pub const KIMCHI_FLAG_MARKET_FX: u16 = 1; // 환율이 시장 중간 가격 (없으면 고정 환율)
+
+/// 김치 프리미엄 아이템 (message_type 13, 패킷당 1개)
+///
+/// 헤더 심볼은 KRW 심볼(`X^KRW`), 거래소는 KRW 거래소입니다. 모든 값은 10^8 고정 소수점이며
+/// 해외 거래소는 v2 venue_id(고정 목록, 0 = 목록에 없음)입니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KimchiPremiumItem {
+    pub premium: i64,              // 8B, 프리미엄 비율 (0.03 = 3%, 음수면 역프리미엄)
+    pub krw_price: i64,            // 8B, KRW 거래소 중간 가격
+    pub foreign_price: i64,        // 8B, 해외 거래소 중간 가격 (kimchi_quote 기준)
+    pub fx_rate: i64,              // 8B, 1 kimchi_quote = fx_rate KRW
+    pub foreign_venue: u16,        // 2B, 해외 거래소 venue_id
+    pub flags: u16,                // 2B, KIMCHI_FLAG_*
+    pub reserved: u32,             // 4B, 0
+} // 총 40 바이트
+
+/// 체결 흐름 아이템 (message_type 14, 집계 구간당 1개)
+///
+/// 헤더 심볼/거래소는 원래 종목입니다. vwap/체결량은 종목 스케일, imbalance는 10^8입니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TradeFlowItem {
+    pub vwap: i64,                 // 8B, 구간 체결량 가중 평균 가격 (체결 없으면 0)
+    pub buy_volume: i64,           // 8B, 매수 테이커 체결량
+    pub sell_volume: i64,          // 8B, 매도 테이커 체결량
+    pub imbalance: i64,            // 8B, (매수 - 매도) / (매수 + 매도)
+    pub window_secs: u32,          // 4B, 집계 구간 (초)
+    pub trade_count: u32,          // 4B, 구간 체결 수
+} // 총 40 바이트
+
+// 프로토콜 버전
+pub const PROTOCOL_VERSION_V1: u8 = 1;
+pub const PROTOCOL_VERSION_V2: u8 = 2;
+
+// v2 헤더 flags 비트
+pub const V2_FLAG_LAST: u8 = 0b0000_0001;      // 메시지의 마지막 패킷
+pub const V2_FLAG_SNAPSHOT: u8 = 0b0000_0010;  // 전체 스냅샷 (증분 아님)
+pub const V2_FLAG_REPLAY: u8 = 0b0000_0100;    // 리플레이 모드에서 재전송된 패킷
+pub const V2_FLAG_CRC: u8 = 0b0000_1000;       // crc32 필드 유효
+pub const V2_FLAG_FRAGMENT: u8 = 0b0001_0000;  // 페이로드 앞에 FragmentHeader(8B) 포함
+pub const V2_FLAG_UPDATE_IDS: u8 = 0b0010_0000; // 페이로드 앞에 UpdateIdRange(16B) 포함 (FragmentHeader 다음)
+
+/// v1 flags_and_count의 item_count 최대값 (하위 7비트)
+pub const MAX_ITEM_COUNT: usize = 127;
+
+/// v2 패킷 헤더 (udp_packet.md 6장)
+///
+/// 심볼/거래소 문자열 대신 숫자 ID를 사용하고, 페이로드 길이와 채널(포트)별 시퀀스, 선택적 CRC32를 포함합니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PacketHeaderV2 {
+    pub protocol_version: u8,      // 1B, 항상 2
+    pub message_type: u8,          // 1B
+    pub flags: u8,                 // 1B, V2_FLAG_*
+    pub item_count: u8,            // 1B
+    pub venue_id: u16,             // 2B
+    pub payload_length: u16,       // 2B, 헤더 이후 바이트 수
+    pub instrument_id: u32,        // 4B
+    pub crc32: u32,                // 4B, V2_FLAG_CRC일 때 crc32 필드를 0으로 둔 헤더 + 페이로드의 CRC-32(IEEE)
+    pub sequence_number: u64,      // 8B, 채널(포트)별 1부터 증가
+    pub exchange_timestamp: u64,   // 8B
+    pub local_timestamp: u64,      // 8B
+} // 총 40 바이트
+
+/// 다중 패킷 메시지 분할 정보 (udp_packet.md 6.1절)
+///
+/// v2 `V2_FLAG_FRAGMENT` 패킷의 헤더 바로 뒤(payload_length에 포함)에 위치합니다.
+/// 같은 메시지의 모든 패킷은 같은 `message_id`를 가지며, 수신 측은 순서와 관계없이
+/// `fragment_total`개를 모두 받으면 메시지를 완성하고 빠진 조각을 구분할 수 있습니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FragmentHeader {
+    pub message_id: u32,           // 4B, 피더가 다중 패킷 메시지마다 1씩 증가
+    pub fragment_index: u16,       // 2B, 0부터 시작
+    pub fragment_total: u16,       // 2B, 메시지의 전체 패킷 수
+} // 총 8 바이트
+
+/// 오더북 증분의 거래소 업데이트 ID 범위 (udp_packet.md 6.2절)
+///
+/// v2 `V2_FLAG_UPDATE_IDS` 패킷의 헤더 확장으로 전송되며, 분할된 메시지는 모든 조각에 같은 값이 들어갑니다.
+/// 수신 측은 이전 메시지의 `last_update_id`와 이어지는지로 거래소 단 누락을 확인하고 REST 스냅샷과 맞춥니다.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UpdateIdRange {
+    pub first_update_id: u64,      // 8B, Binance depth `U`
+    pub last_update_id: u64,       // 8B, Binance depth `u`
+} // 총 16 바이트
+
+impl Default for PacketHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PacketHeader {
     pub fn new() -> Self {
         Self {
@@ -77,12 +296,6 @@ impl PacketHeader {
         }
     }
 
-    /// 헤더를 바이트 배열에서 역직렬화 (테스트용)
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        assert!(bytes.len() >= mem::size_of::<Self>());
-        unsafe { *(bytes.as_ptr() as *const Self) }
-    }
-
     /// is_last 플래그를 추출하는 헬퍼 함수
     pub fn is_last(&self) -> bool {
         (self.flags_and_count & 0b1000_0000) != 0
@@ -105,8 +318,8 @@ impl PacketHeader {
 
     /// is_last와 item_count로 flags_and_count 필드를 설정하는 헬퍼 함수
     pub fn set_flags_and_count(&mut self, is_last: bool, count: u8) {
-        // count가 최대값(80)을 넘지 않도록 보장 (MTU 안전 범위 유지)
-        let item_count = count.min(80) & 0b0111_1111; 
+        // 7비트 필드 최대값(127)을 넘지 않도록 보장 (패킷당 개수는 PacketBuilder가 MTU 기준으로 결정)
+        let item_count = count.min(MAX_ITEM_COUNT as u8);
         if is_last {
             self.flags_and_count = item_count | 0b1000_0000;
         } else {
@@ -149,27 +362,32 @@ impl PacketHeader {
         self.exchange[..len].copy_from_slice(&bytes[..len]);
     }
 
+    /// 심볼 문자열 (null 이전까지, 할당 없음)
+    pub fn symbol_str(&self) -> &str {
+        null_terminated(&self.symbol)
+    }
+
+    /// 거래소 문자열 (null 이전까지, 할당 없음)
+    pub fn exchange_str(&self) -> &str {
+        null_terminated(&self.exchange)
+    }
+
     /// 헤더를 바이트 배열로 직렬화
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 }
 
 impl TradeTickItem {
-    pub fn new(price: f64, quantity: f64, is_buyer_taker: bool) -> Self {
-        let scaled_price = (price * PRICE_SCALE as f64) as i64;
-        let scaled_quantity = (quantity * QUANTITY_SCALE as f64) as i64;
-        
-        let mut item = Self {
-            price: scaled_price,
-            quantity_with_flags: 0,
-        };
-        
-        item.set_quantity_and_flag(scaled_quantity, is_buyer_taker);
-        item
+    /// 종목 스케일을 적용하여 생성 (범위 초과 시 오류)
+    pub fn scaled(price: f64, quantity: f64, is_buyer_taker: bool, scale: InstrumentScale) -> Result<Self, ScaleError> {
+        let (price, quantity_with_flags) = scaled_price_quantity(price, quantity, is_buyer_taker, scale)?;
+        Ok(Self { price, quantity_with_flags })
+    }
+
+    /// 기본 스케일(10^8)로 생성 (범위 초과 시 오류)
+    pub fn new(price: f64, quantity: f64, is_buyer_taker: bool) -> Result<Self, ScaleError> {
+        Self::scaled(price, quantity, is_buyer_taker, InstrumentScale::DEFAULT)
     }
 
     /// is_buyer_taker 플래그를 추출하는 헬퍼 함수
@@ -193,22 +411,13 @@ impl TradeTickItem {
     }
 
     /// TradeTickItem을 바이트 배열로 직렬화
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 
     /// TradeTickItem을 지정된 버퍼 끝에 추가 (추가 할당 회피)
     pub fn append_to_vec(&self, dst: &mut Vec<u8>) {
-        let start_len = dst.len();
-        dst.resize(start_len + mem::size_of::<Self>(), 0);
-        unsafe {
-            let src_ptr = self as *const Self as *const u8;
-            let dst_ptr = dst.as_mut_ptr().add(start_len);
-            std::ptr::copy_nonoverlapping(src_ptr, dst_ptr, mem::size_of::<Self>());
-        }
+        self.encode_into(dst);
     }
 
     /// 실제 가격을 추출 (스케일링 해제)
@@ -223,17 +432,15 @@ impl TradeTickItem {
 }
 
 impl OrderBookItem {
-    pub fn new(price: f64, quantity: f64, is_ask: bool) -> Self {
-        let scaled_price = (price * PRICE_SCALE as f64) as i64;
-        let scaled_quantity = (quantity * QUANTITY_SCALE as f64) as i64;
-        
-        let mut item = Self {
-            price: scaled_price,
-            quantity_with_flags: 0,
-        };
-        
-        item.set_quantity_and_flag(scaled_quantity, is_ask);
-        item
+    /// 종목 스케일을 적용하여 생성 (범위 초과 시 오류)
+    pub fn scaled(price: f64, quantity: f64, is_ask: bool, scale: InstrumentScale) -> Result<Self, ScaleError> {
+        let (price, quantity_with_flags) = scaled_price_quantity(price, quantity, is_ask, scale)?;
+        Ok(Self { price, quantity_with_flags })
+    }
+
+    /// 기본 스케일(10^8)로 생성 (범위 초과 시 오류)
+    pub fn new(price: f64, quantity: f64, is_ask: bool) -> Result<Self, ScaleError> {
+        Self::scaled(price, quantity, is_ask, InstrumentScale::DEFAULT)
     }
 
     /// is_ask 플래그를 추출하는 헬퍼 함수
@@ -257,22 +464,13 @@ impl OrderBookItem {
     }
 
     /// OrderBookItem을 바이트 배열로 직렬화
-    pub fn to_bytes(&self) -> Vec<u8> {
-        unsafe {
-            let ptr = self as *const Self as *const u8;
-            std::slice::from_raw_parts(ptr, mem::size_of::<Self>()).to_vec()
-        }
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
     }
 
     /// OrderBookItem을 지정된 버퍼 끝에 추가 (추가 할당 회피)
     pub fn append_to_vec(&self, dst: &mut Vec<u8>) {
-        let start_len = dst.len();
-        dst.resize(start_len + mem::size_of::<Self>(), 0);
-        unsafe {
-            let src_ptr = self as *const Self as *const u8;
-            let dst_ptr = dst.as_mut_ptr().add(start_len);
-            std::ptr::copy_nonoverlapping(src_ptr, dst_ptr, mem::size_of::<Self>());
-        }
+        self.encode_into(dst);
     }
 
     /// 실제 가격을 추출 (스케일링 해제)
@@ -286,10 +484,816 @@ impl OrderBookItem {
     }
 }
 
+/// 종목별 가격/수량 스케일 지수 (값 = 정수 / 10^n)
+///
+/// 기본값은 가격/수량 모두 10^8이며, 가격이 매우 작은 종목(SHIB 등)은 가격 지수를 높이고
+/// 수량이 매우 큰 종목(KRW 마켓 등)은 수량 지수를 낮춰 i64 범위를 유지합니다.
+/// 수신 측에는 종목 정의(udp_packet.md 7장)의 price_scale/quantity_scale로 전달됩니다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstrumentScale {
+    pub price_exponent: u8,
+    pub quantity_exponent: u8,
+}
+
+/// 스케일 변환 오류
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ScaleError {
+    #[error("유한하지 않은 값: {0}")]
+    NotFinite(f64),
+    #[error("음수 수량: {0}")]
+    NegativeQuantity(f64),
+    #[error("{value} × 10^{exponent}이(가) i64 범위를 초과")]
+    Overflow { value: f64, exponent: u8 },
+}
+
+impl Default for InstrumentScale {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl InstrumentScale {
+    /// 하위호환 기본 스케일 (PRICE_SCALE/QUANTITY_SCALE = 10^8)
+    pub const DEFAULT: Self = Self { price_exponent: 8, quantity_exponent: 8 };
+    /// 허용 최대 지수 (10^18 < 2^63)
+    pub const MAX_EXPONENT: u8 = 18;
+
+    pub fn new(price_exponent: u8, quantity_exponent: u8) -> Option<Self> {
+        (price_exponent <= Self::MAX_EXPONENT && quantity_exponent <= Self::MAX_EXPONENT)
+            .then_some(Self { price_exponent, quantity_exponent })
+    }
+
+    /// 가격 스케일링 (반올림, 범위 초과 시 오류)
+    pub fn scale_price(&self, price: f64) -> Result<i64, ScaleError> {
+        scale_checked(price, self.price_exponent)
+    }
+
+    /// 수량 스케일링 (음수 불가, 플래그 비트를 제외한 63비트 범위 검사)
+    pub fn scale_quantity(&self, quantity: f64) -> Result<i64, ScaleError> {
+        if quantity < 0.0 {
+            return Err(ScaleError::NegativeQuantity(quantity));
+        }
+        scale_checked(quantity, self.quantity_exponent)
+    }
+
+    pub fn price(&self, raw: i64) -> f64 {
+        raw as f64 / 10f64.powi(self.price_exponent as i32)
+    }
+
+    pub fn quantity(&self, raw: i64) -> f64 {
+        raw as f64 / 10f64.powi(self.quantity_exponent as i32)
+    }
+
+    /// `심볼:가격지수:수량지수` 쉼표 구분 목록 파싱 (예: `SHIB^USDT:12:4, BTC^KRW:4:8`)
+    pub fn parse_list(value: &str) -> Result<HashMap<String, Self>, String> {
+        let mut scales = HashMap::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            let [symbol, price, quantity] = parts[..] else {
+                return Err(format!("스케일 항목 형식 오류: '{}' (심볼:가격지수:수량지수)", entry));
+            };
+            let scale = price.parse::<u8>().ok()
+                .zip(quantity.parse::<u8>().ok())
+                .and_then(|(p, q)| Self::new(p, q))
+                .ok_or_else(|| format!("스케일 지수 오류: '{}' (0~{})", entry, Self::MAX_EXPONENT))?;
+            scales.insert(symbol.to_string(), scale);
+        }
+        Ok(scales)
+    }
+}
+
+/// value × 10^exponent를 반올림하여 i64로 변환 (범위 초과 검사)
+fn scale_checked(value: f64, exponent: u8) -> Result<i64, ScaleError> {
+    if !value.is_finite() {
+        return Err(ScaleError::NotFinite(value));
+    }
+    let scaled = (value * 10f64.powi(exponent as i32)).round();
+    // i64::MAX as f64 = 2^63 이므로 상한은 미만 비교
+    if scaled >= i64::MAX as f64 || scaled < i64::MIN as f64 {
+        return Err(ScaleError::Overflow { value, exponent });
+    }
+    Ok(scaled as i64)
+}
+
+/// 스케일된 가격/수량과 플래그로 16바이트 아이템 필드 구성
+fn scaled_price_quantity(price: f64, quantity: f64, flag: bool, scale: InstrumentScale) -> Result<(i64, i64), ScaleError> {
+    let price = scale.scale_price(price)?;
+    let quantity = scale.scale_quantity(quantity)?;
+    Ok((price, if flag { quantity | (1i64 << 63) } else { quantity }))
+}
+
+/// 와이어 구조체 역직렬화 오류
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WireError {
+    #[error("{structure} 길이 부족: {expected} bytes 필요, {actual} bytes 수신")]
+    TooShort { structure: &'static str, expected: usize, actual: usize },
+}
+
+/// 명시적 Little Endian 직렬화 (호스트 엔디언/정렬과 무관, unsafe 없음)
+///
+/// 필드를 명세 순서대로 LE로 기록하므로 메모리 레이아웃과 무관합니다 (`repr(C, packed)` 구조체와도 바이트 단위로 동일).
+pub trait WireFormat: Sized {
+    /// 와이어 상 크기 (bytes)
+    const WIRE_SIZE: usize;
+    /// 오류 메시지용 구조체 이름
+    const NAME: &'static str;
+
+    /// `WIRE_SIZE` 바이트를 `dst` 끝에 추가
+    fn encode_into(&self, dst: &mut Vec<u8>);
+
+    /// 길이가 검증된 `WIRE_SIZE` 바이트에서 필드 복원
+    fn decode_fields(reader: &mut WireReader<'_>) -> Self;
+
+    fn encode(&self) -> Vec<u8> {
+        let mut dst = Vec::with_capacity(Self::WIRE_SIZE);
+        self.encode_into(&mut dst);
+        dst
+    }
+
+    /// 앞 `WIRE_SIZE` 바이트를 해석 (이후 바이트는 무시)
+    fn decode(bytes: &[u8]) -> std::result::Result<Self, WireError> {
+        let bytes = bytes.get(..Self::WIRE_SIZE).ok_or(WireError::TooShort {
+            structure: Self::NAME,
+            expected: Self::WIRE_SIZE,
+            actual: bytes.len(),
+        })?;
+        Ok(Self::decode_fields(&mut WireReader::new(bytes)))
+    }
+}
+
+/// 길이 검증 후 사용하는 LE 필드 읽기 커서
+pub struct WireReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> WireReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn array<const N: usize>(&mut self) -> [u8; N] {
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        head.try_into().expect("split_at(N)은 N 바이트를 반환")
+    }
+
+    pub fn u8(&mut self) -> u8 { self.array::<1>()[0] }
+    pub fn u16(&mut self) -> u16 { u16::from_le_bytes(self.array()) }
+    pub fn u32(&mut self) -> u32 { u32::from_le_bytes(self.array()) }
+    pub fn u64(&mut self) -> u64 { u64::from_le_bytes(self.array()) }
+    pub fn i64(&mut self) -> i64 { i64::from_le_bytes(self.array()) }
+}
+
+/// `WireFormat` 구조체에 `TryFrom<&[u8]>` 구현
+macro_rules! impl_try_from_wire {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl TryFrom<&[u8]> for $ty {
+                type Error = $crate::protocol::WireError;
+
+                fn try_from(bytes: &[u8]) -> std::result::Result<Self, Self::Error> {
+                    <$ty as $crate::protocol::WireFormat>::decode(bytes)
+                }
+            }
+        )+
+    };
+}
+pub(crate) use impl_try_from_wire;
+
+impl WireFormat for PacketHeader {
+    const WIRE_SIZE: usize = 67;
+    const NAME: &'static str = "PacketHeader";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.push(self.protocol_version);
+        dst.extend_from_slice(&{ self.sequence_number }.to_le_bytes());
+        dst.extend_from_slice(&{ self.exchange_timestamp }.to_le_bytes());
+        dst.extend_from_slice(&{ self.local_timestamp }.to_le_bytes());
+        dst.push(self.message_type);
+        dst.push(self.flags_and_count);
+        dst.extend_from_slice(&self.symbol);
+        dst.extend_from_slice(&self.exchange);
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            protocol_version: r.u8(),
+            sequence_number: r.u64(),
+            exchange_timestamp: r.u64(),
+            local_timestamp: r.u64(),
+            message_type: r.u8(),
+            flags_and_count: r.u8(),
+            symbol: r.array(),
+            exchange: r.array(),
+        }
+    }
+}
+
+/// 가격 + 플래그 포함 수량 16바이트 아이템 공통 구현
+macro_rules! impl_price_quantity_wire {
+    ($($ty:ident),+) => {
+        $(
+            impl WireFormat for $ty {
+                const WIRE_SIZE: usize = 16;
+                const NAME: &'static str = stringify!($ty);
+
+                fn encode_into(&self, dst: &mut Vec<u8>) {
+                    dst.extend_from_slice(&{ self.price }.to_le_bytes());
+                    dst.extend_from_slice(&{ self.quantity_with_flags }.to_le_bytes());
+                }
+
+                fn decode_fields(r: &mut WireReader<'_>) -> Self {
+                    Self { price: r.i64(), quantity_with_flags: r.i64() }
+                }
+            }
+        )+
+    };
+}
+
+/// 단일 값 8바이트 아이템 공통 구현
+macro_rules! impl_value_wire {
+    ($($ty:ident),+) => {
+        $(
+            impl WireFormat for $ty {
+                const WIRE_SIZE: usize = 8;
+                const NAME: &'static str = stringify!($ty);
+
+                fn encode_into(&self, dst: &mut Vec<u8>) {
+                    dst.extend_from_slice(&{ self.value }.to_le_bytes());
+                }
+
+                fn decode_fields(r: &mut WireReader<'_>) -> Self {
+                    Self { value: r.i64() }
+                }
+            }
+        )+
+    };
+}
+
+impl_price_quantity_wire!(OrderBookItem, TradeTickItem, LiquidationItem);
+impl_value_wire!(PriceValueItem, FundingRateItem);
+impl WireFormat for PacketHeaderV2 {
+    const WIRE_SIZE: usize = 40;
+    const NAME: &'static str = "PacketHeaderV2";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.push(self.protocol_version);
+        dst.push(self.message_type);
+        dst.push(self.flags);
+        dst.push(self.item_count);
+        dst.extend_from_slice(&{ self.venue_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.payload_length }.to_le_bytes());
+        dst.extend_from_slice(&{ self.instrument_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.crc32 }.to_le_bytes());
+        dst.extend_from_slice(&{ self.sequence_number }.to_le_bytes());
+        dst.extend_from_slice(&{ self.exchange_timestamp }.to_le_bytes());
+        dst.extend_from_slice(&{ self.local_timestamp }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            protocol_version: r.u8(),
+            message_type: r.u8(),
+            flags: r.u8(),
+            item_count: r.u8(),
+            venue_id: r.u16(),
+            payload_length: r.u16(),
+            instrument_id: r.u32(),
+            crc32: r.u32(),
+            sequence_number: r.u64(),
+            exchange_timestamp: r.u64(),
+            local_timestamp: r.u64(),
+        }
+    }
+}
+
+impl WireFormat for TradeTickIdItem {
+    const WIRE_SIZE: usize = 32;
+    const NAME: &'static str = "TradeTickIdItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.quantity_with_flags }.to_le_bytes());
+        dst.extend_from_slice(&{ self.trade_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.trade_time }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { price: r.i64(), quantity_with_flags: r.i64(), trade_id: r.u64(), trade_time: r.u64() }
+    }
+}
+
+impl WireFormat for KlineItem {
+    const WIRE_SIZE: usize = 64;
+    const NAME: &'static str = "KlineItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.open }.to_le_bytes());
+        dst.extend_from_slice(&{ self.high }.to_le_bytes());
+        dst.extend_from_slice(&{ self.low }.to_le_bytes());
+        dst.extend_from_slice(&{ self.close }.to_le_bytes());
+        dst.extend_from_slice(&{ self.volume_with_flags }.to_le_bytes());
+        dst.extend_from_slice(&{ self.quote_volume }.to_le_bytes());
+        dst.extend_from_slice(&{ self.start_time }.to_le_bytes());
+        dst.extend_from_slice(&{ self.trade_count }.to_le_bytes());
+        dst.extend_from_slice(&{ self.interval_secs }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            open: r.i64(),
+            high: r.i64(),
+            low: r.i64(),
+            close: r.i64(),
+            volume_with_flags: r.i64(),
+            quote_volume: r.i64(),
+            start_time: r.u64(),
+            trade_count: r.u32(),
+            interval_secs: r.u32(),
+        }
+    }
+}
+
+impl WireFormat for Ticker24hItem {
+    const WIRE_SIZE: usize = 64;
+    const NAME: &'static str = "Ticker24hItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.last_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.open_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.high_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.low_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.volume }.to_le_bytes());
+        dst.extend_from_slice(&{ self.quote_volume }.to_le_bytes());
+        dst.extend_from_slice(&{ self.price_change_percent }.to_le_bytes());
+        dst.extend_from_slice(&{ self.trade_count }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            last_price: r.i64(),
+            open_price: r.i64(),
+            high_price: r.i64(),
+            low_price: r.i64(),
+            volume: r.i64(),
+            quote_volume: r.i64(),
+            price_change_percent: r.i64(),
+            trade_count: r.u64(),
+        }
+    }
+}
+
+impl WireFormat for OpenInterestItem {
+    const WIRE_SIZE: usize = 24;
+    const NAME: &'static str = "OpenInterestItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.open_interest }.to_le_bytes());
+        dst.extend_from_slice(&{ self.open_interest_value }.to_le_bytes());
+        dst.extend_from_slice(&{ self.long_short_ratio }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { open_interest: r.i64(), open_interest_value: r.i64(), long_short_ratio: r.i64() }
+    }
+}
+
+impl WireFormat for FundingInfoItem {
+    const WIRE_SIZE: usize = 32;
+    const NAME: &'static str = "FundingInfoItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.funding_rate }.to_le_bytes());
+        dst.extend_from_slice(&{ self.predicted_rate }.to_le_bytes());
+        dst.extend_from_slice(&{ self.next_funding_time }.to_le_bytes());
+        dst.extend_from_slice(&{ self.interval_secs }.to_le_bytes());
+        dst.extend_from_slice(&{ self.flags }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            funding_rate: r.i64(),
+            predicted_rate: r.i64(),
+            next_funding_time: r.u64(),
+            interval_secs: r.u32(),
+            flags: r.u32(),
+        }
+    }
+}
+
+impl WireFormat for LiquidationDetailItem {
+    const WIRE_SIZE: usize = 40;
+    const NAME: &'static str = "LiquidationDetailItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.quantity_with_flags }.to_le_bytes());
+        dst.extend_from_slice(&{ self.filled_quantity }.to_le_bytes());
+        dst.extend_from_slice(&{ self.order_time }.to_le_bytes());
+        dst.extend_from_slice(&{ self.order_status }.to_le_bytes());
+        dst.extend_from_slice(&{ self.reserved }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            price: r.i64(),
+            quantity_with_flags: r.i64(),
+            filled_quantity: r.i64(),
+            order_time: r.u64(),
+            order_status: r.u32(),
+            reserved: r.u32(),
+        }
+    }
+}
+
+impl WireFormat for ConsolidatedBboItem {
+    const WIRE_SIZE: usize = 40;
+    const NAME: &'static str = "ConsolidatedBboItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.bid_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.bid_quantity }.to_le_bytes());
+        dst.extend_from_slice(&{ self.ask_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.ask_quantity }.to_le_bytes());
+        dst.extend_from_slice(&{ self.bid_venue }.to_le_bytes());
+        dst.extend_from_slice(&{ self.ask_venue }.to_le_bytes());
+        dst.extend_from_slice(&{ self.venue_count }.to_le_bytes());
+        dst.extend_from_slice(&{ self.flags }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            bid_price: r.i64(),
+            bid_quantity: r.i64(),
+            ask_price: r.i64(),
+            ask_quantity: r.i64(),
+            bid_venue: r.u16(),
+            ask_venue: r.u16(),
+            venue_count: r.u16(),
+            flags: r.u16(),
+        }
+    }
+}
+
+impl WireFormat for KimchiPremiumItem {
+    const WIRE_SIZE: usize = 40;
+    const NAME: &'static str = "KimchiPremiumItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.premium }.to_le_bytes());
+        dst.extend_from_slice(&{ self.krw_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.foreign_price }.to_le_bytes());
+        dst.extend_from_slice(&{ self.fx_rate }.to_le_bytes());
+        dst.extend_from_slice(&{ self.foreign_venue }.to_le_bytes());
+        dst.extend_from_slice(&{ self.flags }.to_le_bytes());
+        dst.extend_from_slice(&{ self.reserved }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            premium: r.i64(),
+            krw_price: r.i64(),
+            foreign_price: r.i64(),
+            fx_rate: r.i64(),
+            foreign_venue: r.u16(),
+            flags: r.u16(),
+            reserved: r.u32(),
+        }
+    }
+}
+
+impl WireFormat for TradeFlowItem {
+    const WIRE_SIZE: usize = 40;
+    const NAME: &'static str = "TradeFlowItem";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.vwap }.to_le_bytes());
+        dst.extend_from_slice(&{ self.buy_volume }.to_le_bytes());
+        dst.extend_from_slice(&{ self.sell_volume }.to_le_bytes());
+        dst.extend_from_slice(&{ self.imbalance }.to_le_bytes());
+        dst.extend_from_slice(&{ self.window_secs }.to_le_bytes());
+        dst.extend_from_slice(&{ self.trade_count }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            vwap: r.i64(),
+            buy_volume: r.i64(),
+            sell_volume: r.i64(),
+            imbalance: r.i64(),
+            window_secs: r.u32(),
+            trade_count: r.u32(),
+        }
+    }
+}
+
+impl WireFormat for UpdateIdRange {
+    const WIRE_SIZE: usize = 16;
+    const NAME: &'static str = "UpdateIdRange";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.first_update_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.last_update_id }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self { first_update_id: r.u64(), last_update_id: r.u64() }
+    }
+}
+
+impl WireFormat for FragmentHeader {
+    const WIRE_SIZE: usize = 8;
+    const NAME: &'static str = "FragmentHeader";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.message_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.fragment_index }.to_le_bytes());
+        dst.extend_from_slice(&{ self.fragment_total }.to_le_bytes());
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            message_id: r.u32(),
+            fragment_index: r.u16(),
+            fragment_total: r.u16(),
+        }
+    }
+}
+
+impl FragmentHeader {
+    pub fn new(message_id: u32, fragment_index: u16, fragment_total: u16) -> Self {
+        Self { message_id, fragment_index, fragment_total }
+    }
+
+    pub fn is_last(&self) -> bool {
+        let (index, total) = (self.fragment_index, self.fragment_total);
+        index + 1 == total
+    }
+
+    /// 인덱스가 전체 개수 안에 있는지
+    pub fn is_valid(&self) -> bool {
+        let (index, total) = (self.fragment_index, self.fragment_total);
+        index < total
+    }
+}
+
+impl_try_from_wire!(PacketHeader, PacketHeaderV2, FragmentHeader, UpdateIdRange, OrderBookItem, TradeTickItem, TradeTickIdItem, KlineItem, Ticker24hItem, OpenInterestItem, FundingInfoItem, LiquidationItem, LiquidationDetailItem, ConsolidatedBboItem, KimchiPremiumItem, TradeFlowItem, PriceValueItem, FundingRateItem);
+
+impl PriceValueItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl FundingRateItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl TradeTickIdItem {
+    /// 종목 스케일을 적용하여 생성 (범위 초과 시 오류, trade_id가 없으면 0)
+    pub fn scaled(price: f64, quantity: f64, is_buyer_taker: bool, trade_id: Option<u64>, trade_time: u64, scale: InstrumentScale) -> Result<Self, ScaleError> {
+        let (price, quantity_with_flags) = scaled_price_quantity(price, quantity, is_buyer_taker, scale)?;
+        Ok(Self { price, quantity_with_flags, trade_id: trade_id.unwrap_or(0), trade_time })
+    }
+
+    pub fn is_buyer_taker(&self) -> bool {
+        (self.quantity_with_flags & (1i64 << 63)) != 0
+    }
+
+    pub fn quantity(&self) -> i64 {
+        self.quantity_with_flags & 0x7FFF_FFFF_FFFF_FFFF
+    }
+
+    /// 지정된 버퍼 끝에 추가 (추가 할당 회피)
+    pub fn append_to_vec(&self, dst: &mut Vec<u8>) {
+        self.encode_into(dst);
+    }
+}
+
+impl KlineItem {
+    pub fn is_closed(&self) -> bool {
+        (self.volume_with_flags & (1i64 << 63)) != 0
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.volume_with_flags & 0x7FFF_FFFF_FFFF_FFFF
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl Ticker24hItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl OpenInterestItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl FundingInfoItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// 예상 펀딩비 (제공되지 않으면 None, 10^8 스케일 값)
+    pub fn predicted_rate(&self) -> Option<i64> {
+        (self.flags & FUNDING_FLAG_PREDICTED_RATE != 0).then_some(self.predicted_rate)
+    }
+}
+
+impl LiquidationItem {
+    /// 종목 스케일을 적용하여 생성 (범위 초과 시 오류)
+    pub fn scaled(price: f64, quantity: f64, is_sell: bool, scale: InstrumentScale) -> Result<Self, ScaleError> {
+        let (price, quantity_with_flags) = scaled_price_quantity(price, quantity, is_sell, scale)?;
+        Ok(Self { price, quantity_with_flags })
+    }
+
+    /// 기본 스케일(10^8)로 생성 (범위 초과 시 오류)
+    pub fn new(price: f64, quantity: f64, is_sell: bool) -> Result<Self, ScaleError> {
+        Self::scaled(price, quantity, is_sell, InstrumentScale::DEFAULT)
+    }
+
+    /// is_sell 플래그를 추출하는 헬퍼 함수
+    pub fn is_sell(&self) -> bool {
+        (self.quantity_with_flags & (1i64 << 63)) != 0
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl LiquidationDetailItem {
+    /// 종목 스케일을 적용하여 생성 (범위 초과 시 오류)
+    pub fn scaled(price: f64, quantity: f64, filled_quantity: f64, is_sell: bool, scale: InstrumentScale) -> Result<Self, ScaleError> {
+        let (price, quantity_with_flags) = scaled_price_quantity(price, quantity, is_sell, scale)?;
+        let filled_quantity = scale.scale_quantity(filled_quantity)?;
+        Ok(Self { price, quantity_with_flags, filled_quantity, order_time: 0, order_status: LIQUIDATION_STATUS_UNKNOWN, reserved: 0 })
+    }
+
+    pub fn is_sell(&self) -> bool {
+        (self.quantity_with_flags & (1i64 << 63)) != 0
+    }
+
+    pub fn quantity(&self) -> i64 {
+        self.quantity_with_flags & 0x7FFF_FFFF_FFFF_FFFF
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl ConsolidatedBboItem {
+    pub fn is_locked(&self) -> bool {
+        self.flags & BBO_FLAG_LOCKED != 0
+    }
+
+    pub fn is_crossed(&self) -> bool {
+        self.flags & BBO_FLAG_CROSSED != 0
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl KimchiPremiumItem {
+    pub fn is_market_fx(&self) -> bool {
+        self.flags & KIMCHI_FLAG_MARKET_FX != 0
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+impl TradeFlowItem {
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+/// v2 헤더 crc32 필드 위치
+pub const V2_CRC_OFFSET: usize = 12;
+
+/// v2 헤더 payload_length 필드 위치
+const V2_PAYLOAD_LENGTH_OFFSET: usize = 6;
+
+/// null-terminated 고정 길이 문자열 필드 (set_symbol/set_exchange가 ASCII만 기록하므로 UTF-8 오류는 빈 문자열)
+fn null_terminated(bytes: &[u8]) -> &str {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..end]).unwrap_or("")
+}
+
+impl PacketHeaderV2 {
+    pub fn is_last(&self) -> bool { self.flags & V2_FLAG_LAST != 0 }
+    pub fn is_snapshot(&self) -> bool { self.flags & V2_FLAG_SNAPSHOT != 0 }
+    pub fn is_replay(&self) -> bool { self.flags & V2_FLAG_REPLAY != 0 }
+    pub fn has_crc(&self) -> bool { self.flags & V2_FLAG_CRC != 0 }
+    pub fn is_fragmented(&self) -> bool { self.flags & V2_FLAG_FRAGMENT != 0 }
+    pub fn has_update_ids(&self) -> bool { self.flags & V2_FLAG_UPDATE_IDS != 0 }
+
+    /// v1 헤더의 공통 필드(메시지 타입/마지막 패킷/아이템 수/타임스탬프)로 v2 헤더 생성
+    ///
+    /// 시퀀스와 CRC는 채널(포트)별로 전송 시 기록하므로 0으로 둡니다.
+    pub fn from_v1(header: &PacketHeader, venue_id: u16, instrument_id: u32) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION_V2,
+            message_type: header.message_type,
+            flags: if header.is_last() { V2_FLAG_LAST } else { 0 },
+            item_count: header.item_count(),
+            venue_id,
+            payload_length: 0,
+            instrument_id,
+            crc32: 0,
+            sequence_number: 0,
+            exchange_timestamp: header.exchange_timestamp,
+            local_timestamp: header.local_timestamp,
+        }
+    }
+
+    /// 헤더 + 페이로드를 직렬화 (V2_FLAG_CRC가 설정되어 있으면 CRC32를 계산해 채움)
+    pub fn encode_packet(&self, payload: &[u8]) -> Result<Vec<u8>, CryptoFeederError> {
+        self.encode_packet_with(payload.len(), |dst| dst.extend_from_slice(payload))
+    }
+
+    /// 페이로드를 `write_payload`로 버퍼에 바로 기록하여 직렬화 (payload_length는 기록된 길이, 확장 헤더 + 아이템을 복사 한 번으로 조립)
+    ///
+    /// 기록된 페이로드가 u16 payload_length 필드에 담기지 않으면 잘라 쓰지 않고 SerializationError를 반환합니다.
+    pub fn encode_packet_with(&self, payload_capacity: usize, write_payload: impl FnOnce(&mut Vec<u8>)) -> Result<Vec<u8>, CryptoFeederError> {
+        let header = PacketHeaderV2 { crc32: 0, ..*self };
+        let mut packet = Vec::with_capacity(Self::WIRE_SIZE + payload_capacity);
+        header.encode_into(&mut packet);
+        write_payload(&mut packet);
+        let payload_length = u16::try_from(packet.len() - Self::WIRE_SIZE).map_err(|_| CryptoFeederError::SerializationError(
+            format!("v2 페이로드 길이가 u16 범위를 초과함: {} bytes", packet.len() - Self::WIRE_SIZE)
+        ))?;
+        packet[V2_PAYLOAD_LENGTH_OFFSET..V2_PAYLOAD_LENGTH_OFFSET + 2].copy_from_slice(&payload_length.to_le_bytes());
+        if header.has_crc() {
+            let crc = crc32(&packet);
+            packet[V2_CRC_OFFSET..V2_CRC_OFFSET + 4].copy_from_slice(&crc.to_le_bytes());
+        }
+        Ok(packet)
+    }
+}
+
+/// v2 패킷 CRC 검증 (crc32 필드를 0으로 간주하고 계산)
+pub fn verify_v2_crc(packet: &[u8], expected: u32) -> bool {
+    if packet.len() < PacketHeaderV2::WIRE_SIZE {
+        return false;
+    }
+    let mut state = crc32_update(CRC32_INIT, &packet[..V2_CRC_OFFSET]);
+    state = crc32_update(state, &[0; 4]);
+    state = crc32_update(state, &packet[V2_CRC_OFFSET + 4..]);
+    !state == expected
+}
+
+const CRC32_INIT: u32 = 0xFFFF_FFFF;
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32_update(mut state: u32, bytes: &[u8]) -> u32 {
+    for &b in bytes {
+        state = CRC32_TABLE[((state ^ b as u32) & 0xFF) as usize] ^ (state >> 8);
+    }
+    state
+}
+
+/// CRC-32 (IEEE 802.3, zlib/Ethernet과 동일)
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !crc32_update(CRC32_INIT, bytes)
+}
+
 // 컴파일 타임에 구조체 크기 검증
 const _: () = assert!(mem::size_of::<PacketHeader>() == 67);
 const _: () = assert!(mem::size_of::<OrderBookItem>() == 16);
 const _: () = assert!(mem::size_of::<TradeTickItem>() == 16);
+const _: () = assert!(mem::size_of::<PacketHeader>() == <PacketHeader as WireFormat>::WIRE_SIZE);
+const _: () = assert!(mem::size_of::<LiquidationItem>() == <LiquidationItem as WireFormat>::WIRE_SIZE);
+const _: () = assert!(mem::size_of::<PriceValueItem>() == <PriceValueItem as WireFormat>::WIRE_SIZE);
 
 #[cfg(test)]
 mod tests {
@@ -321,7 +1325,7 @@ mod tests {
 
     #[test]
     fn test_trade_tick_flags() {
-        let item = TradeTickItem::new(50000.0, 1.5, true);
+        let item = TradeTickItem::new(50000.0, 1.5, true).unwrap();
         
         assert!(item.is_buyer_taker());
         assert_eq!(item.get_real_price(), 50000.0);
@@ -330,18 +1334,48 @@ mod tests {
 
     #[test]
     fn test_scaling() {
-        let item = OrderBookItem::new(42000.12345678, 0.00000001, true);
+        let item = OrderBookItem::new(42000.12345678, 0.00000001, true).unwrap();
         
         // 스케일링 정확성 검증 (소수점 8자리까지)
         assert!((item.get_real_price() - 42000.12345678).abs() < 0.00000001);
         assert!((item.get_real_quantity() - 0.00000001).abs() < 0.000000001);
         assert!(item.is_ask());
     }
-    
+
+    #[test]
+    fn test_per_instrument_scaling() {
+        // 10^8에서는 0으로 사라지는 SHIB 가격을 10^12로 보존
+        let shib = InstrumentScale::new(12, 4).unwrap();
+        let item = TradeTickItem::scaled(0.000012345678, 150_000_000.0, true, shib).unwrap();
+        assert_eq!({ item.price }, 12_345_678);
+        assert_eq!(item.quantity(), 1_500_000_000_000);
+        assert!(item.is_buyer_taker());
+        assert_eq!(shib.price(item.price), 0.000012345678);
+
+        // 기본 스케일은 기존 new()와 같은 값
+        let book = OrderBookItem::scaled(50_000.5, 1.25, true, InstrumentScale::DEFAULT).unwrap();
+        assert_eq!(book, OrderBookItem::new(50_000.5, 1.25, true).unwrap());
+
+        // 범위 초과/음수/비유한 값은 오류
+        assert_eq!(InstrumentScale::DEFAULT.scale_quantity(1e12), Err(ScaleError::Overflow { value: 1e12, exponent: 8 }));
+        assert!(LiquidationItem::scaled(1.0, -1.0, false, InstrumentScale::DEFAULT).is_err());
+        assert!(InstrumentScale::DEFAULT.scale_price(f64::NAN).is_err());
+        assert!(InstrumentScale::new(19, 8).is_none());
+    }
+
+    #[test]
+    fn test_parse_scale_list() {
+        let scales = InstrumentScale::parse_list("SHIB^USDT:12:4, BTC^KRW : 4 : 8,").unwrap();
+        assert_eq!(scales["SHIB^USDT"], InstrumentScale { price_exponent: 12, quantity_exponent: 4 });
+        assert_eq!(scales["BTC^KRW"], InstrumentScale { price_exponent: 4, quantity_exponent: 8 });
+        assert!(InstrumentScale::parse_list("SHIB^USDT:12").is_err());
+        assert!(InstrumentScale::parse_list("SHIB^USDT:30:4").is_err());
+    }
+
     #[test]
     fn test_orderbook_flags() {
-        let ask_item = OrderBookItem::new(50000.0, 1.5, true);
-        let bid_item = OrderBookItem::new(49999.0, 2.0, false);
+        let ask_item = OrderBookItem::new(50000.0, 1.5, true).unwrap();
+        let bid_item = OrderBookItem::new(49999.0, 2.0, false).unwrap();
         
         assert!(ask_item.is_ask());
         assert!(!bid_item.is_ask());
@@ -350,4 +1384,196 @@ mod tests {
         assert_eq!(bid_item.get_real_price(), 49999.0);
         assert_eq!(bid_item.get_real_quantity(), 2.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_header_wire_layout() {
+        let mut header = PacketHeader::new();
+        header.sequence_number = 0x0102_0304_0506_0708;
+        header.exchange_timestamp = 1_700_000_000_000_000_000;
+        header.set_packet_info(MESSAGE_TYPE_TRADE_TICK, 3, true);
+        header.set_symbol("BTC^USDT");
+        header.set_exchange("binance");
+
+        let bytes = header.to_bytes();
+        assert_eq!(bytes.len(), 67);
+        assert_eq!(bytes[0], 1);
+        assert_eq!(&bytes[1..9], &[8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(u64::from_le_bytes(bytes[9..17].try_into().unwrap()), 1_700_000_000_000_000_000);
+        assert_eq!(bytes[25], MESSAGE_TYPE_TRADE_TICK);
+        assert_eq!(bytes[26], 0b1000_0011);
+        assert_eq!(&bytes[27..35], b"BTC^USDT");
+        assert_eq!(&bytes[47..54], b"binance");
+        assert_eq!(PacketHeader::try_from(bytes.as_slice()).unwrap(), header);
+    }
+
+    #[test]
+    fn test_try_from_too_short() {
+        assert_eq!(
+            PacketHeader::try_from(&[0u8; 66][..]),
+            Err(WireError::TooShort { structure: "PacketHeader", expected: 67, actual: 66 })
+        );
+        assert!(matches!(OrderBookItem::try_from(&[0u8; 15][..]), Err(WireError::TooShort { expected: 16, .. })));
+        assert!(matches!(FundingRateItem::try_from(&[][..]), Err(WireError::TooShort { expected: 8, actual: 0, .. })));
+    }
+
+    #[test]
+    fn test_append_to_vec_matches_to_bytes() {
+        let item = OrderBookItem::new(101.5, 2.25, true).unwrap();
+        let mut buf = vec![0xAA];
+        item.append_to_vec(&mut buf);
+        assert_eq!(&buf[1..], item.to_bytes().as_slice());
+        assert_eq!(LiquidationItem::new(101.5, 2.25, true).unwrap().to_bytes(), item.to_bytes());
+        assert!(LiquidationItem::new(1.0, 1.0, true).unwrap().is_sell());
+    }
+
+    fn assert_bytes_round_trip<T: WireFormat + std::fmt::Debug + PartialEq>(bytes: &[u8]) {
+        let decoded = T::decode(bytes).unwrap();
+        assert_eq!(decoded.encode(), &bytes[..T::WIRE_SIZE]);
+        assert_eq!(T::decode(&decoded.encode()).unwrap(), decoded);
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_v2_packet_crc() {
+        let header = PacketHeaderV2 {
+            protocol_version: PROTOCOL_VERSION_V2,
+            message_type: MESSAGE_TYPE_ORDER_BOOK,
+            flags: V2_FLAG_LAST | V2_FLAG_CRC,
+            item_count: 1,
+            venue_id: 2,
+            payload_length: 0,
+            instrument_id: 7,
+            crc32: 0,
+            sequence_number: 1,
+            exchange_timestamp: 10,
+            local_timestamp: 11,
+        };
+        let mut packet = header.encode_packet(&OrderBookItem::new(100.0, 1.0, true).unwrap().to_bytes()).unwrap();
+        assert_eq!(packet.len(), 56);
+        let decoded = PacketHeaderV2::try_from(packet.as_slice()).unwrap();
+        assert_eq!({ decoded.payload_length }, 16);
+        assert!(decoded.is_last() && decoded.has_crc() && !decoded.is_snapshot());
+        let crc = decoded.crc32;
+        assert!(verify_v2_crc(&packet, crc));
+        packet[50] ^= 1;
+        assert!(!verify_v2_crc(&packet, crc));
+    }
+
+    #[test]
+    fn test_v2_packet_rejects_oversized_payload() {
+        let header = PacketHeaderV2::from_v1(&PacketHeader::default(), 1, 1);
+        assert!(header.encode_packet(&vec![0; u16::MAX as usize]).is_ok());
+        // u16 범위를 넘는 페이로드는 길이를 잘라 쓰지 않고 거부
+        assert!(matches!(header.encode_packet(&vec![0; u16::MAX as usize + 1]), Err(CryptoFeederError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_fragment_header_layout() {
+        let fragment = FragmentHeader::new(0x0102_0304, 2, 3);
+        assert_eq!(fragment.encode(), [0x04, 0x03, 0x02, 0x01, 2, 0, 3, 0]);
+        assert!(fragment.is_last() && fragment.is_valid());
+        assert!(!FragmentHeader::new(1, 3, 3).is_valid());
+
+        // item_count는 7비트 최대값에서 잘림
+        let mut header = PacketHeader::new();
+        header.set_flags_and_count(false, 200);
+        assert_eq!(header.item_count() as usize, MAX_ITEM_COUNT);
+        assert!(!header.is_last());
+    }
+
+    #[test]
+    fn test_trade_tick_id_item_layout() {
+        let item = TradeTickIdItem::scaled(50_000.0, 0.5, true, Some(123_456_789), 1_700_000_000_000_000_000, InstrumentScale::DEFAULT).unwrap();
+        let bytes = item.encode();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[..16], TradeTickItem::new(50_000.0, 0.5, true).unwrap().to_bytes().as_slice());
+        assert_eq!(&bytes[16..24], &123_456_789u64.to_le_bytes());
+        assert_eq!(&bytes[24..], &1_700_000_000_000_000_000u64.to_le_bytes());
+        assert!(item.is_buyer_taker());
+        assert_eq!({ TradeTickIdItem::scaled(1.0, 1.0, false, None, 0, InstrumentScale::DEFAULT).unwrap().trade_id }, 0);
+    }
+
+    #[test]
+    fn test_kline_item_layout() {
+        let item = KlineItem {
+            open: 1, high: 4, low: 0, close: 2, volume_with_flags: 7 | (1i64 << 63), quote_volume: 9,
+            start_time: 1_700_000_040_000_000_000, trade_count: 3, interval_secs: 60,
+        };
+        let bytes = item.to_bytes();
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(&bytes[32..40], &(7 | (1i64 << 63)).to_le_bytes());
+        assert_eq!(&bytes[48..56], &1_700_000_040_000_000_000u64.to_le_bytes());
+        assert_eq!(&bytes[60..], &60u32.to_le_bytes());
+        assert!(item.is_closed());
+        assert_eq!(item.volume(), 7);
+        assert_eq!(KlineItem::try_from(bytes.as_slice()).unwrap(), item);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_header_round_trip(
+            version: u8, seq: u64, exch_ts: u64, local_ts: u64, message_type: u8, flags: u8,
+            symbol: [u8; 20], exchange: [u8; 20],
+        ) {
+            let header = PacketHeader {
+                protocol_version: version,
+                sequence_number: seq,
+                exchange_timestamp: exch_ts,
+                local_timestamp: local_ts,
+                message_type,
+                flags_and_count: flags,
+                symbol,
+                exchange,
+            };
+            let bytes = header.to_bytes();
+            proptest::prop_assert_eq!(bytes.len(), PacketHeader::WIRE_SIZE);
+            proptest::prop_assert_eq!(PacketHeader::try_from(bytes.as_slice()).unwrap(), header);
+        }
+
+        #[test]
+        fn prop_items_round_trip(price: i64, quantity_with_flags: i64) {
+            let book = OrderBookItem { price, quantity_with_flags };
+            let trade = TradeTickItem { price, quantity_with_flags };
+            let liquidation = LiquidationItem { price, quantity_with_flags };
+            proptest::prop_assert_eq!(OrderBookItem::try_from(book.to_bytes().as_slice()).unwrap(), book);
+            proptest::prop_assert_eq!(TradeTickItem::try_from(trade.to_bytes().as_slice()).unwrap(), trade);
+            proptest::prop_assert_eq!(LiquidationItem::try_from(liquidation.to_bytes().as_slice()).unwrap(), liquidation);
+            proptest::prop_assert_eq!(&book.to_bytes()[..8], &price.to_le_bytes());
+        }
+
+        #[test]
+        fn prop_value_items_round_trip(value: i64) {
+            let price = PriceValueItem { value };
+            let funding = FundingRateItem { value };
+            proptest::prop_assert_eq!(PriceValueItem::try_from(price.to_bytes().as_slice()).unwrap(), price);
+            proptest::prop_assert_eq!(FundingRateItem::try_from(funding.to_bytes().as_slice()).unwrap(), funding);
+        }
+
+        #[test]
+        fn prop_arbitrary_bytes_round_trip(bytes in proptest::collection::vec(proptest::num::u8::ANY, 67..100)) {
+            assert_bytes_round_trip::<PacketHeader>(&bytes);
+            assert_bytes_round_trip::<PacketHeaderV2>(&bytes);
+            assert_bytes_round_trip::<FragmentHeader>(&bytes);
+            assert_bytes_round_trip::<UpdateIdRange>(&bytes);
+            assert_bytes_round_trip::<TradeTickIdItem>(&bytes);
+            assert_bytes_round_trip::<KlineItem>(&bytes);
+            assert_bytes_round_trip::<Ticker24hItem>(&bytes);
+            assert_bytes_round_trip::<OpenInterestItem>(&bytes);
+            assert_bytes_round_trip::<FundingInfoItem>(&bytes);
+            assert_bytes_round_trip::<OrderBookItem>(&bytes);
+            assert_bytes_round_trip::<TradeTickItem>(&bytes);
+            assert_bytes_round_trip::<LiquidationItem>(&bytes);
+            assert_bytes_round_trip::<LiquidationDetailItem>(&bytes);
+            assert_bytes_round_trip::<ConsolidatedBboItem>(&bytes);
+            assert_bytes_round_trip::<KimchiPremiumItem>(&bytes);
+            assert_bytes_round_trip::<TradeFlowItem>(&bytes);
+            assert_bytes_round_trip::<PriceValueItem>(&bytes);
+            assert_bytes_round_trip::<FundingRateItem>(&bytes);
+        }
+    }
+}
@@ -0,0 +1,526 @@
+//! 종목 참조 데이터 (InstrumentDefinition)
+//! udp_packet.md 7장: 참조 데이터 전용 포트로 종목 정의를 주기적으로 멀티캐스트
+//!
+//! - 종목 목록: symbol_config.ini의 거래소별 심볼
+//! - 메타데이터: exchangeInfo(Binance) / instruments(OKX) 형식의 로컬 JSON 파일 및/또는 거래소 REST 조회 (틱/로트/계약 승수)
+//! - ID: `InstrumentRegistry`의 venue_id/instrument_id (v2 헤더와 동일 값)
+
+use crate::config::{Config, ReferenceDataConfig};
+use crate::errors::{CryptoFeederError, Result};
+use crate::http_client;
+use crate::instruments::InstrumentRegistry;
+use crate::packet_builder::PacketBuilder;
+use crate::protocol::{impl_try_from_wire, InstrumentScale, WireFormat, WireReader};
+use crate::udp_broadcaster::UdpMulticaster;
+use crate::ws_connect::ConnectOptions;
+
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// 참조 데이터 메시지 타입 (50번대)
+pub const MESSAGE_TYPE_INSTRUMENT_DEFINITION: u8 = 50;
+
+// 시장 유형
+pub const MARKET_TYPE_UNKNOWN: u8 = 0;
+pub const MARKET_TYPE_SPOT: u8 = 1;
+pub const MARKET_TYPE_PERPETUAL: u8 = 2;
+pub const MARKET_TYPE_INVERSE_PERPETUAL: u8 = 3;
+pub const MARKET_TYPE_FUTURES: u8 = 4;
+
+/// REST 메타데이터 조회 제한 시간
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 종목 정의 (96 바이트)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InstrumentDefinition {
+    pub instrument_id: u32,        // 4B, v2 헤더 instrument_id
+    pub venue_id: u16,             // 2B, v2 헤더 venue_id
+    pub market_type: u8,           // 1B, MARKET_TYPE_*
+    pub price_scale: u8,           // 1B, 가격 스케일 지수 (10^n)
+    pub quantity_scale: u8,        // 1B, 수량 스케일 지수 (10^n)
+    pub reserved: [u8; 3],         // 3B
+    pub tick_size: i64,            // 8B, 가격 스케일 적용 (0 = 알 수 없음)
+    pub lot_size: i64,             // 8B, 수량 스케일 적용 (0 = 알 수 없음)
+    pub contract_multiplier: i64,  // 8B, 수량 스케일 적용 (현물 = 1)
+    pub exchange: [u8; 20],        // 20B, 거래소 표시명
+    pub symbol: [u8; 20],          // 20B, 표준 심볼 (A^B)
+    pub base: [u8; 10],            // 10B, 기준 자산
+    pub quote: [u8; 10],           // 10B, 호가 자산
+} // 총 96 바이트
+
+impl WireFormat for InstrumentDefinition {
+    const WIRE_SIZE: usize = 96;
+    const NAME: &'static str = "InstrumentDefinition";
+
+    fn encode_into(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&{ self.instrument_id }.to_le_bytes());
+        dst.extend_from_slice(&{ self.venue_id }.to_le_bytes());
+        dst.push(self.market_type);
+        dst.push(self.price_scale);
+        dst.push(self.quantity_scale);
+        dst.extend_from_slice(&self.reserved);
+        dst.extend_from_slice(&{ self.tick_size }.to_le_bytes());
+        dst.extend_from_slice(&{ self.lot_size }.to_le_bytes());
+        dst.extend_from_slice(&{ self.contract_multiplier }.to_le_bytes());
+        dst.extend_from_slice(&self.exchange);
+        dst.extend_from_slice(&self.symbol);
+        dst.extend_from_slice(&self.base);
+        dst.extend_from_slice(&self.quote);
+    }
+
+    fn decode_fields(r: &mut WireReader<'_>) -> Self {
+        Self {
+            instrument_id: r.u32(),
+            venue_id: r.u16(),
+            market_type: r.u8(),
+            price_scale: r.u8(),
+            quantity_scale: r.u8(),
+            reserved: r.array(),
+            tick_size: r.i64(),
+            lot_size: r.i64(),
+            contract_multiplier: r.i64(),
+            exchange: r.array(),
+            symbol: r.array(),
+            base: r.array(),
+            quote: r.array(),
+        }
+    }
+}
+
+impl_try_from_wire!(InstrumentDefinition);
+
+impl InstrumentDefinition {
+    pub fn exchange_str(&self) -> String { text(&self.exchange) }
+    pub fn symbol_str(&self) -> String { text(&self.symbol) }
+    pub fn base_str(&self) -> String { text(&self.base) }
+    pub fn quote_str(&self) -> String { text(&self.quote) }
+
+    /// 이 종목의 가격/수량 스케일 (시장 데이터 아이템 해석에 사용)
+    pub fn scale(&self) -> InstrumentScale {
+        InstrumentScale { price_exponent: self.price_scale, quantity_exponent: self.quantity_scale }
+    }
+
+    pub fn tick_size_f64(&self) -> f64 { self.scale().price(self.tick_size) }
+    pub fn lot_size_f64(&self) -> f64 { self.scale().quantity(self.lot_size) }
+    pub fn contract_multiplier_f64(&self) -> f64 { self.scale().quantity(self.contract_multiplier) }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        self.encode()
+    }
+}
+
+/// 고정 길이 필드에 문자열 기록 (길이 초과 시 잘림, 나머지는 0)
+fn fixed<const N: usize>(value: &str) -> [u8; N] {
+    let mut out = [0u8; N];
+    let bytes = value.as_bytes();
+    let len = bytes.len().min(N);
+    out[..len].copy_from_slice(&bytes[..len]);
+    out
+}
+
+fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// 시장 유형 이름 (로그/디코더 표시용)
+pub fn market_type_name(market_type: u8) -> &'static str {
+    match market_type {
+        MARKET_TYPE_SPOT => "spot",
+        MARKET_TYPE_PERPETUAL => "perpetual",
+        MARKET_TYPE_INVERSE_PERPETUAL => "inverse_perpetual",
+        MARKET_TYPE_FUTURES => "futures",
+        _ => "unknown",
+    }
+}
+
+/// 거래소 표시명으로 추정한 시장 유형 (메타데이터가 있으면 그 값이 우선)
+pub fn market_type_for(exchange: &str) -> u8 {
+    match exchange {
+        "BinanceFutures" | "BybitLinear" | "OkxSwap" => MARKET_TYPE_PERPETUAL,
+        "BybitInverse" => MARKET_TYPE_INVERSE_PERPETUAL,
+        "OkxFutures" => MARKET_TYPE_FUTURES,
+        name if name.ends_with("Spot") => MARKET_TYPE_SPOT,
+        _ => MARKET_TYPE_UNKNOWN,
+    }
+}
+
+/// 종목별 거래소 메타데이터
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstrumentMetadata {
+    pub tick_size: f64,
+    pub lot_size: f64,
+    pub contract_multiplier: f64,
+    pub market_type: Option<u8>,
+}
+
+/// (거래소 표시명, 표준 심볼)별 메타데이터 저장소
+///
+/// JSON 형식: `{ "<거래소 표시명>": <exchangeInfo 응답 | OKX instruments 응답>, ... }`
+/// exchangeInfo의 `symbols[]`에서 baseAsset/quoteAsset, PRICE_FILTER.tickSize, LOT_SIZE.stepSize,
+/// contractSize(선택), contractType(선택, PERPETUAL 외 만기물은 제외)을 사용합니다.
+/// OKX `/api/v5/public/instruments` 응답은 `data[]`의 instId, tickSz, lotSz, ctVal(SWAP 계약당 기준 자산 수량)을 사용합니다.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataStore {
+    entries: HashMap<(String, String), InstrumentMetadata>,
+}
+
+impl MetadataStore {
+    pub fn from_json(content: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(content)
+            .map_err(|e| CryptoFeederError::JsonParseError(format!("메타데이터 JSON: {}", e)))?;
+        let exchanges = root.as_object()
+            .ok_or_else(|| CryptoFeederError::JsonParseError("메타데이터 JSON 최상위는 거래소별 객체여야 합니다".to_string()))?;
+        let mut store = Self::default();
+        for (exchange, info) in exchanges {
+            store.merge_exchange_info(exchange, info);
+        }
+        Ok(store)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// `reference_metadata_file` 로드 (미설정이면 빈 저장소, 실패는 경고 후 빈 저장소)
+    pub fn load_configured(settings: &ReferenceDataConfig) -> Self {
+        let Some(path) = &settings.metadata_file else { return Self::default() };
+        match Self::load(Path::new(path)) {
+            Ok(store) => {
+                info!("📚 메타데이터 파일 로드: {} ({}개 종목)", path, store.len());
+                store
+            }
+            Err(e) => {
+                warn!("⚠️ 메타데이터 파일 로드 실패 {}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// exchangeInfo 응답의 종목을 추가 (기존 값은 덮어씀), 추가된 종목 수 반환
+    pub fn merge_exchange_info(&mut self, exchange: &str, info: &Value) -> usize {
+        if let Some(instruments) = info.get("data").and_then(Value::as_array) {
+            return self.merge_okx_instruments(exchange, instruments);
+        }
+        let Some(symbols) = info.get("symbols").and_then(Value::as_array) else { return 0 };
+        let mut merged = 0;
+        for entry in symbols {
+            let contract_type = entry.get("contractType").and_then(Value::as_str).unwrap_or("");
+            if !contract_type.is_empty() && contract_type != "PERPETUAL" {
+                continue;
+            }
+            let (Some(base), Some(quote)) = (
+                entry.get("baseAsset").and_then(Value::as_str),
+                entry.get("quoteAsset").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            let filter = |filter_type: &str, field: &str| {
+                entry.get("filters").and_then(Value::as_array)?.iter()
+                    .find(|f| f.get("filterType").and_then(Value::as_str) == Some(filter_type))
+                    .and_then(|f| f.get(field))
+                    .and_then(number)
+            };
+            let metadata = InstrumentMetadata {
+                tick_size: filter("PRICE_FILTER", "tickSize").unwrap_or(0.0),
+                lot_size: filter("LOT_SIZE", "stepSize").unwrap_or(0.0),
+                contract_multiplier: entry.get("contractSize").and_then(number).unwrap_or(1.0),
+                market_type: (contract_type == "PERPETUAL").then_some(MARKET_TYPE_PERPETUAL),
+            };
+            let symbol = format!("{}^{}", base.to_ascii_uppercase(), quote.to_ascii_uppercase());
+            self.entries.insert((exchange.to_string(), symbol), metadata);
+            merged += 1;
+        }
+        merged
+    }
+
+    /// OKX instruments 응답의 SPOT/SWAP 종목을 추가 (만기물/옵션과 ctVal이 quote 통화인 inverse 무기한은 제외)
+    fn merge_okx_instruments(&mut self, exchange: &str, instruments: &[Value]) -> usize {
+        let mut merged = 0;
+        for entry in instruments {
+            let field = |name: &str| entry.get(name).and_then(Value::as_str).unwrap_or("");
+            let market_type = match field("instType") {
+                "SPOT" => MARKET_TYPE_SPOT,
+                "SWAP" if field("ctType") != "inverse" => MARKET_TYPE_PERPETUAL,
+                _ => continue,
+            };
+            let mut parts = field("instId").split('-');
+            let (Some(base), Some(quote)) = (parts.next(), parts.next()) else { continue };
+            let metadata = InstrumentMetadata {
+                tick_size: entry.get("tickSz").and_then(number).unwrap_or(0.0),
+                lot_size: entry.get("lotSz").and_then(number).unwrap_or(0.0),
+                contract_multiplier: entry.get("ctVal").and_then(number).unwrap_or(1.0),
+                market_type: Some(market_type),
+            };
+            self.entries.insert((exchange.to_string(), format!("{}^{}", base, quote)), metadata);
+            merged += 1;
+        }
+        merged
+    }
+
+    /// 계약 단위로 수량을 보내는 종목의 계약당 기준 자산 수량 ((거래소, 심볼)별, 승수 1은 제외)
+    pub fn contract_values(&self) -> HashMap<(String, String), f64> {
+        self.entries.iter()
+            .filter(|(_, m)| m.contract_multiplier.is_finite() && m.contract_multiplier > 0.0 && m.contract_multiplier != 1.0)
+            .map(|(key, m)| (key.clone(), m.contract_multiplier))
+            .collect()
+    }
+
+    pub fn get(&self, exchange: &str, symbol: &str) -> Option<&InstrumentMetadata> {
+        self.entries.get(&(exchange.to_string(), symbol.to_string()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 문자열("0.01") 또는 숫자 JSON 값
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// REST exchangeInfo 조회를 지원하는 거래소의 URL
+pub fn exchange_info_url(exchange: &str) -> Option<&'static str> {
+    match exchange {
+        "BinanceSpot" => Some("https://api.binance.com/api/v3/exchangeInfo"),
+        "BinanceFutures" => Some("https://fapi.binance.com/fapi/v1/exchangeInfo"),
+        _ => None,
+    }
+}
+
+/// 설정된 거래소 중 조회 가능한 거래소의 exchangeInfo를 받아 병합 (실패한 거래소는 경고 후 건너뜀)
+pub async fn fetch_metadata(config: &Config, store: &mut MetadataStore) {
+    let Some(symbol_config) = &config.symbol_config else { return };
+    let exchanges: BTreeSet<&String> = symbol_config.exchanges.keys().collect();
+    for exchange in exchanges {
+        let Some(url) = exchange_info_url(exchange) else { continue };
+        let endpoint = config.endpoint_config.as_ref().and_then(|e| e.get_exchange_endpoint(exchange));
+        let result = async {
+            let options = ConnectOptions::for_session(endpoint, 0)?;
+            let body = http_client::get(&url::Url::parse(url)?, &options, FETCH_TIMEOUT).await?;
+            serde_json::from_str::<Value>(&body).map_err(|e| CryptoFeederError::JsonParseError(e.to_string()))
+        }.await;
+        match result {
+            Ok(info) => info!("📚 {} exchangeInfo 조회: {}개 종목", exchange, store.merge_exchange_info(exchange, &info)),
+            Err(e) => warn!("⚠️ {} exchangeInfo 조회 실패: {}", exchange, e),
+        }
+    }
+}
+
+/// symbol_config.ini의 거래소/심볼로 종목 정의 목록 생성 (거래소 이름, 심볼 순)
+pub fn build_definitions(config: &Config, registry: &InstrumentRegistry, metadata: &MetadataStore) -> Vec<InstrumentDefinition> {
+    let Some(symbol_config) = &config.symbol_config else { return Vec::new() };
+    let exchanges: BTreeMap<&String, BTreeSet<&String>> = symbol_config.exchanges.iter()
+        .map(|(name, group)| (name, group.sessions.iter().flat_map(|s| s.symbols.iter()).collect()))
+        .collect();
+
+    let mut definitions = Vec::new();
+    for (exchange, symbols) in exchanges {
+        for symbol in symbols {
+            let (base, quote) = symbol.split_once('^').unwrap_or((symbol.as_str(), ""));
+            let meta = metadata.get(exchange, symbol);
+            let scale = config.instrument_scales.get(symbol.as_str()).copied().unwrap_or_default();
+            // 범위를 벗어나는 틱/로트는 알 수 없음(0)으로 전송
+            let tick_size = meta.and_then(|m| scale.scale_price(m.tick_size).ok()).unwrap_or(0);
+            let lot_size = meta.and_then(|m| scale.scale_quantity(m.lot_size).ok()).unwrap_or(0);
+            let contract_multiplier = scale.scale_quantity(meta.map(|m| m.contract_multiplier).unwrap_or(1.0)).unwrap_or(0);
+            definitions.push(InstrumentDefinition {
+                instrument_id: registry.instrument_id(symbol),
+                venue_id: registry.venue_id(exchange),
+                market_type: meta.and_then(|m| m.market_type).unwrap_or_else(|| market_type_for(exchange)),
+                price_scale: scale.price_exponent,
+                quantity_scale: scale.quantity_exponent,
+                reserved: [0; 3],
+                tick_size,
+                lot_size,
+                contract_multiplier,
+                exchange: fixed(exchange),
+                symbol: fixed(symbol),
+                base: fixed(base),
+                quote: fixed(quote),
+            });
+        }
+    }
+    definitions
+}
+
+/// 참조 데이터 포트로 종목 정의를 주기적으로 재전송하는 발행기
+pub struct ReferenceDataPublisher {
+    settings: ReferenceDataConfig,
+    registry: Arc<InstrumentRegistry>,
+    metadata: MetadataStore,
+    packet_builder: Arc<PacketBuilder>,
+    broadcaster: Arc<UdpMulticaster>,
+    // ID 파일에 마지막으로 저장한 종목 수 (핫 리로드로 새 심볼이 배정되면 다시 저장)
+    saved_instruments: AtomicUsize,
+}
+
+impl ReferenceDataPublisher {
+    /// 메타데이터 파일 로드 및 (설정 시) REST 조회
+    pub async fn new(config: &Config, registry: Arc<InstrumentRegistry>, packet_builder: Arc<PacketBuilder>, broadcaster: Arc<UdpMulticaster>) -> Self {
+        let settings = config.reference.clone();
+        let mut metadata = MetadataStore::load_configured(&settings);
+        if settings.fetch {
+            fetch_metadata(config, &mut metadata).await;
+        }
+        let saved_instruments = AtomicUsize::new(registry.instruments().len());
+        Self { settings, registry, metadata, packet_builder, broadcaster, saved_instruments }
+    }
+
+    /// 현재 설정의 전체 종목 정의를 한 번 전송하고 전송한 정의 수를 반환
+    pub async fn publish(&self, config: &Config) -> Result<usize> {
+        let definitions = build_definitions(config, &self.registry, &self.metadata);
+        for packet in self.packet_builder.build_instrument_definition_packets(&definitions)? {
+            self.broadcaster.send_packet_to_port(packet, self.settings.port).await?;
+        }
+
+        let assigned = self.registry.instruments().len();
+        if let Some(path) = &self.settings.id_file {
+            if self.saved_instruments.swap(assigned, Ordering::Relaxed) != assigned {
+                self.registry.save_ids(Path::new(path))?;
+            }
+        }
+        Ok(definitions.len())
+    }
+
+    /// 주기적 발행 태스크 시작 (`config_source`로 핫 리로드된 최신 설정 사용)
+    pub fn spawn(self, config_source: impl Fn() -> Arc<Config> + Send + 'static) {
+        let interval_secs = self.settings.interval_secs.max(1);
+        info!("📚 참조 데이터 발행 시작: 포트 {}, {}초 간격", self.settings.port, interval_secs);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                ticker.tick().await;
+                match self.publish(&config_source()).await {
+                    Ok(count) => debug!("📚 종목 정의 {}개 전송", count),
+                    Err(e) => warn!("⚠️ 참조 데이터 전송 실패: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ExchangeSymbolGroup, SymbolConfig, SymbolSession};
+    use crate::protocol::PacketHeader;
+
+    const EXCHANGE_INFO: &str = r#"{
+        "BinanceFutures": { "symbols": [
+            { "symbol": "BTCUSDT", "baseAsset": "BTC", "quoteAsset": "USDT", "contractType": "PERPETUAL",
+              "filters": [ { "filterType": "PRICE_FILTER", "tickSize": "0.10" }, { "filterType": "LOT_SIZE", "stepSize": "0.001" } ] },
+            { "symbol": "BTCUSDT_250328", "baseAsset": "BTC", "quoteAsset": "USDT", "contractType": "CURRENT_QUARTER",
+              "filters": [ { "filterType": "PRICE_FILTER", "tickSize": "1" } ] }
+        ] }
+    }"#;
+
+    fn config_with(exchange: &str, symbols: &[&str]) -> Config {
+        let mut config = Config::load().unwrap();
+        let session = SymbolSession { symbols: symbols.iter().map(|s| s.to_string()).collect(), is_btc_session: false, port: 55555, streams: Default::default() };
+        let group = ExchangeSymbolGroup { exchange_name: exchange.to_string(), sessions: vec![session] };
+        config.symbol_config = Some(SymbolConfig { exchanges: HashMap::from([(exchange.to_string(), group)]) });
+        config
+    }
+
+    #[test]
+    fn test_definition_wire_layout() {
+        let definition = InstrumentDefinition {
+            instrument_id: 7, venue_id: 2, market_type: MARKET_TYPE_PERPETUAL, price_scale: 8, quantity_scale: 8, reserved: [0; 3],
+            tick_size: 10_000_000, lot_size: 100_000, contract_multiplier: 100_000_000,
+            exchange: fixed("BinanceFutures"), symbol: fixed("BTC^USDT"), base: fixed("BTC"), quote: fixed("USDT"),
+        };
+        let bytes = definition.to_bytes();
+        assert_eq!(bytes.len(), InstrumentDefinition::WIRE_SIZE);
+        assert_eq!(&bytes[0..4], &7u32.to_le_bytes());
+        assert_eq!(&bytes[12..20], &10_000_000i64.to_le_bytes());
+        assert_eq!(&bytes[36..50], b"BinanceFutures");
+        assert_eq!(InstrumentDefinition::try_from(bytes.as_slice()).unwrap(), definition);
+        assert!(InstrumentDefinition::try_from(&bytes[..95]).is_err());
+    }
+
+    #[test]
+    fn test_build_definitions_with_metadata() {
+        let metadata = MetadataStore::from_json(EXCHANGE_INFO).unwrap();
+        assert_eq!(metadata.len(), 1, "만기물은 제외");
+
+        let config = config_with("BinanceFutures", &["ETH^USDT", "BTC^USDT"]);
+        let registry = InstrumentRegistry::from_config(&config);
+        let definitions = build_definitions(&config, &registry, &metadata);
+        assert_eq!(definitions.len(), 2);
+
+        let btc = &definitions[0];
+        assert_eq!(btc.symbol_str(), "BTC^USDT");
+        assert_eq!((btc.base_str(), btc.quote_str()), ("BTC".to_string(), "USDT".to_string()));
+        assert_eq!({ btc.instrument_id }, registry.instrument_id("BTC^USDT"));
+        assert_eq!({ btc.venue_id }, 2);
+        assert_eq!(btc.market_type, MARKET_TYPE_PERPETUAL);
+        assert_eq!(({ btc.tick_size }, { btc.lot_size }, { btc.contract_multiplier }), (10_000_000, 100_000, 100_000_000));
+
+        // 메타데이터가 없으면 틱/로트 0, 시장 유형은 거래소명 기준
+        let eth = &definitions[1];
+        assert_eq!(({ eth.tick_size }, { eth.lot_size }), (0, 0));
+        assert_eq!(eth.market_type, MARKET_TYPE_PERPETUAL);
+    }
+
+    #[test]
+    fn test_okx_instruments_metadata() {
+        let metadata = MetadataStore::from_json(r#"{ "OkxSwap": { "code": "0", "data": [
+            { "instType": "SWAP", "instId": "BTC-USDT-SWAP", "ctType": "linear", "ctVal": "0.01", "ctValCcy": "BTC", "tickSz": "0.1", "lotSz": "0.01" },
+            { "instType": "SWAP", "instId": "BTC-USD-SWAP", "ctType": "inverse", "ctVal": "100", "ctValCcy": "USD", "tickSz": "0.1", "lotSz": "1" },
+            { "instType": "FUTURES", "instId": "BTC-USDT-250328", "ctType": "linear", "ctVal": "0.01", "tickSz": "0.1", "lotSz": "1" }
+        ] } }"#).unwrap();
+        assert_eq!(metadata.len(), 1, "inverse 무기한과 만기물은 제외");
+        let btc = metadata.get("OkxSwap", "BTC^USDT").unwrap();
+        assert_eq!((btc.tick_size, btc.lot_size, btc.contract_multiplier), (0.1, 0.01, 0.01));
+        assert_eq!(btc.market_type, Some(MARKET_TYPE_PERPETUAL));
+        assert_eq!(metadata.contract_values(), HashMap::from([(("OkxSwap".to_string(), "BTC^USDT".to_string()), 0.01)]));
+        // 승수 1(현물/USDⓈ-M)은 환산 대상 아님
+        assert!(MetadataStore::from_json(EXCHANGE_INFO).unwrap().contract_values().is_empty());
+    }
+
+    #[test]
+    fn test_definitions_carry_instrument_scale() {
+        let metadata = MetadataStore::from_json(EXCHANGE_INFO).unwrap();
+        let mut config = config_with("BinanceFutures", &["BTC^USDT"]);
+        config.instrument_scales.insert("BTC^USDT".to_string(), InstrumentScale::new(10, 3).unwrap());
+        let definitions = build_definitions(&config, &InstrumentRegistry::from_config(&config), &metadata);
+
+        let btc = &definitions[0];
+        assert_eq!((btc.price_scale, btc.quantity_scale), (10, 3));
+        assert_eq!(({ btc.tick_size }, { btc.lot_size }, { btc.contract_multiplier }), (1_000_000_000, 1, 1_000));
+        assert_eq!((btc.tick_size_f64(), btc.lot_size_f64()), (0.1, 0.001));
+    }
+
+    #[test]
+    fn test_definition_packets_split_and_decode() {
+        let symbols: Vec<String> = (0..20).map(|i| format!("C{}^USDT", i)).collect();
+        let config = config_with("BinanceSpot", &symbols.iter().map(String::as_str).collect::<Vec<_>>());
+        let registry = InstrumentRegistry::from_config(&config);
+        let definitions = build_definitions(&config, &registry, &MetadataStore::default());
+
+        let packets = PacketBuilder::new().build_instrument_definition_packets(&definitions).unwrap();
+        assert_eq!(packets.len(), 2);
+        let mut decoded = Vec::new();
+        for (i, packet) in packets.iter().enumerate() {
+            let header = PacketHeader::try_from(packet.data.as_slice()).unwrap();
+            assert_eq!(header.message_type, MESSAGE_TYPE_INSTRUMENT_DEFINITION);
+            assert_eq!(header.is_last(), i == 1);
+            let payload = &packet.data[PacketHeader::WIRE_SIZE..];
+            assert_eq!(payload.len(), header.item_count() as usize * InstrumentDefinition::WIRE_SIZE);
+            decoded.extend(payload.chunks(InstrumentDefinition::WIRE_SIZE).map(|b| InstrumentDefinition::try_from(b).unwrap()));
+        }
+        assert_eq!(decoded, definitions);
+        assert!(decoded.iter().all(|d| d.market_type == MARKET_TYPE_SPOT));
+    }
+}
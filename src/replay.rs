@@ -0,0 +1,408 @@
+//! 저널 리플레이
+//! 기록된 원시 프레임을 WebSocket 대신 실시간 수신 루프와 같은 처리 단계(`FeedStage`) → UdpMulticaster 경로로 재생
+//!
+//! 세션 메타데이터 레코드로 세션 필터(부가 스트림, 청산 범위)를 적용하고, 티커 병합/로컬 봉/파생 데이터도 실시간과 같이 처리합니다.
+//! 메타데이터가 없는 세션(기본 경로, 이전 저널)은 필터 없이 파싱/패킷 생성만 합니다.
+//! 주기 발행(봉 마감, 병합 티커, 파생 데이터)은 기록된 수신 시각 기준의 발행 간격으로 실행하며, 마지막 레코드 뒤에도 다음 발행 시각까지 마무리 발행합니다.
+//!
+//! 여러 저널 파일은 수신 시각(recv_timestamp_ns) 기준으로 병합되며, 같은 시각이면 입력 파일 순서를 따릅니다.
+//! 결정적 모드에서는 패킷 헤더 local_timestamp를 기록된 수신 시각으로 고정하여 같은 입력에 대해
+//! 바이트 단위로 동일한 패킷을 생성합니다 (시퀀스 번호는 새 PacketBuilder 기준 1부터 시작).
+
+use crate::config::Config;
+use crate::data_parser::{DataParser, ParsedData};
+use crate::errors::{CryptoFeederError, Result};
+use crate::feed_stage::{FeedStage, SessionFilter};
+use crate::journal::{list_journal_files, JournalReader, JournalRecord, SessionMeta};
+use crate::packet_builder::{PacketBuilder, UdpPacket};
+use crate::udp_broadcaster::UdpMulticaster;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 재생 속도
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// 대기 없이 최대 속도
+    AsFastAsPossible,
+    /// 기록된 수신 간격 그대로
+    Realtime,
+    /// 기록된 간격의 1/N (N배속)
+    Multiplier(f64),
+}
+
+impl ReplaySpeed {
+    /// `max`, `realtime`, `10x`/`10` 형식 파싱
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "max" | "fast" => Some(Self::AsFastAsPossible),
+            "realtime" | "1x" => Some(Self::Realtime),
+            v => {
+                let n: f64 = v.trim_end_matches('x').parse().ok()?;
+                (n.is_finite() && n > 0.0).then_some(Self::Multiplier(n))
+            }
+        }
+    }
+
+    /// 기록상 경과 시간에 대응하는 재생 경과 시간 (최대 속도면 None)
+    pub fn scaled(&self, elapsed_ns: u64) -> Option<Duration> {
+        match self {
+            Self::AsFastAsPossible => None,
+            Self::Realtime => Some(Duration::from_nanos(elapsed_ns)),
+            Self::Multiplier(n) => Some(Duration::from_nanos((elapsed_ns as f64 / n) as u64)),
+        }
+    }
+}
+
+/// 리플레이 옵션
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    /// 저널 파일 또는 디렉터리 (디렉터리는 하위 .wsj 파일 전체)
+    pub inputs: Vec<PathBuf>,
+    pub speed: ReplaySpeed,
+    /// local_timestamp를 기록된 수신 시각으로 고정
+    pub deterministic: bool,
+}
+
+/// 리플레이 결과 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    pub records: u64,
+    pub parse_errors: u64,
+    pub packets_sent: u64,
+    pub send_errors: u64,
+}
+
+/// 입력 경로를 저널 파일 목록으로 확장 (디렉터리는 정렬된 하위 파일)
+pub fn resolve_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            files.extend(list_journal_files(input));
+        } else if input.is_file() {
+            files.push(input.clone());
+        } else {
+            return Err(CryptoFeederError::Other(format!("리플레이 입력을 찾을 수 없음: {}", input.display())));
+        }
+    }
+    Ok(files)
+}
+
+/// 여러 저널을 수신 시각 순으로 병합하는 반복자
+pub struct MergedJournal<R: Read> {
+    readers: Vec<JournalReader<R>>,
+    heads: Vec<Option<JournalRecord>>,
+}
+
+impl<R: Read> MergedJournal<R> {
+    pub fn new(readers: Vec<JournalReader<R>>) -> Self {
+        let mut merged = Self { heads: Vec::with_capacity(readers.len()), readers };
+        for idx in 0..merged.readers.len() {
+            let head = merged.read_next(idx);
+            merged.heads.push(head);
+        }
+        merged
+    }
+
+    /// 파일의 다음 레코드 (손상/잘린 레코드 이후는 해당 파일 재생 중단)
+    fn read_next(&mut self, idx: usize) -> Option<JournalRecord> {
+        match self.readers[idx].next()? {
+            Ok(record) => Some(record),
+            Err(e) => {
+                warn!("⚠️ 저널 #{} 읽기 중단: {}", idx, e);
+                None
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for MergedJournal<R> {
+    type Item = JournalRecord;
+
+    fn next(&mut self) -> Option<JournalRecord> {
+        let idx = self.heads.iter().enumerate()
+            .filter_map(|(i, h)| h.as_ref().map(|r| (r.recv_timestamp_ns, i)))
+            .min()?
+            .1;
+        let next = self.read_next(idx);
+        std::mem::replace(&mut self.heads[idx], next)
+    }
+}
+
+/// 저널 레코드를 실시간 경로와 동일하게 처리하는 리플레이어
+pub struct Replayer {
+    stage: FeedStage,
+    data_parser: Arc<DataParser>,
+    packet_builder: Arc<PacketBuilder>,
+    options: ReplayOptions,
+    /// (거래소, 세션)별 마지막 세션 메타데이터
+    metas: Mutex<HashMap<(String, String), SessionMeta>>,
+}
+
+impl Replayer {
+    pub fn new(config: Arc<Config>, data_parser: Arc<DataParser>, packet_builder: Arc<PacketBuilder>, options: ReplayOptions) -> Self {
+        let stage = FeedStage::new(config, Arc::clone(&data_parser), Arc::clone(&packet_builder));
+        Self { stage, data_parser, packet_builder, options, metas: Mutex::new(HashMap::new()) }
+    }
+
+    /// 레코드 하나를 처리 단계에 넣어 UDP 패킷 생성
+    ///
+    /// 세션 메타데이터 레코드는 이후 프레임의 세션 필터로, 오더북 REST 스냅샷 레코드는 파생 데이터 호가창에 반영하며 패킷은 없습니다.
+    pub fn packets_for(&self, record: &JournalRecord) -> Result<Vec<UdpPacket>> {
+        if record.session_meta {
+            if let Some(meta) = record.meta() {
+                self.metas.lock().unwrap().insert((record.exchange.clone(), record.session.clone()), meta);
+            }
+            return Ok(Vec::new());
+        }
+        if record.depth_snapshot {
+            if let Some(snapshot) = record.depth_snapshot() {
+                self.stage.ingest_derived(&ParsedData::OrderBook(snapshot), record.recv_timestamp_ns);
+            }
+            return Ok(Vec::new());
+        }
+        if self.options.deterministic {
+            self.packet_builder.set_fixed_local_timestamp(Some(record.recv_timestamp_ns));
+        }
+        // 현재 설정에 없는 거래소의 기록도 재생할 수 있도록 파서 등록
+        self.data_parser.register_exchange(&record.exchange);
+        let meta = self.metas.lock().unwrap().get(&(record.exchange.clone(), record.session.clone())).cloned();
+        let output = match meta {
+            Some(meta) => {
+                let in_session = |symbol: &str| meta.symbols.iter().any(|s| s == symbol);
+                let filter = SessionFilter { port: record.port, streams: meta.streams, in_session: &in_session };
+                self.stage.process(&record.exchange, record.data.clone(), Some(&filter), record.recv_timestamp_ns)?
+            }
+            None => self.stage.process(&record.exchange, record.data.clone(), None, record.recv_timestamp_ns)?,
+        };
+        Ok(output.packets)
+    }
+
+    /// 기록된 시각 `until_ns`까지 도래한 주기 발행 실행 (`deadlines`는 발행 종류별 다음 발행 시각)
+    fn publish_due(&self, deadlines: &mut [u64], until_ns: u64) -> Vec<(u16, UdpPacket)> {
+        let mut out = Vec::new();
+        while let Some((idx, deadline)) = deadlines.iter().copied().enumerate()
+            .filter(|&(_, d)| d <= until_ns)
+            .min_by_key(|&(_, d)| d)
+        {
+            let (publisher, interval) = self.stage.publishers()[idx];
+            if self.options.deterministic {
+                self.packet_builder.set_fixed_local_timestamp(Some(deadline));
+            }
+            out.extend(self.stage.publish(publisher, deadline));
+            deadlines[idx] = deadline + (interval.as_nanos() as u64).max(1);
+        }
+        out
+    }
+
+    /// 마지막 레코드 이후 주기 발행 마무리 (가장 늦은 다음 발행 시각까지 실행해 모든 발행 종류가 한 번 이상 실행되도록 함)
+    fn drain_publishers(&self, deadlines: &mut [u64]) -> (u64, Vec<(u16, UdpPacket)>) {
+        let until_ns = deadlines.iter().copied().max().unwrap_or(0);
+        (until_ns, self.publish_due(deadlines, until_ns))
+    }
+
+    /// 레코드를 재생 단계로 변환 (프레임 레코드마다 한 단계, 마지막에 주기 발행 마무리 단계)
+    ///
+    /// 각 단계는 기록 시각까지 도래한 주기 발행 패킷 뒤에 레코드 패킷을 담으며, 전송 없이 패킷만 생성합니다.
+    fn steps<'a>(&'a self, records: impl Iterator<Item = JournalRecord> + 'a) -> impl Iterator<Item = ReplayStep> + 'a {
+        let mut records = records;
+        let mut deadlines: Option<Vec<u64>> = None;
+        let mut drained = false;
+        std::iter::from_fn(move || {
+            for record in records.by_ref() {
+                // 세션 메타데이터/오더북 스냅샷은 프레임이 아니므로 상태만 반영
+                if record.session_meta || record.depth_snapshot {
+                    let _ = self.packets_for(&record);
+                    continue;
+                }
+                let deadlines = deadlines.get_or_insert_with(|| self.stage.publishers().iter()
+                    .map(|(_, interval)| record.recv_timestamp_ns + interval.as_nanos() as u64)
+                    .collect());
+                let mut packets = self.publish_due(deadlines, record.recv_timestamp_ns);
+                let parsed = self.packets_for(&record);
+                if let Err(e) = &parsed {
+                    // 구독 응답 등 데이터가 아닌 메시지 포함
+                    debug!("🔍 {} [세션 #{}] 파싱 건너뜀: {}", record.exchange, record.session, e);
+                }
+                let parse_error = parsed.is_err();
+                packets.extend(parsed.unwrap_or_default().into_iter().map(|p| (record.port, p)));
+                return Some(ReplayStep { recv_timestamp_ns: record.recv_timestamp_ns, packets, frame: true, parse_error });
+            }
+            if drained {
+                return None;
+            }
+            drained = true;
+            let (recv_timestamp_ns, packets) = self.drain_publishers(deadlines.as_mut()?);
+            Some(ReplayStep { recv_timestamp_ns, packets, frame: false, parse_error: false })
+        })
+    }
+
+    /// 레코드를 재생 속도에 맞춰 처리하고 기록된 세션 포트로 전송
+    pub async fn run<R: Read>(&self, records: MergedJournal<R>, udp: &UdpMulticaster) -> ReplayStats {
+        let mut stats = ReplayStats::default();
+        let started = tokio::time::Instant::now();
+        let mut first_ts = None;
+
+        for step in self.steps(records) {
+            let first = *first_ts.get_or_insert(step.recv_timestamp_ns);
+            if let Some(offset) = self.options.speed.scaled(step.recv_timestamp_ns.saturating_sub(first)) {
+                tokio::time::sleep_until(started + offset).await;
+            }
+            if step.frame {
+                stats.records += 1;
+            }
+            if step.parse_error {
+                stats.parse_errors += 1;
+            }
+            for (port, packet) in step.packets {
+                send(udp, packet, port, &mut stats).await;
+            }
+        }
+
+        self.packet_builder.set_fixed_local_timestamp(None);
+        stats
+    }
+}
+
+/// 재생 단계 하나 (프레임 레코드 한 건 또는 마지막 주기 발행)
+struct ReplayStep {
+    /// 기록 시각 (재생 속도 대기 기준)
+    recv_timestamp_ns: u64,
+    /// 전송할 (포트, 패킷)
+    packets: Vec<(u16, UdpPacket)>,
+    /// 프레임 레코드 단계인지 (마지막 주기 발행은 false)
+    frame: bool,
+    parse_error: bool,
+}
+
+/// 패킷 하나를 기록된 포트로 전송 (0이면 기본 포트)
+async fn send(udp: &UdpMulticaster, packet: UdpPacket, port: u16, stats: &mut ReplayStats) {
+    let result = if port == 0 {
+        udp.send_packet(packet).await
+    } else {
+        udp.send_packet_to_port(packet, port).await
+    };
+    match result {
+        Ok(()) => stats.packets_sent += 1,
+        Err(e) => {
+            warn!("⚠️ 리플레이 패킷 전송 실패: {}", e);
+            stats.send_errors += 1;
+        }
+    }
+}
+
+/// 입력 파일을 열어 리플레이 실행
+pub async fn run_replay(
+    options: ReplayOptions,
+    config: Arc<Config>,
+    data_parser: Arc<DataParser>,
+    packet_builder: Arc<PacketBuilder>,
+    udp: &UdpMulticaster,
+) -> Result<ReplayStats> {
+    let files = resolve_inputs(&options.inputs)?;
+    if files.is_empty() {
+        return Err(CryptoFeederError::Other("리플레이할 저널 파일이 없습니다".to_string()));
+    }
+    info!("⏪ 리플레이 시작: 파일 {}개, 속도 {:?}, 결정적 타임스탬프 {}",
+          files.len(), options.speed, options.deterministic);
+
+    let readers = files.iter().map(|f| JournalReader::open(f)).collect::<Result<Vec<_>>>()?;
+    let replayer = Replayer::new(config, data_parser, packet_builder, options);
+    let stats = replayer.run(MergedJournal::new(readers), udp).await;
+
+    info!("⏪ 리플레이 완료: 레코드 {}건, 파싱 제외 {}건, 전송 패킷 {}개, 전송 실패 {}건",
+          stats.records, stats.parse_errors, stats.packets_sent, stats.send_errors);
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ts: u64, data: &str) -> JournalRecord {
+        JournalRecord { recv_timestamp_ns: ts, exchange: "BinanceSpot".into(), session: "0".into(), port: 55555, binary: false, session_meta: false, depth_snapshot: false, data: data.as_bytes().to_vec() }
+    }
+
+    fn journal_bytes(records: &[JournalRecord]) -> Vec<u8> {
+        let mut out = crate::journal::JOURNAL_MAGIC.to_vec();
+        for r in records {
+            r.encode(&mut out);
+        }
+        out
+    }
+
+    fn trade(id: u32, price: &str) -> String {
+        format!(r#"{{"stream":"btcusdt@trade","data":{{"e":"trade","E":1700000000000,"s":"BTCUSDT","t":{},"p":"{}","q":"0.1","T":1700000000000,"m":false}}}}"#, id, price)
+    }
+
+    #[test]
+    fn test_speed_parse() {
+        assert_eq!(ReplaySpeed::parse("max"), Some(ReplaySpeed::AsFastAsPossible));
+        assert_eq!(ReplaySpeed::parse("realtime"), Some(ReplaySpeed::Realtime));
+        assert_eq!(ReplaySpeed::parse("10x"), Some(ReplaySpeed::Multiplier(10.0)));
+        assert_eq!(ReplaySpeed::parse("0.5"), Some(ReplaySpeed::Multiplier(0.5)));
+        assert_eq!(ReplaySpeed::parse("0x"), None);
+        assert_eq!(ReplaySpeed::Multiplier(4.0).scaled(4_000), Some(Duration::from_nanos(1_000)));
+    }
+
+    #[test]
+    fn test_merge_orders_by_receive_time() {
+        let a = journal_bytes(&[record(1, "a1"), record(5, "a5")]);
+        let b = journal_bytes(&[record(3, "b3"), record(5, "b5")]);
+        let merged = MergedJournal::new(vec![JournalReader::new(&a[..]).unwrap(), JournalReader::new(&b[..]).unwrap()]);
+        let order: Vec<String> = merged.map(|r| String::from_utf8(r.data).unwrap()).collect();
+        assert_eq!(order, vec!["a1", "b3", "a5", "b5"]);
+    }
+
+    #[test]
+    fn test_deterministic_replay_is_byte_identical() {
+        let records = [record(1_700_000_000_100_000_000, &trade(1, "50000.0")), record(1_700_000_000_200_000_000, "{\"result\":null,\"id\":1}"), record(1_700_000_000_300_000_000, &trade(2, "50001.0"))];
+        let options = ReplayOptions { inputs: vec![], speed: ReplaySpeed::AsFastAsPossible, deterministic: true };
+        let run = || {
+            let replayer = Replayer::new(Arc::new(Config::load().unwrap()), Arc::new(DataParser::new()), Arc::new(PacketBuilder::new()), options.clone());
+            records.iter()
+                .filter_map(|r| replayer.packets_for(r).ok())
+                .flatten()
+                .map(|p| p.data)
+                .collect::<Vec<_>>()
+        };
+        let first = run();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first, run());
+        let header = crate::protocol::PacketHeader::try_from(first[0].as_slice()).unwrap();
+        let local_timestamp = header.local_timestamp;
+        assert_eq!(local_timestamp, 1_700_000_000_100_000_000);
+    }
+
+    #[test]
+    fn test_replay_drains_derived_publishers() {
+        // 체결 흐름 발행 간격(1초)이 기록 구간(0.2초)보다 길어도 마지막에 한 번 발행
+        // 파생 데이터는 세션 경로에서만 반영되므로 세션 메타데이터를 먼저 기록
+        let meta = SessionMeta { symbols: vec!["BTC^USDT".into()], streams: Default::default() };
+        let meta = JournalRecord { session_meta: true, data: serde_json::to_vec(&meta).unwrap(), ..record(1_700_000_000_000_000_000, "") };
+        let records = [meta, record(1_700_000_000_100_000_000, &trade(1, "50000.0")), record(1_700_000_000_300_000_000, &trade(2, "50001.0"))];
+        let mut config = Config::load().unwrap();
+        config.trade_flow.port = 55601;
+        config.trade_flow.interval_ms = 1000;
+        let config = Arc::new(config);
+        let options = ReplayOptions { inputs: vec![], speed: ReplaySpeed::AsFastAsPossible, deterministic: true };
+        let run = || {
+            let replayer = Replayer::new(Arc::clone(&config), Arc::new(DataParser::new()), Arc::new(PacketBuilder::new()), options.clone());
+            replayer.steps(records.iter().cloned())
+                .flat_map(|step| step.packets)
+                .map(|(port, p)| (port, p.data))
+                .collect::<Vec<_>>()
+        };
+        let first = run();
+        assert_eq!(first, run());
+        assert_eq!(first.iter().filter(|(port, _)| *port == 55555).count(), 2);
+        let (port, flow) = first.last().unwrap();
+        assert_eq!(*port, 55601);
+        let header = crate::protocol::PacketHeader::try_from(flow.as_slice()).unwrap();
+        let local_timestamp = header.local_timestamp;
+        assert_eq!(local_timestamp, 1_700_000_001_100_000_000);
+    }
+}
@@ -0,0 +1,280 @@
+//! 세션 분할 계획기
+//! 거래소 어댑터별 스트림/구독/URL 길이 제한에 맞춰 symbol_config 세션을 여러 WebSocket 연결로 분할
+
+use crate::config::{ExchangeEndpoint, LiquidationScope, SessionStreams, SymbolSession};
+use crate::errors::Result;
+use log::{info, warn};
+
+/// 어댑터별 연결 제한
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdapterLimits {
+    pub max_streams_per_connection: usize,
+    pub max_subscriptions_per_connection: usize,
+    pub max_url_length: usize,
+    /// 구독 요청 한 건에 담을 수 있는 토픽 수 (None이면 제한 없음)
+    pub max_topics_per_request: Option<usize>,
+}
+
+/// 분할 계획에 따른 실제 WebSocket 연결 단위
+#[derive(Debug, Clone)]
+pub struct PlannedSession {
+    /// symbol_config.ini 상의 원본 세션 인덱스
+    pub session_idx: usize,
+    /// 원본 세션 내 분할 인덱스 (0부터)
+    pub shard_idx: usize,
+    /// 원본 세션이 분할된 연결 수
+    pub shard_count: usize,
+    /// 이 연결이 담당하는 심볼 (포트는 원본 세션과 동일)
+    pub session: SymbolSession,
+    /// 이 연결의 스트림(구독 토픽) 수
+    pub stream_count: usize,
+}
+
+impl PlannedSession {
+    /// 로그 표시용 라벨 (분할되지 않은 세션은 "#0", 분할된 세션은 "#0.1")
+    pub fn label(&self) -> String {
+        if self.shard_count > 1 {
+            format!("{}.{}", self.session_idx, self.shard_idx)
+        } else {
+            self.session_idx.to_string()
+        }
+    }
+}
+
+impl AdapterLimits {
+    /// 거래소 표시명 기준 기본 제한
+    pub fn for_exchange(exchange_name: &str) -> Self {
+        match exchange_name {
+            // Binance USDⓈ-M: 연결당 최대 200 스트림
+            "BinanceFutures" => Self { max_streams_per_connection: 200, max_subscriptions_per_connection: 200, max_url_length: 4096, max_topics_per_request: None },
+            // Binance Spot: 연결당 최대 1024 스트림
+            name if name.starts_with("Binance") => Self { max_streams_per_connection: 1024, max_subscriptions_per_connection: 1024, max_url_length: 4096, max_topics_per_request: None },
+            // Bybit: 구독 요청당 최대 10 토픽, Spot은 연결당 토픽 수도 보수적으로 제한
+            "BybitSpot" => Self { max_streams_per_connection: 200, max_subscriptions_per_connection: 200, max_url_length: 4096, max_topics_per_request: Some(10) },
+            name if name.starts_with("Bybit") => Self { max_streams_per_connection: 500, max_subscriptions_per_connection: 500, max_url_length: 4096, max_topics_per_request: Some(10) },
+            name if name.starts_with("Okx") => Self { max_streams_per_connection: 480, max_subscriptions_per_connection: 480, max_url_length: 4096, max_topics_per_request: None },
+            _ => Self { max_streams_per_connection: 200, max_subscriptions_per_connection: 200, max_url_length: 4096, max_topics_per_request: None },
+        }
+    }
+
+    /// endpoint.ini에 지정된 값으로 기본 제한을 덮어씀
+    pub fn with_endpoint_overrides(mut self, endpoint: Option<&ExchangeEndpoint>) -> Self {
+        if let Some(endpoint) = endpoint {
+            if let Some(v) = endpoint.max_streams_per_connection { self.max_streams_per_connection = v; }
+            if let Some(v) = endpoint.max_subscriptions_per_connection { self.max_subscriptions_per_connection = v; }
+            if let Some(v) = endpoint.max_url_length { self.max_url_length = v; }
+        }
+        self
+    }
+
+    /// 스트림/구독 수 기준 연결당 허용 토픽 수
+    fn max_topics(&self) -> usize {
+        self.max_streams_per_connection.min(self.max_subscriptions_per_connection).max(1)
+    }
+}
+
+/// 심볼 하나가 차지하는 스트림(구독 토픽) 수
+pub fn streams_per_symbol(exchange_name: &str, streams: SessionStreams) -> usize {
+    let base = match exchange_name {
+        // trade, depth@0ms, markPrice@1s (+ forceOrder, 전체 시장 청산 세션은 !forceOrder@arr로 대체)
+        "BinanceFutures" => if streams.liquidations == LiquidationScope::All { 3 } else { 4 },
+        // 그 외: trade, depth
+        _ => 2,
+    };
+    // 미결제약정/펀딩: OKX 파생은 채널별 추가, Bybit 파생은 tickers 토픽 공유, Binance는 REST 조회/markPrice 포함
+    let derivatives = match exchange_name {
+        "OkxSwap" => usize::from(streams.open_interest) + usize::from(streams.funding),
+        name if name.starts_with("Bybit") && name != "BybitSpot" => usize::from((streams.open_interest || streams.funding) && !streams.ticker),
+        _ => 0,
+    };
+    // Bybit 파생 청산은 심볼별 allLiquidation 토픽 (OKX는 연결당 liquidation-orders 하나)
+    let liquidations = match exchange_name {
+        name if name.starts_with("Bybit") && name != "BybitSpot" => usize::from(streams.liquidations != LiquidationScope::Off),
+        _ => 0,
+    };
+    // 거래소 kline/ticker 스트림은 각각 심볼당 토픽 1개 추가
+    base + streams.extra_topics() + derivatives + liquidations
+}
+
+/// 한 거래소의 세션 목록을 어댑터 제한에 맞춰 분할
+///
+/// `url_len`은 주어진 심볼 목록(과 세션의 부가 스트림)으로 생성될 WebSocket URL 길이를 반환해야 합니다.
+/// 구독 메시지 방식 거래소는 심볼과 무관하게 기본 URL 길이를 반환하면 됩니다. URL 생성 오류는 그대로 반환됩니다.
+pub fn plan_exchange_sessions<F>(
+    exchange_name: &str,
+    sessions: &[SymbolSession],
+    limits: &AdapterLimits,
+    url_len: F,
+) -> Result<Vec<PlannedSession>>
+where
+    F: Fn(&[String], SessionStreams) -> Result<usize>,
+{
+    let mut planned = Vec::new();
+
+    for (session_idx, session) in sessions.iter().enumerate() {
+        let per_symbol = streams_per_symbol(exchange_name, session.streams);
+        // 전체 시장 토픽은 연결마다 한 번 구독
+        let market_wide = session.streams.market_wide_topics(exchange_name);
+        let max_topics = limits.max_topics().saturating_sub(market_wide).max(1);
+        let mut shards: Vec<Vec<String>> = Vec::new();
+        let mut current: Vec<String> = Vec::new();
+
+        for symbol in &session.symbols {
+            let mut candidate = current.clone();
+            candidate.push(symbol.clone());
+            let fits = candidate.len() * per_symbol <= max_topics
+                && url_len(&candidate, session.streams)? <= limits.max_url_length;
+
+            if fits || current.is_empty() {
+                if !fits {
+                    warn!("⚠️ {} [세션 #{}] 심볼 {} 단독으로도 제한 초과 (limits: {:?})", exchange_name, session_idx, symbol, limits);
+                }
+                current = candidate;
+            } else {
+                shards.push(std::mem::take(&mut current));
+                current.push(symbol.clone());
+            }
+        }
+        // 심볼 없는 전체 시장 청산 세션도 연결 하나로 계획
+        if !current.is_empty() || (shards.is_empty() && market_wide > 0) {
+            shards.push(current);
+        }
+
+        let shard_count = shards.len();
+        for (shard_idx, symbols) in shards.into_iter().enumerate() {
+            let is_btc_session = symbols.len() == 1 && symbols[0].starts_with("BTC^");
+            planned.push(PlannedSession {
+                session_idx,
+                shard_idx,
+                shard_count,
+                stream_count: symbols.len() * per_symbol + market_wide,
+                session: SymbolSession { symbols, is_btc_session, port: session.port, streams: session.streams },
+            });
+        }
+    }
+
+    Ok(planned)
+}
+
+/// 분할 계획을 로그로 출력
+pub fn log_plan(exchange_name: &str, limits: &AdapterLimits, plan: &[PlannedSession]) {
+    let split_sessions = plan.iter().filter(|p| p.shard_count > 1 && p.shard_idx == 0).count();
+    info!("📐 {} 세션 계획: {}개 연결 (분할된 세션 {}개, 제한: streams={} subs={} url={})",
+          exchange_name, plan.len(), split_sessions,
+          limits.max_streams_per_connection, limits.max_subscriptions_per_connection, limits.max_url_length);
+    for p in plan {
+        info!("   └─ 연결 #{} → 포트 {}: {}개 심볼, {}개 스트림 ({})",
+              p.label(), p.session.port, p.session.symbols.len(), p.stream_count, p.session.symbols.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{test_session, KlineSource};
+
+    #[test]
+    fn test_session_within_limits_is_not_split() {
+        let limits = AdapterLimits::for_exchange("BinanceFutures");
+        let sessions = vec![test_session(55567, &["DOGE^USDT", "XRP^USDT", "SOL^USDT"])];
+        let plan = plan_exchange_sessions("BinanceFutures", &sessions, &limits, |_, _| Ok(100)).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].stream_count, 12);
+        assert_eq!(plan[0].label(), "0");
+    }
+
+    #[test]
+    fn test_session_split_by_stream_limit_keeps_port() {
+        let limits = AdapterLimits { max_streams_per_connection: 8, max_subscriptions_per_connection: 100, max_url_length: 4096, max_topics_per_request: None };
+        let sessions = vec![test_session(55567, &["A^USDT", "B^USDT", "C^USDT", "D^USDT", "E^USDT"])];
+        let plan = plan_exchange_sessions("BinanceFutures", &sessions, &limits, |_, _| Ok(100)).unwrap();
+        assert_eq!(plan.len(), 3);
+        assert!(plan.iter().all(|p| p.session.port == 55567 && p.shard_count == 3));
+        assert_eq!(plan[0].session.symbols, vec!["A^USDT", "B^USDT"]);
+        assert_eq!(plan[2].session.symbols, vec!["E^USDT"]);
+        assert_eq!(plan[1].label(), "0.1");
+    }
+
+    #[test]
+    fn test_exchange_klines_add_stream_per_symbol() {
+        let limits = AdapterLimits { max_streams_per_connection: 10, max_subscriptions_per_connection: 100, max_url_length: 4096, max_topics_per_request: None };
+        let mut kline_session = test_session(55567, &["A^USDT", "B^USDT", "C^USDT"]);
+        kline_session.streams.klines = KlineSource::Exchange;
+        let plan = plan_exchange_sessions("BinanceFutures", &[kline_session], &limits, |_, _| Ok(100)).unwrap();
+        // 심볼당 5 스트림 → 연결당 2심볼
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].stream_count, 10);
+        assert!(plan.iter().all(|p| p.session.streams.klines == KlineSource::Exchange));
+        let local_with_ticker = SessionStreams { klines: KlineSource::Local, ticker: true, ..SessionStreams::default() };
+        assert_eq!(streams_per_symbol("BinanceSpot", local_with_ticker), 3);
+        let open_interest = SessionStreams { open_interest: true, ..SessionStreams::default() };
+        assert_eq!(streams_per_symbol("OkxSwap", open_interest), 3);
+        assert_eq!(streams_per_symbol("BybitLinear", open_interest), 3);
+        assert_eq!(streams_per_symbol("BybitLinear", SessionStreams { ticker: true, ..open_interest }), 3);
+        assert_eq!(streams_per_symbol("BinanceFutures", open_interest), 4);
+        let with_funding = SessionStreams { funding: true, ..open_interest };
+        assert_eq!(streams_per_symbol("OkxSwap", with_funding), 4);
+        assert_eq!(streams_per_symbol("BybitLinear", with_funding), 3);
+    }
+
+    #[test]
+    fn test_market_wide_liquidation_session() {
+        let limits = AdapterLimits { max_streams_per_connection: 7, max_subscriptions_per_connection: 100, max_url_length: 4096, max_topics_per_request: None };
+        let all = SessionStreams { liquidations: LiquidationScope::All, ..SessionStreams::default() };
+        // 심볼 없는 전용 세션은 !forceOrder@arr 연결 하나
+        let dedicated = SymbolSession { streams: all, ..test_session(55580, &[]) };
+        let plan = plan_exchange_sessions("BinanceFutures", &[dedicated], &limits, |_, _| Ok(100)).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert!(plan[0].session.symbols.is_empty());
+        assert_eq!(plan[0].stream_count, 1);
+
+        // 심볼별 forceOrder 대신 연결당 !forceOrder@arr: 심볼당 3 스트림 + 1 → 연결당 2심볼
+        let with_symbols = SymbolSession { streams: all, ..test_session(55581, &["A^USDT", "B^USDT", "C^USDT"]) };
+        let plan = plan_exchange_sessions("BinanceFutures", &[with_symbols], &limits, |_, _| Ok(100)).unwrap();
+        assert_eq!(plan.iter().map(|p| p.stream_count).collect::<Vec<_>>(), vec![7, 4]);
+
+        let on = SessionStreams { liquidations: LiquidationScope::Symbols, ..SessionStreams::default() };
+        assert_eq!(streams_per_symbol("BybitLinear", on), 3);
+        assert_eq!(streams_per_symbol("BybitSpot", on), 2);
+        assert_eq!(streams_per_symbol("OkxSwap", on), 2);
+    }
+
+    #[test]
+    fn test_session_split_by_url_length() {
+        let limits = AdapterLimits { max_streams_per_connection: 1024, max_subscriptions_per_connection: 1024, max_url_length: 60, max_topics_per_request: None };
+        let sessions = vec![test_session(55558, &["ETH^USDT", "ADA^USDT", "SOL^USDT"])];
+        // 심볼당 25바이트 + 기본 10바이트로 가정
+        let plan = plan_exchange_sessions("BinanceSpot", &sessions, &limits, |syms, _| Ok(10 + syms.len() * 25)).unwrap();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].session.symbols.len(), 2);
+        assert_eq!(plan[1].session.symbols.len(), 1);
+
+        // URL을 만들 수 없으면 분할하지 않고 오류 반환
+        let failing = plan_exchange_sessions("BinanceSpot", &sessions, &limits, |_, _| {
+            Err(crate::errors::CryptoFeederError::Other("BinanceSpot 거래소가 비활성화됨".into()))
+        });
+        assert!(failing.is_err());
+    }
+
+    #[test]
+    fn test_endpoint_overrides() {
+        let endpoint = ExchangeEndpoint {
+            exchange_name: "BinanceSpot".to_string(),
+            ws_url_base: "wss://stream.binance.com:9443/ws/".to_string(),
+            timeout_ms: 5000,
+            ping_interval_ms: None,
+            enabled: true,
+            max_streams_per_connection: Some(10),
+            max_subscriptions_per_connection: None,
+            max_url_length: Some(2000),
+            proxy: None,
+            bind_addrs: vec![],
+            resolve: vec![],
+            rest_url_base: None,
+        };
+        let limits = AdapterLimits::for_exchange("BinanceSpot").with_endpoint_overrides(Some(&endpoint));
+        assert_eq!(limits.max_streams_per_connection, 10);
+        assert_eq!(limits.max_subscriptions_per_connection, 1024);
+        assert_eq!(limits.max_url_length, 2000);
+    }
+}
@@ -0,0 +1,124 @@
+//! 24시간 티커 병합기
+//! 거래소 ticker 채널의 잦은 업데이트를 (포트, 거래소, 심볼)별 최신 값으로 병합하여 config.ini `ticker_interval_ms` 주기로 전송
+
+use crate::data_parser::{ParsedData, StandardizedTicker};
+use std::collections::{BTreeSet, HashMap};
+
+/// 티커 식별 키 (포트, 거래소, 심볼)
+type TickerKey = (u16, String, String);
+
+/// 파싱된 데이터에서 티커를 분리 (나머지 데이터, 티커 목록)
+pub fn split_tickers(data: ParsedData) -> (ParsedData, Vec<StandardizedTicker>) {
+    match data {
+        ParsedData::Ticker(ticker) => (ParsedData::Multi(Vec::new()), vec![ticker]),
+        ParsedData::Multi(items) if items.iter().any(|it| matches!(it, ParsedData::Ticker(_))) => {
+            let mut rest = Vec::new();
+            let mut tickers = Vec::new();
+            for item in items {
+                match item {
+                    ParsedData::Ticker(ticker) => tickers.push(ticker),
+                    other => rest.push(other),
+                }
+            }
+            (ParsedData::Multi(rest), tickers)
+        }
+        other => (other, Vec::new()),
+    }
+}
+
+/// 티커 병합기
+///
+/// 델타 업데이트는 직전 값에 덮어써 완전한 티커로 만든 뒤 보관합니다. 병합 주기가 0이면 업데이트마다 바로 반환하고,
+/// 그렇지 않으면 `flush` 때 마지막 전송 이후 바뀐 티커만 반환합니다. 필드가 모두 채워지기 전에는 전송하지 않습니다.
+pub struct TickerConflator {
+    interval_ms: u64,
+    latest: HashMap<TickerKey, StandardizedTicker>,
+    dirty: BTreeSet<TickerKey>,
+}
+
+impl TickerConflator {
+    pub fn new(interval_ms: u64) -> Self {
+        Self { interval_ms, latest: HashMap::new(), dirty: BTreeSet::new() }
+    }
+
+    /// 티커 업데이트 반영. 즉시 전송할 티커가 있으면 반환 (병합 주기 0일 때만)
+    pub fn update(&mut self, port: u16, ticker: StandardizedTicker) -> Option<StandardizedTicker> {
+        let key = (port, ticker.exchange.clone(), ticker.symbol.clone());
+        let merged = self.latest.entry(key.clone())
+            .and_modify(|current| current.merge_from(&ticker))
+            .or_insert(ticker);
+        if !merged.is_complete() {
+            return None;
+        }
+        if self.interval_ms == 0 {
+            return Some(merged.clone());
+        }
+        self.dirty.insert(key);
+        None
+    }
+
+    /// 마지막 전송 이후 바뀐 티커를 (포트, 티커) 목록으로 반환
+    pub fn flush(&mut self) -> Vec<(u16, StandardizedTicker)> {
+        std::mem::take(&mut self.dirty).into_iter()
+            .filter_map(|key| self.latest.get(&key).map(|ticker| (key.0, ticker.clone())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(last_price: f64, volume: f64) -> StandardizedTicker {
+        StandardizedTicker {
+            symbol: "BTC^USDT".into(),
+            exchange: "BybitLinear".into(),
+            last_price,
+            open_price: 100.0,
+            high_price: 110.0,
+            low_price: 90.0,
+            volume,
+            quote_volume: 1000.0,
+            price_change_percent: 1.0,
+            trade_count: 0,
+            timestamp: 1,
+        }
+    }
+
+    #[test]
+    fn test_conflates_to_latest_per_interval() {
+        let mut conflator = TickerConflator::new(1000);
+        assert!(conflator.update(55555, ticker(101.0, 10.0)).is_none());
+        assert!(conflator.update(55555, ticker(102.0, 11.0)).is_none());
+        let flushed = conflator.flush();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!((flushed[0].0, flushed[0].1.last_price), (55555, 102.0));
+        // 변경이 없으면 다시 보내지 않음
+        assert!(conflator.flush().is_empty());
+    }
+
+    #[test]
+    fn test_delta_merged_before_send() {
+        let mut conflator = TickerConflator::new(0);
+        let mut partial = ticker(101.0, f64::NAN);
+        assert!(conflator.update(55555, partial.clone()).is_none());
+
+        let full = conflator.update(55555, ticker(101.0, 10.0)).unwrap();
+        assert_eq!(full.volume, 10.0);
+
+        partial.last_price = 105.0;
+        let merged = conflator.update(55555, partial).unwrap();
+        assert_eq!((merged.last_price, merged.volume), (105.0, 10.0));
+    }
+
+    #[test]
+    fn test_split_tickers() {
+        let data = ParsedData::Multi(vec![
+            ParsedData::Ticker(ticker(1.0, 1.0)),
+            ParsedData::MarkPrice { symbol: "BTC^USDT".into(), exchange: "BinanceFutures".into(), value: 1.0, timestamp: 0 },
+        ]);
+        let (rest, tickers) = split_tickers(data);
+        assert_eq!(tickers.len(), 1);
+        assert!(matches!(rest, ParsedData::Multi(items) if items.len() == 1));
+    }
+}
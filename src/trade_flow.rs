@@ -0,0 +1,218 @@
+//! 체결 흐름 분석기
+//! 체결(`Trade`/`TradeBatch`)로 (거래소, 심볼)별 이동 구간(기본 1s/10s/60s)의 VWAP, 매수/매도 체결량, 체결 수, 불균형을 계산
+//!
+//! - 매수/매도는 `is_buyer_taker` 기준 (매수 테이커 체결 = 매수 체결량)
+//! - 구간은 체결 수신 시각(로컬) 기준이라 거래소 시계 차이와 무관
+//! - 가장 긴 구간 안에 체결이 있는 종목만 전송 (짧은 구간은 체결이 없으면 0)
+//! - 계약 단위 수량(OKX 무기한 sz)은 참조 메타데이터의 계약당 기준 자산 수량(ctVal)으로 환산
+
+use crate::config::TradeFlowConfig;
+use crate::data_parser::{ParsedData, StandardizedTrade};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// 구간 계산에 쓰는 체결 하나
+#[derive(Debug, Clone, Copy)]
+struct FlowTrade {
+    received_at: u64,
+    price: f64,
+    quantity: f64,
+    is_buy: bool,
+}
+
+/// 구간 하나의 체결 흐름
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowStats {
+    pub window_secs: u32,
+    pub trade_count: u32,
+    /// 체결량 가중 평균 가격 (체결이 없으면 0)
+    pub vwap: f64,
+    /// 매수 테이커 체결량
+    pub buy_volume: f64,
+    /// 매도 테이커 체결량
+    pub sell_volume: f64,
+}
+
+impl FlowStats {
+    /// (매수 - 매도) / (매수 + 매도), -1~1 (체결이 없으면 0)
+    pub fn imbalance(&self) -> f64 {
+        let total = self.buy_volume + self.sell_volume;
+        if total > 0.0 { (self.buy_volume - self.sell_volume) / total } else { 0.0 }
+    }
+}
+
+/// (거래소, 심볼) 하나의 구간별 체결 흐름
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowSnapshot {
+    pub exchange: String,
+    pub symbol: String,
+    /// 설정 구간 순서 (짧은 구간부터)
+    pub windows: Vec<FlowStats>,
+    /// 계산 시각 (나노초)
+    pub timestamp: u64,
+}
+
+/// 체결 흐름 분석기
+pub struct TradeFlow {
+    windows_secs: Vec<u32>,
+    /// 가장 긴 구간 (나노초, 이보다 오래된 체결은 삭제)
+    horizon_ns: u64,
+    /// (거래소, 심볼)별 계약당 기준 자산 수량 (없으면 수량이 이미 기준 자산 단위)
+    contract_values: HashMap<(String, String), f64>,
+    trades: BTreeMap<(String, String), VecDeque<FlowTrade>>,
+}
+
+impl TradeFlow {
+    pub fn new(config: &TradeFlowConfig) -> Self {
+        let horizon_secs = config.windows_secs.iter().copied().max().unwrap_or(0);
+        Self {
+            windows_secs: config.windows_secs.clone(),
+            horizon_ns: horizon_secs as u64 * NANOS_PER_SEC,
+            contract_values: HashMap::new(),
+            trades: BTreeMap::new(),
+        }
+    }
+
+    /// 계약 단위 수량을 기준 자산 수량으로 환산할 (거래소, 심볼)별 계약당 수량 지정
+    pub fn with_contract_values(mut self, contract_values: HashMap<(String, String), f64>) -> Self {
+        self.contract_values = contract_values;
+        self
+    }
+
+    /// 파싱된 데이터의 체결 반영 (`now_ns`는 수신 시각)
+    pub fn ingest(&mut self, data: &ParsedData, now_ns: u64) {
+        match data {
+            ParsedData::Trade(trade) => self.on_trade(trade, now_ns),
+            ParsedData::TradeBatch(batch) => {
+                for trade in &batch.trades {
+                    self.on_trade(trade, now_ns);
+                }
+            }
+            ParsedData::Multi(items) => {
+                for item in items {
+                    self.ingest(item, now_ns);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_trade(&mut self, trade: &StandardizedTrade, now_ns: u64) {
+        if !trade.price.is_finite() || trade.price <= 0.0 || !trade.quantity.is_finite() || trade.quantity <= 0.0 {
+            return;
+        }
+        let key = (trade.exchange.clone(), trade.symbol.clone());
+        let quantity = trade.quantity * self.contract_values.get(&key).copied().unwrap_or(1.0);
+        let trades = self.trades.entry(key).or_default();
+        trades.push_back(FlowTrade { received_at: now_ns, price: trade.price, quantity, is_buy: trade.is_buyer_taker });
+        prune(trades, now_ns, self.horizon_ns);
+    }
+
+    /// 가장 긴 구간 안에 체결이 있는 모든 종목의 구간별 체결 흐름 (오래된 체결은 삭제)
+    pub fn flush(&mut self, now_ns: u64) -> Vec<FlowSnapshot> {
+        let horizon_ns = self.horizon_ns;
+        self.trades.retain(|_, trades| {
+            prune(trades, now_ns, horizon_ns);
+            !trades.is_empty()
+        });
+        self.trades.iter()
+            .map(|((exchange, symbol), trades)| FlowSnapshot {
+                exchange: exchange.clone(),
+                symbol: symbol.clone(),
+                windows: self.windows_secs.iter().map(|&secs| window_stats(trades, secs, now_ns)).collect(),
+                timestamp: now_ns,
+            })
+            .collect()
+    }
+}
+
+/// `now_ns - horizon_ns` 이전 체결 삭제
+fn prune(trades: &mut VecDeque<FlowTrade>, now_ns: u64, horizon_ns: u64) {
+    let cutoff = now_ns.saturating_sub(horizon_ns);
+    while trades.front().is_some_and(|t| t.received_at < cutoff) {
+        trades.pop_front();
+    }
+}
+
+/// 최근 `window_secs`초 체결 집계
+fn window_stats(trades: &VecDeque<FlowTrade>, window_secs: u32, now_ns: u64) -> FlowStats {
+    let cutoff = now_ns.saturating_sub(window_secs as u64 * NANOS_PER_SEC);
+    let mut stats = FlowStats { window_secs, trade_count: 0, vwap: 0.0, buy_volume: 0.0, sell_volume: 0.0 };
+    let mut notional = 0.0;
+    for trade in trades.iter().rev().take_while(|t| t.received_at >= cutoff) {
+        stats.trade_count += 1;
+        notional += trade.price * trade.quantity;
+        if trade.is_buy {
+            stats.buy_volume += trade.quantity;
+        } else {
+            stats.sell_volume += trade.quantity;
+        }
+    }
+    let volume = stats.buy_volume + stats.sell_volume;
+    if volume > 0.0 {
+        stats.vwap = notional / volume;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_parser::StandardizedTradeBatch;
+
+    const SEC: u64 = NANOS_PER_SEC;
+
+    fn trade(price: f64, quantity: f64, is_buyer_taker: bool) -> StandardizedTrade {
+        StandardizedTrade {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            price,
+            quantity,
+            is_buyer_taker,
+            timestamp: 0,
+            trade_id: None,
+        }
+    }
+
+    #[test]
+    fn test_rolling_windows() {
+        let mut flow = TradeFlow::new(&TradeFlowConfig::default());
+        flow.ingest(&ParsedData::Trade(trade(100.0, 3.0, false)), 0);
+        flow.ingest(&ParsedData::TradeBatch(StandardizedTradeBatch {
+            symbol: "BTC^USDT".into(),
+            exchange: "BinanceSpot".into(),
+            exchange_timestamp: 0,
+            trades: vec![trade(110.0, 1.0, true), trade(120.0, 1.0, true)],
+        }), 5 * SEC);
+
+        let snapshots = flow.flush(5 * SEC + SEC / 2);
+        assert_eq!(snapshots.len(), 1);
+        let [one, ten, sixty] = &snapshots[0].windows[..] else { panic!("3 windows expected") };
+        // 1초: 배치 두 건만
+        assert_eq!((one.window_secs, one.trade_count, one.vwap, one.buy_volume, one.sell_volume), (1, 2, 115.0, 2.0, 0.0));
+        assert_eq!(one.imbalance(), 1.0);
+        // 10초/60초: 세 건 모두
+        assert_eq!((ten.trade_count, ten.vwap), (3, 106.0));
+        assert_eq!(ten.imbalance(), -0.2);
+        assert_eq!(sixty, &FlowStats { window_secs: 60, ..ten.clone() });
+
+        // 가장 긴 구간이 지나면 체결이 삭제되어 전송하지 않음
+        let later = flow.flush(20 * SEC);
+        assert_eq!((later[0].windows[0].trade_count, later[0].windows[0].vwap, later[0].windows[1].trade_count), (0, 0.0, 0));
+        assert!(flow.flush(66 * SEC).is_empty());
+    }
+
+    #[test]
+    fn test_contract_quantities_converted_to_base() {
+        let contract_values = HashMap::from([(("OkxSwap".to_string(), "BTC^USDT".to_string()), 0.01)]);
+        let mut flow = TradeFlow::new(&TradeFlowConfig::default()).with_contract_values(contract_values);
+        flow.ingest(&ParsedData::Trade(trade(100.0, 2.0, true)), 0);
+        flow.ingest(&ParsedData::Trade(StandardizedTrade { exchange: "OkxSwap".into(), ..trade(100.0, 300.0, false) }), 0);
+
+        let snapshots = flow.flush(0);
+        let volumes: Vec<_> = snapshots.iter().map(|s| (s.exchange.as_str(), s.windows[0].buy_volume + s.windows[0].sell_volume)).collect();
+        // OKX 무기한 300계약 = 3 BTC
+        assert_eq!(volumes, vec![("BinanceSpot", 2.0), ("OkxSwap", 3.0)]);
+    }
+}
@@ -1,12 +1,13 @@
-/// UDP 멀티캐스터
-/// 생성된 UDP 패킷을 네트워크에 멀티캐스트 전송
+//! UDP 멀티캐스터
+//! 생성된 UDP 패킷을 네트워크에 멀티캐스트 전송
 
-use crate::config::UdpConfig;
+use crate::config::{ProtocolConfig, UdpConfig};
 use crate::packet_builder::UdpPacket;
+use crate::protocol::{FragmentHeader, PacketHeaderV2, UpdateIdRange, WireFormat, PROTOCOL_VERSION_V2, V2_FLAG_CRC, V2_FLAG_FRAGMENT, V2_FLAG_REPLAY, V2_FLAG_UPDATE_IDS};
 use crate::errors::{CryptoFeederError, Result};
 
 use log::{info, debug, error, warn};
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
 use std::sync::{Mutex, Arc};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -22,6 +23,12 @@ pub struct UdpMulticaster {
     sockets_by_port: Mutex<HashMap<u16, Arc<UdpSocket>>>,
     packets_sent: AtomicU64,
     bytes_sent: AtomicU64,
+    // 포트별 프로토콜 버전 (v2 포트는 PacketBuilder가 채운 v2 헤더로 전송)
+    protocol: ProtocolConfig,
+    // v2 헤더에 리플레이 플래그 설정 (리플레이 모드)
+    replay: bool,
+    // v2 채널(포트)별 마지막 시퀀스
+    channel_sequences: Mutex<HashMap<u16, Arc<Mutex<u64>>>>,
 }
 
 impl UdpMulticaster {
@@ -38,7 +45,7 @@ impl UdpMulticaster {
 
         // 송신 전용 UDP 소켓 바인드 (임의 포트)
         let socket = UdpSocket::bind((interface_ip, 0))
-            .map_err(|e| CryptoFeederError::UdpError(e))?;
+            .map_err(CryptoFeederError::UdpError)?;
 
         // 멀티캐스트 옵션 설정
         socket.set_nonblocking(true).ok();
@@ -75,24 +82,74 @@ impl UdpMulticaster {
             sockets_by_port: Mutex::new(HashMap::new()),
             packets_sent: AtomicU64::new(0),
             bytes_sent: AtomicU64::new(0),
+            protocol: ProtocolConfig::default(),
+            replay: false,
+            channel_sequences: Mutex::new(HashMap::new()),
         })
     }
 
+    /// 포트별 프로토콜 버전 설정 (v2 헤더의 ID는 PacketBuilder::with_instruments로 채움)
+    pub fn with_protocol(mut self, protocol: ProtocolConfig) -> Self {
+        if protocol.uses_v2() {
+            info!("🧾 프로토콜: 기본 v{}, 포트별 {:?}, v2 CRC {}", protocol.default_version, protocol.port_versions,
+                  if protocol.v2_crc { "사용" } else { "미사용" });
+        }
+        self.protocol = protocol;
+        self
+    }
+
+    /// v2 패킷에 리플레이 플래그 설정 (리플레이 모드용)
+    pub fn with_replay_flag(mut self) -> Self {
+        self.replay = true;
+        self
+    }
+
+    /// 포트의 프로토콜 버전에 맞춰 전송 (v2는 채널 시퀀스 배정과 전송을 같은 잠금 안에서 수행해 순서 보장)
+    fn send_encoded(&self, packet: &UdpPacket, port: u16, send: impl Fn(&[u8]) -> std::io::Result<usize>) -> (std::io::Result<usize>, usize) {
+        let data = packet.data.as_slice();
+        if self.protocol.version_for(port) != PROTOCOL_VERSION_V2 {
+            return (send(data), data.len());
+        }
+        let channel = self.channel_sequences.lock().unwrap().entry(port).or_default().clone();
+        let mut sequence = channel.lock().unwrap();
+        let mut flags = 0;
+        if self.protocol.v2_crc { flags |= V2_FLAG_CRC; }
+        if self.replay { flags |= V2_FLAG_REPLAY; }
+        match encode_v2_packet(packet, *sequence + 1, flags) {
+            Ok(Some(packet)) => {
+                // 전송 실패(드롭)도 시퀀스를 소비하여 수신 측에서 누락으로 보이도록 함
+                *sequence += 1;
+                (send(&packet), packet.len())
+            }
+            Err(e) => {
+                // 직렬화 실패도 드롭과 같이 시퀀스를 소비
+                *sequence += 1;
+                (Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())), 0)
+            }
+            Ok(None) => {
+                warn!("⚠️ v2 헤더가 없는 패킷은 v1 그대로 전송: {} bytes", data.len());
+                (send(data), data.len())
+            }
+        }
+    }
+
     /// UDP 패킷 전송
     pub async fn send_packet(&self, packet: UdpPacket) -> Result<()> {
         debug!("📤 UDP 패킷 전송 시도: {} bytes", packet.size);
 
         // 논블로킹 전송 시도 (connected 우선)
-        let send_result = if self.connected {
-            self.socket.send(&packet.data)
-        } else {
-            self.socket.send_to(&packet.data, self.target_addr)
-        };
+        let (send_result, expected) = self.send_encoded(&packet, self.target_addr.port(), |data| {
+            if self.connected {
+                self.socket.send(data)
+            } else {
+                self.socket.send_to(data, self.target_addr)
+            }
+        });
 
         match send_result {
             Ok(bytes_sent) => {
-                if bytes_sent != packet.size {
-                    error!("⚠️ 부분 전송: {}/{} bytes", bytes_sent, packet.size);
+                if bytes_sent != expected {
+                    error!("⚠️ 부분 전송: {}/{} bytes", bytes_sent, expected);
                 } else {
                     debug!("✅ 패킷 전송 성공: {} bytes", bytes_sent);
                 }
@@ -127,7 +184,7 @@ impl UdpMulticaster {
         let maybe_send = {
             // 한 번만 잠금 유지
             let mut map = self.sockets_by_port.lock().unwrap();
-            if !map.contains_key(&port) {
+            if let Entry::Vacant(slot) = map.entry(port) {
                 let target_addr = SocketAddr::from((self.multicast_ip, port));
                 match UdpSocket::bind((self.interface_ip, 0)) {
                     Ok(sock) => {
@@ -139,7 +196,7 @@ impl UdpMulticaster {
                         } else {
                             info!("🔗 UDP connect 성공: {}", target_addr);
                         }
-                        map.insert(port, Arc::new(sock));
+                        slot.insert(Arc::new(sock));
                     }
                     Err(e) => {
                         error!("❌ 포트 {}용 UDP 소켓 생성 실패: {}", port, e);
@@ -152,10 +209,10 @@ impl UdpMulticaster {
         };
 
         if let Some(sock) = maybe_send {
-            let send_result = sock.send(&packet.data);
+            let (send_result, expected) = self.send_encoded(&packet, port, |data| sock.send(data));
             match send_result {
                 Ok(bytes_sent) => {
-                    if bytes_sent != packet.size { error!("⚠️ 부분 전송: {}/{} bytes", bytes_sent, packet.size); }
+                    if bytes_sent != expected { error!("⚠️ 부분 전송: {}/{} bytes", bytes_sent, expected); }
                     self.packets_sent.fetch_add(1, Ordering::Relaxed);
                     self.bytes_sent.fetch_add(bytes_sent as u64, Ordering::Relaxed);
                     Ok(())
@@ -194,6 +251,28 @@ impl UdpMulticaster {
     }
 }
 
+/// PacketBuilder가 채운 v2 헤더로 v2 패킷 직렬화 (v2 헤더가 없으면 None)
+///
+/// 채널 시퀀스와 전송 플래그(CRC/리플레이)만 기록하고 패킷의 아이템은 그대로 복사합니다.
+/// 다중 패킷 메시지의 분할 정보가 있으면 FRAGMENT 플래그와 함께 페이로드 앞에 붙이고,
+/// 오더북 업데이트 ID 범위가 있으면 UPDATE_IDS 플래그와 함께 그 뒤에 붙입니다.
+pub fn encode_v2_packet(packet: &UdpPacket, sequence_number: u64, mut flags: u8) -> Result<Option<Vec<u8>>> {
+    let Some(header) = packet.v2_header else { return Ok(None) };
+    let items = packet.items();
+    if packet.fragment.is_some() { flags |= V2_FLAG_FRAGMENT; }
+    if packet.update_ids.is_some() { flags |= V2_FLAG_UPDATE_IDS; }
+    let header = PacketHeaderV2 { sequence_number, flags: header.flags | flags, ..header };
+    header.encode_packet_with(FragmentHeader::WIRE_SIZE + UpdateIdRange::WIRE_SIZE + items.len(), |dst| {
+        if let Some(fragment) = packet.fragment {
+            fragment.encode_into(dst);
+        }
+        if let Some(update_ids) = packet.update_ids {
+            update_ids.encode_into(dst);
+        }
+        dst.extend_from_slice(items);
+    }).map(Some)
+}
+
 #[derive(Debug, Clone)]
 pub struct UdpStats {
     pub packets_sent: u64,
@@ -224,12 +303,14 @@ impl Drop for UdpMulticaster {
 mod tests {
     use super::*;
     use crate::config::UdpConfig;
+    use crate::protocol::PacketHeader;
 
     fn create_test_config() -> UdpConfig {
         UdpConfig {
             multicast_addr: "239.1.1.1".to_string(),
             port: 19001, // 테스트용 포트
             interface_addr: "127.0.0.1".to_string(),
+            mtu: UdpConfig::DEFAULT_MTU,
         }
     }
 
@@ -245,6 +326,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_v2_ports_use_channel_sequences() {
+        let mut protocol = ProtocolConfig::from_ini(&[
+            ("protocol_port_versions".to_string(), "19002:2".to_string()),
+        ].into_iter().collect()).unwrap();
+        assert_eq!((protocol.version_for(19001), protocol.version_for(19002)), (1, 2));
+        protocol.v2_crc = true;
+        let multicaster = UdpMulticaster::new(&create_test_config()).unwrap()
+            .with_protocol(protocol);
+
+        let mut header = PacketHeader::new();
+        header.set_packet_info(0, 0, true);
+        header.set_exchange("BinanceSpot");
+        let v1 = header.to_bytes();
+        let v2_header = Some(PacketHeaderV2::from_v1(&header, 1, 0));
+        let packet = UdpPacket { size: v1.len(), data: v1.clone(), fragment: None, update_ids: None, v2_header };
+        let fragmented = UdpPacket { size: v1.len(), data: v1.clone(), fragment: Some(FragmentHeader::new(9, 1, 2)), update_ids: Some(UpdateIdRange { first_update_id: 100, last_update_id: 110 }), v2_header };
+        // v2 헤더가 없는 패킷(레지스트리 없는 PacketBuilder)은 v2 포트에도 v1 그대로 전송
+        let plain = UdpPacket { size: v1.len(), data: v1.clone(), fragment: None, update_ids: None, v2_header: None };
+        let sent = std::cell::RefCell::new(Vec::new());
+        let capture = |data: &[u8]| { sent.borrow_mut().push(data.to_vec()); Ok(data.len()) };
+        let send = |packet: &UdpPacket, port: u16| {
+            let (result, len) = multicaster.send_encoded(packet, port, capture);
+            assert_eq!(result.unwrap(), len);
+        };
+        send(&packet, 19001);
+        send(&packet, 19002);
+        send(&fragmented, 19002);
+        send(&packet, 19003);
+        send(&plain, 19002);
+
+        let sent = sent.into_inner();
+        assert_eq!(sent[0], v1);
+        let seqs: Vec<u64> = sent[1..3].iter().map(|p| PacketHeaderV2::try_from(p.as_slice()).unwrap().sequence_number).collect();
+        assert_eq!(seqs, vec![1, 2]);
+        assert!(PacketHeaderV2::try_from(sent[1].as_slice()).unwrap().has_crc());
+        // 분할 정보(8바이트)와 업데이트 ID 범위(16바이트)는 플래그와 함께 페이로드 앞에 순서대로 전송
+        let v2 = PacketHeaderV2::try_from(sent[2].as_slice()).unwrap();
+        assert!(v2.is_fragmented() && v2.has_update_ids());
+        assert_eq!({ v2.payload_length }, 24);
+        assert_eq!(FragmentHeader::try_from(&sent[2][PacketHeaderV2::WIRE_SIZE..]).unwrap(), FragmentHeader::new(9, 1, 2));
+        let update_ids = UpdateIdRange::try_from(&sent[2][PacketHeaderV2::WIRE_SIZE + FragmentHeader::WIRE_SIZE..]).unwrap();
+        assert_eq!(({ update_ids.first_update_id }, { update_ids.last_update_id }), (100, 110));
+        assert_eq!(sent[3], v1);
+        assert_eq!(sent[4], v1);
+        assert_eq!({ PacketHeaderV2::try_from(sent[1].as_slice()).unwrap().venue_id }, 1);
+
+        assert!(ProtocolConfig::from_ini(&[("protocol_version".to_string(), "3".to_string())].into_iter().collect()).is_err());
+    }
+
     #[test]
     fn test_stats_calculation() {
         let stats = UdpStats {
@@ -270,6 +401,9 @@ mod tests {
             let test_packet = UdpPacket {
                 data: vec![1, 2, 3, 4, 5],
                 size: 5,
+                fragment: None,
+                update_ids: None,
+                v2_header: None,
             };
             
             // 전송 시도 (테스트 환경에서는 실패할 수 있음)
@@ -280,10 +414,7 @@ mod tests {
             if result.is_err() {
                 println!("UDP 전송 실패 (테스트 환경에서 정상): {:?}", result.err().unwrap());
             }
-            
-            // 멀티캐스터가 정상적으로 생성되었다면 성공으로 간주
-            assert!(true);
-        } else {
+                    } else {
             panic!("UdpMulticaster 생성 실패");
         }
     }
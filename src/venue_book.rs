@@ -0,0 +1,273 @@
+//! 거래소별 최우선 호가 추적용 로컬 호가창
+//! 파생 데이터 계산기(통합 최우선 호가, 김치 프리미엄)가 오더북 메시지로 (거래소, 심볼)별 호가창을 유지
+
+use crate::data_parser::{OrderBookLevel, StandardizedOrderBookUpdate};
+use std::collections::{BTreeMap, VecDeque};
+
+/// 호가창 한쪽의 최대 호가 수 (증분만 받으므로 먼 호가가 쌓이지 않도록 제한)
+const MAX_LEVELS_PER_SIDE: usize = 1000;
+
+/// 스냅샷을 기다리는 동안 보관하는 최대 증분 수 (넘치면 오래된 것부터 버림)
+const MAX_PENDING_UPDATES: usize = 1000;
+
+/// 가격 키 배율 (10^8 틱으로 정렬/삭제)
+const PRICE_KEY_SCALE: f64 = 1e8;
+
+/// 가격 비교/정렬용 정수 키
+pub fn price_key(price: f64) -> i64 {
+    (price * PRICE_KEY_SCALE).round() as i64
+}
+
+/// 증분의 업데이트 ID 연속성
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sequence {
+    /// 이어지는 증분 (또는 ID가 없어 확인할 수 없음)
+    Apply,
+    /// 이미 반영된 범위 (`u <= 마지막 ID`)
+    Stale,
+    /// 빠진 증분이 있음 (`U > 마지막 ID + 1`)
+    Gap,
+}
+
+/// 마지막으로 반영한 업데이트 ID 기준으로 증분 `U..=u`의 연속성 판단
+///
+/// 스냅샷 직후 첫 증분은 `U <= 마지막 ID + 1 <= u`이면 이어지는 것으로 봅니다 (Binance 로컬 호가창 규칙).
+pub fn sequence(last_update_id: Option<u64>, update: &StandardizedOrderBookUpdate) -> Sequence {
+    let (Some(last), Some(first), Some(final_id)) = (last_update_id, update.first_update_id, update.last_update_id) else {
+        return Sequence::Apply;
+    };
+    if final_id <= last {
+        Sequence::Stale
+    } else if first > last + 1 {
+        Sequence::Gap
+    } else {
+        Sequence::Apply
+    }
+}
+
+/// 거래소 하나의 호가창
+///
+/// 스냅샷(`is_snapshot`)으로 시작하고, 그 전에 받은 증분은 보관했다가 스냅샷 뒤에 업데이트 ID 순서대로 반영합니다.
+/// 증분의 업데이트 ID가 끊기면 호가를 비우고 다음 스냅샷을 기다리며, 그동안 최우선 호가는 없습니다.
+/// 증분은 수량 0이면 호가를 지우고, 새 호가와 교차하는 반대쪽 호가는 이미 체결/취소된 것으로 보고 지웁니다.
+#[derive(Debug, Default)]
+pub struct VenueBook {
+    bids: BTreeMap<i64, OrderBookLevel>,
+    asks: BTreeMap<i64, OrderBookLevel>,
+    /// 스냅샷 이후 끊김 없이 반영 중인지
+    synced: bool,
+    /// 마지막으로 반영한 업데이트 ID
+    last_update_id: Option<u64>,
+    /// 스냅샷 전에 받은 증분
+    pending: VecDeque<StandardizedOrderBookUpdate>,
+    /// 마지막 메시지 수신 시각 (로컬 나노초)
+    updated_at: u64,
+}
+
+impl VenueBook {
+    pub fn apply(&mut self, update: &StandardizedOrderBookUpdate, now_ns: u64) {
+        self.updated_at = now_ns;
+        if update.is_snapshot {
+            self.bids.clear();
+            self.asks.clear();
+            self.apply_levels(update);
+            self.synced = true;
+            self.last_update_id = update.last_update_id;
+            let pending = std::mem::take(&mut self.pending);
+            for (idx, diff) in pending.iter().enumerate() {
+                if !self.apply_diff(diff) {
+                    // 스냅샷보다 앞선 증분이 빠짐: 남은 증분을 다시 보관하고 다음 스냅샷 대기
+                    self.pending.extend(pending.iter().skip(idx + 1).cloned());
+                    break;
+                }
+            }
+        } else if !self.synced {
+            if self.pending.len() == MAX_PENDING_UPDATES {
+                self.pending.pop_front();
+            }
+            self.pending.push_back(update.clone());
+        } else {
+            self.apply_diff(update);
+        }
+    }
+
+    /// 스냅샷 이후 증분 반영 (끊기면 호가를 비우고 이 증분부터 다시 보관, 반영/건너뜀이면 true)
+    fn apply_diff(&mut self, update: &StandardizedOrderBookUpdate) -> bool {
+        match sequence(self.last_update_id, update) {
+            Sequence::Stale => true,
+            Sequence::Gap => {
+                self.bids.clear();
+                self.asks.clear();
+                self.synced = false;
+                self.last_update_id = None;
+                self.pending.push_back(update.clone());
+                false
+            }
+            Sequence::Apply => {
+                self.apply_levels(update);
+                self.last_update_id = update.last_update_id.or(self.last_update_id);
+                true
+            }
+        }
+    }
+
+    fn apply_levels(&mut self, update: &StandardizedOrderBookUpdate) {
+        for level in &update.bids {
+            apply_level(&mut self.bids, level);
+            if level.quantity > 0.0 {
+                let key = price_key(level.price);
+                self.asks.retain(|k, _| *k > key);
+            }
+        }
+        for level in &update.asks {
+            apply_level(&mut self.asks, level);
+            if level.quantity > 0.0 {
+                let key = price_key(level.price);
+                self.bids.retain(|k, _| *k < key);
+            }
+        }
+        while self.bids.len() > MAX_LEVELS_PER_SIDE {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > MAX_LEVELS_PER_SIDE {
+            self.asks.pop_last();
+        }
+    }
+
+    /// 스냅샷 이후 끊김 없이 반영 중인지 (false면 최우선 호가 없음)
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    pub fn best_bid(&self) -> Option<&OrderBookLevel> {
+        self.bids.last_key_value().map(|(_, level)| level)
+    }
+
+    pub fn best_ask(&self) -> Option<&OrderBookLevel> {
+        self.asks.first_key_value().map(|(_, level)| level)
+    }
+
+    /// 최우선 매수/매도 중간 가격 (한쪽이라도 없으면 None)
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()?.price + self.best_ask()?.price) / 2.0)
+    }
+
+    /// `now_ns` 기준 `stale_ns`보다 오래 갱신되지 않았는지
+    pub fn is_stale(&self, now_ns: u64, stale_ns: u64) -> bool {
+        now_ns.saturating_sub(self.updated_at) > stale_ns
+    }
+}
+
+fn apply_level(side: &mut BTreeMap<i64, OrderBookLevel>, level: &OrderBookLevel) {
+    if !level.price.is_finite() || level.price <= 0.0 || !level.quantity.is_finite() {
+        return;
+    }
+    let key = price_key(level.price);
+    if level.quantity > 0.0 {
+        side.insert(key, level.clone());
+    } else {
+        side.remove(&key);
+    }
+}
+
+/// 파생 데이터 계산기 테스트 공용 오더북 메시지
+#[cfg(test)]
+pub(crate) mod test_books {
+    use crate::data_parser::{OrderBookLevel, ParsedData, StandardizedOrderBookUpdate};
+
+    pub fn update(exchange: &str, symbol: &str, bids: &[(f64, f64)], asks: &[(f64, f64)], is_snapshot: bool) -> StandardizedOrderBookUpdate {
+        let levels = |l: &[(f64, f64)]| l.iter().map(|&(price, quantity)| OrderBookLevel { price, quantity }).collect();
+        StandardizedOrderBookUpdate {
+            symbol: symbol.into(),
+            exchange: exchange.into(),
+            bids: levels(bids),
+            asks: levels(asks),
+            timestamp: 0,
+            first_update_id: None,
+            last_update_id: None,
+            is_snapshot,
+        }
+    }
+
+    /// 이전 호가를 모두 대체하는 스냅샷
+    pub fn snapshot(exchange: &str, symbol: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> ParsedData {
+        ParsedData::OrderBook(update(exchange, symbol, bids, asks, true))
+    }
+
+    /// 증분
+    pub fn diff(exchange: &str, symbol: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> ParsedData {
+        ParsedData::OrderBook(update(exchange, symbol, bids, asks, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(bids: &[(f64, f64)], asks: &[(f64, f64)], is_snapshot: bool) -> StandardizedOrderBookUpdate {
+        test_books::update("UpbitSpot", "BTC^KRW", bids, asks, is_snapshot)
+    }
+
+    /// 업데이트 ID 범위가 있는 Binance 증분/스냅샷
+    fn sequenced(bids: &[(f64, f64)], asks: &[(f64, f64)], ids: (Option<u64>, u64)) -> StandardizedOrderBookUpdate {
+        let mut update = test_books::update("BinanceSpot", "BTC^USDT", bids, asks, ids.0.is_none());
+        (update.first_update_id, update.last_update_id) = (ids.0, Some(ids.1));
+        update
+    }
+
+    #[test]
+    fn test_diff_and_snapshot() {
+        let mut book = VenueBook::default();
+        book.apply(&update(&[(100.0, 1.0), (99.0, 1.0)], &[(101.0, 1.0)], true), 0);
+        assert_eq!(book.mid(), Some(100.5));
+
+        // 증분: 수량 0이면 삭제
+        book.apply(&update(&[(100.0, 0.0)], &[], false), 0);
+        assert_eq!(book.best_bid().unwrap().price, 99.0);
+
+        // 스냅샷: 이전 호가 모두 대체
+        book.apply(&update(&[(90.0, 1.0)], &[(91.0, 1.0)], true), 5);
+        assert_eq!((book.best_bid().unwrap().price, book.best_ask().unwrap().price), (90.0, 91.0));
+        assert!(!book.is_stale(10, 5) && book.is_stale(11, 5));
+    }
+
+    #[test]
+    fn test_partial_diff_before_snapshot_waits_for_seed() {
+        let mut book = VenueBook::default();
+        // 스냅샷 전 증분만으로는 최우선 호가를 만들지 않음 (먼 호가 변경만 보고 100.0을 최우선으로 착각하지 않도록)
+        book.apply(&sequenced(&[(95.0, 2.0)], &[], (Some(101), 103)), 0);
+        book.apply(&sequenced(&[(100.0, 0.0)], &[(104.0, 1.0)], (Some(104), 105)), 0);
+        assert!(!book.is_synced());
+        assert!(book.best_bid().is_none() && book.mid().is_none());
+
+        // 스냅샷(lastUpdateId=102) 뒤에 보관한 증분을 이어서 반영: 101..=103은 U <= 103 <= u, 104..=105는 연속
+        book.apply(&sequenced(&[(100.0, 1.0), (98.0, 1.0)], &[(103.0, 1.0), (104.0, 5.0)], (None, 102)), 0);
+        assert!(book.is_synced());
+        assert_eq!((book.best_bid().unwrap().price, book.best_ask().unwrap().price), (98.0, 103.0));
+        assert_eq!(book.best_ask().unwrap().quantity, 1.0);
+        assert_eq!(book.bids.get(&price_key(95.0)).unwrap().quantity, 2.0);
+
+        // 이미 반영한 범위는 무시
+        book.apply(&sequenced(&[(98.0, 0.0)], &[], (Some(100), 105)), 0);
+        assert_eq!(book.best_bid().unwrap().price, 98.0);
+
+        // 빠진 증분(106)이 있으면 호가를 비우고 다음 스냅샷 대기
+        book.apply(&sequenced(&[(99.0, 1.0)], &[], (Some(107), 107)), 0);
+        assert!(!book.is_synced());
+        assert_eq!(book.mid(), None);
+        book.apply(&sequenced(&[(97.0, 1.0)], &[(102.0, 1.0)], (None, 106)), 0);
+        assert_eq!((book.best_bid().unwrap().price, book.best_ask().unwrap().price), (99.0, 102.0));
+    }
+
+    #[test]
+    fn test_sequence() {
+        let diff = sequenced(&[], &[], (Some(11), 12));
+        assert_eq!(sequence(None, &diff), Sequence::Apply);
+        assert_eq!(sequence(Some(10), &diff), Sequence::Apply);
+        assert_eq!(sequence(Some(11), &diff), Sequence::Apply);
+        assert_eq!(sequence(Some(12), &diff), Sequence::Stale);
+        assert_eq!(sequence(Some(9), &diff), Sequence::Gap);
+        // ID가 없는 거래소는 확인하지 않음
+        assert_eq!(sequence(Some(9), &update(&[], &[], false)), Sequence::Apply);
+    }
+}